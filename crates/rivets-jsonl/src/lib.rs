@@ -170,6 +170,7 @@ use futures::stream::StreamExt;
 use serde::de::DeserializeOwned;
 use std::path::Path;
 use tokio::fs::File;
+use tokio::io::AsyncRead;
 
 /// Reads an entire JSONL file into a Vec while resiliently handling malformed lines.
 ///
@@ -331,13 +332,29 @@ where
     P: AsRef<Path>,
 {
     let file = File::open(path).await?;
-    let reader = JsonlReader::new(file);
+    Ok(parse_jsonl_resilient_with_line_numbers(file).await)
+}
+
+/// Reads JSONL from any async reader resiliently, retaining each decoded
+/// record's physical 1-based source line number.
+///
+/// This is [`read_jsonl_resilient_with_line_numbers`] for content that is not
+/// a file on disk, such as bytes held in memory. Reading stops at the first
+/// I/O error, keeping the records decoded before it.
+pub async fn parse_jsonl_resilient_with_line_numbers<T, R>(
+    reader: R,
+) -> (Vec<(usize, T)>, Vec<Warning>)
+where
+    T: DeserializeOwned + 'static,
+    R: AsyncRead + Unpin,
+{
+    let reader = JsonlReader::new(reader);
     let (stream, collector) = reader.stream_resilient_with_line_numbers();
 
     let values: Vec<(usize, T)> = std::pin::pin!(stream).collect().await;
     let warnings = collector.into_warnings();
 
-    Ok((values, warnings))
+    (values, warnings)
 }
 
 #[cfg(test)]
@@ -672,4 +689,22 @@ mod tests {
             assert_eq!(warnings.len(), 2);
         }
     }
+
+    mod parse_jsonl_resilient_with_line_numbers_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn parses_bytes_with_line_numbers() {
+            let content =
+                b"{\"id\": 1, \"name\": \"Alice\"}\n\n{invalid}\n{\"id\": 2, \"name\": \"Bob\"}\n";
+            let (records, warnings) =
+                parse_jsonl_resilient_with_line_numbers::<TestRecord, _>(&content[..]).await;
+
+            let lines: Vec<usize> = records.iter().map(|(line, _)| *line).collect();
+            assert_eq!(lines, vec![1, 4]);
+            assert_eq!(records[1].1.name, "Bob");
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].line_number(), 3);
+        }
+    }
 }
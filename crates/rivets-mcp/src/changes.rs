@@ -0,0 +1,498 @@
+//! In-process change feed.
+//!
+//! Every mutation made through [`crate::tools::Tools`] and every change picked
+//! up by reloading a workspace from disk is recorded in the [`ChangeFeed`]
+//! shared by all sessions of a server. Each recorded [`ChangeEvent`] names the
+//! issue, what changed it and the field-level diff, and carries a cursor so
+//! an orchestrating agent can poll `changes_since` for everything other
+//! agents changed since it last looked.
+//!
+//! Sessions that enable MCP logging with `logging/setLevel` also receive
+//! each event as a `notifications/message` through [`ChangeNotifications`].
+
+use chrono::{DateTime, Utc};
+use rivets::domain::Issue;
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+use rmcp::{Peer, RoleServer};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Maximum number of events the feed retains; older events are dropped.
+const MAX_RETAINED_EVENTS: usize = 1000;
+
+/// Capacity of the channel forwarding events to logging sessions.
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 256;
+
+/// Logger name of change notifications.
+pub const CHANGE_LOGGER: &str = "rivets.changes";
+
+/// Source recorded for changes picked up by reloading from disk.
+pub const RELOAD_SOURCE: &str = "reload";
+
+/// How an issue changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The issue was created.
+    Created,
+    /// The issue was modified.
+    Updated,
+    /// The issue was deleted.
+    Deleted,
+}
+
+/// One field of an issue that changed.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct FieldChange {
+    /// The field, as named in the issue JSON.
+    pub field: String,
+
+    /// The previous value; `null` if the field or issue did not exist.
+    pub before: Value,
+
+    /// The new value; `null` if the field or issue no longer exists.
+    pub after: Value,
+}
+
+/// A recorded change to one issue.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ChangeEvent {
+    /// Position in the feed; pass the last one seen to `changes_since`.
+    pub cursor: u64,
+
+    /// When the change was recorded.
+    pub at: DateTime<Utc>,
+
+    /// Canonical root of the workspace the issue belongs to.
+    pub workspace_root: String,
+
+    /// The changed issue.
+    pub issue_id: String,
+
+    /// How the issue changed.
+    pub kind: ChangeKind,
+
+    /// The tool that made the change, or `reload` for changes made outside
+    /// the server and picked up from disk.
+    pub source: String,
+
+    /// Who made the change: the agent named in a claim call, otherwise the
+    /// MCP client's name, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+
+    /// The fields that changed.
+    pub changes: Vec<FieldChange>,
+}
+
+/// Response from the `changes_since` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ChangesSinceResponse {
+    /// Events after the requested cursor, oldest first.
+    pub events: Vec<ChangeEvent>,
+
+    /// Cursor to pass to the next `changes_since` call.
+    pub cursor: u64,
+
+    /// Whether events after the requested cursor were dropped from the feed
+    /// before they could be returned.
+    pub truncated: bool,
+}
+
+/// The change to one issue between two snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueDiff {
+    /// The changed issue.
+    pub issue_id: String,
+    /// How the issue changed.
+    pub kind: ChangeKind,
+    /// The fields that changed.
+    pub changes: Vec<FieldChange>,
+}
+
+/// Diff two snapshots of a workspace's issues, as returned by `export_all`.
+///
+/// Issues are compared field by field in their JSON form. The result is
+/// ordered by issue ID.
+#[must_use]
+pub fn diff_snapshots(before: &[Issue], after: &[Issue]) -> Vec<IssueDiff> {
+    let before = snapshot_fields(before);
+    let after = snapshot_fields(after);
+    let ids: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let old = before.get(id);
+            let new = after.get(id);
+            let kind = match (old, new) {
+                (None, Some(_)) => ChangeKind::Created,
+                (Some(_), None) => ChangeKind::Deleted,
+                (Some(old), Some(new)) if old != new => ChangeKind::Updated,
+                _ => return None,
+            };
+            Some(IssueDiff {
+                issue_id: id.clone(),
+                kind,
+                changes: diff_fields(old, new),
+            })
+        })
+        .collect()
+}
+
+type IssueFields = serde_json::Map<String, Value>;
+
+/// Index a snapshot's issues by ID as JSON objects.
+fn snapshot_fields(issues: &[Issue]) -> BTreeMap<String, IssueFields> {
+    issues
+        .iter()
+        .filter_map(|issue| match serde_json::to_value(issue) {
+            Ok(Value::Object(fields)) => Some((issue.id.to_string(), fields)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The fields whose values differ between two versions of an issue.
+fn diff_fields(before: Option<&IssueFields>, after: Option<&IssueFields>) -> Vec<FieldChange> {
+    let empty = IssueFields::new();
+    let before = before.unwrap_or(&empty);
+    let after = after.unwrap_or(&empty);
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).unwrap_or(&Value::Null);
+            let new = after.get(field).unwrap_or(&Value::Null);
+            (old != new).then(|| FieldChange {
+                field: field.clone(),
+                before: old.clone(),
+                after: new.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The change feed shared by every session of a server.
+pub struct ChangeFeed {
+    state: Mutex<FeedState>,
+    sender: broadcast::Sender<ChangeEvent>,
+}
+
+#[derive(Default)]
+struct FeedState {
+    /// Retained events, oldest first.
+    events: VecDeque<ChangeEvent>,
+    /// Cursor of the most recent event; 0 before any.
+    last_cursor: u64,
+}
+
+impl ChangeFeed {
+    /// Create an empty feed.
+    #[must_use]
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY);
+        Self {
+            state: Mutex::new(FeedState::default()),
+            sender,
+        }
+    }
+
+    /// Record the differences between two snapshots of `workspace`.
+    ///
+    /// Returns the number of events recorded.
+    pub fn record(
+        &self,
+        workspace: &Path,
+        source: &str,
+        actor: Option<&str>,
+        before: &[Issue],
+        after: &[Issue],
+    ) -> usize {
+        let diffs = diff_snapshots(before, after);
+        if diffs.is_empty() {
+            return 0;
+        }
+
+        let at = Utc::now();
+        let workspace_root = workspace.display().to_string();
+        let mut state = self.lock();
+        for diff in &diffs {
+            state.last_cursor += 1;
+            let event = ChangeEvent {
+                cursor: state.last_cursor,
+                at,
+                workspace_root: workspace_root.clone(),
+                issue_id: diff.issue_id.clone(),
+                kind: diff.kind,
+                source: source.to_string(),
+                actor: actor.map(str::to_string),
+                changes: diff.changes.clone(),
+            };
+            if state.events.len() == MAX_RETAINED_EVENTS {
+                state.events.pop_front();
+            }
+            state.events.push_back(event.clone());
+            // Nobody listening is not an error.
+            let _ = self.sender.send(event);
+        }
+        diffs.len()
+    }
+
+    /// Events for `workspace` after `cursor`, oldest first, at most `limit`.
+    ///
+    /// Without a cursor every retained event is eligible. The returned cursor
+    /// is that of the last event returned, or the feed's latest cursor when
+    /// none are left, so polling with it never repeats or skips an event.
+    #[must_use]
+    pub fn since(
+        &self,
+        workspace: &Path,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> ChangesSinceResponse {
+        let workspace_root = workspace.display().to_string();
+        let after = cursor.unwrap_or(0);
+        let state = self.lock();
+
+        let oldest = state
+            .events
+            .front()
+            .map_or(state.last_cursor + 1, |event| event.cursor);
+        let truncated = cursor.is_some() && after + 1 < oldest;
+
+        let mut remaining = state
+            .events
+            .iter()
+            .filter(|event| event.cursor > after)
+            .filter(|event| event.workspace_root == workspace_root);
+        let events: Vec<ChangeEvent> = remaining.by_ref().take(limit).cloned().collect();
+        let cursor = match (remaining.next(), events.last()) {
+            (Some(_), Some(last)) => last.cursor,
+            _ => state.last_cursor.max(after),
+        };
+
+        ChangesSinceResponse {
+            events,
+            cursor,
+            truncated,
+        }
+    }
+
+    /// Receive every event recorded from now on.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FeedState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forwards change events to one session as MCP logging notifications.
+///
+/// Forwarding starts the first time the client sets a logging level, and
+/// events are sent at `info` level while the client's level allows it.
+#[derive(Default)]
+pub struct ChangeNotifications {
+    state: Mutex<NotificationState>,
+}
+
+#[derive(Default)]
+struct NotificationState {
+    /// The client's minimum level, shared with the forwarding task.
+    level: Arc<AtomicU8>,
+    /// The forwarding task, once started.
+    task: Option<JoinHandle<()>>,
+}
+
+impl ChangeNotifications {
+    /// Set the session's logging level, starting to forward `feed` to `peer`.
+    pub fn set_level(&self, level: LoggingLevel, feed: &ChangeFeed, peer: Peer<RoleServer>) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.level.store(level as u8, Ordering::Relaxed);
+        if state.task.is_none() {
+            let events = feed.subscribe();
+            state.task = Some(tokio::spawn(forward_changes(
+                events,
+                Arc::clone(&state.level),
+                peer,
+            )));
+        }
+    }
+}
+
+impl Drop for ChangeNotifications {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        if let Some(task) = state.task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Send each event to `peer` until the feed closes or the peer goes away.
+///
+/// MCP logging is deprecated by a newer protocol revision, but it is the
+/// push channel clients of the negotiated 2025-06-18 protocol understand;
+/// `changes_since` stays the durable way to follow the feed.
+#[allow(deprecated)]
+async fn forward_changes(
+    mut events: broadcast::Receiver<ChangeEvent>,
+    level: Arc<AtomicU8>,
+    peer: Peer<RoleServer>,
+) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                debug!(skipped, "Change notifications lagged behind the feed");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if level.load(Ordering::Relaxed) > LoggingLevel::Info as u8 {
+            continue;
+        }
+        let Ok(data) = serde_json::to_value(&event) else {
+            continue;
+        };
+        let notification = LoggingMessageNotificationParam::new(LoggingLevel::Info, data)
+            .with_logger(CHANGE_LOGGER);
+        if let Err(error) = peer.notify_logging_message(notification).await {
+            debug!(%error, "Stopped sending change notifications");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rivets::domain::{IssueKind, IssueUpdate, NewIssue};
+    use rivets::storage::IssueStorage;
+    use rivets::storage::in_memory::new_in_memory_storage;
+
+    fn new_issue(title: &str) -> NewIssue {
+        NewIssue {
+            title: title.to_string(),
+            description: String::new(),
+            priority: 2,
+            issue_kind: IssueKind::Task,
+            assignee: None,
+            labels: vec![],
+            design: None,
+            acceptance_criteria: None,
+            initial_note: None,
+            custom_fields: BTreeMap::new(),
+            due_at: None,
+            milestone: None,
+            dependencies: vec![],
+        }
+    }
+
+    async fn storage_with(titles: &[&str]) -> (Box<dyn IssueStorage>, Vec<Issue>) {
+        let mut storage = new_in_memory_storage("test".to_string());
+        let mut issues = Vec::new();
+        for title in titles {
+            issues.push(storage.create(new_issue(title)).await.unwrap());
+        }
+        (storage, issues)
+    }
+
+    #[tokio::test]
+    async fn diff_reports_created_updated_and_deleted_issues() {
+        let (mut storage, issues) = storage_with(&["Kept", "Old title", "Gone"]).await;
+        let before = storage.export_all().await.unwrap();
+        let renamed = storage
+            .update(
+                &issues[1].id,
+                IssueUpdate {
+                    title: Some("New title".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        storage.delete(&issues[2].id).await.unwrap();
+        let added = storage.create(new_issue("Added")).await.unwrap();
+        let after = storage.export_all().await.unwrap();
+
+        let diffs = diff_snapshots(&before, &after);
+        assert_eq!(diffs.len(), 3);
+        let diff_of = |id: &str| {
+            diffs
+                .iter()
+                .find(|diff| diff.issue_id == id)
+                .expect("changed issue should be diffed")
+        };
+
+        let updated = diff_of(renamed.id.as_str());
+        assert_eq!(updated.kind, ChangeKind::Updated);
+        let title = updated
+            .changes
+            .iter()
+            .find(|change| change.field == "title")
+            .expect("title should be diffed");
+        assert_eq!(title.before, Value::from("Old title"));
+        assert_eq!(title.after, Value::from("New title"));
+
+        let deleted = diff_of(issues[2].id.as_str());
+        assert_eq!(deleted.kind, ChangeKind::Deleted);
+        assert!(deleted.changes.iter().all(|change| change.after.is_null()));
+        assert_eq!(diff_of(added.id.as_str()).kind, ChangeKind::Created);
+    }
+
+    #[tokio::test]
+    async fn since_pages_by_cursor_and_filters_by_workspace() {
+        let (_, issues) = storage_with(&["One", "Two"]).await;
+        let feed = ChangeFeed::new();
+        let ours = Path::new("/work/ours");
+        let theirs = Path::new("/work/theirs");
+        feed.record(ours, "create", Some("agent-a"), &[], &issues);
+        feed.record(theirs, "create", None, &[], &issues[..1]);
+
+        let first = feed.since(ours, None, 1);
+        assert_eq!(first.events.len(), 1);
+        assert_eq!(first.events[0].actor.as_deref(), Some("agent-a"));
+        assert_eq!(first.cursor, 1);
+
+        let second = feed.since(ours, Some(first.cursor), 10);
+        assert_eq!(second.events.len(), 1);
+        assert_eq!(second.cursor, 3);
+        assert!(!second.truncated);
+
+        let caught_up = feed.since(ours, Some(second.cursor), 10);
+        assert!(caught_up.events.is_empty());
+        assert_eq!(caught_up.cursor, 3);
+    }
+
+    #[tokio::test]
+    async fn since_reports_events_dropped_from_the_feed() {
+        let (_, issues) = storage_with(&["One"]).await;
+        let feed = ChangeFeed::new();
+        let workspace = Path::new("/work");
+        for _ in 0..=MAX_RETAINED_EVENTS {
+            feed.record(workspace, "create", None, &[], &issues);
+        }
+
+        let response = feed.since(workspace, Some(0), 1);
+        assert!(response.truncated);
+        assert_eq!(response.events[0].cursor, 2);
+    }
+}
@@ -14,6 +14,7 @@
 //! Never attempt to acquire a context lock while holding a storage lock.
 //! This prevents potential deadlocks in concurrent scenarios.
 
+use crate::changes::ChangeFeed;
 use crate::error::{Error, Result};
 use rivets::commands::init::RivetsConfig;
 use rivets::storage::{IssueStorage, create_storage};
@@ -31,8 +32,11 @@ const MAX_CACHED_WORKSPACES: usize = 32;
 /// Global context state for the MCP server.
 ///
 /// Manages workspace contexts and storage instances for multi-workspace support.
+/// Sessions sharing a context choose their own workspace with
+/// [`Context::open_workspace`]; the current workspace is the default for
+/// sessions that have not.
 pub struct Context {
-    /// The current active workspace root.
+    /// The current active workspace root, shared by every session as a default.
     current_workspace: Option<PathBuf>,
 
     /// Per-workspace storage instances (limited to [`MAX_CACHED_WORKSPACES`]).
@@ -43,6 +47,12 @@ pub struct Context {
 
     /// Insertion order for FIFO cache eviction.
     cache_order: VecDeque<PathBuf>,
+
+    /// Canonical roots workspaces must lie under; empty allows any.
+    allowed_roots: Vec<PathBuf>,
+
+    /// Changes made to any workspace, shared by every session.
+    changes: Arc<ChangeFeed>,
 }
 
 impl Context {
@@ -54,6 +64,46 @@ impl Context {
             storage_cache: HashMap::new(),
             database_paths: HashMap::new(),
             cache_order: VecDeque::new(),
+            allowed_roots: Vec::new(),
+            changes: Arc::new(ChangeFeed::new()),
+        }
+    }
+
+    /// Create a context that only opens workspaces under `roots`.
+    ///
+    /// A workspace is allowed if its canonical path is one of the roots or
+    /// lies beneath one. An empty list allows any workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::WorkspaceNotFound` if a root does not exist.
+    pub fn with_allowed_roots(roots: &[PathBuf]) -> Result<Self> {
+        let allowed_roots = roots
+            .iter()
+            .map(|root| {
+                root.canonicalize().map_err(|e| Error::WorkspaceNotFound {
+                    path: root.display().to_string(),
+                    source: Some(e),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            allowed_roots,
+            ..Self::new()
+        })
+    }
+
+    /// Reject a canonical workspace path outside the allowed roots.
+    fn check_allowed(&self, canonical: &Path) -> Result<()> {
+        if self.allowed_roots.is_empty()
+            || self
+                .allowed_roots
+                .iter()
+                .any(|root| canonical.starts_with(root))
+        {
+            Ok(())
+        } else {
+            Err(Error::WorkspaceNotAllowed(canonical.display().to_string()))
         }
     }
 
@@ -82,6 +132,23 @@ impl Context {
     }
 
     /// Load and cache a workspace without changing the current workspace.
+    ///
+    /// Sessions sharing this context open their own workspace with this,
+    /// leaving the current workspace as the default for the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the workspace path doesn't exist, is not allowed,
+    /// has no `.rivets/` directory, or if storage creation fails.
+    pub async fn open_workspace(&mut self, workspace_root: &Path) -> Result<WorkspaceInfo> {
+        debug!(path = %workspace_root.display(), "Opening workspace");
+        self.initialize_workspace(workspace_root, None).await
+    }
+
+    /// Load and cache a workspace without changing the current workspace.
+    ///
+    /// Neither the current workspace nor `protected_workspace` is evicted
+    /// to make room for it.
     async fn initialize_workspace(
         &mut self,
         workspace_root: &Path,
@@ -97,6 +164,7 @@ impl Context {
 
         // Validate path is safe
         validate_path(&canonical)?;
+        self.check_allowed(&canonical)?;
 
         // Verify .rivets directory exists
         let rivets_dir = canonical.join(".rivets");
@@ -156,16 +224,13 @@ impl Context {
         })
     }
 
-    /// Evict the oldest cached workspace other than the protected workspace.
+    /// Evict the oldest cached workspace other than the current workspace
+    /// and the protected workspace.
     fn evict_oldest_except(&mut self, protected_workspace: Option<&Path>) -> bool {
-        let Some(eviction_index) =
-            self.cache_order
-                .iter()
-                .position(|workspace| match protected_workspace {
-                    Some(protected) => workspace != protected,
-                    None => true,
-                })
-        else {
+        let Some(eviction_index) = self.cache_order.iter().position(|workspace| {
+            Some(workspace.as_path()) != protected_workspace
+                && Some(workspace) != self.current_workspace.as_ref()
+        }) else {
             return false;
         };
         let Some(oldest) = self.cache_order.remove(eviction_index) else {
@@ -184,6 +249,18 @@ impl Context {
         self.current_workspace.as_ref()
     }
 
+    /// The change feed shared by every session using this context.
+    #[must_use]
+    pub fn changes(&self) -> &Arc<ChangeFeed> {
+        &self.changes
+    }
+
+    /// Get the database path of a cached workspace.
+    #[must_use]
+    pub fn database_path(&self, workspace_root: &Path) -> Option<&PathBuf> {
+        self.database_paths.get(workspace_root)
+    }
+
     /// Get the database path for the current workspace.
     #[must_use]
     pub fn current_database_path(&self) -> Option<&PathBuf> {
@@ -219,13 +296,7 @@ impl Context {
         &self,
         workspace_root: Option<&Path>,
     ) -> Result<Arc<RwLock<Box<dyn IssueStorage>>>> {
-        let workspace = match workspace_root {
-            Some(path) => path.canonicalize().map_err(|e| Error::WorkspaceNotFound {
-                path: path.display().to_string(),
-                source: Some(e),
-            })?,
-            None => self.current_workspace.clone().ok_or(Error::NoContext)?,
-        };
+        let workspace = self.workspace_root_for(workspace_root)?;
 
         self.storage_cache
             .get(&workspace)
@@ -233,10 +304,31 @@ impl Context {
             .ok_or_else(|| Error::WorkspaceNotInitialized(workspace.display().to_string()))
     }
 
+    /// Resolve a workspace root, or the current one if not specified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set and no workspace path is provided,
+    /// or the workspace path doesn't exist or is not allowed.
+    pub fn workspace_root_for(&self, workspace_root: Option<&Path>) -> Result<PathBuf> {
+        match workspace_root {
+            Some(path) => {
+                let canonical = path.canonicalize().map_err(|e| Error::WorkspaceNotFound {
+                    path: path.display().to_string(),
+                    source: Some(e),
+                })?;
+                self.check_allowed(&canonical)?;
+                Ok(canonical)
+            }
+            None => self.current_workspace.clone().ok_or(Error::NoContext),
+        }
+    }
+
     /// Get storage for a workspace, initializing an uncached workspace on first use.
     ///
     /// An explicit workspace is cached without changing the current workspace. Calls
-    /// without an explicit workspace still require a current context.
+    /// without an explicit workspace still require a current context. Neither the
+    /// current workspace nor `protected_workspace` is evicted to make room.
     ///
     /// # Errors
     ///
@@ -245,6 +337,7 @@ impl Context {
     pub async fn storage_for_or_init(
         &mut self,
         workspace_root: Option<&Path>,
+        protected_workspace: Option<&Path>,
     ) -> Result<Arc<RwLock<Box<dyn IssueStorage>>>> {
         match self.storage_for(workspace_root) {
             Ok(storage) => return Ok(storage),
@@ -256,9 +349,8 @@ impl Context {
             Some(path) => path.to_path_buf(),
             None => self.current_workspace.clone().ok_or(Error::NoContext)?,
         };
-        let protected_workspace = self.current_workspace.clone();
         let info = self
-            .initialize_workspace(&workspace, protected_workspace.as_deref())
+            .initialize_workspace(&workspace, protected_workspace)
             .await?;
         self.storage_cache
             .get(&info.workspace_root)
@@ -464,6 +556,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_allowed_roots_reject_workspaces_outside_them() {
+        let allowed = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let nested = allowed.path().join("project");
+        std::fs::create_dir(&nested).unwrap();
+
+        let context = Context::with_allowed_roots(&[allowed.path().to_path_buf()]).unwrap();
+        assert!(matches!(
+            context.workspace_root_for(Some(outside.path())),
+            Err(Error::WorkspaceNotAllowed(_))
+        ));
+        assert!(context.workspace_root_for(Some(&nested)).is_ok());
+        assert!(context.workspace_root_for(Some(allowed.path())).is_ok());
+
+        assert!(matches!(
+            Context::with_allowed_roots(&[PathBuf::from("/nonexistent/allowed/root")]),
+            Err(Error::WorkspaceNotFound { .. })
+        ));
+    }
+
     #[test]
     fn test_validate_path_rejects_relative() {
         let result = validate_path(Path::new("relative/path"));
@@ -531,8 +644,12 @@ mod tests {
         assert!(!context.storage_cache.contains_key(protected));
         assert_eq!(context.cache_size(), 1);
 
-        assert!(context.evict_oldest_except(None));
+        // The current workspace is never evicted.
         assert!(!context.evict_oldest_except(None));
-        assert_eq!(context.cache_size(), 0);
+        assert!(
+            context
+                .storage_cache
+                .contains_key(Path::new("/test/workspace2"))
+        );
     }
 }
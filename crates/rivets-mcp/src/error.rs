@@ -1,6 +1,6 @@
 //! Error types for the rivets MCP server.
 
-use rivets::error::Error as RivetsError;
+use rivets::error::{ConfigError, Error as RivetsError};
 use thiserror::Error;
 
 /// Errors that can occur in the rivets MCP server.
@@ -29,6 +29,28 @@ pub enum Error {
     #[error("Invalid resource: {0}")]
     InvalidResource(#[from] rivets::domain::ResourceError),
 
+    /// A custom field name or value was rejected by the workspace schema.
+    #[error("Invalid custom field: {0}")]
+    InvalidCustomField(#[from] rivets::domain::CustomFieldError),
+
+    /// A milestone was invalid, missing or in the wrong state.
+    #[error("Invalid milestone: {0}")]
+    InvalidMilestone(#[from] rivets::domain::MilestoneError),
+
+    /// An issue template was missing, unparsable or invalid.
+    #[error("Template error: {source}")]
+    InvalidTemplate {
+        /// The template name, or the issue kind when chosen by kind.
+        name: String,
+        /// Why the template could not be used.
+        #[source]
+        source: ConfigError,
+    },
+
+    /// A claim, renewal or release was rejected, or its lease was invalid.
+    #[error("Claim rejected: {0}")]
+    InvalidClaim(#[from] rivets::domain::LeaseError),
+
     /// A status change violated the domain transition rules.
     ///
     /// Transparent so MCP rejects a transition with the same observable
@@ -40,6 +62,10 @@ pub enum Error {
     #[error("Issue not found: {0}")]
     IssueNotFound(String),
 
+    /// A resource URI did not name a rivets resource.
+    #[error("Unknown resource URI: {0}")]
+    UnknownResourceUri(String),
+
     /// The specified workspace was not found or path is invalid.
     #[error("Workspace not found: {path}")]
     WorkspaceNotFound {
@@ -50,6 +76,10 @@ pub enum Error {
         source: Option<std::io::Error>,
     },
 
+    /// The workspace lies outside the roots the server allows.
+    #[error("Workspace not allowed by server configuration: {0}")]
+    WorkspaceNotAllowed(String),
+
     /// Workspace exists but is not present in the context cache.
     #[error("Workspace not initialized: {0}")]
     WorkspaceNotInitialized(String),
@@ -69,6 +99,16 @@ pub enum Error {
         reason: String,
     },
 
+    /// An operation in a `batch` call failed; no operation was applied.
+    #[error("Batch operation {index} failed, no changes were applied: {source}")]
+    BatchOperation {
+        /// Zero-based position of the failed operation.
+        index: usize,
+        /// Why the operation failed.
+        #[source]
+        source: Box<Error>,
+    },
+
     /// An error from the rivets storage layer.
     #[error("Storage error: {0}")]
     Storage(#[source] RivetsError),
@@ -89,11 +129,15 @@ impl From<RivetsError> for Error {
     fn from(error: RivetsError) -> Self {
         match error {
             RivetsError::IssueNotFound(issue_id) => Self::IssueNotFound(issue_id.to_string()),
+            RivetsError::Milestone(source) => Self::InvalidMilestone(source),
             RivetsError::Storage(storage_error) => match storage_error.try_into_resource_error() {
                 Ok(source) => Self::InvalidResource(source),
                 Err(storage_error) => match storage_error.try_into_status_transition_error() {
                     Ok(source) => Self::InvalidStatusTransition(source),
-                    Err(storage_error) => Self::Storage(RivetsError::Storage(storage_error)),
+                    Err(storage_error) => match storage_error.try_into_lease_error() {
+                        Ok(source) => Self::InvalidClaim(source),
+                        Err(storage_error) => Self::Storage(RivetsError::Storage(storage_error)),
+                    },
                 },
             },
             error @ (RivetsError::Io(_)
@@ -105,6 +149,7 @@ impl From<RivetsError> for Error {
             | RivetsError::InvalidPriority(_)
             | RivetsError::DependencyNotFound { .. }
             | RivetsError::IssueAlreadyExists(_)
+            | RivetsError::Git(_)
             | RivetsError::Json(_)) => Self::Storage(error),
         }
     }
@@ -113,7 +158,9 @@ impl From<RivetsError> for Error {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rivets::domain::{IssueId, IssueStatus, ResourceError, StatusTransitionError};
+    use rivets::domain::{
+        IssueId, IssueStatus, LeaseError, MilestoneError, ResourceError, StatusTransitionError,
+    };
     use rivets::error::StorageError;
 
     #[test]
@@ -125,6 +172,17 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn core_milestone_error_maps_to_invalid_milestone() {
+        let error = Error::from(RivetsError::Milestone(MilestoneError::NotFound(
+            "sprint-1".to_string(),
+        )));
+        assert!(matches!(
+            error,
+            Error::InvalidMilestone(MilestoneError::NotFound(name)) if name == "sprint-1"
+        ));
+    }
+
     #[test]
     fn storage_resource_error_maps_to_invalid_resource() {
         let error = Error::from(RivetsError::Storage(StorageError::Resource(
@@ -151,6 +209,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn storage_lease_error_maps_to_invalid_claim() {
+        let error = Error::from(RivetsError::Storage(StorageError::Lease(
+            LeaseError::Blocked(IssueId::new("test-1")),
+        )));
+        assert!(matches!(error, Error::InvalidClaim(LeaseError::Blocked(_))));
+    }
+
     #[test]
     fn non_resource_storage_error_remains_a_storage_error() {
         let error = Error::from(RivetsError::Storage(StorageError::InvalidFormat(
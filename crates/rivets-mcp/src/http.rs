@@ -0,0 +1,252 @@
+//! Streamable HTTP transport.
+//!
+//! Over stdio every agent spawns its own server with its own storage cache,
+//! and those servers race each other on the same `issues.jsonl`. In HTTP mode
+//! one long-lived process serves every client: each MCP session gets its own
+//! [`RivetsMcpServer`], but all sessions share a single [`Context`], so a
+//! workspace is loaded once and writes to it are serialized by its storage
+//! lock. Each session picks its own workspace with `set_context`, and
+//! resource subscriptions are shared, so a change made in one session
+//! notifies subscribers in every other.
+//!
+//! The server binds to loopback addresses only unless remote access is
+//! explicitly allowed, and can require an `Authorization: Bearer <token>`
+//! header on every request.
+
+use crate::context::Context;
+use crate::resources::Subscriptions;
+use crate::server::RivetsMcpServer;
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// Path the MCP endpoint is served under.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Options for serving MCP over streamable HTTP.
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Address to listen on.
+    pub addr: SocketAddr,
+    /// Token clients must send as `Authorization: Bearer <token>`, if any.
+    pub bearer_token: Option<String>,
+    /// Allow binding to non-loopback addresses and accept any `Host` header.
+    pub allow_remote: bool,
+    /// Serve only the tools that cannot modify the tracker.
+    pub read_only: bool,
+}
+
+impl HttpOptions {
+    /// Options for a local-only server on `addr` without authentication.
+    #[must_use]
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            bearer_token: None,
+            allow_remote: false,
+            read_only: false,
+        }
+    }
+}
+
+/// Build the HTTP router serving MCP at [`MCP_PATH`].
+///
+/// Every session shares `context` and one subscription registry. Cancelling `shutdown` closes open
+/// sessions so a graceful shutdown does not wait on their event streams.
+pub fn router(
+    context: Arc<RwLock<Context>>,
+    options: &HttpOptions,
+    shutdown: CancellationToken,
+) -> Router {
+    let mut config = StreamableHttpServerConfig::default().with_cancellation_token(shutdown);
+    if options.allow_remote {
+        config = config.disable_allowed_hosts();
+    }
+
+    let read_only = options.read_only;
+    let subscriptions = Arc::new(Subscriptions::default());
+    let service = StreamableHttpService::new(
+        move || {
+            let server =
+                RivetsMcpServer::for_session(Arc::clone(&context), Arc::clone(&subscriptions));
+            Ok(if read_only {
+                server.read_only()
+            } else {
+                server
+            })
+        },
+        Arc::new(LocalSessionManager::default()),
+        config,
+    );
+
+    let router = Router::new().nest_service(MCP_PATH, service);
+    match options.bearer_token.clone() {
+        Some(token) => router.layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            require_bearer_token,
+        )),
+        None => router,
+    }
+}
+
+/// Serve MCP over streamable HTTP until `shutdown` completes.
+///
+/// Every session shares `context`, including its workspace allowlist.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `options.addr` is not a loopback address and
+/// remote access was not allowed, or any error binding or serving the socket.
+pub async fn serve(
+    options: HttpOptions,
+    context: Context,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    if !options.addr.ip().is_loopback() && !options.allow_remote {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "refusing to listen on non-loopback address {}; pass --allow-remote to expose the server",
+                options.addr
+            ),
+        ));
+    }
+    if options.allow_remote && options.bearer_token.is_none() {
+        tracing::warn!("remote access is allowed without a bearer token");
+    }
+
+    let listener = tokio::net::TcpListener::bind(options.addr).await?;
+    tracing::info!(
+        addr = %listener.local_addr()?,
+        path = MCP_PATH,
+        "Serving MCP over streamable HTTP"
+    );
+
+    let sessions = CancellationToken::new();
+    let app = router(Arc::new(RwLock::new(context)), &options, sessions.clone());
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown.await;
+            sessions.cancel();
+        })
+        .await
+}
+
+/// Reject requests without the expected bearer token.
+async fn require_bearer_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match presented {
+        Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "missing or invalid bearer token",
+        )
+            .into_response(),
+    }
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0.0.0"}}}"#;
+
+    fn initialize_request(authorization: Option<&str>) -> Request {
+        let mut builder = Request::post(MCP_PATH)
+            .header(header::HOST, "127.0.0.1")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream");
+        if let Some(authorization) = authorization {
+            builder = builder.header(header::AUTHORIZATION, authorization);
+        }
+        builder.body(Body::from(INITIALIZE)).unwrap()
+    }
+
+    fn test_router(token: Option<&str>) -> Router {
+        let options = HttpOptions {
+            bearer_token: token.map(str::to_string),
+            ..HttpOptions::new("127.0.0.1:0".parse().unwrap())
+        };
+        router(
+            Arc::new(RwLock::new(Context::new())),
+            &options,
+            CancellationToken::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn serves_without_token_by_default() {
+        let response = test_router(None)
+            .oneshot(initialize_request(None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_or_wrong_bearer_token() {
+        for authorization in [None, Some("Bearer wrong"), Some("secret")] {
+            let response = test_router(Some("secret"))
+                .oneshot(initialize_request(authorization))
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::UNAUTHORIZED,
+                "authorization {authorization:?}"
+            );
+        }
+
+        let response = test_router(Some("secret"))
+            .oneshot(initialize_request(Some("Bearer secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn refuses_non_loopback_address_without_opt_in() {
+        let options = HttpOptions::new("0.0.0.0:0".parse().unwrap());
+        let err = serve(options, Context::new(), std::future::pending())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn constant_time_eq_compares_contents_and_length() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+}
@@ -24,10 +24,24 @@
 //! }
 //! ```
 //!
+//! Or serve several clients from one process over streamable HTTP, optionally
+//! requiring a bearer token (see [`http`]):
+//!
+//! ```bash
+//! RIVETS_MCP_TOKEN=secret rivets-mcp --http 127.0.0.1:8377
+//! ```
+//!
+//! `--allow-workspace <DIR>` limits which workspaces clients may open (see
+//! [`context::Context::with_allowed_roots`]), and `--read-only` serves only
+//! the query tools (see [`RivetsMcpServer::read_only`]).
+//!
 //! # Architecture
 //!
 //! The server uses the `rmcp` crate for MCP protocol handling and directly
 //! wraps the `IssueStorage` trait from the rivets crate.
+//! Every tool publishes an output schema generated from the rivets models
+//! (via the rivets `schemars` feature) and returns structured content
+//! alongside its JSON text.
 //!
 //! # Tools
 //!
@@ -40,12 +54,40 @@
 //! - `list` - List issues with filters
 //! - `show` - Show issue details with dependencies
 //! - `blocked` - Get blocked issues with their blockers
+//! - `dep_list` - List an issue's dependencies or dependents
+//! - `dep_tree` - Get an issue's dependency tree
+//! - `stats` - Get issue counts for the workspace
+//! - `changes_since` - List issue changes after a cursor (see [`changes`])
 //!
 //! ## Issue Modification
 //! - `create` - Create a new issue
 //! - `update` - Update issue fields
 //! - `close` - Mark an issue as complete
 //! - `dep` - Add a dependency between issues
+//! - `dep_remove` - Remove a dependency between issues
+//! - `delete` - Delete an issue
+//! - `batch` - Apply several modifications atomically
+//!
+//! ## Work Claims
+//! - `claim` - Claim an issue for an agent with a lease
+//! - `renew` - Extend an agent's claim
+//! - `release` - Give up an agent's claim
+//!
+//! # Resources
+//!
+//! Issues and work queues are also readable as MCP resources, which clients
+//! can subscribe to for `notifications/resources/updated`:
+//! - `rivets://<workspace>/issue/<id>` - a single issue
+//! - `rivets://<workspace>/ready` - issues ready to work on
+//! - `rivets://<workspace>/blocked` - blocked issues with their blockers
+//!
+//! # Prompts
+//!
+//! Prompts pair workflow instructions with the issue data they need:
+//! - `triage_new_issues` - set kind, priority and labels on new issues
+//! - `plan_epic` - break an epic down into child tasks
+//! - `closing_summary` - summarize and close an issue
+//! - `next_ready_work` - pick the next issue from the ready queue
 //!
 //! # Debugging
 //!
@@ -55,9 +97,13 @@
 //! RUST_LOG=debug rivets-mcp
 //! ```
 
+pub mod changes;
 pub mod context;
 pub mod error;
+pub mod http;
 pub mod models;
+pub mod prompts;
+pub mod resources;
 pub mod server;
 pub mod tools;
 
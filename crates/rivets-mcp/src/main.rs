@@ -1,12 +1,45 @@
 //! Rivets MCP server binary.
 //!
-//! This binary runs the MCP server using stdio transport.
+//! This binary runs the MCP server over stdio, or over streamable HTTP with
+//! `--http <ADDR>`.
 
+use clap::Parser;
 use rivets_mcp::RivetsMcpServer;
+use rivets_mcp::context::Context;
+use rivets_mcp::http::{self, HttpOptions};
 use rmcp::ServiceExt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::io::{stdin, stdout};
+use tokio::sync::RwLock;
 use tracing_subscriber::EnvFilter;
 
+/// MCP server for rivets issue tracking.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Serve MCP over streamable HTTP on ADDR (e.g. 127.0.0.1:8377) instead of stdio
+    #[arg(long, value_name = "ADDR")]
+    http: Option<SocketAddr>,
+
+    /// Require `Authorization: Bearer <TOKEN>` on HTTP requests
+    #[arg(long, env = "RIVETS_MCP_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Allow listening on non-loopback addresses
+    #[arg(long, requires = "http")]
+    allow_remote: bool,
+
+    /// Only open workspaces at or below DIR (repeatable; default: any)
+    #[arg(long = "allow-workspace", value_name = "DIR")]
+    allowed_workspaces: Vec<PathBuf>,
+
+    /// Hide every tool that can modify the tracker
+    #[arg(long)]
+    read_only: bool,
+}
+
 const DEFAULT_LOG_FILTER: &str = "error,rivets_mcp=info,rivets=info";
 
 fn env_filter_from(rust_log: Option<&str>) -> EnvFilter {
@@ -32,16 +65,40 @@ fn env_filter() -> EnvFilter {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
     // Initialize tracing to stderr (stdout is used for MCP protocol)
     tracing_subscriber::fmt()
         .with_env_filter(env_filter())
         .with_writer(std::io::stderr)
         .init();
 
-    tracing::info!("Starting rivets-mcp server");
+    tracing::info!(read_only = args.read_only, "Starting rivets-mcp server");
+
+    let context = Context::with_allowed_roots(&args.allowed_workspaces)?;
+
+    if let Some(addr) = args.http {
+        let options = HttpOptions {
+            addr,
+            bearer_token: args.token,
+            allow_remote: args.allow_remote,
+            read_only: args.read_only,
+        };
+        http::serve(options, context, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+        tracing::info!("Rivets MCP server stopped");
+        return Ok(());
+    }
 
     // Create the server
-    let server = RivetsMcpServer::new();
+    let server = RivetsMcpServer::with_context(Arc::new(RwLock::new(context)));
+    let server = if args.read_only {
+        server.read_only()
+    } else {
+        server
+    };
 
     // Serve over stdio transport
     let service = server.serve((stdin(), stdout())).await?;
@@ -69,6 +126,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn args_parse_http_options() {
+        let args = Args::try_parse_from(["rivets-mcp"]).unwrap();
+        assert!(args.http.is_none());
+
+        let args = Args::try_parse_from(["rivets-mcp", "--http", "127.0.0.1:8377", "--token", "t"])
+            .unwrap();
+        assert_eq!(args.http, Some("127.0.0.1:8377".parse().unwrap()));
+        assert_eq!(args.token.as_deref(), Some("t"));
+
+        assert!(Args::try_parse_from(["rivets-mcp", "--allow-remote"]).is_err());
+    }
+
+    #[test]
+    fn args_parse_access_options() {
+        let args = Args::try_parse_from([
+            "rivets-mcp",
+            "--read-only",
+            "--allow-workspace",
+            "/work/a",
+            "--allow-workspace",
+            "/work/b",
+        ])
+        .unwrap();
+        assert!(args.read_only);
+        assert_eq!(
+            args.allowed_workspaces,
+            [PathBuf::from("/work/a"), PathBuf::from("/work/b")]
+        );
+    }
+
     #[test]
     fn empty_rust_log_uses_default_filter() {
         assert_default_directives(&env_filter_from(Some("")));
@@ -1,43 +1,22 @@
 //! MCP models.
 //!
 //! This module contains tool input parameter types and the few response
-//! envelopes that have no domain counterpart (context, stats, blocked
-//! groupings). Domain records (Issue, Note, Resource, Dependency) serialize
-//! directly through their own serde derives per ADR-0004; nothing here
-//! mirrors them.
-
-use rivets::domain::{Issue, IssueKind};
+//! envelopes that have no domain counterpart (context, blocked groupings,
+//! delete and dependency removal acknowledgements, and the object wrappers
+//! structured tool output requires around lists). Domain records (Issue,
+//! Note, Resource, Dependency) serialize directly through their own serde
+//! derives per ADR-0004, and their output schemas come from the rivets
+//! `schemars` feature; nothing here mirrors them.
+
+use rivets::domain::{AssociatedResource, Dependency, Issue, IssueKind};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // ============================================================================
 // Tool Input Parameters
 // ============================================================================
 
-/// Schema-only stand-in for the domain [`IssueKind`] wire vocabulary.
-///
-/// schemars cannot derive `JsonSchema` for the domain type from another
-/// crate (orphan rule), and the rivets crate must not gain a schemars
-/// dependency, so this local type renders the `issue_kind` enum values in
-/// tool schemas. It performs no parsing; its variant list is a fenced
-/// duplicate of the domain vocabulary — the schema fence test
-/// (`issue_kind_schema_matches_domain_display`) pins its values to the
-/// domain enum's Display strings so a new Kind cannot silently drift.
-#[derive(JsonSchema)]
-#[serde(rename_all = "lowercase")]
-pub enum McpIssueKindSchema {
-    /// Bug fix.
-    Bug,
-    /// New feature.
-    Feature,
-    /// General task.
-    Task,
-    /// Epic grouping.
-    Epic,
-    /// Maintenance chore.
-    Chore,
-}
-
 /// Canonical and migration-only names for an MCP Issue Kind input.
 ///
 /// `issue_type` remains accepted for compatibility but is omitted from the
@@ -46,7 +25,6 @@ pub enum McpIssueKindSchema {
 pub struct IssueKindInput {
     /// Canonical Issue Kind.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    #[schemars(with = "Option<McpIssueKindSchema>")]
     pub issue_kind: Option<IssueKind>,
 
     #[serde(default, rename = "issue_type", skip_serializing)]
@@ -109,6 +87,62 @@ pub struct ReadyParams {
     /// Filter by label.
     pub label: Option<String>,
 
+    /// Only return issues planned for this milestone.
+    pub milestone: Option<String>,
+
+    /// Only return issues planned for the current milestone (the open
+    /// milestone covering today). Cannot be combined with `milestone`.
+    pub current_milestone: Option<bool>,
+
+    /// Agent identity. Issues claimed by other agents are hidden either way;
+    /// with an agent, that agent's own claims are included.
+    pub agent: Option<String>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// Parameters for the `claim` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClaimParams {
+    /// The issue ID to claim. Claims the next ready issue if omitted.
+    pub issue_id: Option<String>,
+
+    /// Agent identity to claim the issue for.
+    pub agent: String,
+
+    /// Lease length, e.g. "30m", "2h" or "1d" (default: 1h).
+    pub lease: Option<String>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// Parameters for the `renew` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RenewParams {
+    /// The claimed issue ID.
+    pub issue_id: String,
+
+    /// Agent identity holding the claim.
+    pub agent: String,
+
+    /// New lease length from now, e.g. "30m", "2h" or "1d" (default: 1h).
+    pub lease: Option<String>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// Parameters for the `release` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReleaseParams {
+    /// The claimed issue ID.
+    pub issue_id: String,
+
+    /// Agent identity holding the claim.
+    pub agent: String,
+
     /// Optional workspace root (uses current context if not specified).
     pub workspace_root: Option<String>,
 }
@@ -132,6 +166,15 @@ pub struct ListParams {
     /// Filter by label.
     pub label: Option<String>,
 
+    /// Filter by custom field values; all must match.
+    pub custom_fields: Option<BTreeMap<String, serde_json::Value>>,
+
+    /// Only return issues past their due date that are not closed.
+    pub overdue: Option<bool>,
+
+    /// Filter by milestone.
+    pub milestone: Option<String>,
+
     /// Maximum number of issues to return.
     pub limit: Option<usize>,
 
@@ -187,6 +230,19 @@ pub struct CreateParams {
     /// Initial Note.
     pub initial_note: Option<String>,
 
+    /// Issue template from `.rivets/templates/<name>.yaml`. Without it, the
+    /// template named after the Issue Kind is applied if present.
+    pub template: Option<String>,
+
+    /// Custom field values, keyed by field name declared in the workspace config.
+    pub custom_fields: Option<BTreeMap<String, serde_json::Value>>,
+
+    /// Due date: `YYYY-MM-DD` (end of day UTC) or an RFC 3339 timestamp.
+    pub due_at: Option<String>,
+
+    /// Milestone to plan the issue for (must exist).
+    pub milestone: Option<String>,
+
     /// Optional workspace root (uses current context if not specified).
     pub workspace_root: Option<String>,
 }
@@ -225,6 +281,16 @@ pub struct UpdateParams {
     /// New labels (replaces existing labels).
     pub labels: Option<Vec<String>>,
 
+    /// Custom field values to set; `null` clears a field.
+    pub custom_fields: Option<BTreeMap<String, serde_json::Value>>,
+
+    /// New due date (`YYYY-MM-DD` or RFC 3339); an empty string clears it.
+    pub due_at: Option<String>,
+
+    /// New milestone (must exist); an empty string removes the issue from
+    /// its milestone.
+    pub milestone: Option<String>,
+
     /// Optional workspace root (uses current context if not specified).
     pub workspace_root: Option<String>,
 }
@@ -349,6 +415,45 @@ pub struct DepParams {
     pub workspace_root: Option<String>,
 }
 
+/// Parameters for the `dep_remove` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DepRemoveParams {
+    /// The issue that has the dependency.
+    pub issue_id: String,
+
+    /// The issue that is depended on.
+    pub depends_on_id: String,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// Parameters for the `dep_list` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DepListParams {
+    /// The issue ID to list dependencies for.
+    pub issue_id: String,
+
+    /// List dependents (issues that depend on this one) instead of dependencies.
+    pub reverse: Option<bool>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// Parameters for the `dep_tree` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DepTreeParams {
+    /// The root issue ID of the tree.
+    pub issue_id: String,
+
+    /// Maximum depth to traverse (default: 5, 0 for unlimited).
+    pub depth: Option<usize>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
 /// Parameters for the `reopen` tool.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ReopenParams {
@@ -362,10 +467,21 @@ pub struct ReopenParams {
     pub workspace_root: Option<String>,
 }
 
+/// Parameters for the `delete` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteParams {
+    /// The issue ID to delete.
+    pub issue_id: String,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
 /// Parameters for the `stale` tool.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct StaleParams {
     /// Number of days since last update to consider stale (default: 30).
+    /// Issues whose claim expired are stale regardless.
     pub days: Option<u32>,
 
     /// Filter by status.
@@ -378,6 +494,29 @@ pub struct StaleParams {
     pub workspace_root: Option<String>,
 }
 
+/// Parameters for the `stats` tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatsParams {
+    /// Include a breakdown by priority.
+    pub detailed: Option<bool>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// Parameters for the `changes_since` tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ChangesSinceParams {
+    /// Cursor returned by the previous call; omit to get every retained change.
+    pub cursor: Option<u64>,
+
+    /// Maximum number of changes to return (default: 100).
+    pub limit: Option<usize>,
+
+    /// Optional workspace root (uses current context if not specified).
+    pub workspace_root: Option<String>,
+}
+
 /// Parameters for the `label_add` tool.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LabelAddParams {
@@ -421,6 +560,55 @@ pub struct LabelListAllParams {
     pub workspace_root: Option<String>,
 }
 
+/// Parameters for the `batch` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchParams {
+    /// Operations to apply in order. All succeed and are saved together, or
+    /// none are applied.
+    pub operations: Vec<BatchOperation>,
+
+    /// Optional workspace root for every operation (uses current context if
+    /// not specified).
+    pub workspace_root: Option<String>,
+}
+
+/// One operation in a `batch` call, selected by its `op` field.
+///
+/// Issue ID fields accept `@<handle>` to refer to an issue created by an
+/// earlier `create` operation in the same batch. Operations take their
+/// workspace from the batch and must not set `workspace_root` themselves.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    /// Create an issue, with the `create` tool's parameters.
+    Create {
+        /// Name later operations use to refer to the new issue as `@<handle>`.
+        handle: Option<String>,
+
+        /// The `create` tool's parameters.
+        #[serde(flatten)]
+        params: CreateParams,
+    },
+
+    /// Update an issue, with the `update` tool's parameters.
+    Update(UpdateParams),
+
+    /// Add a dependency, with the `dep` tool's parameters.
+    Dep(DepParams),
+
+    /// Add a label, with the `label_add` tool's parameters.
+    LabelAdd(LabelAddParams),
+
+    /// Remove a label, with the `label_remove` tool's parameters.
+    LabelRemove(LabelRemoveParams),
+
+    /// Associate a resource, with the `resource_add` tool's parameters.
+    ResourceAdd(ResourceAddParams),
+
+    /// Append a note, with the `add_note` tool's parameters.
+    Note(AddNoteParams),
+}
+
 // ============================================================================
 // Tool Output Responses
 // ============================================================================
@@ -455,7 +643,7 @@ pub struct WhereAmIResponse {
 }
 
 /// Blocked issue response.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct BlockedIssueResponse {
     /// The blocked issue.
     pub issue: Issue,
@@ -464,26 +652,153 @@ pub struct BlockedIssueResponse {
     pub blockers: Vec<Issue>,
 }
 
-/// Statistics response.
+/// Response from the `delete` tool, matching `rivets delete --json`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteResponse {
+    /// The deleted issue ID.
+    pub deleted: String,
+
+    /// Always `success`.
+    pub status: String,
+}
+
+/// Response from the `dep_remove` tool, matching `rivets dep remove --json`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DepRemoveResponse {
+    /// Always `remove`.
+    pub action: String,
+
+    /// The issue that had the dependency.
+    pub from: String,
+
+    /// The issue that was depended on.
+    pub to: String,
+
+    /// Always `success`.
+    pub status: String,
+}
+
+/// Response from the `batch` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BatchResponse {
+    /// IDs of the issues created under a handle, keyed by handle.
+    pub handles: BTreeMap<String, String>,
+
+    /// Every issue the batch created or changed, in the order first touched.
+    pub issues: Vec<Issue>,
+}
+
+// ============================================================================
+// Structured Tool Output
+// ============================================================================
+//
+// MCP requires structured tool output to be a JSON object. Tools returning a
+// list or a bare value keep that shape in their text content for existing
+// clients and publish it wrapped in one of these objects as structured
+// content.
+
+/// Structured output of the `ready`, `list` and `stale` tools.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct IssueListResponse {
+    /// The matching issues.
+    pub issues: Vec<Issue>,
+}
+
+/// Structured output of the `blocked` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BlockedListResponse {
+    /// Blocked issues with their blockers.
+    pub blocked: Vec<BlockedIssueResponse>,
+}
+
+/// Structured output of the `claim` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ClaimResponse {
+    /// The claimed issue, or `null` when nothing was claimable.
+    pub issue: Option<Issue>,
+}
+
+/// Structured output of the `resource_list` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ResourceListResponse {
+    /// The issue's resources.
+    pub resources: Vec<AssociatedResource>,
+}
+
+/// Structured output of the `dep` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DepResponse {
+    /// Confirmation of the added dependency.
+    pub message: String,
+}
+
+/// Structured output of the `dep_list` tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DependencyListResponse {
+    /// The listed dependencies.
+    pub dependencies: Vec<Dependency>,
+}
+
+/// Structured output of the `label_list` and `label_list_all` tools.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LabelListResponse {
+    /// The labels, sorted.
+    pub labels: Vec<String>,
+}
+
+/// Output schema of the `dep_tree` tool, matching `rivets dep tree --json`.
+///
+/// The tree JSON is built by the rivets output layer; this type documents
+/// its shape for clients.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct StatsResponse {
-    /// Total number of issues.
-    pub total: usize,
+#[serde(deny_unknown_fields)]
+pub struct DepTreeResponse {
+    /// The root issue ID.
+    pub id: String,
 
-    /// Number of open issues.
-    pub open: usize,
+    /// The root issue's title.
+    pub title: String,
+
+    /// The root issue's priority.
+    pub priority: u8,
 
-    /// Number of in-progress issues.
-    pub in_progress: usize,
+    /// The root issue's status.
+    pub status: String,
 
-    /// Number of blocked issues.
-    pub blocked: usize,
+    /// The issues the root depends on, transitively.
+    pub dependencies: Vec<DepTreeNodeResponse>,
 
-    /// Number of closed issues.
-    pub closed: usize,
+    /// The issues that depend on the root.
+    pub dependents: Vec<DepTreeDependentResponse>,
+}
+
+/// A dependency in a [`DepTreeResponse`].
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DepTreeNodeResponse {
+    /// The issue ID.
+    pub id: String,
+
+    /// How the parent depends on this issue.
+    pub dep_type: String,
+
+    /// The issue status, absent if the issue no longer exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// The issues this one depends on.
+    pub dependencies: Vec<DepTreeNodeResponse>,
+}
+
+/// An issue depending on the root of a [`DepTreeResponse`].
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DepTreeDependentResponse {
+    /// The dependent issue ID.
+    pub depends_on_id: String,
 
-    /// Number of ready-to-work issues.
-    pub ready: usize,
+    /// The dependency type.
+    pub dep_type: String,
 }
 
 #[cfg(test)]
@@ -578,9 +893,8 @@ mod tests {
 
     #[test]
     fn issue_kind_schema_matches_domain_display() {
-        // Fence: the schema mirror's enum values must equal the domain
-        // enum's Display strings, so a new Kind cannot silently drift from
-        // the tool schema.
+        // Fence: the published enum values must equal the domain enum's
+        // Display strings, so the schema and the parser cannot drift.
         use schemars::schema_for;
 
         let schema = schema_for!(IssueKindInput);
@@ -0,0 +1,412 @@
+//! MCP prompts for common agent workflows.
+//!
+//! Each prompt renders a single user message that pairs instructions for
+//! the workflow with the issue data it needs, fetched through the same
+//! [`Tools`] methods the `ready`, `show`, `list` and `blocked` tools use.
+//! Keeping the instructions here means every agent gets the same workflow
+//! and it evolves with the data model it describes.
+
+use crate::error::{Error, Result};
+use crate::models::{ListParams, ReadyParams};
+use crate::tools::Tools;
+use chrono::{Duration, Utc};
+use rivets::domain::{DependencyType, Issue, IssueKind};
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Days back `triage_new_issues` looks for new issues by default.
+const DEFAULT_TRIAGE_DAYS: i64 = 7;
+
+/// Names of all prompts, as listed in error messages.
+const PROMPT_NAMES: &str = "triage_new_issues, plan_epic, closing_summary, next_ready_work";
+
+/// The prompts offered by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// Triage recently created open issues.
+    TriageNewIssues,
+    /// Break an epic down into child tasks.
+    PlanEpic,
+    /// Write a closing summary for an issue.
+    ClosingSummary,
+    /// Pick the next issue to work on.
+    NextReadyWork,
+}
+
+impl PromptKind {
+    /// Every prompt, in listing order.
+    pub const ALL: [Self; 4] = [
+        Self::TriageNewIssues,
+        Self::PlanEpic,
+        Self::ClosingSummary,
+        Self::NextReadyWork,
+    ];
+
+    /// The prompt name clients request.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::TriageNewIssues => "triage_new_issues",
+            Self::PlanEpic => "plan_epic",
+            Self::ClosingSummary => "closing_summary",
+            Self::NextReadyWork => "next_ready_work",
+        }
+    }
+
+    /// Look up a prompt by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if no prompt has that name.
+    pub fn from_name(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| Error::InvalidArgument {
+                field: "prompt",
+                value: name.to_string(),
+                valid_values: PROMPT_NAMES,
+            })
+    }
+
+    /// The prompt's listing entry.
+    #[must_use]
+    pub fn definition(self) -> Prompt {
+        let workspace_root = PromptArgument::new("workspace_root")
+            .with_description("Workspace root (uses current context if not specified)")
+            .with_required(false);
+        let (title, description, arguments) = match self {
+            Self::TriageNewIssues => (
+                "Triage new issues",
+                "Review recently created open issues and set their kind, priority, labels and dependencies",
+                vec![
+                    PromptArgument::new("days")
+                        .with_description("How many days back to look for new issues (default 7)")
+                        .with_required(false),
+                    workspace_root,
+                ],
+            ),
+            Self::PlanEpic => (
+                "Plan an epic",
+                "Break an epic down into child tasks linked with parent-child dependencies",
+                vec![
+                    PromptArgument::new("epic_id")
+                        .with_description("ID of the epic to plan")
+                        .with_required(true),
+                    workspace_root,
+                ],
+            ),
+            Self::ClosingSummary => (
+                "Write a closing summary",
+                "Summarize the work done on an issue and close it with that summary",
+                vec![
+                    PromptArgument::new("issue_id")
+                        .with_description("ID of the issue to summarize")
+                        .with_required(true),
+                    workspace_root,
+                ],
+            ),
+            Self::NextReadyWork => (
+                "Pick next ready work",
+                "Choose the next issue to work on from the ready queue",
+                vec![
+                    PromptArgument::new("assignee")
+                        .with_description("Only consider ready issues assigned to this person")
+                        .with_required(false),
+                    workspace_root,
+                ],
+            ),
+        };
+        Prompt::new(self.name(), Some(description), Some(arguments)).with_title(title)
+    }
+}
+
+/// Listing entries for every prompt.
+#[must_use]
+pub fn prompt_definitions() -> Vec<Prompt> {
+    PromptKind::ALL
+        .into_iter()
+        .map(PromptKind::definition)
+        .collect()
+}
+
+/// Render the prompt called `name` with the given arguments.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] for an unknown prompt or a missing or
+/// malformed argument, and any error from the underlying tool calls.
+pub async fn render_prompt(
+    tools: &Tools,
+    name: &str,
+    arguments: Option<&JsonObject>,
+) -> Result<GetPromptResult> {
+    let kind = PromptKind::from_name(name)?;
+    let arguments = PromptArguments(arguments);
+    let workspace_root = arguments.string("workspace_root");
+    let workspace_root = workspace_root.as_deref();
+
+    let text = match kind {
+        PromptKind::TriageNewIssues => {
+            let days = arguments.days()?;
+            let since = Utc::now() - Duration::days(days);
+            let mut issues: Vec<Issue> = tools
+                .list(ListParams {
+                    status: Some("open".to_string()),
+                    limit: Some(usize::MAX),
+                    workspace_root: workspace_root.map(str::to_string),
+                    ..Default::default()
+                })
+                .await?
+                .into_iter()
+                .filter(|issue| issue.created_at >= since)
+                .collect();
+            issues.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            triage_text(days, &issues)?
+        }
+        PromptKind::PlanEpic => {
+            let epic_id = arguments.required("epic_id", "the ID of an epic")?;
+            let epic = tools.show(&epic_id, workspace_root).await?;
+            if epic.issue_kind != IssueKind::Epic {
+                return Err(Error::InvalidArgument {
+                    field: "epic_id",
+                    value: epic_id,
+                    valid_values: "the ID of an issue whose kind is epic",
+                });
+            }
+            let children: Vec<Issue> = tools
+                .list(ListParams {
+                    limit: Some(usize::MAX),
+                    workspace_root: workspace_root.map(str::to_string),
+                    ..Default::default()
+                })
+                .await?
+                .into_iter()
+                .filter(|issue| {
+                    issue.dependencies.iter().any(|dep| {
+                        dep.dep_type == DependencyType::ParentChild && dep.depends_on_id == epic.id
+                    })
+                })
+                .collect();
+            plan_epic_text(&epic, &children)?
+        }
+        PromptKind::ClosingSummary => {
+            let issue_id = arguments.required("issue_id", "the ID of an issue")?;
+            let issue = tools.show(&issue_id, workspace_root).await?;
+            closing_summary_text(&issue)?
+        }
+        PromptKind::NextReadyWork => {
+            let assignee = arguments.string("assignee");
+            let ready = tools
+                .ready(ReadyParams {
+                    assignee: assignee.clone(),
+                    workspace_root: workspace_root.map(str::to_string),
+                    ..Default::default()
+                })
+                .await?;
+            let blocked = tools.blocked(workspace_root).await?;
+            next_ready_work_text(assignee.as_deref(), &ready, &blocked)?
+        }
+    };
+
+    let description = kind.definition().description.unwrap_or_default();
+    Ok(
+        GetPromptResult::new(vec![PromptMessage::new_text(PromptMessageRole::User, text)])
+            .with_description(description),
+    )
+}
+
+/// Prompt arguments as sent by the client.
+///
+/// MCP sends prompt arguments as strings; numbers are accepted too for
+/// clients that pass JSON values through unchanged.
+struct PromptArguments<'a>(Option<&'a JsonObject>);
+
+impl PromptArguments<'_> {
+    fn string(&self, name: &str) -> Option<String> {
+        match self.0?.get(name)? {
+            serde_json::Value::String(value) if !value.trim().is_empty() => Some(value.clone()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    fn required(&self, field: &'static str, valid_values: &'static str) -> Result<String> {
+        self.string(field).ok_or(Error::InvalidArgument {
+            field,
+            value: String::new(),
+            valid_values,
+        })
+    }
+
+    fn days(&self) -> Result<i64> {
+        let Some(days) = self.string("days") else {
+            return Ok(DEFAULT_TRIAGE_DAYS);
+        };
+        days.trim()
+            .parse::<u16>()
+            .map(i64::from)
+            .map_err(|_| Error::InvalidArgument {
+                field: "days",
+                value: days,
+                valid_values: "a whole number of days",
+            })
+    }
+}
+
+/// Render `value` as a fenced JSON block.
+fn json_block<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    Ok(format!(
+        "```json\n{}\n```\n",
+        serde_json::to_string_pretty(value)?
+    ))
+}
+
+fn triage_text(days: i64, issues: &[Issue]) -> Result<String> {
+    let mut text = format!(
+        "Triage the open issues created in the last {days} days.\n\n\
+         For each issue:\n\
+         1. Check the title and description are clear enough to act on; add a Note with `add_note` asking for what is missing.\n\
+         2. Set an accurate issue_kind and priority (0 = critical, 4 = backlog) with `update`.\n\
+         3. Add labels with `label_add`, reusing labels from `label_list_all` where they fit.\n\
+         4. Link related, blocking or duplicate work with `dep`.\n\n\
+         Finish with a short table of each issue and what you changed.\n\n"
+    );
+    if issues.is_empty() {
+        text.push_str("There are no new open issues to triage.\n");
+    } else {
+        let _ = writeln!(text, "New open issues ({}):\n", issues.len());
+        text.push_str(&json_block(issues)?);
+    }
+    Ok(text)
+}
+
+fn plan_epic_text(epic: &Issue, children: &[Issue]) -> Result<String> {
+    let mut text = format!(
+        "Plan epic {} into child tasks.\n\n\
+         1. Read the epic's description, design and acceptance criteria below.\n\
+         2. Propose a list of child tasks, each small enough to finish in one sitting, with a title, description and acceptance criteria. Do not duplicate existing children.\n\
+         3. Create each task with `create`, then link it to the epic with `dep` (issue_id: the task, depends_on_id: {}, dep_type: parent-child).\n\
+         4. Where one task must finish before another can start, link them with `dep` using the `blocks` type.\n\n\
+         Epic:\n\n",
+        epic.id, epic.id
+    );
+    text.push_str(&json_block(epic)?);
+    if children.is_empty() {
+        text.push_str("\nThe epic has no child issues yet.\n");
+    } else {
+        let _ = writeln!(text, "\nExisting children ({}):\n", children.len());
+        text.push_str(&json_block(children)?);
+    }
+    Ok(text)
+}
+
+fn closing_summary_text(issue: &Issue) -> Result<String> {
+    let mut text = format!(
+        "Write a closing summary for issue {}.\n\n\
+         The summary should say in a few sentences what was done, how it meets the acceptance criteria, and any follow-up work left behind. Use the Notes and Associated Resources below as the record of the work.\n\n\
+         Then close the issue with `close`, passing the summary as the reason. If follow-up work remains, create it with `create` and link it to this issue with a `discovered-from` dependency first.\n\n\
+         Issue:\n\n",
+        issue.id
+    );
+    text.push_str(&json_block(issue)?);
+    Ok(text)
+}
+
+fn next_ready_work_text(
+    assignee: Option<&str>,
+    ready: &[Issue],
+    blocked: &[crate::models::BlockedIssueResponse],
+) -> Result<String> {
+    let scope = assignee.map_or_else(String::new, |assignee| format!(" assigned to {assignee}"));
+    let mut text = format!(
+        "Pick the next issue to work on from the ready issues{scope} below.\n\n\
+         Prefer the lowest priority number, then work that unblocks the blocked issues listed after it, then the oldest issue. \
+         Explain your choice in one or two sentences, then mark it in progress with `update` (status: in_progress) and read it in full with `show`.\n\n"
+    );
+    if ready.is_empty() {
+        text.push_str(
+            "No issues are ready. Suggest which blocker to resolve first to free up work.\n",
+        );
+    } else {
+        let _ = writeln!(text, "Ready issues ({}):\n", ready.len());
+        text.push_str(&json_block(ready)?);
+    }
+    if !blocked.is_empty() {
+        let _ = writeln!(text, "\nBlocked issues ({}):\n", blocked.len());
+        text.push_str(&json_block(blocked)?);
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value as JsonValue, json};
+
+    #[test]
+    fn every_prompt_is_listed_and_found_by_name() {
+        let names: Vec<String> = prompt_definitions()
+            .into_iter()
+            .map(|prompt| prompt.name)
+            .collect();
+        assert_eq!(names.join(", "), PROMPT_NAMES);
+        for kind in PromptKind::ALL {
+            assert_eq!(PromptKind::from_name(kind.name()).unwrap(), kind);
+        }
+        assert!(matches!(
+            PromptKind::from_name("summarize"),
+            Err(Error::InvalidArgument {
+                field: "prompt",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn required_arguments_are_marked() {
+        let required = |kind: PromptKind| -> Vec<String> {
+            kind.definition()
+                .arguments
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|argument| argument.required == Some(true))
+                .map(|argument| argument.name)
+                .collect()
+        };
+        assert!(required(PromptKind::TriageNewIssues).is_empty());
+        assert_eq!(required(PromptKind::PlanEpic), vec!["epic_id"]);
+        assert_eq!(required(PromptKind::ClosingSummary), vec!["issue_id"]);
+        assert!(required(PromptKind::NextReadyWork).is_empty());
+    }
+
+    #[test]
+    fn arguments_accept_strings_and_numbers() {
+        let JsonValue::Object(object) = json!({ "days": 3, "issue_id": "  ", "assignee": "alice" })
+        else {
+            unreachable!()
+        };
+        let arguments = PromptArguments(Some(&object));
+        assert_eq!(arguments.days().unwrap(), 3);
+        assert_eq!(arguments.string("assignee").as_deref(), Some("alice"));
+        assert!(matches!(
+            arguments.required("issue_id", "an issue ID"),
+            Err(Error::InvalidArgument {
+                field: "issue_id",
+                ..
+            })
+        ));
+        assert_eq!(PromptArguments(None).days().unwrap(), DEFAULT_TRIAGE_DAYS);
+
+        let JsonValue::Object(object) = json!({ "days": "-1" }) else {
+            unreachable!()
+        };
+        assert!(matches!(
+            PromptArguments(Some(&object)).days(),
+            Err(Error::InvalidArgument { field: "days", .. })
+        ));
+    }
+}
@@ -0,0 +1,502 @@
+//! MCP resources exposing issues and work queues.
+//!
+//! Each workspace publishes three kinds of resource, addressed by the
+//! workspace's canonical root path:
+//!
+//! - `rivets://<workspace>/issue/<id>` - a single issue
+//! - `rivets://<workspace>/ready` - issues ready to work on
+//! - `rivets://<workspace>/blocked` - blocked issues and their blockers
+//!
+//! Resource contents are the same JSON the `show`, `ready` and `blocked`
+//! tools return. Clients may subscribe to any of these URIs; after a tool
+//! changes an issue, [`Subscriptions::notify`] sends
+//! `notifications/resources/updated` to every session subscribed to a URI
+//! the change may have affected.
+//!
+//! `resources/list` returns at most [`RESOURCE_PAGE_SIZE`] resources per
+//! page, with a cursor for the next one.
+
+use crate::error::{Error, Result};
+use rivets::domain::Issue;
+use rmcp::model::{
+    AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceTemplate,
+    ResourceUpdatedNotificationParam,
+};
+use rmcp::{Peer, RoleServer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::debug;
+
+/// URI scheme of rivets resources.
+pub const RESOURCE_SCHEME: &str = "rivets://";
+
+/// MIME type of every rivets resource.
+pub const RESOURCE_MIME_TYPE: &str = "application/json";
+
+/// Maximum number of resources in one `resources/list` page.
+pub const RESOURCE_PAGE_SIZE: usize = 100;
+
+/// A parsed rivets resource URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    /// A single issue.
+    Issue {
+        /// Workspace root the issue belongs to.
+        workspace: PathBuf,
+        /// The issue identifier.
+        id: String,
+    },
+    /// Issues ready to work on.
+    Ready {
+        /// Workspace root.
+        workspace: PathBuf,
+    },
+    /// Blocked issues and their blockers.
+    Blocked {
+        /// Workspace root.
+        workspace: PathBuf,
+    },
+}
+
+impl ResourceUri {
+    /// Parse a `rivets://` URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownResourceUri`] if the URI does not use the
+    /// rivets scheme, does not name an absolute workspace path, or does not
+    /// end in `/ready`, `/blocked` or `/issue/<id>`.
+    pub fn parse(uri: &str) -> Result<Self> {
+        let unknown = || Error::UnknownResourceUri(uri.to_string());
+        let rest = uri.strip_prefix(RESOURCE_SCHEME).ok_or_else(unknown)?;
+
+        let parsed = if let Some(workspace) = rest.strip_suffix("/ready") {
+            Self::Ready {
+                workspace: PathBuf::from(workspace),
+            }
+        } else if let Some(workspace) = rest.strip_suffix("/blocked") {
+            Self::Blocked {
+                workspace: PathBuf::from(workspace),
+            }
+        } else {
+            let (workspace, id) = rest.rsplit_once("/issue/").ok_or_else(unknown)?;
+            if id.is_empty() || id.contains('/') {
+                return Err(unknown());
+            }
+            Self::Issue {
+                workspace: PathBuf::from(workspace),
+                id: id.to_string(),
+            }
+        };
+
+        if parsed.workspace().is_absolute() {
+            Ok(parsed)
+        } else {
+            Err(unknown())
+        }
+    }
+
+    /// The workspace root this resource belongs to.
+    #[must_use]
+    pub fn workspace(&self) -> &Path {
+        match self {
+            Self::Issue { workspace, .. }
+            | Self::Ready { workspace }
+            | Self::Blocked { workspace } => workspace,
+        }
+    }
+
+    /// The same resource with its workspace root replaced.
+    ///
+    /// Used to canonicalize the workspace so that equivalent URIs compare
+    /// equal.
+    #[must_use]
+    pub fn with_workspace(self, workspace: PathBuf) -> Self {
+        match self {
+            Self::Issue { id, .. } => Self::Issue { workspace, id },
+            Self::Ready { .. } => Self::Ready { workspace },
+            Self::Blocked { .. } => Self::Blocked { workspace },
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let workspace = self.workspace().display();
+        match self {
+            Self::Issue { id, .. } => write!(f, "{RESOURCE_SCHEME}{workspace}/issue/{id}"),
+            Self::Ready { .. } => write!(f, "{RESOURCE_SCHEME}{workspace}/ready"),
+            Self::Blocked { .. } => write!(f, "{RESOURCE_SCHEME}{workspace}/blocked"),
+        }
+    }
+}
+
+/// Resources listed for a workspace: its ready and blocked views, followed
+/// by one resource per issue.
+#[must_use]
+pub fn workspace_resources(workspace: &Path, issues: &[Issue]) -> Vec<Resource> {
+    let view = |uri: ResourceUri, name: &str, title: &str, description: &str| {
+        RawResource::new(uri.to_string(), name)
+            .with_title(title)
+            .with_description(description)
+            .with_mime_type(RESOURCE_MIME_TYPE)
+            .no_annotation()
+    };
+
+    let mut resources = vec![
+        view(
+            ResourceUri::Ready {
+                workspace: workspace.to_path_buf(),
+            },
+            "ready",
+            "Ready issues",
+            "Issues with no blockers that are ready to be worked on",
+        ),
+        view(
+            ResourceUri::Blocked {
+                workspace: workspace.to_path_buf(),
+            },
+            "blocked",
+            "Blocked issues",
+            "Blocked issues and the dependencies blocking them",
+        ),
+    ];
+    resources.extend(issues.iter().map(|issue| {
+        let uri = ResourceUri::Issue {
+            workspace: workspace.to_path_buf(),
+            id: issue.id.to_string(),
+        };
+        RawResource::new(uri.to_string(), issue.id.to_string())
+            .with_title(issue.title.clone())
+            .with_mime_type(RESOURCE_MIME_TYPE)
+            .no_annotation()
+    }));
+    resources
+}
+
+/// Templates for addressing any workspace's resources.
+#[must_use]
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    let template = |uri_template: &str, name: &str, description: &str| {
+        RawResourceTemplate::new(uri_template, name)
+            .with_description(description)
+            .with_mime_type(RESOURCE_MIME_TYPE)
+            .no_annotation()
+    };
+
+    vec![
+        template(
+            "rivets://{+workspace}/issue/{id}",
+            "issue",
+            "A single issue, by absolute workspace root and issue ID",
+        ),
+        template(
+            "rivets://{+workspace}/ready",
+            "ready",
+            "Issues ready to work on in a workspace",
+        ),
+        template(
+            "rivets://{+workspace}/blocked",
+            "blocked",
+            "Blocked issues and their blockers in a workspace",
+        ),
+    ]
+}
+
+/// The page of `items` starting at `cursor`, at most `page_size` long, and
+/// the cursor of the page after it.
+///
+/// Cursors are opaque to clients; they hold the offset of the page's first
+/// item.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if `cursor` was not returned by an
+/// earlier page.
+pub fn page<T>(
+    items: Vec<T>,
+    cursor: Option<&str>,
+    page_size: usize,
+) -> Result<(Vec<T>, Option<String>)> {
+    let start = match cursor {
+        None => 0,
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .ok()
+            .filter(|start| *start <= items.len())
+            .ok_or_else(|| Error::InvalidArgument {
+                field: "cursor",
+                value: cursor.to_string(),
+                valid_values: "a nextCursor returned by resources/list",
+            })?,
+    };
+    let end = start.saturating_add(page_size).min(items.len());
+    let next_cursor = (end < items.len()).then(|| end.to_string());
+    Ok((
+        items.into_iter().skip(start).take(end - start).collect(),
+        next_cursor,
+    ))
+}
+
+/// Identifies one client session's subscriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SessionId(u64);
+
+/// Resource subscriptions and the sessions to notify about them.
+#[derive(Default)]
+pub struct Subscriptions {
+    next_session: AtomicU64,
+    state: Mutex<SubscriptionState>,
+}
+
+#[derive(Default)]
+struct SubscriptionState {
+    /// Sessions subscribed to each canonical resource URI.
+    uris: BTreeMap<String, BTreeSet<SessionId>>,
+    /// Peer reaching each session with a subscription.
+    peers: BTreeMap<SessionId, Peer<RoleServer>>,
+}
+
+impl SubscriptionState {
+    fn remove_session(&mut self, session: SessionId) {
+        self.peers.remove(&session);
+        self.uris.retain(|_, sessions| {
+            sessions.remove(&session);
+            !sessions.is_empty()
+        });
+    }
+}
+
+impl Subscriptions {
+    /// Allocate the ID a new session subscribes under.
+    pub fn new_session(&self) -> SessionId {
+        SessionId(self.next_session.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Subscribe `session`, reachable through `peer`, to `uri`.
+    pub fn subscribe(&self, uri: &ResourceUri, session: SessionId, peer: Peer<RoleServer>) {
+        let mut state = self.lock();
+        state
+            .uris
+            .entry(uri.to_string())
+            .or_default()
+            .insert(session);
+        state.peers.insert(session, peer);
+    }
+
+    /// Drop `session`'s subscription to `uri`, returning whether one existed.
+    pub fn unsubscribe(&self, uri: &str, session: SessionId) -> bool {
+        let mut state = self.lock();
+        let Some(sessions) = state.uris.get_mut(uri) else {
+            return false;
+        };
+        let removed = sessions.remove(&session);
+        if sessions.is_empty() {
+            state.uris.remove(uri);
+        }
+        if !state
+            .uris
+            .values()
+            .any(|sessions| sessions.contains(&session))
+        {
+            state.peers.remove(&session);
+        }
+        removed
+    }
+
+    /// Drop every subscription `session` holds.
+    pub fn remove_session(&self, session: SessionId) {
+        self.lock().remove_session(session);
+    }
+
+    /// Subscribed URIs a change to `issue_ids` in `workspace` may affect.
+    ///
+    /// Any issue change can move issues in or out of the ready and blocked
+    /// views, so those are always included when subscribed.
+    #[must_use]
+    pub fn affected(&self, workspace: &Path, issue_ids: &[&str]) -> Vec<String> {
+        let workspace = workspace.to_path_buf();
+        let candidates = [
+            ResourceUri::Ready {
+                workspace: workspace.clone(),
+            },
+            ResourceUri::Blocked {
+                workspace: workspace.clone(),
+            },
+        ]
+        .into_iter()
+        .chain(issue_ids.iter().map(|id| ResourceUri::Issue {
+            workspace: workspace.clone(),
+            id: (*id).to_string(),
+        }));
+
+        let state = self.lock();
+        candidates
+            .map(|uri| uri.to_string())
+            .filter(|uri| state.uris.contains_key(uri))
+            .collect()
+    }
+
+    /// Send `notifications/resources/updated` to every session subscribed
+    /// to a URI a change to `issue_ids` in `workspace` may affect.
+    ///
+    /// A session whose notification cannot be delivered has disconnected,
+    /// so its subscriptions are dropped. Failures are otherwise ignored: the
+    /// change has already been saved.
+    pub async fn notify(&self, workspace: &Path, issue_ids: &[&str]) {
+        let uris = self.affected(workspace, issue_ids);
+        let deliveries: Vec<(String, SessionId, Peer<RoleServer>)> = {
+            let state = self.lock();
+            uris.iter()
+                .filter_map(|uri| state.uris.get(uri).map(|sessions| (uri, sessions)))
+                .flat_map(|(uri, sessions)| {
+                    sessions.iter().filter_map(|session| {
+                        let peer = state.peers.get(session)?;
+                        Some((uri.clone(), *session, peer.clone()))
+                    })
+                })
+                .collect()
+        };
+
+        let mut disconnected = BTreeSet::new();
+        for (uri, session, peer) in deliveries {
+            if disconnected.contains(&session) {
+                continue;
+            }
+            if let Err(error) = peer
+                .notify_resource_updated(ResourceUpdatedNotificationParam::new(uri.clone()))
+                .await
+            {
+                debug!(%uri, %error, "Failed to send resource update notification");
+                disconnected.insert(session);
+            }
+        }
+        let mut state = self.lock();
+        for session in disconnected {
+            state.remove_session(session);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SubscriptionState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::issue(
+        "rivets:///work/proj/issue/proj-abc",
+        ResourceUri::Issue { workspace: PathBuf::from("/work/proj"), id: "proj-abc".to_string() }
+    )]
+    #[case::ready("rivets:///work/proj/ready", ResourceUri::Ready { workspace: PathBuf::from("/work/proj") })]
+    #[case::blocked("rivets:///work/proj/blocked", ResourceUri::Blocked { workspace: PathBuf::from("/work/proj") })]
+    #[case::issue_dir_in_path(
+        "rivets:///work/issue/proj/issue/proj-1",
+        ResourceUri::Issue { workspace: PathBuf::from("/work/issue/proj"), id: "proj-1".to_string() }
+    )]
+    fn parse_and_display_roundtrip(#[case] uri: &str, #[case] expected: ResourceUri) {
+        let parsed = ResourceUri::parse(uri).unwrap();
+        assert_eq!(parsed, expected);
+        assert_eq!(parsed.to_string(), uri);
+    }
+
+    #[rstest]
+    #[case::wrong_scheme("file:///work/proj/ready")]
+    #[case::relative_workspace("rivets://proj/ready")]
+    #[case::unknown_view("rivets:///work/proj/stale")]
+    #[case::empty_id("rivets:///work/proj/issue/")]
+    #[case::nested_id("rivets:///work/proj/issue/a/b")]
+    fn parse_rejects_unknown_uris(#[case] uri: &str) {
+        assert!(matches!(
+            ResourceUri::parse(uri),
+            Err(Error::UnknownResourceUri(value)) if value == uri
+        ));
+    }
+
+    #[test]
+    fn pages_follow_their_cursors() {
+        let (first, cursor) = page((0..5).collect(), None, 2).unwrap();
+        assert_eq!(first, vec![0, 1]);
+        let (second, cursor) = page((0..5).collect(), cursor.as_deref(), 2).unwrap();
+        assert_eq!(second, vec![2, 3]);
+        let (last, cursor) = page((0..5).collect(), cursor.as_deref(), 2).unwrap();
+        assert_eq!(last, vec![4]);
+        assert_eq!(cursor, None);
+
+        assert!(page((0..5).collect::<Vec<_>>(), Some("6"), 2).is_err());
+        assert!(page((0..5).collect::<Vec<_>>(), Some("next"), 2).is_err());
+    }
+
+    #[test]
+    fn templates_cover_every_resource_kind() {
+        let templates: Vec<String> = resource_templates()
+            .into_iter()
+            .map(|template| template.raw.uri_template)
+            .collect();
+        assert_eq!(
+            templates,
+            vec![
+                "rivets://{+workspace}/issue/{id}",
+                "rivets://{+workspace}/ready",
+                "rivets://{+workspace}/blocked",
+            ]
+        );
+    }
+
+    #[test]
+    fn affected_only_returns_subscribed_uris_in_the_workspace() {
+        let subscriptions = Subscriptions::default();
+        let (first, second) = (subscriptions.new_session(), subscriptions.new_session());
+        let workspace = Path::new("/work/proj");
+        {
+            let mut state = subscriptions.lock();
+            for (uri, session) in [
+                ("rivets:///work/proj/ready", first),
+                ("rivets:///work/proj/ready", second),
+                ("rivets:///work/proj/issue/proj-1", first),
+                ("rivets:///work/other/issue/proj-2", second),
+            ] {
+                state
+                    .uris
+                    .entry(uri.to_string())
+                    .or_default()
+                    .insert(session);
+            }
+        }
+
+        assert_eq!(
+            subscriptions.affected(workspace, &["proj-1", "proj-2"]),
+            vec![
+                "rivets:///work/proj/ready".to_string(),
+                "rivets:///work/proj/issue/proj-1".to_string(),
+            ]
+        );
+        assert!(
+            subscriptions
+                .affected(Path::new("/work/elsewhere"), &["proj-1"])
+                .is_empty()
+        );
+
+        // The ready view stays subscribed until every session unsubscribes.
+        assert!(subscriptions.unsubscribe("rivets:///work/proj/ready", first));
+        assert!(!subscriptions.unsubscribe("rivets:///work/proj/ready", first));
+        assert_eq!(subscriptions.affected(workspace, &[]).len(), 1);
+        assert!(subscriptions.unsubscribe("rivets:///work/proj/ready", second));
+        assert_eq!(
+            subscriptions.affected(workspace, &["proj-1"]),
+            vec!["rivets:///work/proj/issue/proj-1".to_string()]
+        );
+
+        subscriptions.remove_session(first);
+        assert!(subscriptions.affected(workspace, &["proj-1"]).is_empty());
+    }
+}
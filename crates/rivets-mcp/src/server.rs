@@ -2,52 +2,128 @@
 //!
 //! This module contains the main server setup using rmcp.
 
+use crate::changes::{ChangeNotifications, ChangesSinceResponse};
 use crate::context::Context;
 use crate::error::Error;
 use crate::models::{
-    AddNoteParams, BlockedParams, CloseParams, CreateParams, DepParams, LabelAddParams,
-    LabelListAllParams, LabelListParams, LabelRemoveParams, ListParams, ReadyParams, ReopenParams,
-    ResourceAddParams, ResourceListParams, ResourceRemoveParams, ResourceUpdateParams,
-    SetContextParams, ShowParams, StaleParams, UpdateParams,
+    AddNoteParams, BatchParams, BatchResponse, BlockedListResponse, BlockedParams,
+    ChangesSinceParams, ClaimParams, ClaimResponse, CloseParams, CreateParams, DeleteParams,
+    DeleteResponse, DepListParams, DepParams, DepRemoveParams, DepRemoveResponse, DepResponse,
+    DepTreeParams, DepTreeResponse, DependencyListResponse, IssueListResponse, LabelAddParams,
+    LabelListAllParams, LabelListParams, LabelListResponse, LabelRemoveParams, ListParams,
+    ReadyParams, ReleaseParams, RenewParams, ReopenParams, ResourceAddParams, ResourceListParams,
+    ResourceListResponse, ResourceRemoveParams, ResourceUpdateParams, SetContextParams,
+    SetContextResponse, ShowParams, StaleParams, StatsParams, UpdateParams, WhereAmIResponse,
+};
+use crate::prompts::{prompt_definitions, render_prompt};
+use crate::resources::{
+    RESOURCE_MIME_TYPE, RESOURCE_PAGE_SIZE, ResourceUri, SessionId, Subscriptions, page,
+    resource_templates, workspace_resources,
 };
 use crate::tools::Tools;
+use rivets::commands::stats::IssueStats;
+use rivets::domain::Issue;
 use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::handler::server::tool::schema_for_output;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{
-    CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+    CallToolResult, Content, GetPromptRequestParams, GetPromptResult, Implementation,
+    InitializeRequestParams, InitializeResult, JsonObject, ListPromptsResult,
+    ListResourceTemplatesResult, ListResourcesResult, PaginatedRequestParams, ProtocolVersion,
+    ReadResourceRequestParams, ReadResourceResult, ResourceContents, ServerCapabilities,
+    ServerInfo, SetLevelRequestParams, SubscribeRequestParams, UnsubscribeRequestParams,
 };
+use rmcp::service::RequestContext;
 use rmcp::{
-    ErrorData as McpError, handler::server::ServerHandler, tool, tool_handler, tool_router,
+    ErrorData as McpError, RoleServer, handler::server::ServerHandler, tool, tool_handler,
+    tool_router,
 };
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Maps error types to appropriate MCP error codes:
 /// - `NoContext`, `InvalidArgument`, `InvalidNote`, `InvalidResource`,
+///   `InvalidCustomField`, `InvalidMilestone`, `InvalidTemplate`, `InvalidClaim`,
 ///   `InvalidStatusTransition` -> `invalid_params` (user needs to fix their request)
-/// - `IssueNotFound` -> `invalid_params` (requested resource doesn't exist)
+/// - `IssueNotFound`, `UnknownResourceUri` -> `invalid_params` (requested
+///   resource doesn't exist)
+/// - `WorkspaceNotAllowed` -> `invalid_params` (outside the server's
+///   workspace allowlist)
+/// - `BatchOperation` -> the code of the operation's own error
 /// - Other errors -> `internal_error`
 fn to_mcp_error(e: &Error) -> McpError {
     match e {
+        Error::BatchOperation { source, .. } => {
+            McpError::new(to_mcp_error(source).code, e.to_string(), None)
+        }
         Error::NoContext
+        | Error::UnknownResourceUri(_)
         | Error::InvalidArgument { .. }
         | Error::InvalidNote(_)
         | Error::InvalidResource(_)
+        | Error::InvalidCustomField(_)
+        | Error::InvalidMilestone(_)
+        | Error::InvalidTemplate { .. }
+        | Error::InvalidClaim(_)
         | Error::InvalidStatusTransition(_)
-        | Error::IssueNotFound(_) => McpError::invalid_params(e.to_string(), None),
+        | Error::IssueNotFound(_)
+        | Error::WorkspaceNotAllowed(_) => McpError::invalid_params(e.to_string(), None),
         _ => McpError::internal_error(e.to_string(), None),
     }
 }
 
+/// Output schema published for a tool whose structured content is a `T`.
+///
+/// # Panics
+///
+/// Panics if `T`'s schema is not an object, which MCP requires of
+/// structured output; every tool hits this when the router is built.
+fn output_schema<T: JsonSchema + 'static>() -> Arc<JsonObject> {
+    schema_for_output::<T>().unwrap_or_else(|e| panic!("invalid output schema: {e}"))
+}
+
+/// A tool result with `output` as both its JSON text and its structured
+/// content.
+fn json_result(output: &impl Serialize) -> Result<CallToolResult, McpError> {
+    structured_result(Content::json(output)?, output)
+}
+
+/// A tool result with `content` as its text and `structured` as its
+/// structured content.
+///
+/// Tools returning a list keep the bare list as text for clients that
+/// predate structured output, and wrap it in an object for the structured
+/// content.
+fn structured_result(
+    content: Content,
+    structured: &impl Serialize,
+) -> Result<CallToolResult, McpError> {
+    let structured = serde_json::to_value(structured)
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    let mut result = CallToolResult::success(vec![content]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
 /// The rivets MCP server.
 ///
-/// Provides MCP protocol handling over stdio transport.
+/// Provides MCP protocol handling for one client session. Sessions served
+/// over HTTP share a [`Context`] and resource subscriptions via
+/// [`RivetsMcpServer::for_session`].
 #[derive(Clone)]
 pub struct RivetsMcpServer {
     /// Shared context for workspace management.
     context: Arc<RwLock<Context>>,
     /// Tool implementations.
     tools: Arc<Tools>,
+    /// Resource subscriptions to notify after issue changes.
+    subscriptions: Arc<Subscriptions>,
+    /// The ID this session subscribes under.
+    session: SessionId,
+    /// Change feed forwarding once the client enables logging.
+    notifications: Arc<ChangeNotifications>,
     /// Tool router for MCP dispatch.
     tool_router: ToolRouter<Self>,
 }
@@ -56,58 +132,139 @@ pub struct RivetsMcpServer {
 impl RivetsMcpServer {
     /// Set the workspace context for subsequent operations.
     #[tool(
-        description = "Set the default workspace root for calls that omit workspace_root. Calls with workspace_root initialize that workspace directly."
+        description = "Set the default workspace root for calls that omit workspace_root. Calls with workspace_root initialize that workspace directly.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<SetContextResponse>(),
     )]
     async fn set_context(
         &self,
         Parameters(params): Parameters<SetContextParams>,
     ) -> Result<CallToolResult, McpError> {
         match self.tools.set_context(&params.workspace_root).await {
-            Ok(response) => Ok(CallToolResult::success(vec![Content::json(response)?])),
+            Ok(response) => json_result(&response),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Get current workspace context information.
-    #[tool(description = "Show current workspace context and database path. Useful for debugging.")]
+    #[tool(
+        description = "Show current workspace context and database path. Useful for debugging.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<WhereAmIResponse>(),
+    )]
     async fn where_am_i(&self) -> Result<CallToolResult, McpError> {
         match self.tools.where_am_i().await {
-            Ok(response) => Ok(CallToolResult::success(vec![Content::json(response)?])),
+            Ok(response) => json_result(&response),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Find issues ready to work on.
     #[tool(
-        description = "Find tasks that have no blockers and are ready to be worked on. Returns up to 100 results by default if no limit specified. Uses workspace_root if provided, otherwise uses current context."
+        description = "Find tasks that have no blockers and are ready to be worked on. Set milestone, or current_milestone: true, to only pick work from that milestone. Issues claimed by other agents are hidden; pass agent to keep your own claims. Returns up to 100 results by default if no limit specified. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<IssueListResponse>(),
     )]
     async fn ready(
         &self,
         Parameters(params): Parameters<ReadyParams>,
     ) -> Result<CallToolResult, McpError> {
         match self.tools.ready(params).await {
-            Ok(issues) => Ok(CallToolResult::success(vec![Content::json(issues)?])),
+            Ok(issues) => structured_result(Content::json(&issues)?, &IssueListResponse { issues }),
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Claim an issue for an agent.
+    #[tool(
+        description = "Claim an issue for an agent: assigns it with a lease (default 1h, e.g. \"30m\", \"2h\") that expires unless renewed. Only unassigned issues, your own claims, or issues whose claim expired can be claimed. Omit issue_id to claim the next ready issue; returns null when nothing is claimable. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<ClaimResponse>(),
+    )]
+    async fn claim(
+        &self,
+        Parameters(params): Parameters<ClaimParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let workspace_root = params.workspace_root.clone();
+        match self.tools.claim(params).await {
+            Ok(issue) => {
+                if let Some(issue) = &issue {
+                    self.publish_changes(workspace_root.as_deref(), &[issue.id.as_str()])
+                        .await;
+                }
+                structured_result(Content::json(&issue)?, &ClaimResponse { issue })
+            }
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Renew an agent's claim on an issue.
+    #[tool(
+        description = "Extend your claim on an issue by a lease from now (default 1h). Fails if another agent holds the claim. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
+    )]
+    async fn renew(
+        &self,
+        Parameters(params): Parameters<RenewParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let workspace_root = params.workspace_root.clone();
+        match self.tools.renew(params).await {
+            Ok(issue) => {
+                self.publish_changes(workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Release an agent's claim on an issue.
+    #[tool(
+        description = "Release your claim on an issue and unassign it so other agents can claim it. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
+    )]
+    async fn release(
+        &self,
+        Parameters(params): Parameters<ReleaseParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .release(
+                &params.issue_id,
+                &params.agent,
+                params.workspace_root.as_deref(),
+            )
+            .await
+        {
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// List issues with optional filters.
     #[tool(
-        description = "List all issues with optional filters (status, priority, kind, assignee, label). Returns up to 100 results by default if no limit specified. Uses workspace_root if provided, otherwise uses current context."
+        description = "List all issues with optional filters (status, priority, kind, assignee, label, custom_fields, overdue, milestone). Returns up to 100 results by default if no limit specified. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<IssueListResponse>(),
     )]
     async fn list(
         &self,
         Parameters(params): Parameters<ListParams>,
     ) -> Result<CallToolResult, McpError> {
         match self.tools.list(params).await {
-            Ok(issues) => Ok(CallToolResult::success(vec![Content::json(issues)?])),
+            Ok(issues) => structured_result(Content::json(&issues)?, &IssueListResponse { issues }),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Show detailed information about a specific issue.
     #[tool(
-        description = "Show detailed information about a specific issue including dependencies and dependents. Uses workspace_root if provided, otherwise uses current context."
+        description = "Show detailed information about a specific issue including dependencies and dependents. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<Issue>(),
     )]
     async fn show(
         &self,
@@ -118,56 +275,73 @@ impl RivetsMcpServer {
             .show(&params.issue_id, params.workspace_root.as_deref())
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => json_result(&issue),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Get blocked issues and their blockers.
     #[tool(
-        description = "Get blocked issues showing what dependencies are blocking them from being worked on. Uses workspace_root if provided, otherwise uses current context."
+        description = "Get blocked issues showing what dependencies are blocking them from being worked on. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<BlockedListResponse>(),
     )]
     async fn blocked(
         &self,
         Parameters(params): Parameters<BlockedParams>,
     ) -> Result<CallToolResult, McpError> {
         match self.tools.blocked(params.workspace_root.as_deref()).await {
-            Ok(blocked) => Ok(CallToolResult::success(vec![Content::json(blocked)?])),
+            Ok(blocked) => {
+                structured_result(Content::json(&blocked)?, &BlockedListResponse { blocked })
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Create a new issue.
     #[tool(
-        description = "Create a new issue (bug, feature, task, epic, or chore) with an optional initial Note, design, acceptance criteria, and dependencies. Uses workspace_root if provided, otherwise uses current context."
+        description = "Create a new issue (bug, feature, task, epic, or chore) with an optional initial Note, design, acceptance criteria, and dependencies. Unset fields are prefilled from the named template or the template for the Issue Kind in .rivets/templates. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn create(
         &self,
         Parameters(params): Parameters<CreateParams>,
     ) -> Result<CallToolResult, McpError> {
+        let workspace_root = params.workspace_root.clone();
         match self.tools.create(params).await {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Update an existing issue.
     #[tool(
-        description = "Update an existing issue's status, priority, kind, assignee, labels, description, design notes, or acceptance criteria. Use empty string for assignee to clear it. Labels replace existing labels when provided. Uses workspace_root if provided, otherwise uses current context."
+        description = "Update an existing issue's status, priority, kind, assignee, labels, description, design notes, acceptance criteria, custom fields, due date, or milestone. Use empty string for assignee, due_at or milestone to clear it. Labels replace existing labels when provided. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn update(
         &self,
         Parameters(params): Parameters<UpdateParams>,
     ) -> Result<CallToolResult, McpError> {
+        let workspace_root = params.workspace_root.clone();
         match self.tools.update(params).await {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Append an immutable Note to an Issue.
     #[tool(
-        description = "Append one immutable, timestamped Note to an issue. Existing Note history is preserved. Uses workspace_root if provided, otherwise uses current context."
+        description = "Append one immutable, timestamped Note to an issue. Existing Note history is preserved. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn add_note(
         &self,
@@ -182,14 +356,19 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(error) => Err(to_mcp_error(&error)),
         }
     }
 
     /// Associate a Web URL or Workspace Path target with an Issue.
     #[tool(
-        description = "Associate an absolute HTTP or HTTPS Web URL or a workspace-relative Path with an issue using a canonical role and optional label. Exactly one of url/path is required. Uses workspace_root if provided, otherwise uses current context."
+        description = "Associate an absolute HTTP or HTTPS Web URL or a workspace-relative Path with an issue using a canonical role and optional label. Exactly one of url/path is required. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn resource_add(
         &self,
@@ -207,28 +386,39 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(error) => Err(to_mcp_error(&error)),
         }
     }
 
     /// Update an Issue's Associated Resource by its stable identifier.
     #[tool(
-        description = "Update an issue's Associated Resource by its stable resource identifier. Only the provided fields change; the resource keeps its identifier and position. Uses workspace_root if provided, otherwise uses current context."
+        description = "Update an issue's Associated Resource by its stable resource identifier. Only the provided fields change; the resource keeps its identifier and position. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn resource_update(
         &self,
         Parameters(params): Parameters<ResourceUpdateParams>,
     ) -> Result<CallToolResult, McpError> {
+        let workspace_root = params.workspace_root.clone();
         match self.tools.resource_update(params).await {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(error) => Err(to_mcp_error(&error)),
         }
     }
 
     /// Remove an Issue's Associated Resource by its stable identifier.
     #[tool(
-        description = "Remove an issue's Associated Resource by its stable resource identifier. The remaining resources keep their identifiers and positions. Uses workspace_root if provided, otherwise uses current context."
+        description = "Remove an issue's Associated Resource by its stable resource identifier. The remaining resources keep their identifiers and positions. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn resource_remove(
         &self,
@@ -243,14 +433,20 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(error) => Err(to_mcp_error(&error)),
         }
     }
 
     /// List an Issue's Associated Resources.
     #[tool(
-        description = "List an issue's Associated Resources in insertion order. Uses workspace_root if provided, otherwise uses current context."
+        description = "List an issue's Associated Resources in insertion order. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<ResourceListResponse>(),
     )]
     async fn resource_list(
         &self,
@@ -261,14 +457,18 @@ impl RivetsMcpServer {
             .resource_list(&params.issue_id, params.workspace_root.as_deref())
             .await
         {
-            Ok(resources) => Ok(CallToolResult::success(vec![Content::json(resources)?])),
+            Ok(resources) => structured_result(
+                Content::json(&resources)?,
+                &ResourceListResponse { resources },
+            ),
             Err(error) => Err(to_mcp_error(&error)),
         }
     }
 
     /// Close an issue.
     #[tool(
-        description = "Close (complete) an issue. Mark work as done when you've finished implementing/fixing it. Uses workspace_root if provided, otherwise uses current context."
+        description = "Close (complete) an issue. Mark work as done when you've finished implementing/fixing it. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn close(
         &self,
@@ -283,14 +483,19 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Add a dependency between issues.
     #[tool(
-        description = "Add a dependency between issues. Types: blocks (hard blocker), related (soft link), parent-child (epic/subtask), discovered-from (found during work). Uses workspace_root if provided, otherwise uses current context."
+        description = "Add a dependency between issues. Types: blocks (hard blocker), related (soft link), parent-child (epic/subtask), discovered-from (found during work). Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<DepResponse>(),
     )]
     async fn dep(
         &self,
@@ -306,14 +511,103 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(message) => Ok(CallToolResult::success(vec![Content::text(message)])),
+            Ok(message) => {
+                self.publish_changes(
+                    params.workspace_root.as_deref(),
+                    &[&params.issue_id, &params.depends_on_id],
+                )
+                .await;
+                structured_result(Content::text(&message), &DepResponse { message })
+            }
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Remove a dependency between issues.
+    #[tool(
+        description = "Remove the dependency of issue_id on depends_on_id, whatever its type. Returns the same JSON as `rivets dep remove --json`. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<DepRemoveResponse>(),
+    )]
+    async fn dep_remove(
+        &self,
+        Parameters(params): Parameters<DepRemoveParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .dep_remove(
+                &params.issue_id,
+                &params.depends_on_id,
+                params.workspace_root.as_deref(),
+            )
+            .await
+        {
+            Ok(response) => {
+                self.publish_changes(
+                    params.workspace_root.as_deref(),
+                    &[&params.issue_id, &params.depends_on_id],
+                )
+                .await;
+                json_result(&response)
+            }
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// List an issue's dependencies or dependents.
+    #[tool(
+        description = "List the issues an issue depends on, or with reverse: true the issues that depend on it, with their dependency types. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<DependencyListResponse>(),
+    )]
+    async fn dep_list(
+        &self,
+        Parameters(params): Parameters<DepListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .dep_list(
+                &params.issue_id,
+                params.reverse.unwrap_or(false),
+                params.workspace_root.as_deref(),
+            )
+            .await
+        {
+            Ok(deps) => structured_result(
+                Content::json(&deps)?,
+                &DependencyListResponse { dependencies: deps },
+            ),
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Get the dependency tree of an issue.
+    #[tool(
+        description = "Get the transitive dependency tree of an issue, plus the issues that depend on it. Default depth is 5; use 0 for unlimited. Returns the same JSON as `rivets dep tree --json`. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<DepTreeResponse>(),
+    )]
+    async fn dep_tree(
+        &self,
+        Parameters(params): Parameters<DepTreeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .dep_tree(
+                &params.issue_id,
+                params.depth,
+                params.workspace_root.as_deref(),
+            )
+            .await
+        {
+            Ok(tree) => json_result(&tree),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Reopen a closed issue.
     #[tool(
-        description = "Reopen a previously closed issue. Use when work needs to continue or was not actually complete. Uses workspace_root if provided, otherwise uses current context."
+        description = "Reopen a previously closed issue. Use when work needs to continue or was not actually complete. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn reopen(
         &self,
@@ -328,14 +622,43 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Delete an issue.
+    #[tool(
+        description = "Permanently delete an issue. Fails if other issues depend on it; remove those dependencies with dep_remove first. Prefer close for finished work. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<DeleteResponse>(),
+    )]
+    async fn delete(
+        &self,
+        Parameters(params): Parameters<DeleteParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .delete(&params.issue_id, params.workspace_root.as_deref())
+            .await
+        {
+            Ok(response) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[&params.issue_id])
+                    .await;
+                json_result(&response)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Find stale issues.
     #[tool(
-        description = "Find issues that haven't been updated recently. Default is 30 days. Useful for identifying forgotten work or issues needing attention. Uses workspace_root if provided, otherwise uses current context."
+        description = "Find issues that haven't been updated recently, or whose claim expired. Default is 30 days. Useful for identifying forgotten work or issues needing attention. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<IssueListResponse>(),
     )]
     async fn stale(
         &self,
@@ -351,14 +674,15 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issues) => Ok(CallToolResult::success(vec![Content::json(issues)?])),
+            Ok(issues) => structured_result(Content::json(&issues)?, &IssueListResponse { issues }),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Add a label to an issue.
     #[tool(
-        description = "Add a label to an issue for categorization. Labels should be lowercase, alphanumeric with hyphens/underscores. Uses workspace_root if provided, otherwise uses current context."
+        description = "Add a label to an issue for categorization. Labels should be lowercase, alphanumeric with hyphens/underscores. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn label_add(
         &self,
@@ -373,14 +697,19 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// Remove a label from an issue.
     #[tool(
-        description = "Remove a label from an issue. Uses workspace_root if provided, otherwise uses current context."
+        description = "Remove a label from an issue. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<Issue>(),
     )]
     async fn label_remove(
         &self,
@@ -395,14 +724,20 @@ impl RivetsMcpServer {
             )
             .await
         {
-            Ok(issue) => Ok(CallToolResult::success(vec![Content::json(issue)?])),
+            Ok(issue) => {
+                self.publish_changes(params.workspace_root.as_deref(), &[issue.id.as_str()])
+                    .await;
+                json_result(&issue)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// List labels for a specific issue.
     #[tool(
-        description = "List all labels assigned to a specific issue. Uses workspace_root if provided, otherwise uses current context."
+        description = "List all labels assigned to a specific issue. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<LabelListResponse>(),
     )]
     async fn label_list(
         &self,
@@ -413,14 +748,16 @@ impl RivetsMcpServer {
             .label_list(&params.issue_id, params.workspace_root.as_deref())
             .await
         {
-            Ok(labels) => Ok(CallToolResult::success(vec![Content::json(labels)?])),
+            Ok(labels) => structured_result(Content::json(&labels)?, &LabelListResponse { labels }),
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
 
     /// List all unique labels across all issues.
     #[tool(
-        description = "List all unique labels used across all issues in the workspace. Useful for understanding available categorizations. Uses workspace_root if provided, otherwise uses current context."
+        description = "List all unique labels used across all issues in the workspace. Useful for understanding available categorizations. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<LabelListResponse>(),
     )]
     async fn label_list_all(
         &self,
@@ -431,7 +768,79 @@ impl RivetsMcpServer {
             .label_list_all(params.workspace_root.as_deref())
             .await
         {
-            Ok(labels) => Ok(CallToolResult::success(vec![Content::json(labels)?])),
+            Ok(labels) => structured_result(Content::json(&labels)?, &LabelListResponse { labels }),
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Get workspace statistics.
+    #[tool(
+        description = "Get issue counts by status, ready and blocked-by-dependency counts, and the overdue count. Set detailed: true for a breakdown by priority. Returns the same JSON as `rivets stats --json`. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<IssueStats>(),
+    )]
+    async fn stats(
+        &self,
+        Parameters(params): Parameters<StatsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .stats(
+                params.detailed.unwrap_or(false),
+                params.workspace_root.as_deref(),
+            )
+            .await
+        {
+            Ok(stats) => json_result(&stats),
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Get changes recorded since a cursor.
+    #[tool(
+        description = "List changes to issues since a cursor: who changed which issue, with which tool, and a field-by-field before/after diff. Includes changes made by other agents and edits picked up from disk (source \"reload\"). Pass the returned cursor to the next call to get only newer changes; omit it to get every retained change. Returns up to 100 changes by default. Uses workspace_root if provided, otherwise uses current context.",
+        annotations(read_only_hint = true),
+        output_schema = output_schema::<ChangesSinceResponse>()
+    )]
+    async fn changes_since(
+        &self,
+        Parameters(params): Parameters<ChangesSinceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .tools
+            .changes_since(
+                params.cursor,
+                params.limit,
+                params.workspace_root.as_deref(),
+            )
+            .await
+        {
+            Ok(changes) => json_result(&changes),
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    /// Apply several modifications atomically.
+    #[tool(
+        description = "Apply an ordered list of operations in one transaction: all succeed and are saved once, or none are applied. Each operation has an `op` (create, update, dep, label_add, label_remove, resource_add, note) plus that tool's parameters, without workspace_root. A create may set `handle`; later operations refer to the new issue as `@<handle>` in any issue ID field. Returns the handle-to-ID map and every issue the batch touched. Uses workspace_root if provided, otherwise uses current context.",
+        output_schema = output_schema::<BatchResponse>(),
+    )]
+    async fn batch(
+        &self,
+        Parameters(params): Parameters<BatchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let workspace_root = params.workspace_root.clone();
+        match self.tools.batch(params).await {
+            Ok(response) => {
+                let issue_ids: Vec<&str> = response
+                    .issues
+                    .iter()
+                    .map(|issue| issue.id.as_str())
+                    .collect();
+                self.publish_changes(workspace_root.as_deref(), &issue_ids)
+                    .await;
+                json_result(&response)
+            }
             Err(e) => Err(to_mcp_error(&e)),
         }
     }
@@ -441,21 +850,100 @@ impl RivetsMcpServer {
     /// Create a new rivets MCP server.
     #[must_use]
     pub fn new() -> Self {
-        let context = Arc::new(RwLock::new(Context::new()));
+        Self::with_context(Arc::new(RwLock::new(Context::new())))
+    }
+
+    /// Create a server for a single session over `context`.
+    #[must_use]
+    pub fn with_context(context: Arc<RwLock<Context>>) -> Self {
+        Self::for_session(context, Arc::new(Subscriptions::default()))
+    }
+
+    /// Create a server for one session over a context and subscriptions
+    /// shared with others.
+    ///
+    /// Loaded workspaces and the default workspace are shared, but
+    /// `set_context` only changes this session's workspace. A change made
+    /// in any session notifies every session subscribed to it.
+    #[must_use]
+    pub fn for_session(context: Arc<RwLock<Context>>, subscriptions: Arc<Subscriptions>) -> Self {
         let tools = Arc::new(Tools::new(Arc::clone(&context)));
 
         Self {
             context,
             tools,
+            session: subscriptions.new_session(),
+            subscriptions,
+            notifications: Arc::new(ChangeNotifications::default()),
             tool_router: Self::tool_router(),
         }
     }
 
+    /// Remove every tool that can modify the tracker.
+    ///
+    /// Only tools annotated `read_only_hint` stay listed and callable, so a
+    /// read-only server cannot change issues through any tool, including
+    /// ones added later without the annotation.
+    #[must_use]
+    pub fn read_only(mut self) -> Self {
+        for tool in self.tool_router.list_all() {
+            let read_only = tool
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.read_only_hint)
+                .unwrap_or(false);
+            if !read_only {
+                self.tool_router.remove_route(&tool.name);
+            }
+        }
+        self
+    }
+
     /// Get a reference to the context.
     #[must_use]
     pub fn context(&self) -> &Arc<RwLock<Context>> {
         &self.context
     }
+
+    /// Notify resource subscribers after a tool changed `issue_ids`.
+    async fn publish_changes(&self, workspace_root: Option<&str>, issue_ids: &[&str]) {
+        let workspace = self.tools.workspace_root_for(workspace_root).await;
+        if let Ok(workspace) = workspace {
+            self.subscriptions.notify(&workspace, issue_ids).await;
+        }
+    }
+
+    /// Parse a resource URI and canonicalize its workspace root.
+    async fn resolve_resource(&self, uri: &str) -> Result<ResourceUri, Error> {
+        let resource = ResourceUri::parse(uri)?;
+        let workspace = self
+            .context
+            .read()
+            .await
+            .workspace_root_for(Some(resource.workspace()))?;
+        Ok(resource.with_workspace(workspace))
+    }
+
+    /// Read a resource as the JSON its matching tool returns.
+    async fn read_resource_json(&self, resource: &ResourceUri) -> Result<String, Error> {
+        let workspace_root = resource.workspace().display().to_string();
+        let json = match resource {
+            ResourceUri::Issue { id, .. } => {
+                serde_json::to_string_pretty(&self.tools.show(id, Some(&workspace_root)).await?)?
+            }
+            ResourceUri::Ready { .. } => {
+                let params = ReadyParams {
+                    workspace_root: Some(workspace_root),
+                    ..Default::default()
+                };
+                serde_json::to_string_pretty(&self.tools.ready(params).await?)?
+            }
+            ResourceUri::Blocked { .. } => {
+                serde_json::to_string_pretty(&self.tools.blocked(Some(&workspace_root)).await?)?
+            }
+        };
+        Ok(json)
+    }
 }
 
 impl Default for RivetsMcpServer {
@@ -466,9 +954,19 @@ impl Default for RivetsMcpServer {
 
 #[tool_handler(router = self.tool_router)]
 impl ServerHandler for RivetsMcpServer {
+    // Logging carries change notifications; see `crate::changes`.
+    #[allow(deprecated)]
     fn get_info(&self) -> ServerInfo {
-        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
-            .with_protocol_version(ProtocolVersion::V_2024_11_05)
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_prompts()
+                .enable_logging()
+                .build(),
+        )
+            .with_protocol_version(ProtocolVersion::V_2025_06_18)
             .with_server_info(Implementation::new(
                 "rivets-mcp",
                 env!("CARGO_PKG_VERSION"),
@@ -477,6 +975,132 @@ impl ServerHandler for RivetsMcpServer {
                 "Rivets MCP server for issue tracking. Pass workspace_root to a tool call, or use set_context to set the default workspace.",
             )
     }
+
+    async fn initialize(
+        &self,
+        request: InitializeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        self.tools.set_client_name(&request.client_info.name);
+        context.peer.set_peer_info(request);
+        Ok(self.get_info())
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        let feed = Arc::clone(self.context.read().await.changes());
+        self.notifications
+            .set_level(request.level, &feed, context.peer);
+        Ok(())
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let Some(workspace) = self.tools.current_workspace().await else {
+            return Ok(ListResourcesResult::with_all_items(Vec::new()));
+        };
+        let issues = self
+            .tools
+            .list(ListParams {
+                workspace_root: Some(workspace.display().to_string()),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| to_mcp_error(&e))?;
+        let cursor = request.and_then(|request| request.cursor);
+        let (resources, next_cursor) = page(
+            workspace_resources(&workspace, &issues),
+            cursor.as_deref(),
+            RESOURCE_PAGE_SIZE,
+        )
+        .map_err(|e| to_mcp_error(&e))?;
+        Ok(ListResourcesResult {
+            next_cursor,
+            ..ListResourcesResult::with_all_items(resources)
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            resource_templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let json = match self.resolve_resource(&request.uri).await {
+            Ok(resource) => self.read_resource_json(&resource).await,
+            Err(e) => Err(e),
+        };
+        match json {
+            Ok(json) => Ok(ReadResourceResult::new(vec![
+                ResourceContents::text(json, request.uri).with_mime_type(RESOURCE_MIME_TYPE),
+            ])),
+            Err(e @ (Error::UnknownResourceUri(_) | Error::IssueNotFound(_))) => {
+                Err(McpError::resource_not_found(e.to_string(), None))
+            }
+            Err(e) => Err(to_mcp_error(&e)),
+        }
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        let resource = self
+            .resolve_resource(&request.uri)
+            .await
+            .map_err(|e| to_mcp_error(&e))?;
+        self.subscriptions
+            .subscribe(&resource, self.session, context.peer);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        // Fall back to the URI as given when its workspace no longer resolves.
+        let uri = match self.resolve_resource(&request.uri).await {
+            Ok(resource) => resource.to_string(),
+            Err(_) => request.uri,
+        };
+        self.subscriptions.unsubscribe(&uri, self.session);
+        Ok(())
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult::with_all_items(prompt_definitions()))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        render_prompt(&self.tools, &request.name, request.arguments.as_ref())
+            .await
+            .map_err(|e| to_mcp_error(&e))
+    }
 }
 
 #[cfg(test)]
@@ -504,6 +1128,14 @@ mod tests {
         assert_eq!(info.server_info.name, "rivets-mcp");
         assert!(!info.server_info.version.is_empty());
         assert!(info.instructions.is_some());
+        let resources = info
+            .capabilities
+            .resources
+            .expect("resources capability should be enabled");
+        assert_eq!(resources.subscribe, Some(true));
+        assert!(info.capabilities.prompts.is_some());
+        assert!(info.capabilities.logging.is_some());
+        assert_eq!(info.protocol_version, ProtocolVersion::V_2025_06_18);
     }
 
     #[test]
@@ -536,6 +1168,11 @@ mod tests {
         assert!(tool_names.contains(&"resource_list"));
         assert!(tool_names.contains(&"resource_update"));
         assert!(tool_names.contains(&"resource_remove"));
+        assert!(tool_names.contains(&"delete"));
+        assert!(tool_names.contains(&"dep_remove"));
+        assert!(tool_names.contains(&"dep_list"));
+        assert!(tool_names.contains(&"dep_tree"));
+        assert!(tool_names.contains(&"stats"));
         let input_properties = |name: &str| {
             tools
                 .iter()
@@ -552,7 +1189,53 @@ mod tests {
         assert!(input_properties("resource_add").contains_key("role"));
         assert!(input_properties("resource_update").contains_key("resource_id"));
         assert!(input_properties("resource_remove").contains_key("resource_id"));
-        assert_eq!(tools.len(), 21);
+        assert!(input_properties("batch").contains_key("operations"));
+        assert!(input_properties("claim").contains_key("agent"));
+        assert!(input_properties("ready").contains_key("agent"));
+        assert_eq!(tools.len(), 31);
+    }
+
+    #[test]
+    fn test_read_only_server_removes_mutating_tools() {
+        let server = RivetsMcpServer::new().read_only();
+        let mut tool_names: Vec<_> = server
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        tool_names.sort();
+
+        assert_eq!(
+            tool_names,
+            [
+                "blocked",
+                "changes_since",
+                "dep_list",
+                "dep_tree",
+                "label_list",
+                "label_list_all",
+                "list",
+                "ready",
+                "resource_list",
+                "set_context",
+                "show",
+                "stale",
+                "stats",
+                "where_am_i",
+            ]
+        );
+        for mutating in [
+            "create",
+            "update",
+            "close",
+            "dep",
+            "label_add",
+            "batch",
+            "claim",
+        ] {
+            assert!(!server.tool_router.has_route(mutating), "{mutating}");
+        }
     }
 
     #[test]
@@ -579,6 +1262,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_every_tool_publishes_object_output_schema() {
+        let server = RivetsMcpServer::new();
+        for tool in server.tool_router.list_all() {
+            let schema = tool
+                .output_schema
+                .unwrap_or_else(|| panic!("{} should publish an output schema", tool.name));
+            assert_eq!(schema.get("type"), Some(&serde_json::json!("object")));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_return_structured_content_alongside_text() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let rivets_dir = workspace.path().join(".rivets");
+        std::fs::create_dir(&rivets_dir).unwrap();
+        std::fs::write(
+            rivets_dir.join("config.yaml"),
+            "issue-prefix: test\nstorage:\n  backend: jsonl\n  data_file: .rivets/issues.jsonl\n",
+        )
+        .unwrap();
+        let server = RivetsMcpServer::new();
+        server
+            .set_context(Parameters(SetContextParams {
+                workspace_root: workspace.path().display().to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let created = server
+            .create(Parameters(
+                serde_json::from_value(serde_json::json!({ "title": "Structured" })).unwrap(),
+            ))
+            .await
+            .unwrap();
+        let issue = created
+            .structured_content
+            .expect("create returns an object");
+        assert_eq!(issue["title"], "Structured");
+        let text: serde_json::Value =
+            serde_json::from_str(&created.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(text, issue);
+
+        // Lists keep the bare array as text and wrap it as structured content.
+        let listed = server
+            .list(Parameters(ListParams::default()))
+            .await
+            .unwrap();
+        let text: serde_json::Value =
+            serde_json::from_str(&listed.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(text, serde_json::json!([issue]));
+        assert_eq!(
+            listed.structured_content,
+            Some(serde_json::json!({ "issues": [issue] }))
+        );
+    }
+
     // =========================================================================
     // Tool dispatch integration tests
     // =========================================================================
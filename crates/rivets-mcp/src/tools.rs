@@ -10,25 +10,42 @@
 //!   in a single session without repeatedly calling `set_context`
 //! - **MCP protocol compatibility**: Each tool call can specify its target workspace,
 //!   matching how MCP tools receive parameters from the client
-//! - **Fallback behavior**: If `workspace_root` is `None`, the current context
-//!   (set via `set_context`) is used
+//! - **Fallback behavior**: If `workspace_root` is `None`, the session's workspace
+//!   (set via `set_context`) is used, or the server's default workspace if the
+//!   session has not chosen one
 //!
 //! This design mirrors the beads MCP server's approach for compatibility.
 
+use crate::changes::{ChangeFeed, ChangesSinceResponse, RELOAD_SOURCE};
 use crate::context::Context;
 use crate::error::{Error, Result};
 use crate::models::{
-    BlockedIssueResponse, CreateParams, ListParams, ReadyParams, ResourceUpdateParams,
+    BatchOperation, BatchParams, BatchResponse, BlockedIssueResponse, ClaimParams, CreateParams,
+    DeleteResponse, DepRemoveResponse, ListParams, ReadyParams, RenewParams, ResourceUpdateParams,
     SetContextResponse, UpdateParams, WhereAmIResponse,
 };
+use chrono::{DateTime, Utc};
+use rivets::commands::claims::claim_next_with_previous;
+use rivets::commands::dep_tree::build_dep_tree;
+use rivets::commands::init::{CONFIG_FILE_NAME, RIVETS_DIR_NAME, RivetsConfig};
+use rivets::commands::milestones::{current_milestone_name, require_milestone};
+use rivets::commands::stats::{IssueStats, collect_stats};
+use rivets::commands::templates::{ExplicitFields, IssueTemplate};
 use rivets::domain::{
-    AssociatedResource, DependencyType, Issue, IssueFilter, IssueId, IssueKind, IssueStatus,
-    IssueUpdate, NewIssue, NewResource, NoteContent, ResourceId, ResourceLabel, ResourceRole,
-    ResourceTarget, ResourceUpdate, WebUrl, WorkspacePath,
+    AssociatedResource, CustomFieldError, CustomFieldSchema, CustomFieldValue,
+    DEFAULT_LEASE_DURATION, DUE_DATE_FORMATS, Dependency, DependencyType, Issue, IssueFilter,
+    IssueId, IssueKind, IssueStatus, IssueUpdate, NewIssue, NewResource, NoteContent, ResourceId,
+    ResourceLabel, ResourceRole, ResourceTarget, ResourceUpdate, WebUrl, WorkspacePath,
+    parse_due_date, parse_lease_duration,
 };
+use rivets::error::{ConfigError, Error as RivetsError};
+use rivets::hooks::Hooks;
+use rivets::output::dep_tree_to_json_public;
 use rivets::storage::IssueStorage;
-use std::path::Path;
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::slice;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 use tracing::{debug, instrument};
 
@@ -38,6 +55,20 @@ use tracing::{debug, instrument};
 /// queries always have a reasonable upper bound.
 const DEFAULT_QUERY_LIMIT: usize = 100;
 
+/// Default `dep_tree` depth when none is specified, matching `rivets dep tree`.
+const DEFAULT_DEP_TREE_DEPTH: usize = 5;
+
+/// Source hooks report for changes made by tools.
+const HOOK_SOURCE: &str = "mcp";
+
+/// Parse a lease duration, defaulting to [`DEFAULT_LEASE_DURATION`].
+fn lease_duration(lease: Option<&str>) -> Result<chrono::Duration> {
+    Ok(lease
+        .map(parse_lease_duration)
+        .transpose()?
+        .unwrap_or(DEFAULT_LEASE_DURATION))
+}
+
 /// Parse and validate a status string.
 fn validate_status(status: &str) -> Result<IssueStatus> {
     status.parse().map_err(|_| Error::InvalidArgument {
@@ -47,6 +78,15 @@ fn validate_status(status: &str) -> Result<IssueStatus> {
     })
 }
 
+/// Parse and validate a due date string.
+fn validate_due_date(due_at: &str) -> Result<DateTime<Utc>> {
+    parse_due_date(due_at).map_err(|_| Error::InvalidArgument {
+        field: "due_at",
+        value: due_at.to_string(),
+        valid_values: DUE_DATE_FORMATS,
+    })
+}
+
 /// Parse and validate a Resource Role string.
 fn validate_resource_role(role: &str) -> Result<ResourceRole> {
     role.parse().map_err(|_| Error::InvalidArgument {
@@ -86,6 +126,20 @@ fn parse_resource_target(url: Option<String>, path: Option<String>) -> Result<Re
     })
 }
 
+/// Build an Associated Resource from `resource_add` inputs.
+fn new_resource_from(
+    url: Option<String>,
+    path: Option<String>,
+    role: &str,
+    label: Option<String>,
+) -> Result<NewResource> {
+    Ok(NewResource {
+        target: parse_resource_target(url, path)?,
+        role: validate_resource_role(role)?,
+        label: label.map(ResourceLabel::new).transpose()?,
+    })
+}
+
 /// Parse and validate a dependency type string.
 fn validate_dep_type(dep_type: &str) -> Result<DependencyType> {
     dep_type.parse().map_err(|_| Error::InvalidArgument {
@@ -95,48 +149,358 @@ fn validate_dep_type(dep_type: &str) -> Result<DependencyType> {
     })
 }
 
-async fn save_or_reload(storage: &mut dyn IssueStorage) -> Result<()> {
-    if let Err(error) = storage.save().await {
-        if let Err(reload_error) = storage.reload().await {
-            tracing::error!(error = %reload_error, "Failed to reload after save error");
+/// Resolve the issue template for a workspace.
+///
+/// A missing, unparsable or invalid template is reported with the template
+/// name, whether it was named explicitly or chosen by kind, and the reason
+/// it was rejected, rather than as a storage failure.
+async fn load_template(
+    workspace_root: &Path,
+    name: Option<&str>,
+    kind: IssueKind,
+) -> Result<Option<IssueTemplate>> {
+    let rivets_dir = workspace_root.join(RIVETS_DIR_NAME);
+    IssueTemplate::resolve(&rivets_dir, name, kind)
+        .await
+        .map_err(|error| match error {
+            RivetsError::Config(
+                source @ (ConfigError::TemplateNotFound(_)
+                | ConfigError::InvalidTemplate { .. }
+                | ConfigError::Parse { .. }),
+            ) => Error::InvalidTemplate {
+                name: name.map_or_else(|| kind.to_string(), str::to_string),
+                source,
+            },
+            error => error.into(),
+        })
+}
+
+/// Load the custom field schema declared in a workspace's configuration.
+async fn load_custom_fields(workspace_root: &Path) -> Result<CustomFieldSchema> {
+    let config_path = workspace_root.join(RIVETS_DIR_NAME).join(CONFIG_FILE_NAME);
+    let config = RivetsConfig::load(&config_path)
+        .await
+        .map_err(|e| Error::ConfigLoad {
+            path: config_path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+    Ok(config.custom_fields)
+}
+
+/// Check custom field input against the workspace schema.
+///
+/// A JSON `null` yields `None`, which clears the field on update.
+fn check_custom_fields(
+    schema: &CustomFieldSchema,
+    fields: BTreeMap<String, serde_json::Value>,
+) -> Result<BTreeMap<String, Option<CustomFieldValue>>> {
+    fields
+        .into_iter()
+        .map(|(name, value)| {
+            let field_type = schema.field_type(&name)?;
+            let value = match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::Bool(value) => Some(CustomFieldValue::Bool(value)),
+                serde_json::Value::String(value) => Some(CustomFieldValue::Text(value)),
+                serde_json::Value::Number(ref number) if number.is_i64() => {
+                    number.as_i64().map(CustomFieldValue::Int)
+                }
+                value => {
+                    return Err(CustomFieldError::InvalidValue {
+                        name,
+                        value: value.to_string(),
+                        expected: field_type.to_string(),
+                    }
+                    .into());
+                }
+            };
+            let value = value.map(|value| schema.check(&name, value)).transpose()?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Check custom field input where every field must carry a value.
+fn require_custom_field_values(
+    schema: &CustomFieldSchema,
+    fields: BTreeMap<String, serde_json::Value>,
+) -> Result<BTreeMap<String, CustomFieldValue>> {
+    check_custom_fields(schema, fields)?
+        .into_iter()
+        .map(|(name, value)| {
+            value
+                .map(|value| (name.clone(), value))
+                .ok_or(Error::InvalidArgument {
+                    field: "custom_fields",
+                    value: name,
+                    valid_values: "a value for the field (null only clears fields on update)",
+                })
+        })
+        .collect()
+}
+
+/// Where a tool call records the changes it makes to a workspace.
+struct ChangeSink {
+    feed: Arc<ChangeFeed>,
+    workspace: PathBuf,
+    actor: Option<String>,
+}
+
+impl ChangeSink {
+    /// Attribute the recorded changes to `actor`.
+    fn by(self, actor: &str) -> Self {
+        Self {
+            actor: Some(actor.to_string()),
+            ..self
         }
-        return Err(error.into());
+    }
+
+    /// Record the differences between two snapshots of the workspace.
+    fn record(&self, source: &str, actor: Option<&str>, before: &[Issue], after: &[Issue]) {
+        let recorded = self
+            .feed
+            .record(&self.workspace, source, actor, before, after);
+        debug!(source, recorded, "Recorded changes");
+    }
+
+    /// Save `storage` after `tool` changed the issues in `before` into those
+    /// in `after`, and record the change.
+    ///
+    /// Only the issues a tool touched are compared, so a mutation never
+    /// copies the whole workspace. A save refused because another process
+    /// changed the file discards the in-flight mutation rather than
+    /// overwriting their changes; whatever the reload brings in is recorded
+    /// as a reload.
+    async fn save(
+        &self,
+        storage: &mut dyn IssueStorage,
+        tool: &str,
+        before: &[Issue],
+        after: &[Issue],
+    ) -> Result<()> {
+        if let Err(error) = storage.save().await {
+            let unsaved = without_change(storage.export_all().await?, before, after);
+            match storage.reload().await {
+                Ok(()) => self.record(RELOAD_SOURCE, None, &unsaved, &storage.export_all().await?),
+                Err(reload_error) => {
+                    tracing::error!(error = %reload_error, "Failed to reload after save error");
+                }
+            }
+            return Err(error.into());
+        }
+        self.record(tool, self.actor.as_deref(), before, after);
+        self.fire_hooks(before, after).await;
+        Ok(())
+    }
+
+    /// Fire the workspace's hooks for a change a tool made, in the background
+    /// so a slow hook never delays the tool's response.
+    ///
+    /// Hooks are read from `config.yaml` on every change, so edits to them
+    /// apply without restarting the server.
+    async fn fire_hooks(&self, before: &[Issue], after: &[Issue]) {
+        match Hooks::load(&self.workspace).await {
+            Ok(hooks) if !hooks.is_empty() => {
+                let before = before.to_vec();
+                let after = after.to_vec();
+                tokio::spawn(async move { hooks.fire(HOOK_SOURCE, &before, &after).await });
+            }
+            Ok(_) => {}
+            Err(error) => tracing::warn!(%error, "Failed to load hooks"),
+        }
+    }
+}
+
+/// The workspace `snapshot` as it was before a change turned the issues in
+/// `before` into those in `after`.
+fn without_change(mut snapshot: Vec<Issue>, before: &[Issue], after: &[Issue]) -> Vec<Issue> {
+    snapshot.retain(|issue| {
+        !before
+            .iter()
+            .chain(after)
+            .any(|changed| changed.id == issue.id)
+    });
+    snapshot.extend_from_slice(before);
+    snapshot
+}
+
+/// Reload `storage` if its data file changed since it was last loaded or saved,
+/// recording what the reload changed.
+///
+/// Checks under the shared lock so unchanged workspaces never block
+/// concurrent readers.
+async fn reload_if_changed(
+    storage: &RwLock<Box<dyn IssueStorage>>,
+    changes: &ChangeSink,
+) -> Result<()> {
+    if !storage.read().await.changed_on_disk().await? {
+        return Ok(());
+    }
+    let mut storage = storage.write().await;
+    // Another call may have reloaded while this one waited for the lock.
+    if storage.changed_on_disk().await? {
+        debug!("Data file changed on disk, reloading workspace");
+        let before = storage.export_all().await?;
+        storage.reload().await?;
+        changes.record(RELOAD_SOURCE, None, &before, &storage.export_all().await?);
     }
     Ok(())
 }
 
 /// Tool implementations for the rivets MCP server.
+///
+/// Each session has its own `Tools`; sessions share the [`Context`] and its
+/// workspace cache, but `set_context` only changes the calling session's
+/// workspace.
 pub struct Tools {
     context: Arc<RwLock<Context>>,
+    /// Workspace this session chose with `set_context`, overriding the
+    /// context's default workspace.
+    session_workspace: std::sync::RwLock<Option<PathBuf>>,
+    /// Name the MCP client gave when it connected, recorded as the actor of
+    /// its changes.
+    client_name: OnceLock<String>,
 }
 
 impl Tools {
     /// Create a new Tools instance with the given context.
     #[must_use]
     pub fn new(context: Arc<RwLock<Context>>) -> Self {
-        Self { context }
+        Self {
+            context,
+            session_workspace: std::sync::RwLock::new(None),
+            client_name: OnceLock::new(),
+        }
     }
 
-    /// Resolve cached storage under a shared lock, escalating only for first use.
+    /// The workspace this session chose with `set_context`, if any.
+    fn session_workspace(&self) -> Option<PathBuf> {
+        self.session_workspace
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// `workspace_root`, or this session's workspace when none is given.
+    ///
+    /// `None` falls back to the context's default workspace.
+    fn workspace_arg(&self, workspace_root: Option<&str>) -> Option<PathBuf> {
+        workspace_root
+            .map(PathBuf::from)
+            .or_else(|| self.session_workspace())
+    }
+
+    /// The workspace calls without `workspace_root` use: this session's, or
+    /// the context's default.
+    pub async fn current_workspace(&self) -> Option<PathBuf> {
+        match self.session_workspace() {
+            Some(workspace) => Some(workspace),
+            None => self.context.read().await.current_workspace().cloned(),
+        }
+    }
+
+    /// Resolve `workspace_root`, or this session's workspace when none is
+    /// given, to a canonical workspace root.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no workspace is set, or the workspace does not
+    /// exist or is not allowed.
+    pub async fn workspace_root_for(&self, workspace_root: Option<&str>) -> Result<PathBuf> {
+        let workspace = self.workspace_arg(workspace_root);
+        self.context
+            .read()
+            .await
+            .workspace_root_for(workspace.as_deref())
+    }
+
+    /// Record changes made through these tools as made by `name`.
+    ///
+    /// Only the first name is kept; a session identifies itself once.
+    pub fn set_client_name(&self, name: &str) {
+        let _ = self.client_name.set(name.to_string());
+    }
+
+    /// Resolve storage for a tool call, reloading it if its data file changed on disk.
+    ///
+    /// The CLI, `git pull` or `git checkout` may rewrite the data file while
+    /// the server holds it in memory; reloading here means every call sees
+    /// their changes and mutations apply on top of them.
     async fn storage_for(
         &self,
         workspace_root: Option<&str>,
     ) -> Result<Arc<RwLock<Box<dyn IssueStorage>>>> {
-        let workspace_path = workspace_root.map(Path::new);
+        let (storage, _) = self.storage_with_changes(workspace_root).await?;
+        Ok(storage)
+    }
+
+    /// Resolve storage for a tool call that changes it, along with where to
+    /// record those changes.
+    async fn storage_with_changes(
+        &self,
+        workspace_root: Option<&str>,
+    ) -> Result<(Arc<RwLock<Box<dyn IssueStorage>>>, ChangeSink)> {
+        let storage = self.cached_storage_for(workspace_root).await?;
+        let workspace = self.workspace_arg(workspace_root);
+        let changes = {
+            let context = self.context.read().await;
+            ChangeSink {
+                feed: Arc::clone(context.changes()),
+                workspace: context.workspace_root_for(workspace.as_deref())?,
+                actor: self.client_name.get().cloned(),
+            }
+        };
+        reload_if_changed(&storage, &changes).await?;
+        Ok((storage, changes))
+    }
+
+    /// Resolve cached storage under a shared lock, escalating only for first use.
+    async fn cached_storage_for(
+        &self,
+        workspace_root: Option<&str>,
+    ) -> Result<Arc<RwLock<Box<dyn IssueStorage>>>> {
+        let workspace_path = self.workspace_arg(workspace_root);
         {
             let context = self.context.read().await;
-            match context.storage_for(workspace_path) {
+            match context.storage_for(workspace_path.as_deref()) {
                 Ok(storage) => return Ok(storage),
                 Err(Error::WorkspaceNotInitialized(_)) => {}
                 Err(error) => return Err(error),
             }
         }
 
+        let session_workspace = self.session_workspace();
         let mut context = self.context.write().await;
-        context.storage_for_or_init(workspace_path).await
+        context
+            .storage_for_or_init(workspace_path.as_deref(), session_workspace.as_deref())
+            .await
     }
 
-    /// Set the workspace context.
+    /// Resolve the `.rivets` directory of the requested or current workspace.
+    async fn rivets_dir_for(&self, workspace_root: Option<&str>) -> Result<PathBuf> {
+        let workspace_root = self.workspace_root_for(workspace_root).await?;
+        Ok(workspace_root.join(RIVETS_DIR_NAME))
+    }
+
+    /// Check that `milestone` exists in the resolved workspace.
+    async fn require_milestone_in(
+        &self,
+        workspace_root: Option<&str>,
+        milestone: &str,
+    ) -> Result<()> {
+        let rivets_dir = self.rivets_dir_for(workspace_root).await?;
+        Ok(require_milestone(&rivets_dir, milestone).await?)
+    }
+
+    /// Load the custom field schema of the resolved workspace.
+    async fn custom_fields_for(&self, workspace_root: Option<&str>) -> Result<CustomFieldSchema> {
+        let workspace_root = self.workspace_root_for(workspace_root).await?;
+        load_custom_fields(&workspace_root).await
+    }
+
+    /// Set this session's workspace context.
+    ///
+    /// Other sessions sharing the context keep their own workspace.
     ///
     /// # Errors
     ///
@@ -145,8 +509,11 @@ impl Tools {
     pub async fn set_context(&self, workspace_root: &str) -> Result<SetContextResponse> {
         debug!("Setting workspace context");
         let path = Path::new(workspace_root);
-        let mut context = self.context.write().await;
-        let info = context.set_workspace(path).await?;
+        let info = self.context.write().await.open_workspace(path).await?;
+        *self
+            .session_workspace
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(info.workspace_root.clone());
 
         debug!(db_path = %info.database_path.display(), "Context set successfully");
         Ok(SetContextResponse {
@@ -162,11 +529,9 @@ impl Tools {
     ///
     /// This function does not currently return errors but returns `Result` for API consistency.
     pub async fn where_am_i(&self) -> Result<WhereAmIResponse> {
-        let context = self.context.read().await;
-
-        match context.current_workspace() {
+        match self.current_workspace().await {
             Some(workspace) => {
-                let db_path = context.current_database_path();
+                let db_path = self.context.read().await.database_path(&workspace).cloned();
 
                 // Try to load the config to get the issue prefix
                 let config_path = workspace.join(".rivets").join("config.yaml");
@@ -211,6 +576,23 @@ impl Tools {
         debug!("Finding ready issues");
         let issue_kind = params.kind.resolve("ready");
 
+        let milestone = match (params.milestone, params.current_milestone) {
+            (Some(milestone), Some(true)) => {
+                return Err(Error::InvalidArgument {
+                    field: "milestone",
+                    value: milestone,
+                    valid_values: "a milestone name, or current_milestone: true, but not both",
+                });
+            }
+            (None, Some(true)) => {
+                let rivets_dir = self
+                    .rivets_dir_for(params.workspace_root.as_deref())
+                    .await?;
+                Some(current_milestone_name(&rivets_dir, Utc::now().date_naive()).await?)
+            }
+            (milestone, _) => milestone,
+        };
+
         // Release context lock before acquiring storage lock to prevent deadlocks
         let storage = self.storage_for(params.workspace_root.as_deref()).await?;
         let storage = storage.read().await;
@@ -220,6 +602,8 @@ impl Tools {
             issue_kind,
             assignee: params.assignee,
             label: params.label,
+            milestone,
+            claimant: params.agent,
             limit: Some(params.limit.unwrap_or(DEFAULT_QUERY_LIMIT)),
             ..Default::default()
         };
@@ -244,6 +628,17 @@ impl Tools {
         let issue_kind = params.kind.resolve("list");
 
         let storage = self.storage_for(params.workspace_root.as_deref()).await?;
+        let custom_fields = match params.custom_fields {
+            Some(fields) => {
+                let schema = self
+                    .custom_fields_for(params.workspace_root.as_deref())
+                    .await?;
+                require_custom_field_values(&schema, fields)?
+                    .into_iter()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
         let storage = storage.read().await;
 
         let filter = IssueFilter {
@@ -252,6 +647,10 @@ impl Tools {
             issue_kind,
             assignee: params.assignee,
             label: params.label,
+            custom_fields,
+            overdue: params.overdue.unwrap_or(false),
+            milestone: params.milestone,
+            claimant: None,
             limit: Some(params.limit.unwrap_or(DEFAULT_QUERY_LIMIT)),
         };
 
@@ -303,29 +702,73 @@ impl Tools {
     #[instrument(skip(self, params), fields(title = %params.title))]
     pub async fn create(&self, params: CreateParams) -> Result<Issue> {
         debug!("Creating issue");
-        let issue_kind = params.kind.resolve("create").unwrap_or(IssueKind::Task);
+        let (storage, changes) = self
+            .storage_with_changes(params.workspace_root.as_deref())
+            .await?;
+        let new_issue = self.new_issue_from(params).await?;
+        let mut storage = storage.write().await;
+
+        let issue = storage.create(new_issue).await?;
+        changes
+            .save(storage.as_mut(), "create", &[], slice::from_ref(&issue))
+            .await?;
+        debug!(issue_id = %issue.id, "Created issue");
+        Ok(issue)
+    }
+
+    /// Build the issue a `create` call describes, applying its template.
+    async fn new_issue_from(&self, params: CreateParams) -> Result<NewIssue> {
+        let issue_kind = params.kind.resolve("create");
         let initial_note = params.initial_note.map(NoteContent::new).transpose()?;
+        let due_at = params
+            .due_at
+            .as_deref()
+            .map(validate_due_date)
+            .transpose()?;
+        let explicit = ExplicitFields {
+            kind: issue_kind.is_some(),
+            priority: params.priority.is_some(),
+            description: params.description.is_some(),
+        };
 
-        let storage = self.storage_for(params.workspace_root.as_deref()).await?;
-        let mut storage = storage.write().await;
+        let workspace_root = self
+            .workspace_root_for(params.workspace_root.as_deref())
+            .await?;
+        let custom_fields = match params.custom_fields {
+            Some(fields) => {
+                require_custom_field_values(&load_custom_fields(&workspace_root).await?, fields)?
+            }
+            None => BTreeMap::new(),
+        };
+        if let Some(milestone) = &params.milestone {
+            require_milestone(&workspace_root.join(RIVETS_DIR_NAME), milestone).await?;
+        }
 
-        let new_issue = NewIssue {
+        let mut new_issue = NewIssue {
             title: params.title,
             description: params.description.unwrap_or_default(),
             priority: params.priority.unwrap_or(2),
-            issue_kind,
+            issue_kind: issue_kind.unwrap_or(IssueKind::Task),
             assignee: params.assignee,
             labels: params.labels.unwrap_or_default(),
             design: params.design,
             acceptance_criteria: params.acceptance,
             initial_note,
             dependencies: vec![],
+            custom_fields,
+            due_at,
+            milestone: params.milestone,
         };
-
-        let issue = storage.create(new_issue).await?;
-        save_or_reload(storage.as_mut()).await?;
-        debug!(issue_id = %issue.id, "Created issue");
-        Ok(issue)
+        if let Some(template) = load_template(
+            &workspace_root,
+            params.template.as_deref(),
+            new_issue.issue_kind,
+        )
+        .await?
+        {
+            template.apply(&mut new_issue, explicit);
+        }
+        Ok(new_issue)
     }
 
     /// Update an existing issue.
@@ -335,17 +778,66 @@ impl Tools {
     #[instrument(skip(self, params), fields(issue_id = %params.issue_id))]
     pub async fn update(&self, params: UpdateParams) -> Result<Issue> {
         debug!("Updating issue");
+        let (storage, changes) = self
+            .storage_with_changes(params.workspace_root.as_deref())
+            .await?;
+        let id = IssueId::new(&params.issue_id);
+        let updates = self.issue_update_from(params).await?;
+        let mut storage = storage.write().await;
+        let before = storage.get(&id).await?;
+
+        let issue = storage.update(&id, updates).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "update",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
+        debug!("Updated issue");
+        Ok(issue)
+    }
+
+    /// Build the changes an `update` call describes.
+    async fn issue_update_from(&self, params: UpdateParams) -> Result<IssueUpdate> {
         let status = params.status.as_deref().map(validate_status).transpose()?;
         let issue_kind = params.kind.resolve("update");
         let assignee = params
             .assignee
             .map(|value| if value.is_empty() { None } else { Some(value) });
+        let due_at = params
+            .due_at
+            .as_deref()
+            .map(|value| {
+                if value.is_empty() {
+                    Ok(None)
+                } else {
+                    validate_due_date(value).map(Some)
+                }
+            })
+            .transpose()?;
+
+        let custom_fields = match params.custom_fields {
+            Some(fields) => {
+                let schema = self
+                    .custom_fields_for(params.workspace_root.as_deref())
+                    .await?;
+                check_custom_fields(&schema, fields)?
+            }
+            None => BTreeMap::new(),
+        };
+        let milestone = match params.milestone {
+            Some(milestone) if milestone.is_empty() => Some(None),
+            Some(milestone) => {
+                self.require_milestone_in(params.workspace_root.as_deref(), &milestone)
+                    .await?;
+                Some(Some(milestone))
+            }
+            None => None,
+        };
 
-        let storage = self.storage_for(params.workspace_root.as_deref()).await?;
-        let mut storage = storage.write().await;
-
-        let id = IssueId::new(&params.issue_id);
-        let updates = IssueUpdate {
+        Ok(IssueUpdate {
             title: params.title,
             description: params.description,
             status,
@@ -356,12 +848,10 @@ impl Tools {
             acceptance_criteria: params.acceptance_criteria,
             note: None,
             labels: params.labels,
-        };
-
-        let issue = storage.update(&id, updates).await?;
-        save_or_reload(storage.as_mut()).await?;
-        debug!("Updated issue");
-        Ok(issue)
+            custom_fields,
+            due_at,
+            milestone,
+        })
     }
 
     /// Append an immutable Note to an Issue.
@@ -378,19 +868,28 @@ impl Tools {
         workspace_root: Option<&str>,
     ) -> Result<Issue> {
         let note = NoteContent::new(content)?;
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
+        let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
 
         let issue = storage
             .update(
-                &IssueId::new(issue_id),
+                &id,
                 IssueUpdate {
                     note: Some(note),
                     ..Default::default()
                 },
             )
             .await?;
-        save_or_reload(storage.as_mut()).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "add_note",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
         Ok(issue)
     }
 
@@ -411,19 +910,21 @@ impl Tools {
         label: Option<String>,
         workspace_root: Option<&str>,
     ) -> Result<Issue> {
-        let target = parse_resource_target(url, path)?;
-        let resource = NewResource {
-            target,
-            role: validate_resource_role(role)?,
-            label: label.map(ResourceLabel::new).transpose()?,
-        };
-        let storage = self.storage_for(workspace_root).await?;
+        let resource = new_resource_from(url, path, role, label)?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
-
-        let issue = storage
-            .add_resource(&IssueId::new(issue_id), resource)
+        let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
+
+        let issue = storage.add_resource(&id, resource).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "resource_add",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
             .await?;
-        save_or_reload(storage.as_mut()).await?;
         Ok(issue)
     }
 
@@ -467,17 +968,22 @@ impl Tools {
             role: role.as_deref().map(validate_resource_role).transpose()?,
             label,
         };
-        let storage = self.storage_for(workspace_root.as_deref()).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root.as_deref()).await?;
         let mut storage = storage.write().await;
+        let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
 
         let issue = storage
-            .update_resource(
-                &IssueId::new(issue_id),
-                &ResourceId::new(resource_id)?,
-                update,
+            .update_resource(&id, &ResourceId::new(resource_id)?, update)
+            .await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "resource_update",
+                before.as_slice(),
+                slice::from_ref(&issue),
             )
             .await?;
-        save_or_reload(storage.as_mut()).await?;
         Ok(issue)
     }
 
@@ -496,13 +1002,22 @@ impl Tools {
         resource_id: &str,
         workspace_root: Option<&str>,
     ) -> Result<Issue> {
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
+        let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
 
         let issue = storage
-            .remove_resource(&IssueId::new(issue_id), &ResourceId::new(resource_id)?)
+            .remove_resource(&id, &ResourceId::new(resource_id)?)
+            .await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "resource_remove",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
             .await?;
-        save_or_reload(storage.as_mut()).await?;
         Ok(issue)
     }
 
@@ -541,10 +1056,11 @@ impl Tools {
     ) -> Result<Issue> {
         debug!("Closing issue");
         let note = reason.map(NoteContent::closing_reason).transpose()?;
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
 
         let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
         let updates = IssueUpdate {
             status: Some(rivets::domain::IssueStatus::Closed),
             note,
@@ -552,7 +1068,14 @@ impl Tools {
         };
 
         let issue = storage.update(&id, updates).await?;
-        save_or_reload(storage.as_mut()).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "close",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
         debug!("Closed issue");
         Ok(issue)
     }
@@ -578,14 +1101,18 @@ impl Tools {
             .transpose()?
             .unwrap_or(DependencyType::Blocks);
 
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
 
         let from = IssueId::new(issue_id);
         let to = IssueId::new(depends_on_id);
+        let before = storage.get(&from).await?;
 
         storage.add_dependency(&from, &to, dep_type).await?;
-        save_or_reload(storage.as_mut()).await?;
+        let after = storage.get(&from).await?;
+        changes
+            .save(storage.as_mut(), "dep", before.as_slice(), after.as_slice())
+            .await?;
 
         let dep_type_str = dep_type.to_string();
         debug!(dep_type = %dep_type_str, "Added dependency");
@@ -594,6 +1121,130 @@ impl Tools {
         ))
     }
 
+    /// Remove a dependency between issues.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, either issue is not found, the
+    /// dependency does not exist, or storage fails.
+    #[instrument(skip(self), fields(%issue_id, %depends_on_id))]
+    pub async fn dep_remove(
+        &self,
+        issue_id: &str,
+        depends_on_id: &str,
+        workspace_root: Option<&str>,
+    ) -> Result<DepRemoveResponse> {
+        debug!("Removing dependency");
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
+        let mut storage = storage.write().await;
+        let from = IssueId::new(issue_id);
+        let before = storage.get(&from).await?;
+
+        storage
+            .remove_dependency(&from, &IssueId::new(depends_on_id))
+            .await?;
+        let after = storage.get(&from).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "dep_remove",
+                before.as_slice(),
+                after.as_slice(),
+            )
+            .await?;
+
+        debug!("Removed dependency");
+        Ok(DepRemoveResponse {
+            action: "remove".to_string(),
+            from: issue_id.to_string(),
+            to: depends_on_id.to_string(),
+            status: "success".to_string(),
+        })
+    }
+
+    /// List an issue's dependencies, or its dependents when `reverse` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, the issue is not found, or
+    /// storage operations fail.
+    #[instrument(skip(self), fields(%issue_id, reverse))]
+    pub async fn dep_list(
+        &self,
+        issue_id: &str,
+        reverse: bool,
+        workspace_root: Option<&str>,
+    ) -> Result<Vec<Dependency>> {
+        let storage = self.storage_for(workspace_root).await?;
+        let storage = storage.read().await;
+
+        let id = IssueId::new(issue_id);
+        let deps = if reverse {
+            storage.get_dependents(&id).await?
+        } else {
+            storage.get_dependencies(&id).await?
+        };
+        Ok(deps)
+    }
+
+    /// Build the dependency tree of an issue, in the `rivets dep tree --json` shape.
+    ///
+    /// `depth` defaults to 5; 0 means unlimited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, the issue is not found, or
+    /// storage operations fail.
+    #[instrument(skip(self), fields(%issue_id, ?depth))]
+    pub async fn dep_tree(
+        &self,
+        issue_id: &str,
+        depth: Option<usize>,
+        workspace_root: Option<&str>,
+    ) -> Result<serde_json::Value> {
+        let max_depth = match depth.unwrap_or(DEFAULT_DEP_TREE_DEPTH) {
+            0 => None,
+            depth => Some(depth),
+        };
+        let storage = self.storage_for(workspace_root).await?;
+        let storage = storage.read().await;
+
+        let id = IssueId::new(issue_id);
+        let root = build_dep_tree(storage.as_ref(), &id, max_depth).await?;
+        let dependents = storage.get_dependents(&id).await?;
+        Ok(dep_tree_to_json_public(&root, &dependents))
+    }
+
+    /// Delete an issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, the issue is not found, other
+    /// issues still depend on it, or storage operations fail.
+    #[instrument(skip(self), fields(%issue_id))]
+    pub async fn delete(
+        &self,
+        issue_id: &str,
+        workspace_root: Option<&str>,
+    ) -> Result<DeleteResponse> {
+        debug!("Deleting issue");
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
+        let mut storage = storage.write().await;
+        let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
+
+        storage.delete(&id).await?;
+        changes
+            .save(storage.as_mut(), "delete", before.as_slice(), &[])
+            .await?;
+
+        debug!("Deleted issue");
+        Ok(DeleteResponse {
+            deleted: issue_id.to_string(),
+            status: "success".to_string(),
+        })
+    }
+
     /// Reopen a closed issue.
     ///
     /// # Errors
@@ -609,10 +1260,11 @@ impl Tools {
     ) -> Result<Issue> {
         debug!("Reopening issue");
         let note = reason.map(NoteContent::reopening_reason).transpose()?;
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
 
         let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
         let updates = IssueUpdate {
             status: Some(IssueStatus::Open),
             note,
@@ -620,7 +1272,14 @@ impl Tools {
         };
 
         let issue = storage.update(&id, updates).await?;
-        save_or_reload(storage.as_mut()).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "reopen",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
         debug!("Reopened issue");
         Ok(issue)
     }
@@ -658,13 +1317,14 @@ impl Tools {
             ..Default::default()
         };
 
-        let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(days));
+        let now = chrono::Utc::now();
+        let cutoff = now - chrono::Duration::days(i64::from(days));
         let issues = storage.list(&filter).await?;
 
-        // Filter by updated_at timestamp and apply limit
+        // Filter by updated_at timestamp or expired claim and apply limit
         let stale_issues: Vec<Issue> = issues
             .into_iter()
-            .filter(|issue| issue.updated_at < cutoff)
+            .filter(|issue| issue.updated_at < cutoff || issue.has_expired_lease(now))
             .take(limit)
             .collect();
 
@@ -672,6 +1332,114 @@ impl Tools {
         Ok(stale_issues)
     }
 
+    /// Claim an issue for an agent, or the next ready issue if no ID is
+    /// given.
+    ///
+    /// Returns `None` when no ready issue can be claimed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, the lease is invalid, the
+    /// issue is not found or cannot be claimed, or storage operations fail.
+    #[instrument(skip(self, params), fields(issue_id = ?params.issue_id, agent = %params.agent))]
+    pub async fn claim(&self, params: ClaimParams) -> Result<Option<Issue>> {
+        debug!("Claiming issue");
+        let lease = lease_duration(params.lease.as_deref())?;
+        let (storage, changes) = self
+            .storage_with_changes(params.workspace_root.as_deref())
+            .await?;
+        let mut storage = storage.write().await;
+
+        let claimed = match params.issue_id {
+            Some(issue_id) => {
+                let id = IssueId::new(issue_id);
+                let before = storage.get(&id).await?;
+                let issue = storage.claim(&id, &params.agent, lease).await?;
+                before.map(|before| (before, issue))
+            }
+            None => claim_next_with_previous(storage.as_mut(), &params.agent, lease).await?,
+        };
+        if let Some((before, issue)) = &claimed {
+            changes
+                .by(&params.agent)
+                .save(
+                    storage.as_mut(),
+                    "claim",
+                    slice::from_ref(before),
+                    slice::from_ref(issue),
+                )
+                .await?;
+        }
+        let claimed = claimed.map(|(_, issue)| issue);
+        debug!(claimed = claimed.is_some(), "Claimed issue");
+        Ok(claimed)
+    }
+
+    /// Extend an agent's claim on an issue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, the lease is invalid, the
+    /// issue is not found, the agent does not hold the claim, or storage
+    /// operations fail.
+    #[instrument(skip(self, params), fields(issue_id = %params.issue_id, agent = %params.agent))]
+    pub async fn renew(&self, params: RenewParams) -> Result<Issue> {
+        debug!("Renewing claim");
+        let lease = lease_duration(params.lease.as_deref())?;
+        let (storage, changes) = self
+            .storage_with_changes(params.workspace_root.as_deref())
+            .await?;
+        let mut storage = storage.write().await;
+
+        let id = IssueId::new(params.issue_id);
+        let before = storage.get(&id).await?;
+        let issue = storage.renew_lease(&id, &params.agent, lease).await?;
+        changes
+            .by(&params.agent)
+            .save(
+                storage.as_mut(),
+                "renew",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
+        debug!("Renewed claim");
+        Ok(issue)
+    }
+
+    /// Release an agent's claim on an issue and unassign it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set, the issue is not found, the
+    /// agent does not hold the claim, or storage operations fail.
+    #[instrument(skip(self), fields(%issue_id, %agent))]
+    pub async fn release(
+        &self,
+        issue_id: &str,
+        agent: &str,
+        workspace_root: Option<&str>,
+    ) -> Result<Issue> {
+        debug!("Releasing claim");
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
+        let mut storage = storage.write().await;
+
+        let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
+        let issue = storage.release(&id, agent).await?;
+        changes
+            .by(agent)
+            .save(
+                storage.as_mut(),
+                "release",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
+        debug!("Released claim");
+        Ok(issue)
+    }
+
     /// Add a label to an issue.
     ///
     /// # Errors
@@ -685,12 +1453,20 @@ impl Tools {
         workspace_root: Option<&str>,
     ) -> Result<Issue> {
         debug!("Adding label to issue");
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
 
         let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
         let issue = storage.add_label(&id, label).await?;
-        save_or_reload(storage.as_mut()).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "label_add",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
         debug!("Added label");
         Ok(issue)
     }
@@ -708,12 +1484,20 @@ impl Tools {
         workspace_root: Option<&str>,
     ) -> Result<Issue> {
         debug!("Removing label from issue");
-        let storage = self.storage_for(workspace_root).await?;
+        let (storage, changes) = self.storage_with_changes(workspace_root).await?;
         let mut storage = storage.write().await;
 
         let id = IssueId::new(issue_id);
+        let before = storage.get(&id).await?;
         let issue = storage.remove_label(&id, label).await?;
-        save_or_reload(storage.as_mut()).await?;
+        changes
+            .save(
+                storage.as_mut(),
+                "label_remove",
+                before.as_slice(),
+                slice::from_ref(&issue),
+            )
+            .await?;
         debug!("Removed label");
         Ok(issue)
     }
@@ -764,6 +1548,340 @@ impl Tools {
         debug!(count = labels.len(), "Found unique labels");
         Ok(labels)
     }
+
+    /// Get workspace statistics, in the `rivets stats --json` shape.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set or storage operations fail.
+    #[instrument(skip(self))]
+    pub async fn stats(&self, detailed: bool, workspace_root: Option<&str>) -> Result<IssueStats> {
+        let storage = self.storage_for(workspace_root).await?;
+        let storage = storage.read().await;
+
+        Ok(collect_stats(storage.as_ref(), detailed, Utc::now()).await?)
+    }
+
+    /// Get the changes recorded for a workspace after `cursor`.
+    ///
+    /// Changes made on disk since the workspace was last read are picked up
+    /// first, so they are included. If no limit is specified, defaults to
+    /// [`DEFAULT_QUERY_LIMIT`] (100) events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no context is set or reloading the workspace fails.
+    #[instrument(skip(self), fields(?cursor, ?limit))]
+    pub async fn changes_since(
+        &self,
+        cursor: Option<u64>,
+        limit: Option<usize>,
+        workspace_root: Option<&str>,
+    ) -> Result<ChangesSinceResponse> {
+        let (_, changes) = self.storage_with_changes(workspace_root).await?;
+        let response = changes.feed.since(
+            &changes.workspace,
+            cursor,
+            limit.unwrap_or(DEFAULT_QUERY_LIMIT),
+        );
+        debug!(
+            count = response.events.len(),
+            cursor = response.cursor,
+            "Found changes"
+        );
+        Ok(response)
+    }
+
+    /// Apply a batch of operations atomically.
+    ///
+    /// Every operation is validated first, then all are applied to the
+    /// in-memory storage under one write lock and saved once. If any
+    /// operation fails, the issues the batch touched are put back as they
+    /// were and the issues it created are removed, discarding the operations
+    /// already applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BatchOperation` naming the first operation that failed
+    /// validation or could not be applied, or an error if no context is set
+    /// or the save fails.
+    #[instrument(skip(self, params), fields(operations = params.operations.len()))]
+    pub async fn batch(&self, params: BatchParams) -> Result<BatchResponse> {
+        debug!("Applying batch");
+        let BatchParams {
+            operations,
+            workspace_root,
+        } = params;
+        let (storage, changes) = self.storage_with_changes(workspace_root.as_deref()).await?;
+
+        let mut steps = Vec::with_capacity(operations.len());
+        for (index, operation) in operations.into_iter().enumerate() {
+            let step = self
+                .prepare_batch_step(operation, workspace_root.as_deref())
+                .await
+                .map_err(|source| Error::BatchOperation {
+                    index,
+                    source: Box::new(source),
+                })?;
+            steps.push(step);
+        }
+
+        let mut storage = storage.write().await;
+        let mut handles = BTreeMap::new();
+        let mut touched = Touched::default();
+        for (index, step) in steps.into_iter().enumerate() {
+            if let Err(source) =
+                apply_batch_step(storage.as_mut(), step, &mut handles, &mut touched).await
+            {
+                if let Err(restore_error) = storage
+                    .restore_issues(touched.before, &touched.created)
+                    .await
+                {
+                    tracing::error!(error = %restore_error, "Failed to roll back batch");
+                }
+                return Err(Error::BatchOperation {
+                    index,
+                    source: Box::new(source),
+                });
+            }
+        }
+
+        let mut issues = Vec::with_capacity(touched.ids.len());
+        for id in &touched.ids {
+            if let Some(issue) = storage.get(id).await? {
+                issues.push(issue);
+            }
+        }
+        changes
+            .save(storage.as_mut(), "batch", &touched.before, &issues)
+            .await?;
+        debug!(issues = issues.len(), "Applied batch");
+        Ok(BatchResponse { handles, issues })
+    }
+
+    /// Validate a batch operation and resolve it into a step to apply.
+    async fn prepare_batch_step(
+        &self,
+        operation: BatchOperation,
+        workspace_root: Option<&str>,
+    ) -> Result<BatchStep> {
+        let batch_workspace = workspace_root.map(str::to_string);
+        Ok(match operation {
+            BatchOperation::Create { handle, mut params } => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                params.workspace_root = batch_workspace;
+                BatchStep::Create {
+                    handle,
+                    issue: self.new_issue_from(params).await?,
+                }
+            }
+            BatchOperation::Update(mut params) => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                params.workspace_root = batch_workspace;
+                BatchStep::Update {
+                    issue_id: params.issue_id.clone(),
+                    update: self.issue_update_from(params).await?,
+                }
+            }
+            BatchOperation::Dep(params) => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                BatchStep::Dep {
+                    dep_type: params
+                        .dep_type
+                        .as_deref()
+                        .map(validate_dep_type)
+                        .transpose()?
+                        .unwrap_or(DependencyType::Blocks),
+                    issue_id: params.issue_id,
+                    depends_on_id: params.depends_on_id,
+                }
+            }
+            BatchOperation::LabelAdd(params) => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                BatchStep::LabelAdd {
+                    issue_id: params.issue_id,
+                    label: params.label,
+                }
+            }
+            BatchOperation::LabelRemove(params) => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                BatchStep::LabelRemove {
+                    issue_id: params.issue_id,
+                    label: params.label,
+                }
+            }
+            BatchOperation::ResourceAdd(params) => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                BatchStep::ResourceAdd {
+                    resource: new_resource_from(
+                        params.url,
+                        params.path,
+                        &params.role,
+                        params.label,
+                    )?,
+                    issue_id: params.issue_id,
+                }
+            }
+            BatchOperation::Note(params) => {
+                reject_operation_workspace(params.workspace_root.as_deref())?;
+                BatchStep::Update {
+                    issue_id: params.issue_id,
+                    update: IssueUpdate {
+                        note: Some(NoteContent::new(params.content)?),
+                        ..Default::default()
+                    },
+                }
+            }
+        })
+    }
+}
+
+/// A validated batch operation, ready to apply to storage.
+///
+/// Issue IDs are kept as given so `@<handle>` references can be resolved
+/// once the issues they name have been created.
+enum BatchStep {
+    Create {
+        handle: Option<String>,
+        issue: NewIssue,
+    },
+    Update {
+        issue_id: String,
+        update: IssueUpdate,
+    },
+    Dep {
+        issue_id: String,
+        depends_on_id: String,
+        dep_type: DependencyType,
+    },
+    LabelAdd {
+        issue_id: String,
+        label: String,
+    },
+    LabelRemove {
+        issue_id: String,
+        label: String,
+    },
+    ResourceAdd {
+        issue_id: String,
+        resource: NewResource,
+    },
+}
+
+/// Reject a `workspace_root` set on a single batch operation.
+fn reject_operation_workspace(workspace_root: Option<&str>) -> Result<()> {
+    match workspace_root {
+        Some(value) => Err(Error::InvalidArgument {
+            field: "workspace_root",
+            value: value.to_string(),
+            valid_values: "unset on batch operations; set workspace_root on the batch",
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Resolve an issue ID, or an `@<handle>` of an issue created earlier in the batch.
+fn resolve_batch_id(reference: &str, handles: &BTreeMap<String, String>) -> Result<IssueId> {
+    match reference.strip_prefix('@') {
+        Some(handle) => handles.get(handle).map(IssueId::new).ok_or_else(|| {
+            Error::InvalidArgument {
+                field: "issue_id",
+                value: reference.to_string(),
+                valid_values: "an issue ID, or @<handle> of an issue created earlier in the batch",
+            }
+        }),
+        None => Ok(IssueId::new(reference)),
+    }
+}
+
+/// The issues a batch has touched so far, in first-touched order, with how
+/// the ones that already existed looked before the batch and which ones it
+/// created, so a failed batch can be rolled back.
+#[derive(Default)]
+struct Touched {
+    ids: Vec<IssueId>,
+    before: Vec<Issue>,
+    created: Vec<IssueId>,
+}
+
+impl Touched {
+    /// Note an issue the batch created.
+    fn created(&mut self, id: IssueId) {
+        self.ids.push(id.clone());
+        self.created.push(id);
+    }
+
+    /// Note that a step is about to change `ids`, remembering the current
+    /// version of any issue the batch has not touched yet.
+    async fn changing(&mut self, storage: &dyn IssueStorage, ids: &[&IssueId]) -> Result<()> {
+        for &id in ids {
+            if self.ids.contains(id) {
+                continue;
+            }
+            if let Some(issue) = storage.get(id).await? {
+                self.before.push(issue);
+            }
+            self.ids.push(id.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Apply one batch step, recording created handles and the issues it touched.
+async fn apply_batch_step(
+    storage: &mut dyn IssueStorage,
+    step: BatchStep,
+    handles: &mut BTreeMap<String, String>,
+    touched: &mut Touched,
+) -> Result<()> {
+    match step {
+        BatchStep::Create { handle, issue } => {
+            let issue = storage.create(issue).await?;
+            if let Some(handle) = handle
+                && handles
+                    .insert(handle.clone(), issue.id.to_string())
+                    .is_some()
+            {
+                return Err(Error::InvalidArgument {
+                    field: "handle",
+                    value: handle,
+                    valid_values: "a handle not used by an earlier operation in the batch",
+                });
+            }
+            touched.created(issue.id);
+        }
+        BatchStep::Update { issue_id, update } => {
+            let id = resolve_batch_id(&issue_id, handles)?;
+            touched.changing(storage, &[&id]).await?;
+            storage.update(&id, update).await?;
+        }
+        BatchStep::Dep {
+            issue_id,
+            depends_on_id,
+            dep_type,
+        } => {
+            let from = resolve_batch_id(&issue_id, handles)?;
+            let to = resolve_batch_id(&depends_on_id, handles)?;
+            touched.changing(storage, &[&from, &to]).await?;
+            storage.add_dependency(&from, &to, dep_type).await?;
+        }
+        BatchStep::LabelAdd { issue_id, label } => {
+            let id = resolve_batch_id(&issue_id, handles)?;
+            touched.changing(storage, &[&id]).await?;
+            storage.add_label(&id, &label).await?;
+        }
+        BatchStep::LabelRemove { issue_id, label } => {
+            let id = resolve_batch_id(&issue_id, handles)?;
+            touched.changing(storage, &[&id]).await?;
+            storage.remove_label(&id, &label).await?;
+        }
+        BatchStep::ResourceAdd { issue_id, resource } => {
+            let id = resolve_batch_id(&issue_id, handles)?;
+            touched.changing(storage, &[&id]).await?;
+            storage.add_resource(&id, resource).await?;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -865,6 +1983,9 @@ mod tests {
             kind: kind_input(issue_kind),
             assignee,
             label,
+            milestone: None,
+            current_milestone: None,
+            agent: None,
             workspace_root: workspace_root.map(str::to_string),
         }
     }
@@ -884,8 +2005,11 @@ mod tests {
             kind: kind_input(issue_kind),
             assignee,
             label,
+            custom_fields: None,
             limit,
             workspace_root: workspace_root.map(str::to_string),
+            overdue: None,
+            milestone: None,
         }
     }
 
@@ -911,7 +2035,11 @@ mod tests {
             design,
             acceptance,
             initial_note: None,
+            template: None,
+            custom_fields: None,
             workspace_root: workspace_root.map(str::to_string),
+            due_at: None,
+            milestone: None,
         }
     }
 
@@ -940,7 +2068,10 @@ mod tests {
             design,
             acceptance_criteria,
             labels,
+            custom_fields: None,
             workspace_root: workspace_root.map(str::to_string),
+            due_at: None,
+            milestone: None,
         }
     }
 
@@ -1121,6 +2252,58 @@ mod tests {
         assert!(result.contains("blocks"));
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_failed_batch_rolls_back_in_memory_storage(#[future] tools: Tools) {
+        let tools = tools.await;
+        let existing = create_issue(&tools, "Existing").await;
+        let blocker = create_issue(&tools, "Blocker").await;
+        let params: BatchParams = serde_json::from_value(serde_json::json!({
+            "operations": [
+                { "op": "label_add", "issue_id": existing.id.as_str(), "label": "backend" },
+                { "op": "dep", "issue_id": existing.id.as_str(), "depends_on_id": blocker.id.as_str() },
+                { "op": "create", "handle": "api", "title": "API" },
+                { "op": "dep", "issue_id": blocker.id.as_str(), "depends_on_id": "@api" },
+                { "op": "label_add", "issue_id": "test-missing", "label": "never" }
+            ]
+        }))
+        .unwrap();
+
+        let error = tools.batch(params).await.unwrap_err();
+        assert!(matches!(error, Error::BatchOperation { index: 4, .. }));
+
+        let issues = tools
+            .list(list_params(None, None, None, None, None, None, None))
+            .await
+            .unwrap();
+        let mut titles: Vec<_> = issues.iter().map(|issue| issue.title.as_str()).collect();
+        titles.sort_unstable();
+        assert_eq!(titles, ["Blocker", "Existing"]);
+        let restored = tools.show(existing.id.as_str(), None).await.unwrap();
+        assert!(restored.labels.is_empty());
+        assert!(
+            tools
+                .dep_list(existing.id.as_str(), false, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            tools
+                .dep_list(blocker.id.as_str(), false, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            tools
+                .dep_list(blocker.id.as_str(), true, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_blocked_issues(#[future] tools: Tools) {
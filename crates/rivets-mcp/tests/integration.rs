@@ -12,9 +12,13 @@ use rivets::domain::{
     Issue, IssueKind, IssueStatus, ResourceTarget, StatusTransitionError, WorkspacePath,
 };
 use rivets::error::{Error as RivetsError, StorageError};
+use rivets_mcp::changes::{ChangeKind, RELOAD_SOURCE};
 use rivets_mcp::context::Context;
 use rivets_mcp::error::Error;
-use rivets_mcp::models::{CreateParams, IssueKindInput, ListParams, ReadyParams, UpdateParams};
+use rivets_mcp::models::{
+    BatchParams, ClaimParams, CreateParams, DepTreeResponse, IssueKindInput, ListParams,
+    ReadyParams, RenewParams, UpdateParams,
+};
 use rivets_mcp::tools::Tools;
 use rmcp::model::Content;
 use rstest::rstest;
@@ -44,6 +48,9 @@ fn ready_params(
         kind: kind_input(issue_kind),
         assignee,
         label,
+        milestone: None,
+        current_milestone: None,
+        agent: None,
         workspace_root: workspace_root.map(str::to_string),
     }
 }
@@ -63,8 +70,11 @@ fn list_params(
         kind: kind_input(issue_kind),
         assignee,
         label,
+        custom_fields: None,
         limit,
         workspace_root: workspace_root.map(str::to_string),
+        overdue: None,
+        milestone: None,
     }
 }
 
@@ -90,7 +100,11 @@ fn create_params(
         design,
         acceptance,
         initial_note: None,
+        template: None,
+        custom_fields: None,
         workspace_root: workspace_root.map(str::to_string),
+        due_at: None,
+        milestone: None,
     }
 }
 
@@ -119,7 +133,10 @@ fn update_params(
         design,
         acceptance_criteria,
         labels,
+        custom_fields: None,
         workspace_root: workspace_root.map(str::to_string),
+        due_at: None,
+        milestone: None,
     }
 }
 
@@ -414,7 +431,11 @@ async fn create_golden_issue(tools: &Tools) -> (Issue, Vec<(String, String)>) {
             design: Some("Pin the canonical Issue wire shape.".to_string()),
             acceptance: Some("- [x] Exact fields\n- [x] Stable nested arrays".to_string()),
             initial_note: Some("Initial context".to_string()),
+            template: None,
+            custom_fields: None,
             workspace_root: None,
+            due_at: None,
+            milestone: None,
         })
         .await
         .expect("golden Issue should be created");
@@ -840,7 +861,11 @@ async fn test_notes_create_append_validate_and_survive_context_restart() {
             design: None,
             acceptance: None,
             initial_note: Some("Initial context".to_string()),
+            template: None,
+            custom_fields: None,
             workspace_root: None,
+            due_at: None,
+            milestone: None,
         })
         .await
         .expect("create with an initial Note should succeed");
@@ -1111,6 +1136,42 @@ async fn test_multi_workspace_context_switching() {
     assert_eq!(issues_a[0].title, "Issue in Workspace A");
 }
 
+/// Test that a workspace allowlist rejects `set_context` and `workspace_root`
+/// outside the allowed roots.
+#[tokio::test]
+async fn test_allowed_roots_restrict_workspaces() {
+    let allowed = create_temp_workspace();
+    let outside = create_temp_workspace();
+    let context = Context::with_allowed_roots(&[allowed.path().to_path_buf()])
+        .expect("allowed root should exist");
+    let tools = Tools::new(Arc::new(RwLock::new(context)));
+
+    set_context(&tools, allowed.path()).await;
+    create_issue(&tools, "Allowed").await;
+
+    let outside_root = outside.path().display().to_string();
+    let result = tools.set_context(&outside_root).await;
+    assert!(
+        matches!(result, Err(Error::WorkspaceNotAllowed(_))),
+        "{result:?}"
+    );
+    let result = tools
+        .list(list_params(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(&outside_root),
+        ))
+        .await;
+    assert!(
+        matches!(result, Err(Error::WorkspaceNotAllowed(_))),
+        "{result:?}"
+    );
+}
+
 /// Test using `workspace_root` parameter to access different workspace without switching context.
 #[tokio::test]
 async fn test_workspace_root_parameter_override() {
@@ -1562,6 +1623,418 @@ async fn test_ready_excludes_blocked() {
     assert_eq!(ready[0].id, blocker.id);
 }
 
+/// The `dep_tree` output schema must describe the tree JSON exactly.
+#[tokio::test]
+async fn test_dep_tree_matches_published_output_schema() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let top = create_issue(&tools, "Top").await;
+    let middle = create_issue(&tools, "Middle").await;
+    let bottom = create_issue(&tools, "Bottom").await;
+    tools
+        .dep(top.id.as_str(), middle.id.as_str(), Some("blocks"), None)
+        .await
+        .unwrap();
+    tools
+        .dep(middle.id.as_str(), bottom.id.as_str(), None, None)
+        .await
+        .unwrap();
+
+    let tree = tools
+        .dep_tree(middle.id.as_str(), None, None)
+        .await
+        .expect("dep_tree should succeed");
+    let tree: DepTreeResponse =
+        serde_json::from_value(tree).expect("dep_tree output should match DepTreeResponse");
+    assert_eq!(tree.title, "Middle");
+    assert_eq!(tree.dependencies[0].id, bottom.id.as_str());
+    assert_eq!(tree.dependents[0].depends_on_id, top.id.as_str());
+}
+
+/// Test listing, walking and removing dependencies, then deleting an issue.
+#[tokio::test]
+async fn test_dep_list_tree_remove_and_delete() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let top = create_issue(&tools, "Top").await;
+    let middle = create_issue(&tools, "Middle").await;
+    let bottom = create_issue(&tools, "Bottom").await;
+    tools
+        .dep(top.id.as_str(), middle.id.as_str(), Some("blocks"), None)
+        .await
+        .unwrap();
+    tools
+        .dep(
+            middle.id.as_str(),
+            bottom.id.as_str(),
+            Some("related"),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let deps = tools
+        .dep_list(middle.id.as_str(), false, None)
+        .await
+        .expect("dep_list should succeed");
+    assert_eq!(deps.len(), 1);
+    assert_eq!(deps[0].depends_on_id, bottom.id);
+    let dependents = tools
+        .dep_list(middle.id.as_str(), true, None)
+        .await
+        .expect("reverse dep_list should succeed");
+    assert_eq!(dependents.len(), 1);
+    assert_eq!(dependents[0].depends_on_id, top.id);
+    assert_eq!(
+        mcp_content_json(&deps),
+        json!([{ "depends_on_id": bottom.id.as_str(), "dep_type": "related" }])
+    );
+
+    let tree = tools
+        .dep_tree(top.id.as_str(), None, None)
+        .await
+        .expect("dep_tree should succeed");
+    assert_eq!(tree["id"], top.id.as_str());
+    assert_eq!(tree["title"], "Top");
+    assert_eq!(tree["dependencies"][0]["id"], middle.id.as_str());
+    assert_eq!(tree["dependencies"][0]["dep_type"], "blocks");
+    assert_eq!(
+        tree["dependencies"][0]["dependencies"][0]["id"],
+        bottom.id.as_str()
+    );
+    assert_eq!(tree["dependents"], json!([]));
+    let shallow = tools
+        .dep_tree(top.id.as_str(), Some(1), None)
+        .await
+        .expect("depth-limited dep_tree should succeed");
+    assert_eq!(shallow["dependencies"][0]["dependencies"], json!([]));
+
+    let delete_blocked = tools.delete(middle.id.as_str(), None).await;
+    assert!(
+        matches!(
+            delete_blocked,
+            Err(Error::Storage(RivetsError::HasDependents { .. }))
+        ),
+        "an issue with dependents must not be deleted: {delete_blocked:?}"
+    );
+
+    let removed = tools
+        .dep_remove(top.id.as_str(), middle.id.as_str(), None)
+        .await
+        .expect("dep_remove should succeed");
+    assert_eq!(
+        mcp_content_json(&removed),
+        json!({
+            "action": "remove",
+            "from": top.id.as_str(),
+            "to": middle.id.as_str(),
+            "status": "success"
+        })
+    );
+    assert!(
+        tools
+            .dep_remove(top.id.as_str(), middle.id.as_str(), None)
+            .await
+            .is_err()
+    );
+
+    let deleted = tools
+        .delete(middle.id.as_str(), None)
+        .await
+        .expect("delete should succeed once nothing depends on the issue");
+    assert_eq!(
+        mcp_content_json(&deleted),
+        json!({ "deleted": middle.id.as_str(), "status": "success" })
+    );
+    assert!(matches!(
+        tools.show(middle.id.as_str(), None).await,
+        Err(Error::IssueNotFound(_))
+    ));
+
+    // The deletion is persisted, not just applied to the cache.
+    let restarted = create_tools();
+    set_context(&restarted, workspace.path()).await;
+    assert!(matches!(
+        restarted.show(middle.id.as_str(), None).await,
+        Err(Error::IssueNotFound(_))
+    ));
+}
+
+/// Test stats counts and the detailed priority breakdown.
+#[tokio::test]
+async fn test_stats() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let blocker = create_issue(&tools, "Blocker").await;
+    let dependent = create_issue(&tools, "Dependent").await;
+    let done = create_issue(&tools, "Done").await;
+    tools
+        .dep(
+            dependent.id.as_str(),
+            blocker.id.as_str(),
+            Some("blocks"),
+            None,
+        )
+        .await
+        .unwrap();
+    tools.close(done.id.as_str(), None, None).await.unwrap();
+
+    let stats = tools
+        .stats(false, None)
+        .await
+        .expect("stats should succeed");
+    assert_eq!(
+        mcp_content_json(&stats),
+        json!({
+            "total": 3,
+            "by_status": { "open": 2, "in_progress": 0, "blocked": 0, "closed": 1 },
+            "ready": 1,
+            "blocked_by_dependencies": 1,
+            "overdue": 0
+        })
+    );
+
+    let detailed = tools.stats(true, None).await.expect("stats should succeed");
+    assert_eq!(
+        mcp_content_json(&detailed)["by_priority"],
+        json!({
+            "p0_critical": 0,
+            "p1_high": 0,
+            "p2_medium": 3,
+            "p3_low": 0,
+            "p4_backlog": 0
+        })
+    );
+}
+
+// ============================================================================
+// Batch Tests
+// ============================================================================
+
+fn batch_params(operations: &Value) -> BatchParams {
+    serde_json::from_value(json!({ "operations": operations }))
+        .expect("batch operations should deserialize")
+}
+
+/// Test a batch that builds a small graph through handles and saves it.
+#[tokio::test]
+async fn test_batch_applies_operations_with_handles() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+    let existing = create_issue(&tools, "Existing").await;
+
+    let response = tools
+        .batch(batch_params(&json!([
+            { "op": "create", "handle": "epic", "title": "Epic", "issue_kind": "epic" },
+            { "op": "create", "handle": "api", "title": "API" },
+            { "op": "dep", "issue_id": "@api", "depends_on_id": "@epic", "dep_type": "parent-child" },
+            { "op": "dep", "issue_id": "@api", "depends_on_id": existing.id.as_str() },
+            { "op": "update", "issue_id": "@api", "priority": 1 },
+            { "op": "label_add", "issue_id": "@api", "label": "backend" },
+            { "op": "resource_add", "issue_id": "@api", "url": "https://example.com/spec", "role": "documentation" },
+            { "op": "note", "issue_id": "@epic", "content": "Planned in one batch" }
+        ])))
+        .await
+        .expect("batch should succeed");
+
+    let epic_id = response.handles["epic"].clone();
+    let api_id = response.handles["api"].clone();
+    let touched: Vec<&str> = response
+        .issues
+        .iter()
+        .map(|issue| issue.id.as_str())
+        .collect();
+    assert_eq!(
+        touched,
+        [epic_id.as_str(), api_id.as_str(), existing.id.as_str()]
+    );
+
+    // Everything was saved, not just applied to the cache.
+    let restarted = create_tools();
+    set_context(&restarted, workspace.path()).await;
+    let api = restarted.show(&api_id, None).await.unwrap();
+    assert_eq!(api.priority, 1);
+    assert_eq!(api.labels, ["backend"]);
+    assert_eq!(api.resources().len(), 1);
+    let deps = restarted.dep_list(&api_id, false, None).await.unwrap();
+    assert_eq!(deps.len(), 2);
+    let epic = restarted.show(&epic_id, None).await.unwrap();
+    assert_eq!(epic.issue_kind, IssueKind::Epic);
+    assert_eq!(epic.notes().len(), 1);
+}
+
+/// Test that a failing operation leaves neither the cache nor the file changed.
+#[tokio::test]
+async fn test_batch_failure_applies_nothing() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+    let existing = create_issue(&tools, "Existing").await;
+
+    let result = tools
+        .batch(batch_params(&json!([
+            { "op": "create", "handle": "task", "title": "Half-built" },
+            { "op": "label_add", "issue_id": existing.id.as_str(), "label": "planned" },
+            { "op": "dep", "issue_id": "@task", "depends_on_id": "@missing" }
+        ])))
+        .await;
+    assert!(
+        matches!(result, Err(Error::BatchOperation { index: 2, .. })),
+        "unknown handle should fail the third operation: {result:?}"
+    );
+
+    let listed = tools
+        .list(list_params(None, None, None, None, None, None, None))
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(
+        tools
+            .show(existing.id.as_str(), None)
+            .await
+            .unwrap()
+            .labels
+            .is_empty()
+    );
+
+    let restarted = create_tools();
+    set_context(&restarted, workspace.path()).await;
+    let listed = restarted
+        .list(list_params(None, None, None, None, None, None, None))
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+
+    // Validation failures are reported before anything is applied.
+    let result = tools
+        .batch(batch_params(&json!([
+            { "op": "create", "title": "Valid" },
+            { "op": "update", "issue_id": existing.id.as_str(), "status": "bogus" }
+        ])))
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::BatchOperation { index: 1, .. })
+    ));
+    let listed = tools
+        .list(list_params(None, None, None, None, None, None, None))
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+}
+
+// ============================================================================
+// Claim Tests
+// ============================================================================
+
+fn claim_params(issue_id: Option<&str>, agent: &str) -> ClaimParams {
+    ClaimParams {
+        issue_id: issue_id.map(str::to_string),
+        agent: agent.to_string(),
+        lease: Some("30m".to_string()),
+        workspace_root: None,
+    }
+}
+
+/// Test that agents claim distinct issues and `ready` hides others' claims.
+#[tokio::test]
+async fn test_claim_next_hands_out_distinct_issues() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+    create_issue(&tools, "First").await;
+    create_issue(&tools, "Second").await;
+
+    let first = tools
+        .claim(claim_params(None, "agent-a"))
+        .await
+        .unwrap()
+        .expect("an issue should be claimable");
+    let second = tools
+        .claim(claim_params(None, "agent-b"))
+        .await
+        .unwrap()
+        .expect("another issue should be claimable");
+    assert_ne!(first.id, second.id);
+    assert!(
+        tools
+            .claim(claim_params(None, "agent-c"))
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let ready = tools
+        .ready(ReadyParams {
+            agent: Some("agent-a".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids: Vec<_> = ready.iter().map(|issue| &issue.id).collect();
+    assert_eq!(ids, vec![&first.id]);
+
+    let result = tools
+        .claim(claim_params(Some(first.id.as_str()), "agent-b"))
+        .await;
+    assert!(matches!(result, Err(Error::InvalidClaim(_))), "{result:?}");
+
+    // The claim is persisted for other server instances.
+    let restarted = create_tools();
+    set_context(&restarted, workspace.path()).await;
+    let reloaded = restarted.show(first.id.as_str(), None).await.unwrap();
+    assert_eq!(reloaded.lease, first.lease);
+}
+
+/// Test renewing and releasing a claim.
+#[tokio::test]
+async fn test_renew_and_release_claim() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+    let issue = create_issue(&tools, "Claimable").await;
+    let claimed = tools
+        .claim(claim_params(Some(issue.id.as_str()), "agent-a"))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let renewed = tools
+        .renew(RenewParams {
+            issue_id: issue.id.to_string(),
+            agent: "agent-a".to_string(),
+            lease: Some("2h".to_string()),
+            workspace_root: None,
+        })
+        .await
+        .unwrap();
+    assert!(renewed.lease.unwrap().expires_at > claimed.lease.unwrap().expires_at);
+
+    let result = tools.release(issue.id.as_str(), "agent-b", None).await;
+    assert!(matches!(result, Err(Error::InvalidClaim(_))));
+    let released = tools
+        .release(issue.id.as_str(), "agent-a", None)
+        .await
+        .unwrap();
+    assert!(released.assignee.is_none());
+    assert!(released.lease.is_none());
+
+    let result = tools
+        .claim(ClaimParams {
+            lease: Some("soon".to_string()),
+            ..claim_params(None, "agent-a")
+        })
+        .await;
+    assert!(matches!(result, Err(Error::InvalidClaim(_))));
+}
+
 // ============================================================================
 // Filter Tests (rstest parameterized)
 // ============================================================================
@@ -2197,27 +2670,96 @@ async fn test_update_persistence() {
     }
 }
 
-// ============================================================================
-// Ready-to-Work Filter Tests (rstest parameterized)
-// ============================================================================
+/// Test that changes written by another process are picked up, not overwritten.
+#[tokio::test]
+async fn test_external_changes_reload_cached_workspace() {
+    let workspace = create_temp_workspace();
+    let server = create_tools();
+    set_context(&server, workspace.path()).await;
+    let issue = create_issue(&server, "Original").await;
+
+    // A second tool instance stands in for the CLI writing the same file.
+    let cli = create_tools();
+    set_context(&cli, workspace.path()).await;
+    cli.update(update_params(
+        issue.id.as_str(),
+        Some("Renamed by CLI".to_string()),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    ))
+    .await
+    .expect("external update should succeed");
+    create_issue(&cli, "Created by CLI").await;
+
+    let reloaded = server.show(issue.id.as_str(), None).await.unwrap();
+    assert_eq!(reloaded.title, "Renamed by CLI");
+    let issues = server
+        .list(list_params(None, None, None, None, None, None, None))
+        .await
+        .unwrap();
+    assert_eq!(issues.len(), 2);
 
-#[rstest]
-#[case::priority_filter(
-    ReadyFilterCase {
-        setup: vec![
-            IssueSetup::new("P0 Issue").with_priority(0),
-            IssueSetup::new("P2 Issue").with_priority(2),
-        ],
-        filter: FilterParams::new().with_priority(0),
-        expected_count: 1,
-        expected_titles: Some(vec!["P0 Issue"]),
-    }
-)]
-#[case::assignee_filter(
-    ReadyFilterCase {
-        setup: vec![
-            IssueSetup::new("Alice's Issue").with_assignee("alice"),
-            IssueSetup::new("Unassigned Issue"),
+    // A mutation after the external change applies on top of it.
+    server
+        .update(update_params(
+            issue.id.as_str(),
+            None,
+            None,
+            Some("in_progress"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .expect("update after reload should succeed");
+
+    let fresh = create_tools();
+    set_context(&fresh, workspace.path()).await;
+    let on_disk = fresh.show(issue.id.as_str(), None).await.unwrap();
+    assert_eq!(on_disk.title, "Renamed by CLI");
+    assert_eq!(on_disk.status, IssueStatus::InProgress);
+    assert_eq!(
+        fresh
+            .list(list_params(None, None, None, None, None, None, None))
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+// ============================================================================
+// Ready-to-Work Filter Tests (rstest parameterized)
+// ============================================================================
+
+#[rstest]
+#[case::priority_filter(
+    ReadyFilterCase {
+        setup: vec![
+            IssueSetup::new("P0 Issue").with_priority(0),
+            IssueSetup::new("P2 Issue").with_priority(2),
+        ],
+        filter: FilterParams::new().with_priority(0),
+        expected_count: 1,
+        expected_titles: Some(vec!["P0 Issue"]),
+    }
+)]
+#[case::assignee_filter(
+    ReadyFilterCase {
+        setup: vec![
+            IssueSetup::new("Alice's Issue").with_assignee("alice"),
+            IssueSetup::new("Unassigned Issue"),
         ],
         filter: FilterParams::new().with_assignee("alice"),
         expected_count: 1,
@@ -3813,7 +4355,7 @@ async fn resource_add_list_and_context_recreation_use_real_storage() {
         ResourceTarget::Web { url } => {
             assert_eq!(url.as_str(), "https://example.com/pr/123");
         }
-        ResourceTarget::Path { .. } => panic!("web add must not produce a path target"),
+        target => panic!("web add must produce a web target, got {target}"),
     }
 
     let restarted = create_tools();
@@ -4284,3 +4826,1001 @@ async fn resource_mutations_persist_across_context_restart() {
         .collect();
     assert_eq!(persisted_ids, ["r1", "r3"]);
 }
+
+#[tokio::test]
+async fn test_create_applies_kind_and_named_templates() {
+    let workspace = create_temp_workspace();
+    let templates_dir = workspace.path().join(".rivets").join("templates");
+    std::fs::create_dir(&templates_dir).expect("templates dir should be created");
+    std::fs::write(
+        templates_dir.join("bug.yaml"),
+        "labels: [bug]\ndescription: Steps to reproduce\n",
+    )
+    .expect("bug template should be written");
+    std::fs::write(
+        templates_dir.join("spike.yaml"),
+        "kind: task\npriority: 3\n",
+    )
+    .expect("spike template should be written");
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let bug = tools
+        .create(create_params(
+            "Crash".to_string(),
+            None,
+            None,
+            Some("bug"),
+            None,
+            Some(vec!["ui".to_string()]),
+            None,
+            None,
+            None,
+        ))
+        .await
+        .expect("create with kind template should succeed");
+    assert_eq!(bug.description, "Steps to reproduce");
+    assert_eq!(bug.labels, ["bug", "ui"]);
+
+    let mut params = create_params(
+        "Spike".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    params.template = Some("spike".to_string());
+    let spike = tools
+        .create(params)
+        .await
+        .expect("create with named template should succeed");
+    assert_eq!(spike.priority, 3);
+
+    let mut params = create_params(
+        "Missing".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    params.template = Some("missing".to_string());
+    let error = tools
+        .create(params)
+        .await
+        .expect_err("unknown template should be rejected");
+    assert!(matches!(
+        &error,
+        Error::InvalidTemplate { name, .. } if name == "missing"
+    ));
+    assert!(error.to_string().contains("Template 'missing' not found"));
+}
+
+#[tokio::test]
+async fn test_create_reports_unparsable_kind_template_by_name() {
+    let workspace = create_temp_workspace();
+    let templates_dir = workspace.path().join(".rivets").join("templates");
+    std::fs::create_dir(&templates_dir).expect("templates dir should be created");
+    std::fs::write(templates_dir.join("bug.yaml"), "labels: [bug\n")
+        .expect("bug template should be written");
+    std::fs::write(
+        templates_dir.join("feature.yaml"),
+        "labels: [Needs Triage]\n",
+    )
+    .expect("feature template should be written");
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    for (kind, reason) in [
+        ("bug", "bug.yaml"),
+        ("feature", "Invalid template 'feature'"),
+    ] {
+        let error = tools
+            .create(create_params(
+                "Broken template".to_string(),
+                None,
+                None,
+                Some(kind),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ))
+            .await
+            .expect_err("invalid template should be rejected");
+        assert!(
+            matches!(&error, Error::InvalidTemplate { name, .. } if name == kind),
+            "unexpected error: {error:?}"
+        );
+        assert!(
+            error.to_string().contains(reason),
+            "error should give the reason: {error}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_custom_fields_are_checked_filtered_and_cleared() {
+    let workspace = create_temp_workspace();
+    let config_path = workspace.path().join(".rivets").join("config.yaml");
+    let mut config = std::fs::read_to_string(&config_path).expect("config should be readable");
+    config.push_str(
+        "custom-fields:\n  severity:\n    type: enum\n    values: [low, high]\n  points:\n    type: int\n",
+    );
+    std::fs::write(&config_path, config).expect("config should be written");
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let mut params = create_params(
+        "Sized".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    params.custom_fields = Some(
+        [
+            ("severity".to_string(), json!("high")),
+            ("points".to_string(), json!("3")),
+        ]
+        .into(),
+    );
+    let sized = tools
+        .create(params)
+        .await
+        .expect("create with declared custom fields should succeed");
+    assert_eq!(
+        serde_json::to_value(&sized.custom_fields).unwrap(),
+        json!({"points": 3, "severity": "high"})
+    );
+    tools
+        .create(create_params(
+            "Plain".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .expect("create without custom fields should succeed");
+
+    let mut params = list_params(None, None, None, None, None, None, None);
+    params.custom_fields = Some([("severity".to_string(), json!("high"))].into());
+    let listed = tools
+        .list(params)
+        .await
+        .expect("filtered list should succeed");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, sized.id);
+
+    let mut params = update_params(
+        sized.id.as_str(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    params.custom_fields = Some([("points".to_string(), Value::Null)].into());
+    let updated = tools
+        .update(params)
+        .await
+        .expect("clearing a custom field should succeed");
+    assert_eq!(
+        serde_json::to_value(&updated.custom_fields).unwrap(),
+        json!({"severity": "high"})
+    );
+
+    for (name, value) in [("owner", json!("me")), ("severity", json!("urgent"))] {
+        let mut params = create_params(
+            "Rejected".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        params.custom_fields = Some([(name.to_string(), value)].into());
+        let error = tools
+            .create(params)
+            .await
+            .expect_err("invalid custom field should be rejected");
+        assert!(matches!(error, Error::InvalidCustomField(_)), "{error:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_due_dates_set_filter_and_clear() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let mut params = create_params(
+        "Late".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    params.due_at = Some("2020-01-01".to_string());
+    let late = tools
+        .create(params)
+        .await
+        .expect("create with a due date should succeed");
+    assert_eq!(
+        late.due_at.map(|due| due.to_rfc3339()),
+        Some("2020-01-01T23:59:59+00:00".to_string())
+    );
+    tools
+        .create(create_params(
+            "Undated".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .expect("create without a due date should succeed");
+
+    let mut params = list_params(None, None, None, None, None, None, None);
+    params.overdue = Some(true);
+    let listed = tools
+        .list(params.clone())
+        .await
+        .expect("list should succeed");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, late.id);
+
+    let mut update = update_params(
+        late.id.as_str(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    update.due_at = Some(String::new());
+    let cleared = tools
+        .update(update)
+        .await
+        .expect("clearing a due date should succeed");
+    assert_eq!(cleared.due_at, None);
+    assert!(
+        tools
+            .list(params)
+            .await
+            .expect("list should succeed")
+            .is_empty()
+    );
+
+    let mut params = create_params(
+        "Bad".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    params.due_at = Some("soon".to_string());
+    let error = tools
+        .create(params)
+        .await
+        .expect_err("invalid due date should be rejected");
+    assert!(matches!(
+        error,
+        Error::InvalidArgument {
+            field: "due_at",
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_ready_scoped_to_current_milestone() {
+    use rivets::commands::milestones::save_milestones;
+    use rivets::domain::{Milestone, MilestoneError};
+
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let now = Utc::now();
+    let today = now.date_naive();
+    let current = Milestone::new(
+        "current",
+        today - chrono::Days::new(1),
+        today + chrono::Days::new(7),
+        now,
+    )
+    .expect("valid milestone");
+    let later = Milestone::new(
+        "later",
+        today + chrono::Days::new(30),
+        today + chrono::Days::new(44),
+        now,
+    )
+    .expect("valid milestone");
+    save_milestones(&workspace.path().join(".rivets"), &[current, later])
+        .await
+        .expect("save milestones");
+
+    let mut ids = Vec::new();
+    for (title, milestone) in [
+        ("Now", Some("current")),
+        ("Soon", Some("later")),
+        ("Unplanned", None),
+    ] {
+        let mut params = create_params(
+            title.to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        params.milestone = milestone.map(str::to_string);
+        let issue = tools.create(params).await.expect("create should succeed");
+        assert_eq!(issue.milestone.as_deref(), milestone);
+        ids.push(issue.id);
+    }
+
+    let mut params = ready_params(None, None, None, None, None, None);
+    params.current_milestone = Some(true);
+    let ready = tools.ready(params).await.expect("ready should succeed");
+    assert_eq!(
+        ready.iter().map(|i| i.id.clone()).collect::<Vec<_>>(),
+        vec![ids[0].clone()]
+    );
+
+    let mut params = ready_params(None, None, None, None, None, None);
+    params.milestone = Some("later".to_string());
+    let ready = tools.ready(params).await.expect("ready should succeed");
+    assert_eq!(
+        ready.iter().map(|i| i.id.clone()).collect::<Vec<_>>(),
+        vec![ids[1].clone()]
+    );
+
+    let mut params = ready_params(None, None, None, None, None, None);
+    params.milestone = Some("later".to_string());
+    params.current_milestone = Some(true);
+    assert!(matches!(
+        tools.ready(params).await,
+        Err(Error::InvalidArgument {
+            field: "milestone",
+            ..
+        })
+    ));
+
+    let mut update = update_params(
+        ids[0].as_str(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    update.milestone = Some(String::new());
+    let cleared = tools
+        .update(update.clone())
+        .await
+        .expect("clearing a milestone should succeed");
+    assert_eq!(cleared.milestone, None);
+
+    update.milestone = Some("missing".to_string());
+    assert!(matches!(
+        tools.update(update).await,
+        Err(Error::InvalidMilestone(MilestoneError::NotFound(name))) if name == "missing"
+    ));
+}
+
+#[tokio::test]
+async fn test_ready_current_milestone_requires_one() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let mut params = ready_params(None, None, None, None, None, None);
+    params.current_milestone = Some(true);
+    assert!(matches!(
+        tools.ready(params).await,
+        Err(Error::InvalidMilestone(
+            rivets::domain::MilestoneError::NoCurrent(_)
+        ))
+    ));
+}
+
+// =============================================================================
+// MCP Resource Tests
+// =============================================================================
+
+/// Client that forwards `notifications/resources/updated` URIs to a channel.
+struct ResourceUpdateClient {
+    updates: tokio::sync::mpsc::UnboundedSender<String>,
+}
+
+impl rmcp::ClientHandler for ResourceUpdateClient {
+    async fn on_resource_updated(
+        &self,
+        params: rmcp::model::ResourceUpdatedNotificationParam,
+        _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        let _ = self.updates.send(params.uri);
+    }
+}
+
+async fn call_tool(
+    client: &rmcp::service::Peer<rmcp::RoleClient>,
+    name: &'static str,
+    arguments: Value,
+) -> rmcp::model::CallToolResult {
+    let Value::Object(arguments) = arguments else {
+        panic!("tool arguments must be an object");
+    };
+    client
+        .call_tool(rmcp::model::CallToolRequestParams::new(name).with_arguments(arguments))
+        .await
+        .unwrap_or_else(|e| panic!("{name} should succeed: {e}"))
+}
+
+fn resource_json(result: &rmcp::model::ReadResourceResult) -> Value {
+    match &result.contents[..] {
+        [
+            rmcp::model::ResourceContents::TextResourceContents {
+                text, mime_type, ..
+            },
+        ] => {
+            assert_eq!(mime_type.as_deref(), Some("application/json"));
+            serde_json::from_str(text).expect("resource text should be JSON")
+        }
+        contents => panic!("expected one text resource, got {contents:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_issue_resources_read_and_notify_subscribers() {
+    use rmcp::ServiceExt;
+    use rmcp::model::{ReadResourceRequestParams, SubscribeRequestParams};
+    use std::time::Duration;
+
+    let workspace = create_temp_workspace();
+    let root = workspace
+        .path()
+        .canonicalize()
+        .expect("canonical workspace");
+
+    let (server_transport, client_transport) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let server = rivets_mcp::RivetsMcpServer::new()
+            .serve(server_transport)
+            .await
+            .expect("server should start");
+        let _ = server.waiting().await;
+    });
+    let (updates, mut received) = tokio::sync::mpsc::unbounded_channel();
+    let client = ResourceUpdateClient { updates }
+        .serve(client_transport)
+        .await
+        .expect("client should connect");
+
+    let capabilities = client
+        .peer_info()
+        .and_then(|info| info.capabilities.resources.clone())
+        .expect("server should advertise resources");
+    assert_eq!(capabilities.subscribe, Some(true));
+
+    // Nothing to list before a workspace is chosen.
+    let listed = client.list_resources(None).await.expect("list resources");
+    assert!(listed.resources.is_empty());
+
+    call_tool(
+        &client,
+        "set_context",
+        json!({ "workspace_root": root.display().to_string() }),
+    )
+    .await;
+    let created = call_tool(&client, "create", json!({ "title": "Pinned issue" })).await;
+    let issue_id = created.content[0]
+        .as_text()
+        .and_then(|text| serde_json::from_str::<Value>(&text.text).ok())
+        .and_then(|issue| issue["id"].as_str().map(str::to_string))
+        .expect("create should return the issue");
+
+    let issue_uri = format!("rivets://{}/issue/{issue_id}", root.display());
+    let ready_uri = format!("rivets://{}/ready", root.display());
+    let listed = client.list_resources(None).await.expect("list resources");
+    let uris: Vec<&str> = listed.resources.iter().map(|r| r.uri.as_str()).collect();
+    assert_eq!(
+        uris,
+        vec![
+            ready_uri.as_str(),
+            format!("rivets://{}/blocked", root.display()).as_str(),
+            issue_uri.as_str(),
+        ]
+    );
+    let templates = client
+        .list_resource_templates(None)
+        .await
+        .expect("list resource templates");
+    assert_eq!(templates.resource_templates.len(), 3);
+
+    let issue = client
+        .read_resource(ReadResourceRequestParams::new(issue_uri.clone()))
+        .await
+        .expect("read issue resource");
+    assert_eq!(resource_json(&issue)["title"], "Pinned issue");
+    let ready = client
+        .read_resource(ReadResourceRequestParams::new(ready_uri.clone()))
+        .await
+        .expect("read ready resource");
+    assert_eq!(resource_json(&ready)[0]["id"], issue_id.as_str());
+
+    let missing = client
+        .read_resource(ReadResourceRequestParams::new(format!(
+            "rivets://{}/issue/test-missing",
+            root.display()
+        )))
+        .await;
+    assert!(missing.is_err(), "reading a missing issue should fail");
+
+    client
+        .subscribe(SubscribeRequestParams::new(issue_uri.clone()))
+        .await
+        .expect("subscribe");
+    call_tool(
+        &client,
+        "update",
+        json!({ "issue_id": issue_id, "title": "Pinned issue, renamed" }),
+    )
+    .await;
+
+    let notified = tokio::time::timeout(Duration::from_secs(5), received.recv())
+        .await
+        .expect("an update notification should arrive")
+        .expect("update channel should stay open");
+    assert_eq!(notified, issue_uri);
+    let issue = client
+        .read_resource(ReadResourceRequestParams::new(issue_uri))
+        .await
+        .expect("read issue resource");
+    assert_eq!(resource_json(&issue)["title"], "Pinned issue, renamed");
+
+    client.cancel().await.expect("client should shut down");
+}
+
+#[tokio::test]
+async fn test_sessions_keep_their_own_workspace_and_share_subscriptions() {
+    use rivets_mcp::context::Context;
+    use rivets_mcp::resources::Subscriptions;
+    use rmcp::ServiceExt;
+    use rmcp::model::SubscribeRequestParams;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::RwLock;
+
+    let first_workspace = create_temp_workspace();
+    let second_workspace = create_temp_workspace();
+    let first_root = first_workspace.path().canonicalize().expect("canonical");
+    let second_root = second_workspace.path().canonicalize().expect("canonical");
+
+    // Two sessions over one context and subscription registry, as HTTP
+    // serves them.
+    let context = Arc::new(RwLock::new(Context::new()));
+    let subscriptions = Arc::new(Subscriptions::default());
+    let mut clients = Vec::new();
+    for _ in 0..2 {
+        let (server_transport, client_transport) = tokio::io::duplex(64 * 1024);
+        let server = rivets_mcp::RivetsMcpServer::for_session(
+            Arc::clone(&context),
+            Arc::clone(&subscriptions),
+        );
+        tokio::spawn(async move {
+            let server = server
+                .serve(server_transport)
+                .await
+                .expect("server should start");
+            let _ = server.waiting().await;
+        });
+        let (updates, received) = tokio::sync::mpsc::unbounded_channel();
+        let client = ResourceUpdateClient { updates }
+            .serve(client_transport)
+            .await
+            .expect("client should connect");
+        clients.push((client, received));
+    }
+    let (bob, mut bob_updates) = clients.pop().expect("second client");
+    let (alice, _) = clients.pop().expect("first client");
+    let workspace_of = |result: rmcp::model::CallToolResult| {
+        result.content[0]
+            .as_text()
+            .and_then(|text| serde_json::from_str::<Value>(&text.text).ok())
+            .and_then(|info| info["workspace_root"].as_str().map(str::to_string))
+    };
+
+    for client in [&alice, &bob] {
+        call_tool(
+            client,
+            "set_context",
+            json!({ "workspace_root": first_root.display().to_string() }),
+        )
+        .await;
+    }
+    let created = call_tool(&alice, "create", json!({ "title": "Shared issue" })).await;
+    let issue_id = created.content[0]
+        .as_text()
+        .and_then(|text| serde_json::from_str::<Value>(&text.text).ok())
+        .and_then(|issue| issue["id"].as_str().map(str::to_string))
+        .expect("create should return the issue");
+    let issue_uri = format!("rivets://{}/issue/{issue_id}", first_root.display());
+    bob.subscribe(SubscribeRequestParams::new(issue_uri.clone()))
+        .await
+        .expect("subscribe");
+
+    // Switching Alice's workspace leaves Bob's alone.
+    call_tool(
+        &alice,
+        "set_context",
+        json!({ "workspace_root": second_root.display().to_string() }),
+    )
+    .await;
+    assert_eq!(
+        workspace_of(call_tool(&alice, "where_am_i", json!({})).await),
+        Some(second_root.display().to_string())
+    );
+    assert_eq!(
+        workspace_of(call_tool(&bob, "where_am_i", json!({})).await),
+        Some(first_root.display().to_string())
+    );
+
+    // Alice's change to the issue reaches Bob's subscription.
+    call_tool(
+        &alice,
+        "update",
+        json!({
+            "issue_id": issue_id,
+            "title": "Shared issue, renamed",
+            "workspace_root": first_root.display().to_string(),
+        }),
+    )
+    .await;
+    let notified = tokio::time::timeout(Duration::from_secs(5), bob_updates.recv())
+        .await
+        .expect("an update notification should arrive")
+        .expect("update channel should stay open");
+    assert_eq!(notified, issue_uri);
+
+    alice.cancel().await.expect("client should shut down");
+    bob.cancel().await.expect("client should shut down");
+}
+
+// =============================================================================
+// MCP Prompt Tests
+// =============================================================================
+
+fn prompt_text(result: &rmcp::model::GetPromptResult) -> &str {
+    match &result.messages[..] {
+        [message] => match &message.content {
+            rmcp::model::PromptMessageContent::Text { text } => text,
+            content => panic!("expected a text prompt, got {content:?}"),
+        },
+        messages => panic!("expected one prompt message, got {messages:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_prompts_embed_issue_data() {
+    use rivets_mcp::prompts::render_prompt;
+
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+
+    let epic = tools
+        .create(create_params(
+            "Checkout revamp".to_string(),
+            None,
+            Some(1),
+            Some("epic"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+        .await
+        .expect("create epic");
+    let child = create_issue(&tools, "Card form").await;
+    let blocker = create_issue(&tools, "Payment API").await;
+    tools
+        .dep(
+            child.id.as_str(),
+            epic.id.as_str(),
+            Some("parent-child"),
+            None,
+        )
+        .await
+        .expect("link child");
+    tools
+        .dep(child.id.as_str(), blocker.id.as_str(), Some("blocks"), None)
+        .await
+        .expect("add blocker");
+
+    let arguments = |value: Value| match value {
+        Value::Object(object) => object,
+        _ => unreachable!(),
+    };
+
+    let plan = render_prompt(
+        &tools,
+        "plan_epic",
+        Some(&arguments(json!({ "epic_id": epic.id.as_str() }))),
+    )
+    .await
+    .expect("plan_epic should render");
+    let text = prompt_text(&plan);
+    assert!(text.contains("Checkout revamp"), "{text}");
+    assert!(text.contains("Existing children (1)"), "{text}");
+    assert!(text.contains("Card form"), "{text}");
+
+    let summary = render_prompt(
+        &tools,
+        "closing_summary",
+        Some(&arguments(json!({ "issue_id": child.id.as_str() }))),
+    )
+    .await
+    .expect("closing_summary should render");
+    assert!(prompt_text(&summary).contains(child.id.as_str()));
+    assert!(summary.description.is_some());
+
+    let next = render_prompt(&tools, "next_ready_work", None)
+        .await
+        .expect("next_ready_work should render");
+    let text = prompt_text(&next);
+    assert!(text.contains("Ready issues (2)"), "{text}");
+    assert!(text.contains("Payment API"), "{text}");
+    assert!(text.contains("Blocked issues (1)"), "{text}");
+
+    let triage = render_prompt(
+        &tools,
+        "triage_new_issues",
+        Some(&arguments(json!({ "days": "1" }))),
+    )
+    .await
+    .expect("triage_new_issues should render");
+    assert!(prompt_text(&triage).contains("New open issues (3)"));
+
+    assert!(matches!(
+        render_prompt(
+            &tools,
+            "plan_epic",
+            Some(&arguments(json!({ "epic_id": child.id.as_str() }))),
+        )
+        .await,
+        Err(Error::InvalidArgument {
+            field: "epic_id",
+            ..
+        })
+    ));
+    assert!(matches!(
+        render_prompt(&tools, "closing_summary", None).await,
+        Err(Error::InvalidArgument {
+            field: "issue_id",
+            ..
+        })
+    ));
+    assert!(matches!(
+        render_prompt(&tools, "standup", None).await,
+        Err(Error::InvalidArgument {
+            field: "prompt",
+            ..
+        })
+    ));
+}
+
+// =============================================================================
+// Change Feed Tests
+// =============================================================================
+
+/// Mutations are recorded with their tool, actor and field diff, and the
+/// cursor pages through them without repeats.
+#[tokio::test]
+async fn test_changes_since_records_tool_mutations() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    tools.set_client_name("orchestrator");
+    set_context(&tools, workspace.path()).await;
+
+    let start = tools.changes_since(None, None, None).await.unwrap();
+    assert!(start.events.is_empty());
+
+    let issue = create_issue(&tools, "Feed me").await;
+    tools
+        .claim(claim_params(Some(issue.id.as_str()), "agent-a"))
+        .await
+        .unwrap();
+
+    let changes = tools
+        .changes_since(Some(start.cursor), None, None)
+        .await
+        .unwrap();
+    let summary: Vec<(&str, ChangeKind, Option<&str>)> = changes
+        .events
+        .iter()
+        .map(|event| (event.source.as_str(), event.kind, event.actor.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("create", ChangeKind::Created, Some("orchestrator")),
+            ("claim", ChangeKind::Updated, Some("agent-a")),
+        ]
+    );
+    let claim = &changes.events[1];
+    assert_eq!(claim.issue_id, issue.id.as_str());
+    let assignee = claim
+        .changes
+        .iter()
+        .find(|change| change.field == "assignee")
+        .expect("claim should change the assignee");
+    assert_eq!(assignee.before, Value::Null);
+    assert_eq!(assignee.after, json!("agent-a"));
+
+    let caught_up = tools
+        .changes_since(Some(changes.cursor), None, None)
+        .await
+        .unwrap();
+    assert!(caught_up.events.is_empty());
+    assert_eq!(caught_up.cursor, changes.cursor);
+}
+
+/// Batches, claims of the next issue and deletes record only the issues they
+/// changed.
+#[tokio::test]
+async fn test_changes_since_records_only_touched_issues() {
+    let workspace = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, workspace.path()).await;
+    let existing = create_issue(&tools, "Existing").await;
+    create_issue(&tools, "Bystander").await;
+    let cursor = tools.changes_since(None, None, None).await.unwrap().cursor;
+
+    let batch = tools
+        .batch(batch_params(&json!([
+            { "op": "label_add", "issue_id": existing.id.as_str(), "label": "backend" },
+            { "op": "create", "handle": "api", "title": "API" },
+            { "op": "dep", "issue_id": "@api", "depends_on_id": existing.id.as_str() }
+        ])))
+        .await
+        .unwrap();
+    let created = batch.handles["api"].clone();
+    let claimed = tools
+        .claim(claim_params(None, "agent-a"))
+        .await
+        .unwrap()
+        .expect("an issue should be ready");
+    tools.delete(&created, None).await.unwrap();
+
+    let changes = tools.changes_since(Some(cursor), None, None).await.unwrap();
+    let summary: Vec<(&str, &str, ChangeKind)> = changes
+        .events
+        .iter()
+        .map(|event| (event.source.as_str(), event.issue_id.as_str(), event.kind))
+        .collect();
+    // A batch records its issues in ID order.
+    let mut expected = vec![
+        ("batch", existing.id.as_str(), ChangeKind::Updated),
+        ("batch", created.as_str(), ChangeKind::Created),
+    ];
+    expected.sort_by_key(|&(_, id, _)| id);
+    expected.extend([
+        ("claim", claimed.id.as_str(), ChangeKind::Updated),
+        ("delete", created.as_str(), ChangeKind::Deleted),
+    ]);
+    assert_eq!(summary, expected);
+}
+
+/// Changes written by another process are recorded when the workspace reloads.
+#[tokio::test]
+async fn test_changes_since_records_reloaded_changes() {
+    let workspace = create_temp_workspace();
+    let server = create_tools();
+    set_context(&server, workspace.path()).await;
+    let issue = create_issue(&server, "Original").await;
+    let cursor = server.changes_since(None, None, None).await.unwrap().cursor;
+
+    // A second tool instance stands in for the CLI writing the same file.
+    let cli = create_tools();
+    set_context(&cli, workspace.path()).await;
+    cli.label_add(issue.id.as_str(), "from-cli", None)
+        .await
+        .unwrap();
+
+    let changes = server
+        .changes_since(Some(cursor), None, None)
+        .await
+        .unwrap();
+    assert_eq!(changes.events.len(), 1);
+    let event = &changes.events[0];
+    assert_eq!(event.source, RELOAD_SOURCE);
+    assert_eq!(event.kind, ChangeKind::Updated);
+    assert_eq!(event.actor, None);
+    assert!(
+        event
+            .changes
+            .iter()
+            .any(|change| change.field == "labels" && change.after == json!(["from-cli"]))
+    );
+}
+
+/// The feed only returns changes for the requested workspace.
+#[tokio::test]
+async fn test_changes_since_is_scoped_to_workspace() {
+    let first = create_temp_workspace();
+    let second = create_temp_workspace();
+    let tools = create_tools();
+    set_context(&tools, first.path()).await;
+    create_issue(&tools, "In first").await;
+
+    let second_root = second.path().display().to_string();
+    let changes = tools
+        .changes_since(None, None, Some(&second_root))
+        .await
+        .unwrap();
+    assert!(changes.events.is_empty());
+    assert_eq!(
+        tools
+            .changes_since(None, None, None)
+            .await
+            .unwrap()
+            .events
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,133 @@
+//! Error responses.
+
+use crate::domain::CustomFieldError;
+use crate::error::{Error, StorageError};
+use axum::extract::rejection::JsonRejection;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+/// An error returned to an API client as
+/// `{"error": {"code": ..., "message": ...}}`.
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    /// The request itself was malformed.
+    pub(crate) fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    /// The request was well-formed but its content was rejected.
+    pub(crate) fn validation(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "validation_error", message)
+    }
+
+    /// The request's `If-Match` header does not match the current issue.
+    pub(crate) fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::PRECONDITION_FAILED,
+            "precondition_failed",
+            message,
+        )
+    }
+
+    /// The request came through a host name the server does not answer to.
+    pub(crate) fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        let (status, code) = match &error {
+            Error::IssueNotFound(_) | Error::DependencyNotFound { .. } => {
+                (StatusCode::NOT_FOUND, "not_found")
+            }
+            Error::Validation { .. }
+            | Error::InvalidIssueId(_)
+            | Error::InvalidPriority(_)
+            | Error::Milestone(_)
+            | Error::Storage(
+                StorageError::Validation(_)
+                | StorageError::InvalidFormat(_)
+                | StorageError::Resource(_),
+            ) => (StatusCode::BAD_REQUEST, "validation_error"),
+            Error::HasDependents { .. }
+            | Error::CircularDependency { .. }
+            | Error::IssueAlreadyExists(_)
+            | Error::Storage(
+                StorageError::DuplicateDependency { .. }
+                | StorageError::InvalidStatusTransition(_)
+                | StorageError::Lease(_)
+                | StorageError::ConcurrentModification(_),
+            ) => (StatusCode::CONFLICT, "conflict"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
+        };
+        Self::new(status, code, error.to_string())
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let message = format!("Invalid request body: {}", rejection.body_text());
+        match rejection {
+            JsonRejection::MissingJsonContentType(_) => Self::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported_media_type",
+                message,
+            ),
+            _ => Self::bad_request(message),
+        }
+    }
+}
+
+impl From<CustomFieldError> for ApiError {
+    fn from(error: CustomFieldError) -> Self {
+        Self::validation(error.to_string())
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: ErrorDetail<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail<'a> {
+    code: &'a str,
+    message: &'a str,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status.is_server_error() {
+            tracing::error!(message = %self.message, "API request failed");
+        }
+        let body = ErrorBody {
+            error: ErrorDetail {
+                code: self.code,
+                message: &self.message,
+            },
+        };
+        let body = serde_json::to_string(&body).unwrap_or_default();
+        (
+            self.status,
+            [(header::CONTENT_TYPE, "application/json")],
+            body,
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,150 @@
+//! Event publishing for `/events` subscribers.
+//!
+//! Every mutation made through the API, and every change picked up by
+//! reloading the data file after another process wrote it, is published to
+//! the [`EventHub`] as [`IssueEvent`](crate::events::IssueEvent)s.
+
+use crate::domain::{Issue, IssueId};
+use crate::events::{IssueEvent, Subject, events_between};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::broadcast;
+
+/// Events a subscriber may fall behind by before it misses some.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Source of events made through the API.
+pub(crate) const API_SOURCE: &str = "api";
+
+/// Source of events picked up by reloading the data file.
+pub(crate) const RELOAD_SOURCE: &str = "reload";
+
+/// An event as published to subscribers.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PublishedEvent {
+    /// Position in the server's event sequence, starting at 1.
+    pub(crate) sequence: u64,
+    /// When the event was published.
+    pub(crate) timestamp: DateTime<Utc>,
+    /// `api` for changes made through the API, `reload` for changes
+    /// another process wrote to the data file.
+    pub(crate) source: &'static str,
+    /// What changed.
+    pub(crate) event: IssueEvent,
+    #[serde(skip)]
+    pub(crate) subject: Subject,
+}
+
+/// Which events a subscriber receives.
+///
+/// Each non-empty list must match: an event passes if its issue is one of
+/// `issue_ids`, carries one of `labels` and is assigned to one of
+/// `assignees`. An empty filter passes everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventFilter {
+    /// Issues to watch.
+    pub issue_ids: Vec<IssueId>,
+    /// Labels to watch.
+    pub labels: Vec<String>,
+    /// Assignees to watch.
+    pub assignees: Vec<String>,
+}
+
+impl EventFilter {
+    pub(crate) fn matches(&self, subject: &Subject) -> bool {
+        (self.issue_ids.is_empty() || self.issue_ids.contains(&subject.issue_id))
+            && (self.labels.is_empty()
+                || self
+                    .labels
+                    .iter()
+                    .any(|label| subject.labels.contains(label)))
+            && (self.assignees.is_empty()
+                || self
+                    .assignees
+                    .iter()
+                    .any(|assignee| subject.assignees.contains(assignee)))
+    }
+}
+
+/// Publishes events to every subscriber of one server.
+pub(crate) struct EventHub {
+    sender: broadcast::Sender<Arc<PublishedEvent>>,
+    sequence: AtomicU64,
+}
+
+impl EventHub {
+    pub(crate) fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// Receive every event published from now on.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Arc<PublishedEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Whether anyone is listening, so callers can skip snapshotting the
+    /// workspace when no one is.
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// Sequence number of the most recent event; 0 before any.
+    pub(crate) fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
+
+    /// Publish the events between two snapshots of the workspace.
+    ///
+    /// Callers hold the workspace's write lock, so sequence numbers follow
+    /// the order in which changes were made.
+    pub(crate) fn publish(&self, source: &'static str, before: &[Issue], after: &[Issue]) {
+        for (subject, event) in events_between(before, after) {
+            let published = PublishedEvent {
+                sequence: self.sequence.fetch_add(1, Ordering::SeqCst) + 1,
+                timestamp: Utc::now(),
+                source,
+                event,
+                subject,
+            };
+            // Sending only fails when nobody is subscribed.
+            let _ = self.sender.send(Arc::new(published));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_match_on_every_given_dimension() {
+        let subject = Subject {
+            issue_id: IssueId::new("test-1"),
+            labels: ["ui".to_string()].into(),
+            assignees: ["alice".to_string()].into(),
+        };
+
+        assert!(EventFilter::default().matches(&subject));
+        let by_label = EventFilter {
+            labels: vec!["backend".to_string(), "ui".to_string()],
+            ..EventFilter::default()
+        };
+        assert!(by_label.matches(&subject));
+        let by_label_and_assignee = EventFilter {
+            assignees: vec!["bob".to_string()],
+            ..by_label
+        };
+        assert!(!by_label_and_assignee.matches(&subject));
+        let by_id = EventFilter {
+            issue_ids: vec![IssueId::new("test-2")],
+            ..EventFilter::default()
+        };
+        assert!(!by_id.matches(&subject));
+    }
+}
@@ -0,0 +1,712 @@
+//! Endpoint handlers.
+//!
+//! Each handler reloads the workspace first if its data file changed on disk,
+//! and every mutation is saved, and its events published, before the response
+//! is sent. A save refused
+//! because another process changed the file discards the mutation, so the
+//! client sees a conflict rather than overwriting that process's changes.
+
+use super::error::ApiError;
+use super::events::{API_SOURCE, EventHub, RELOAD_SOURCE};
+use super::query::{IssueQuery, Page};
+use super::{ApiState, SharedState};
+use crate::app::App;
+use crate::commands::milestones::require_milestone;
+use crate::commands::templates::{ExplicitFields, IssueTemplate};
+use crate::domain::{
+    CustomFieldValue, Dependency, DependencyType, Issue, IssueId, IssueKind, IssueStatus,
+    IssueUpdate, NewIssue, NewResource, NoteContent, ResourceId, ResourceUpdate,
+};
+use crate::output::json::BlockedIssue;
+use axum::Json;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Path, RawQuery, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{Deref, DerefMut};
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+type ApiResult<T = Response> = Result<T, ApiError>;
+
+// ===== Request bodies =====
+
+/// Body of `POST /issues`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CreateIssueRequest {
+    title: String,
+    description: Option<String>,
+    priority: Option<u8>,
+    issue_kind: Option<IssueKind>,
+    assignee: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    design: Option<String>,
+    acceptance_criteria: Option<String>,
+    initial_note: Option<NoteContent>,
+    #[serde(default)]
+    custom_fields: BTreeMap<String, CustomFieldValue>,
+    due_at: Option<DateTime<Utc>>,
+    milestone: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<DependencyRequest>,
+    /// Template to apply; defaults to the one named after the issue kind.
+    template: Option<String>,
+}
+
+/// Body of `POST /issues/{id}/dependencies`, and one entry of a create
+/// request's `dependencies`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct DependencyRequest {
+    depends_on_id: IssueId,
+    dep_type: Option<DependencyType>,
+}
+
+impl DependencyRequest {
+    fn dep_type(&self) -> DependencyType {
+        self.dep_type.unwrap_or(DependencyType::Blocks)
+    }
+}
+
+/// Body of `POST /issues/{id}/close` and `POST /issues/{id}/reopen`; `{}`
+/// changes the status without a note.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ReasonRequest {
+    reason: Option<String>,
+}
+
+// ===== Response bodies =====
+
+#[derive(Serialize)]
+struct IssuePage {
+    issues: Vec<Issue>,
+    total: usize,
+    limit: usize,
+    offset: usize,
+}
+
+impl IssuePage {
+    fn new(issues: Vec<Issue>, page: Page) -> Self {
+        let total = issues.len();
+        Self {
+            issues: issues
+                .into_iter()
+                .skip(page.offset)
+                .take(page.limit)
+                .collect(),
+            total,
+            limit: page.limit,
+            offset: page.offset,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Dependencies {
+    dependencies: Vec<Dependency>,
+    dependents: Vec<Dependency>,
+}
+
+#[derive(Serialize)]
+struct LabelCount {
+    name: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct Health<'a> {
+    status: &'static str,
+    version: &'static str,
+    workspace: &'a std::path::Path,
+    issue_count: usize,
+}
+
+// ===== Helpers =====
+
+/// Serialize `value` as a JSON response.
+fn json<T: Serialize>(status: StatusCode, value: &T) -> ApiResult {
+    let body = serde_json::to_string(value).map_err(crate::error::Error::from)?;
+    Ok((status, [(header::CONTENT_TYPE, "application/json")], body).into_response())
+}
+
+/// Respond with `issue` and its entity tag.
+fn issue_response(status: StatusCode, issue: &Issue) -> ApiResult {
+    let mut response = json(status, issue)?;
+    if let Ok(etag) = HeaderValue::from_str(&etag(issue)) {
+        response.headers_mut().insert(header::ETAG, etag);
+    }
+    Ok(response)
+}
+
+/// Unwrap a JSON request body.
+///
+/// Bodies must be sent as `application/json`, which a browser cannot do
+/// cross-site without a CORS preflight the server never answers.
+fn body<T>(payload: Result<Json<T>, JsonRejection>) -> ApiResult<T> {
+    let Json(value) = payload?;
+    Ok(value)
+}
+
+/// The strong entity tag of an issue's current state.
+pub(crate) fn etag(issue: &Issue) -> String {
+    let json = serde_json::to_vec(issue).unwrap_or_default();
+    let digest = Sha256::digest(&json);
+    let hex: String = digest[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("\"{hex}\"")
+}
+
+/// Whether an `If-Match` header value matches the entity tag `current`.
+///
+/// Weak tags never match, as `If-Match` requires strong comparison.
+fn if_match_matches(header: &str, current: &str) -> bool {
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag == current)
+}
+
+/// Fetch an issue, failing with `not_found` if it does not exist.
+async fn require_issue(app: &App, id: &IssueId) -> ApiResult<Issue> {
+    app.storage()
+        .get(id)
+        .await?
+        .ok_or_else(|| crate::error::Error::IssueNotFound(id.clone()).into())
+}
+
+/// Check the request's `If-Match` header, if any, against the issue's
+/// current state.
+async fn check_precondition(app: &App, id: &IssueId, headers: &HeaderMap) -> ApiResult<()> {
+    let Some(expected) = headers.get(header::IF_MATCH) else {
+        return Ok(());
+    };
+    let expected = expected
+        .to_str()
+        .map_err(|_| ApiError::bad_request("Invalid If-Match header"))?;
+    let current = etag(&require_issue(app, id).await?);
+    if if_match_matches(expected, &current) {
+        Ok(())
+    } else {
+        Err(ApiError::precondition_failed(format!(
+            "Issue {id} has changed since it was read (current ETag {current})"
+        )))
+    }
+}
+
+/// Check custom field values against the workspace schema.
+fn check_custom_fields(
+    app: &App,
+    fields: BTreeMap<String, CustomFieldValue>,
+) -> ApiResult<BTreeMap<String, CustomFieldValue>> {
+    fields
+        .into_iter()
+        .map(|(name, value)| {
+            let value = app.custom_fields().check(&name, value)?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+impl ApiState {
+    /// Lock the workspace for reading, reloading it first if it changed on disk.
+    async fn read(&self) -> ApiResult<RwLockReadGuard<'_, App>> {
+        {
+            let app = self.app.read().await;
+            if !app.storage().changed_on_disk().await? {
+                return Ok(app);
+            }
+        }
+        drop(self.write().await?);
+        Ok(self.app.read().await)
+    }
+
+    /// Lock the workspace for writing, reloading it first if it changed on disk.
+    ///
+    /// Changes picked up by the reload are published as events, and when
+    /// anyone is subscribed or hooks are configured the workspace is
+    /// snapshotted so [`Writer::commit`] can report the caller's changes too.
+    async fn write(&self) -> ApiResult<Writer<'_>> {
+        let mut app = self.app.write().await;
+        if app.storage().changed_on_disk().await? {
+            tracing::debug!("Data file changed on disk, reloading workspace");
+            let before = if self.events.has_subscribers() {
+                Some(app.storage().export_all().await?)
+            } else {
+                None
+            };
+            app.storage_mut().reload().await?;
+            if let Some(before) = before {
+                let after = app.storage().export_all().await?;
+                self.events.publish(RELOAD_SOURCE, &before, &after);
+            }
+        }
+        let before = snapshot(&app, &self.events).await?;
+        Ok(Writer {
+            app,
+            before,
+            events: &self.events,
+        })
+    }
+}
+
+/// Every issue in the workspace, if anyone is subscribed to events or hooks
+/// are configured.
+async fn snapshot(app: &App, events: &EventHub) -> ApiResult<Option<Vec<Issue>>> {
+    if events.has_subscribers() || !app.hooks().is_empty() {
+        Ok(Some(app.storage().export_all().await?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The workspace locked for a mutation.
+struct Writer<'a> {
+    app: RwLockWriteGuard<'a, App>,
+    before: Option<Vec<Issue>>,
+    events: &'a EventHub,
+}
+
+impl Writer<'_> {
+    /// Persist the mutation, publish its events and fire hooks for it,
+    /// discarding it if the save fails.
+    ///
+    /// Hooks run in the background so a slow hook never delays the response.
+    async fn commit(&mut self) -> ApiResult<()> {
+        if let Err(error) = self.app.save().await {
+            if let Err(reload_error) = self.app.storage_mut().reload().await {
+                tracing::error!(error = %reload_error, "Failed to reload after save error");
+            }
+            return Err(error.into());
+        }
+        if let Some(before) = self.before.take() {
+            let after = self.app.storage().export_all().await?;
+            self.events.publish(API_SOURCE, &before, &after);
+            let hooks = self.app.hooks().clone();
+            if !hooks.is_empty() {
+                tokio::spawn(async move { hooks.fire(API_SOURCE, &before, &after).await });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Writer<'_> {
+    type Target = App;
+
+    fn deref(&self) -> &App {
+        &self.app
+    }
+}
+
+impl DerefMut for Writer<'_> {
+    fn deref_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+// ===== Issues =====
+
+/// `GET /issues`
+pub(crate) async fn list_issues(
+    State(state): State<SharedState>,
+    RawQuery(query): RawQuery,
+) -> ApiResult {
+    let app = state.read().await?;
+    let query = IssueQuery::parse(query.as_deref(), app.custom_fields())?;
+    let issues = app.storage().list(&query.filter).await?;
+    json(StatusCode::OK, &IssuePage::new(issues, query.page))
+}
+
+/// `POST /issues`
+pub(crate) async fn create_issue(
+    State(state): State<SharedState>,
+    payload: Result<Json<CreateIssueRequest>, JsonRejection>,
+) -> ApiResult {
+    let request = body(payload)?;
+    let mut app = state.write().await?;
+
+    let custom_fields = check_custom_fields(&app, request.custom_fields)?;
+    if let Some(milestone) = &request.milestone {
+        require_milestone(app.rivets_dir(), milestone).await?;
+    }
+    let explicit = ExplicitFields {
+        kind: request.issue_kind.is_some(),
+        priority: request.priority.is_some(),
+        description: request.description.is_some(),
+    };
+    let mut new_issue = NewIssue {
+        title: request.title,
+        description: request.description.unwrap_or_default(),
+        priority: request.priority.unwrap_or(2),
+        issue_kind: request.issue_kind.unwrap_or(IssueKind::Task),
+        assignee: request.assignee,
+        labels: request.labels,
+        design: request.design,
+        acceptance_criteria: request.acceptance_criteria,
+        initial_note: request.initial_note,
+        custom_fields,
+        due_at: request.due_at,
+        milestone: request.milestone,
+        dependencies: request
+            .dependencies
+            .iter()
+            .map(|dependency| (dependency.depends_on_id.clone(), dependency.dep_type()))
+            .collect(),
+    };
+    if let Some(template) = IssueTemplate::resolve(
+        app.rivets_dir(),
+        request.template.as_deref(),
+        new_issue.issue_kind,
+    )
+    .await?
+    {
+        template.apply(&mut new_issue, explicit);
+    }
+
+    let issue = app.storage_mut().create(new_issue).await?;
+    app.commit().await?;
+    issue_response(StatusCode::CREATED, &issue)
+}
+
+/// `GET /issues/{id}`
+pub(crate) async fn get_issue(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+) -> ApiResult {
+    let app = state.read().await?;
+    issue_response(StatusCode::OK, &require_issue(&app, &id).await?)
+}
+
+/// `PATCH /issues/{id}`
+pub(crate) async fn update_issue(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+    headers: HeaderMap,
+    payload: Result<Json<IssueUpdate>, JsonRejection>,
+) -> ApiResult {
+    let mut updates = body(payload)?;
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+
+    for (name, value) in &mut updates.custom_fields {
+        match value.take() {
+            Some(field_value) => *value = Some(app.custom_fields().check(name, field_value)?),
+            None => {
+                app.custom_fields().field_type(name)?;
+            }
+        }
+    }
+    if let Some(Some(milestone)) = &updates.milestone {
+        require_milestone(app.rivets_dir(), milestone).await?;
+    }
+
+    let issue = app.storage_mut().update(&id, updates).await?;
+    app.commit().await?;
+    issue_response(StatusCode::OK, &issue)
+}
+
+/// `DELETE /issues/{id}`
+pub(crate) async fn delete_issue(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    app.storage_mut().delete(&id).await?;
+    app.commit().await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// `POST /issues/{id}/close`
+pub(crate) async fn close_issue(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+    headers: HeaderMap,
+    payload: Result<Json<ReasonRequest>, JsonRejection>,
+) -> ApiResult {
+    let request = body(payload)?;
+    let note = request
+        .reason
+        .map(NoteContent::closing_reason)
+        .transpose()
+        .map_err(|error| ApiError::validation(error.to_string()))?;
+    change_status(&state, &id, &headers, IssueStatus::Closed, note).await
+}
+
+/// `POST /issues/{id}/reopen`
+pub(crate) async fn reopen_issue(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+    headers: HeaderMap,
+    payload: Result<Json<ReasonRequest>, JsonRejection>,
+) -> ApiResult {
+    let request = body(payload)?;
+    let note = request
+        .reason
+        .map(NoteContent::reopening_reason)
+        .transpose()
+        .map_err(|error| ApiError::validation(error.to_string()))?;
+    change_status(&state, &id, &headers, IssueStatus::Open, note).await
+}
+
+async fn change_status(
+    state: &ApiState,
+    id: &IssueId,
+    headers: &HeaderMap,
+    status: IssueStatus,
+    note: Option<NoteContent>,
+) -> ApiResult {
+    let mut app = state.write().await?;
+    check_precondition(&app, id, headers).await?;
+    let updates = IssueUpdate {
+        status: Some(status),
+        note,
+        ..IssueUpdate::default()
+    };
+    let issue = app.storage_mut().update(id, updates).await?;
+    app.commit().await?;
+    issue_response(StatusCode::OK, &issue)
+}
+
+// ===== Dependencies =====
+
+/// `GET /issues/{id}/dependencies`
+pub(crate) async fn list_dependencies(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+) -> ApiResult {
+    let app = state.read().await?;
+    require_issue(&app, &id).await?;
+    let dependencies = Dependencies {
+        dependencies: app.storage().get_dependencies(&id).await?,
+        dependents: app.storage().get_dependents(&id).await?,
+    };
+    json(StatusCode::OK, &dependencies)
+}
+
+/// `POST /issues/{id}/dependencies`
+pub(crate) async fn add_dependency(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+    headers: HeaderMap,
+    payload: Result<Json<DependencyRequest>, JsonRejection>,
+) -> ApiResult {
+    let request = body(payload)?;
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    let dep_type = request.dep_type();
+    app.storage_mut()
+        .add_dependency(&id, &request.depends_on_id, dep_type)
+        .await?;
+    app.commit().await?;
+    let dependency = Dependency {
+        depends_on_id: request.depends_on_id,
+        dep_type,
+    };
+    json(StatusCode::CREATED, &dependency)
+}
+
+/// `DELETE /issues/{id}/dependencies/{depends_on_id}`
+pub(crate) async fn remove_dependency(
+    State(state): State<SharedState>,
+    Path((id, depends_on_id)): Path<(IssueId, IssueId)>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    app.storage_mut()
+        .remove_dependency(&id, &depends_on_id)
+        .await?;
+    app.commit().await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// ===== Labels =====
+
+/// `GET /issues/{id}/labels`
+pub(crate) async fn list_issue_labels(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+) -> ApiResult {
+    let app = state.read().await?;
+    let issue = require_issue(&app, &id).await?;
+    json(
+        StatusCode::OK,
+        &serde_json::json!({ "labels": issue.labels }),
+    )
+}
+
+/// `POST /issues/{id}/labels/{label}`
+pub(crate) async fn add_label(
+    State(state): State<SharedState>,
+    Path((id, label)): Path<(IssueId, String)>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    let issue = app.storage_mut().add_label(&id, &label).await?;
+    app.commit().await?;
+    issue_response(StatusCode::OK, &issue)
+}
+
+/// `DELETE /issues/{id}/labels/{label}`
+pub(crate) async fn remove_label(
+    State(state): State<SharedState>,
+    Path((id, label)): Path<(IssueId, String)>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    let issue = app.storage_mut().remove_label(&id, &label).await?;
+    app.commit().await?;
+    issue_response(StatusCode::OK, &issue)
+}
+
+/// `GET /labels`
+pub(crate) async fn list_all_labels(State(state): State<SharedState>) -> ApiResult {
+    let app = state.read().await?;
+    let issues = app.storage().export_all().await?;
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for issue in &issues {
+        for label in issue.labels.iter().collect::<BTreeSet<_>>() {
+            *counts.entry(label).or_default() += 1;
+        }
+    }
+    let labels: Vec<LabelCount> = counts
+        .into_iter()
+        .map(|(name, count)| LabelCount {
+            name: name.to_string(),
+            count,
+        })
+        .collect();
+    json(StatusCode::OK, &serde_json::json!({ "labels": labels }))
+}
+
+// ===== Resources =====
+
+/// `GET /issues/{id}/resources`
+pub(crate) async fn list_resources(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+) -> ApiResult {
+    let app = state.read().await?;
+    let issue = require_issue(&app, &id).await?;
+    json(
+        StatusCode::OK,
+        &serde_json::json!({ "resources": issue.resources() }),
+    )
+}
+
+/// `POST /issues/{id}/resources`
+pub(crate) async fn add_resource(
+    State(state): State<SharedState>,
+    Path(id): Path<IssueId>,
+    headers: HeaderMap,
+    payload: Result<Json<NewResource>, JsonRejection>,
+) -> ApiResult {
+    let resource = body(payload)?;
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    let issue = app.storage_mut().add_resource(&id, resource).await?;
+    app.commit().await?;
+    issue_response(StatusCode::CREATED, &issue)
+}
+
+/// `PATCH /issues/{id}/resources/{resource_id}`
+pub(crate) async fn update_resource(
+    State(state): State<SharedState>,
+    Path((id, resource_id)): Path<(IssueId, ResourceId)>,
+    headers: HeaderMap,
+    payload: Result<Json<ResourceUpdate>, JsonRejection>,
+) -> ApiResult {
+    let update = body(payload)?;
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    let issue = app
+        .storage_mut()
+        .update_resource(&id, &resource_id, update)
+        .await?;
+    app.commit().await?;
+    issue_response(StatusCode::OK, &issue)
+}
+
+/// `DELETE /issues/{id}/resources/{resource_id}`
+pub(crate) async fn remove_resource(
+    State(state): State<SharedState>,
+    Path((id, resource_id)): Path<(IssueId, ResourceId)>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let mut app = state.write().await?;
+    check_precondition(&app, &id, &headers).await?;
+    let issue = app.storage_mut().remove_resource(&id, &resource_id).await?;
+    app.commit().await?;
+    issue_response(StatusCode::OK, &issue)
+}
+
+// ===== Queries =====
+
+/// `GET /ready`
+pub(crate) async fn ready(
+    State(state): State<SharedState>,
+    RawQuery(query): RawQuery,
+) -> ApiResult {
+    let app = state.read().await?;
+    let query = IssueQuery::parse(query.as_deref(), app.custom_fields())?;
+    let issues = app
+        .storage()
+        .ready_to_work(Some(&query.filter), query.sort)
+        .await?;
+    json(StatusCode::OK, &IssuePage::new(issues, query.page))
+}
+
+/// `GET /blocked`
+pub(crate) async fn blocked(State(state): State<SharedState>) -> ApiResult {
+    let app = state.read().await?;
+    let blocked = app.storage().blocked_issues().await?;
+    let blocked: Vec<BlockedIssue> = blocked
+        .iter()
+        .map(|(issue, blockers)| BlockedIssue {
+            issue,
+            blocked_by: blockers.iter().collect(),
+        })
+        .collect();
+    json(StatusCode::OK, &serde_json::json!({ "blocked": blocked }))
+}
+
+/// `GET /health`
+pub(crate) async fn health(State(state): State<SharedState>) -> ApiResult {
+    let app = state.read().await?;
+    let issue_count = app.storage().export_all().await?.len();
+    json(
+        StatusCode::OK,
+        &Health {
+            status: "ok",
+            version: env!("CARGO_PKG_VERSION"),
+            workspace: &state.workspace_root,
+            issue_count,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_match_accepts_the_current_tag_a_list_or_a_wildcard() {
+        assert!(if_match_matches("\"abc\"", "\"abc\""));
+        assert!(if_match_matches("\"old\", \"abc\"", "\"abc\""));
+        assert!(if_match_matches("*", "\"abc\""));
+        assert!(!if_match_matches("\"old\"", "\"abc\""));
+        assert!(!if_match_matches("W/\"abc\"", "\"abc\""));
+    }
+}
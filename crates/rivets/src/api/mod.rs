@@ -0,0 +1,716 @@
+//! Local REST API.
+//!
+//! `rivets serve` exposes the [`IssueStorage`](crate::storage::IssueStorage)
+//! operations of one workspace as JSON endpoints under [`API_PREFIX`], so
+//! dashboards and editor plugins can use a stable API instead of shelling out
+//! to `--json`:
+//!
+//! | Endpoint | Methods |
+//! |----------|---------|
+//! | `/issues` | `GET` (filtered, paginated), `POST` |
+//! | `/issues/{id}` | `GET`, `PATCH`, `DELETE` |
+//! | `/issues/{id}/close`, `/issues/{id}/reopen` | `POST` |
+//! | `/issues/{id}/dependencies` | `GET`, `POST` |
+//! | `/issues/{id}/dependencies/{depends_on_id}` | `DELETE` |
+//! | `/issues/{id}/labels` | `GET` |
+//! | `/issues/{id}/labels/{label}` | `POST`, `DELETE` |
+//! | `/issues/{id}/resources` | `GET`, `POST` |
+//! | `/issues/{id}/resources/{resource_id}` | `PATCH`, `DELETE` |
+//! | `/labels`, `/ready`, `/blocked`, `/health` | `GET` |
+//! | `/events` | `GET` (WebSocket) |
+//!
+//! Issue responses carry an `ETag`. Requests that change an issue honor
+//! `If-Match` and fail with `412 Precondition Failed` when the issue changed
+//! since the client read it.
+//!
+//! `/events` upgrades to a WebSocket that streams typed change
+//! [`events`] as issues are created, updated, closed, linked and deleted,
+//! whether through the API or by another process writing the data file.
+//! Subscribers can narrow the stream to particular issues, labels or
+//! assignees.
+//!
+//! The server only listens on loopback addresses and rejects requests whose
+//! `Host` is not a loopback name, so a web page cannot reach it through DNS
+//! rebinding. Requests from a page on another origin are refused by their
+//! `Origin` header, and request bodies must be `application/json`, so a
+//! cross-site form cannot submit one either. See `docs/design/rest-api.md` for request and response shapes.
+
+mod error;
+pub mod events;
+mod handlers;
+mod query;
+mod websocket;
+
+use crate::app::App;
+use axum::Router;
+use axum::extract::Request;
+use axum::http::header;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use error::ApiError;
+use events::EventHub;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+
+/// Path prefix every endpoint is served under.
+pub const API_PREFIX: &str = "/api/v1";
+
+/// Address `rivets serve` listens on by default.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+/// The workspace a server serves.
+pub(crate) struct ApiState {
+    app: RwLock<App>,
+    workspace_root: PathBuf,
+    events: EventHub,
+}
+
+type SharedState = Arc<ApiState>;
+
+/// Build the router serving `app` under [`API_PREFIX`].
+pub fn router(app: App) -> Router {
+    let workspace_root = app
+        .rivets_dir()
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let state = Arc::new(ApiState {
+        app: RwLock::new(app),
+        workspace_root,
+        events: EventHub::new(),
+    });
+
+    let api = Router::new()
+        .route(
+            "/issues",
+            get(handlers::list_issues).post(handlers::create_issue),
+        )
+        .route(
+            "/issues/{id}",
+            get(handlers::get_issue)
+                .patch(handlers::update_issue)
+                .delete(handlers::delete_issue),
+        )
+        .route("/issues/{id}/close", post(handlers::close_issue))
+        .route("/issues/{id}/reopen", post(handlers::reopen_issue))
+        .route(
+            "/issues/{id}/dependencies",
+            get(handlers::list_dependencies).post(handlers::add_dependency),
+        )
+        .route(
+            "/issues/{id}/dependencies/{depends_on_id}",
+            axum::routing::delete(handlers::remove_dependency),
+        )
+        .route("/issues/{id}/labels", get(handlers::list_issue_labels))
+        .route(
+            "/issues/{id}/labels/{label}",
+            post(handlers::add_label).delete(handlers::remove_label),
+        )
+        .route(
+            "/issues/{id}/resources",
+            get(handlers::list_resources).post(handlers::add_resource),
+        )
+        .route(
+            "/issues/{id}/resources/{resource_id}",
+            axum::routing::patch(handlers::update_resource).delete(handlers::remove_resource),
+        )
+        .route("/labels", get(handlers::list_all_labels))
+        .route("/ready", get(handlers::ready))
+        .route("/blocked", get(handlers::blocked))
+        .route("/health", get(handlers::health))
+        .route("/events", get(websocket::events))
+        .with_state(state);
+
+    Router::new()
+        .nest(API_PREFIX, api)
+        .layer(middleware::from_fn(require_loopback_origin))
+        .layer(middleware::from_fn(require_loopback_host))
+}
+
+/// Bind a listener for the API on `addr`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `addr` is not a loopback address, or any error
+/// binding the socket.
+pub async fn bind(addr: SocketAddr) -> io::Result<TcpListener> {
+    if !addr.ip().is_loopback() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("refusing to listen on non-loopback address {addr}"),
+        ));
+    }
+    TcpListener::bind(addr).await
+}
+
+/// Serve the API for `app` on `listener` until `shutdown` completes.
+///
+/// # Errors
+///
+/// Returns any error serving the socket.
+pub async fn serve(
+    listener: TcpListener,
+    app: App,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    tracing::info!(addr = %listener.local_addr()?, prefix = API_PREFIX, "Serving REST API");
+    axum::serve(listener, router(app))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+/// Reject requests addressed to a host name other than a loopback one.
+async fn require_loopback_host(request: Request, next: Next) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .map(|host| host.to_str().unwrap_or_default());
+    match host {
+        Some(host) if !is_loopback_host(host) => {
+            ApiError::forbidden(format!("Host '{host}' is not a loopback address")).into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Reject requests sent by a page whose origin is not on this machine.
+///
+/// Browsers send `Origin` with every cross-origin request, including the
+/// simple `POST`s that skip a CORS preflight; other clients omit it.
+async fn require_loopback_origin(request: Request, next: Next) -> Response {
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .map(|origin| origin.to_str().unwrap_or_default());
+    match origin {
+        Some(origin) if !is_loopback_origin(origin) => {
+            ApiError::forbidden(format!("Origin '{origin}' is not a loopback address"))
+                .into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Whether an `Origin` header names a page served from this machine.
+fn is_loopback_origin(origin: &str) -> bool {
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(is_loopback_host)
+}
+
+/// Whether a `Host` header names this machine.
+fn is_loopback_host(host: &str) -> bool {
+    let name = if let Some(rest) = host.strip_prefix('[') {
+        rest.split(']').next().unwrap_or_default()
+    } else {
+        host.rsplit_once(':').map_or(host, |(name, _)| name)
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init::init;
+    use axum::body::Body;
+    use axum::http::{Method, StatusCode};
+    use futures::{SinkExt, StreamExt};
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+    use tower::ServiceExt;
+
+    struct Fixture {
+        _workspace: TempDir,
+        router: Router,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let workspace = TempDir::new().unwrap();
+            init(workspace.path(), Some("test")).await.unwrap();
+            let app = App::from_directory_direct(workspace.path()).await.unwrap();
+            Self {
+                _workspace: workspace,
+                router: router(app),
+            }
+        }
+
+        async fn send(
+            &self,
+            method: Method,
+            path: &str,
+            headers: &[(header::HeaderName, &str)],
+            body: Option<Value>,
+        ) -> Response {
+            let mut builder = Request::builder()
+                .method(method)
+                .uri(format!("{API_PREFIX}{path}"))
+                .header(header::HOST, "127.0.0.1:7878");
+            if body.is_some() && !headers.iter().any(|(name, _)| name == header::CONTENT_TYPE) {
+                builder = builder.header(header::CONTENT_TYPE, "application/json");
+            }
+            for (name, value) in headers {
+                builder = builder.header(name, *value);
+            }
+            let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+            self.router
+                .clone()
+                .oneshot(builder.body(body).unwrap())
+                .await
+                .unwrap()
+        }
+
+        async fn call(
+            &self,
+            method: Method,
+            path: &str,
+            body: Option<Value>,
+        ) -> (StatusCode, Value) {
+            let response = self.send(method, path, &[], body).await;
+            (response.status(), json_body(response).await)
+        }
+
+        async fn create(&self, body: Value) -> Value {
+            let (status, issue) = self.call(Method::POST, "/issues", Some(body)).await;
+            assert_eq!(status, StatusCode::CREATED, "{issue}");
+            issue
+        }
+    }
+
+    async fn json_body(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn creates_reads_and_lists_issues() {
+        let fixture = Fixture::new().await;
+        let issue = fixture
+            .create(json!({"title": "First", "priority": 1, "labels": ["ui"]}))
+            .await;
+        let id = issue["id"].as_str().unwrap();
+        fixture.create(json!({"title": "Second"})).await;
+
+        let (status, shown) = fixture
+            .call(Method::GET, &format!("/issues/{id}"), None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(shown["title"], "First");
+
+        let (status, page) = fixture.call(Method::GET, "/issues?priority=1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["issues"][0]["id"], id);
+
+        let (_, page) = fixture
+            .call(Method::GET, "/issues?limit=1&offset=1", None)
+            .await;
+        assert_eq!(page["total"], 2);
+        assert_eq!(page["issues"].as_array().unwrap().len(), 1);
+        assert_eq!(page["limit"], 1);
+        assert_eq!(page["offset"], 1);
+
+        let (status, error) = fixture.call(Method::GET, "/issues/test-none", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["error"]["code"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn updates_honor_if_match() {
+        let fixture = Fixture::new().await;
+        let issue = fixture.create(json!({"title": "Original"})).await;
+        let path = format!("/issues/{}", issue["id"].as_str().unwrap());
+
+        let response = fixture.send(Method::GET, &path, &[], None).await;
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let response = fixture
+            .send(
+                Method::PATCH,
+                &path,
+                &[(header::IF_MATCH, &etag)],
+                Some(json!({"title": "Renamed", "assignee": "alice"})),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag.as_str());
+        assert_eq!(json_body(response).await["title"], "Renamed");
+
+        let response = fixture
+            .send(
+                Method::PATCH,
+                &path,
+                &[(header::IF_MATCH, &etag)],
+                Some(json!({"title": "Stale"})),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        let (status, updated) = fixture
+            .call(Method::PATCH, &path, Some(json!({"assignee": null})))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["title"], "Renamed");
+        assert_eq!(updated["assignee"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn manages_dependencies_labels_and_resources() {
+        let fixture = Fixture::new().await;
+        let blocker = fixture.create(json!({"title": "Blocker"})).await;
+        let blocked = fixture.create(json!({"title": "Blocked"})).await;
+        let blocker_id = blocker["id"].as_str().unwrap();
+        let blocked_id = blocked["id"].as_str().unwrap();
+
+        let (status, _) = fixture
+            .call(
+                Method::POST,
+                &format!("/issues/{blocked_id}/dependencies"),
+                Some(json!({"depends_on_id": blocker_id})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (_, ready) = fixture.call(Method::GET, "/ready", None).await;
+        assert_eq!(ready["total"], 1);
+        assert_eq!(ready["issues"][0]["id"], blocker_id);
+        let (_, blocked_list) = fixture.call(Method::GET, "/blocked", None).await;
+        assert_eq!(blocked_list["blocked"][0]["issue"]["id"], blocked_id);
+        assert_eq!(
+            blocked_list["blocked"][0]["blocked_by"][0]["id"],
+            blocker_id
+        );
+
+        let (status, cycle) = fixture
+            .call(
+                Method::POST,
+                &format!("/issues/{blocker_id}/dependencies"),
+                Some(json!({"depends_on_id": blocked_id})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(cycle["error"]["code"], "conflict");
+
+        let (status, _) = fixture
+            .call(
+                Method::DELETE,
+                &format!("/issues/{blocked_id}/dependencies/{blocker_id}"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, labelled) = fixture
+            .call(
+                Method::POST,
+                &format!("/issues/{blocked_id}/labels/backend"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(labelled["labels"], json!(["backend"]));
+        let (_, labels) = fixture.call(Method::GET, "/labels", None).await;
+        assert_eq!(labels["labels"], json!([{"name": "backend", "count": 1}]));
+
+        let (status, _) = fixture
+            .call(
+                Method::POST,
+                &format!("/issues/{blocked_id}/resources"),
+                Some(json!({
+                    "target": {"type": "web", "url": "https://example.com/pull/1"},
+                    "role": "implementation"
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, resources) = fixture
+            .call(
+                Method::GET,
+                &format!("/issues/{blocked_id}/resources"),
+                None,
+            )
+            .await;
+        let resource_id = resources["resources"][0]["id"].as_str().unwrap();
+        let (status, _) = fixture
+            .call(
+                Method::DELETE,
+                &format!("/issues/{blocked_id}/resources/{resource_id}"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn closes_reopens_and_deletes() {
+        let fixture = Fixture::new().await;
+        let issue = fixture.create(json!({"title": "Lifecycle"})).await;
+        let path = format!("/issues/{}", issue["id"].as_str().unwrap());
+
+        let (_, closed) = fixture
+            .call(
+                Method::POST,
+                &format!("{path}/close"),
+                Some(json!({"reason": "Done"})),
+            )
+            .await;
+        assert_eq!(closed["status"], "closed");
+        let (status, _) = fixture
+            .call(Method::POST, &format!("{path}/close"), Some(json!({})))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (_, reopened) = fixture
+            .call(Method::POST, &format!("{path}/reopen"), Some(json!({})))
+            .await;
+        assert_eq!(reopened["status"], "open");
+
+        let (status, _) = fixture.call(Method::DELETE, &path, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = fixture.call(Method::GET, &path, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        let fixture = Fixture::new().await;
+
+        let (status, error) = fixture
+            .call(Method::POST, "/issues", Some(json!({"title": ""})))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["error"]["code"], "validation_error");
+
+        let (status, error) = fixture
+            .call(Method::POST, "/issues", Some(json!({"name": "typo"})))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["error"]["code"], "bad_request");
+
+        let (status, _) = fixture.call(Method::GET, "/issues?colour=red", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, error) = fixture.call(Method::GET, "/events", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["error"]["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn rejects_non_loopback_hosts() {
+        let fixture = Fixture::new().await;
+        let request = Request::get(format!("{API_PREFIX}/health"))
+            .header(header::HOST, "attacker.example:7878")
+            .body(Body::empty())
+            .unwrap();
+        let response = fixture.router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        for host in ["localhost:7878", "127.0.0.1", "[::1]:7878"] {
+            assert!(is_loopback_host(host), "{host}");
+        }
+        assert!(!is_loopback_host("192.168.1.2:7878"));
+    }
+
+    #[tokio::test]
+    async fn rejects_cross_site_requests() {
+        let fixture = Fixture::new().await;
+        let issue = fixture.create(json!({"title": "Target"})).await;
+        let path = format!("/issues/{}", issue["id"].as_str().unwrap());
+
+        let response = fixture
+            .send(
+                Method::POST,
+                "/issues",
+                &[(header::CONTENT_TYPE, "text/plain")],
+                Some(json!({"title": "Forged"})),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let (status, _) = fixture
+            .call(Method::POST, &format!("{path}/close"), None)
+            .await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let response = fixture
+            .send(
+                Method::POST,
+                &format!("{path}/labels/forged"),
+                &[(header::ORIGIN, "https://attacker.example")],
+                None,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let (_, page) = fixture.call(Method::GET, "/issues", None).await;
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["issues"][0]["status"], "open");
+        assert_eq!(page["issues"][0]["labels"], json!([]));
+
+        let response = fixture
+            .send(
+                Method::POST,
+                &format!("{path}/labels/local"),
+                &[(header::ORIGIN, "http://localhost:3000")],
+                None,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!is_loopback_origin("null"));
+    }
+
+    /// A running server and a WebSocket client subscribed to its events.
+    struct EventClient {
+        _workspace: TempDir,
+        addr: SocketAddr,
+        socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    }
+
+    impl EventClient {
+        async fn connect(query: &str) -> Self {
+            let workspace = TempDir::new().unwrap();
+            init(workspace.path(), Some("test")).await.unwrap();
+            let app = App::from_directory_direct(workspace.path()).await.unwrap();
+            let listener = bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(serve(listener, app, std::future::pending()));
+
+            let (socket, _) = connect_async(format!("ws://{addr}{API_PREFIX}/events{query}"))
+                .await
+                .unwrap();
+            let mut client = Self {
+                _workspace: workspace,
+                addr,
+                socket,
+            };
+            assert_eq!(client.receive().await["type"], "connected");
+            client
+        }
+
+        /// Make a plain HTTP request to the server.
+        async fn request(&self, method: &str, path: &str, body: Value) -> Value {
+            let mut stream = TcpStream::connect(self.addr).await.unwrap();
+            let body = body.to_string();
+            let request = format!(
+                "{method} {API_PREFIX}{path} HTTP/1.1\r\nHost: {}\r\n\
+                 Content-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{body}",
+                self.addr,
+                body.len()
+            );
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            let (_, body) = response.split_once("\r\n\r\n").unwrap();
+            serde_json::from_str(body).unwrap()
+        }
+
+        /// Send a text message.
+        async fn send(&mut self, message: Value) {
+            self.socket
+                .send(Message::text(message.to_string()))
+                .await
+                .unwrap();
+        }
+
+        /// Receive the next text message.
+        async fn receive(&mut self) -> Value {
+            let message =
+                tokio::time::timeout(std::time::Duration::from_secs(10), self.socket.next())
+                    .await
+                    .expect("timed out waiting for an event")
+                    .unwrap()
+                    .unwrap();
+            serde_json::from_str(message.to_text().unwrap()).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_filtered_change_events_over_websocket() {
+        let mut client = EventClient::connect("?label=ui").await;
+
+        let other = client
+            .request("POST", "/issues", json!({"title": "Other"}))
+            .await;
+        let issue = client
+            .request("POST", "/issues", json!({"title": "UI", "labels": ["ui"]}))
+            .await;
+        let id = issue["id"].as_str().unwrap();
+
+        let created = client.receive().await;
+        assert_eq!(created["type"], "event");
+        assert_eq!(created["source"], "api");
+        assert_eq!(created["event"]["type"], "created");
+        assert_eq!(created["event"]["issue"]["id"], id);
+
+        client
+            .request(
+                "PATCH",
+                &format!("/issues/{id}"),
+                json!({"title": "Renamed"}),
+            )
+            .await;
+        let updated = client.receive().await;
+        assert_eq!(updated["event"]["type"], "updated");
+        assert!(updated["sequence"].as_u64() > created["sequence"].as_u64());
+        assert_eq!(
+            updated["event"]["changes"],
+            json!([{"field": "title", "before": "UI", "after": "Renamed"}])
+        );
+
+        client
+            .request("POST", &format!("/issues/{id}/close"), json!({}))
+            .await;
+        let closed = client.receive().await;
+        assert_eq!(closed["event"]["type"], "closed");
+        assert_eq!(closed["event"]["issue_id"], id);
+
+        let other_id = other["id"].as_str().unwrap();
+        client
+            .send(json!({"type": "subscribe", "issue_ids": [other_id]}))
+            .await;
+        let subscribed = client.receive().await;
+        assert_eq!(subscribed["type"], "subscribed");
+        assert_eq!(subscribed["filter"]["issue_ids"], json!([other_id]));
+
+        client
+            .request(
+                "POST",
+                &format!("/issues/{other_id}/dependencies"),
+                json!({"depends_on_id": id}),
+            )
+            .await;
+        let added = client.receive().await;
+        assert_eq!(added["event"]["type"], "dependency_added");
+        assert_eq!(added["event"]["dependency"]["depends_on_id"], id);
+
+        client.send(json!({"type": "unsubscribe"})).await;
+        assert_eq!(client.receive().await["type"], "error");
+    }
+
+    #[tokio::test]
+    async fn refuses_non_loopback_address() {
+        let error = bind("0.0.0.0:0".parse().unwrap()).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
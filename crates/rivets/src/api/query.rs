@@ -0,0 +1,213 @@
+//! Query string parameters for issue listings and event subscriptions.
+
+use super::error::ApiError;
+use super::events::EventFilter;
+use crate::domain::{CustomFieldSchema, IssueFilter, IssueId, IssueStatus, SortPolicy};
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+use serde::de::value::StrDeserializer;
+use std::str::FromStr;
+
+/// Page size when a request does not give a `limit`.
+pub(crate) const DEFAULT_LIMIT: usize = 100;
+
+/// Largest `limit` a request may ask for.
+pub(crate) const MAX_LIMIT: usize = 1000;
+
+/// Which slice of a result set to return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Page {
+    pub(crate) limit: usize,
+    pub(crate) offset: usize,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+/// The parameters of `GET /issues` and `GET /ready`.
+///
+/// Every [`IssueFilter`] field has a parameter of the same name; custom
+/// fields are matched with repeated `field=key=value` parameters.
+#[derive(Debug, Default)]
+pub(crate) struct IssueQuery {
+    pub(crate) filter: IssueFilter,
+    pub(crate) sort: Option<SortPolicy>,
+    pub(crate) page: Page,
+}
+
+impl IssueQuery {
+    /// Parse a raw query string, checking custom fields against `schema`.
+    pub(crate) fn parse(query: Option<&str>, schema: &CustomFieldSchema) -> Result<Self, ApiError> {
+        let mut parsed = Self::default();
+        let pairs = url::form_urlencoded::parse(query.unwrap_or_default().as_bytes());
+        for (name, value) in pairs {
+            let filter = &mut parsed.filter;
+            match name.as_ref() {
+                "status" => filter.status = Some(parse::<IssueStatus>(&name, &value)?),
+                "priority" => filter.priority = Some(parse(&name, &value)?),
+                "issue_kind" => filter.issue_kind = Some(parse(&name, &value)?),
+                "assignee" => filter.assignee = Some(value.into_owned()),
+                "label" => filter.label = Some(value.into_owned()),
+                "field" => {
+                    let (field, field_value) = schema.parse_assignment(&value)?;
+                    let field_value = field_value.ok_or_else(|| {
+                        ApiError::validation(format!("Custom field filter '{value}' needs a value"))
+                    })?;
+                    filter.custom_fields.push((field, field_value));
+                }
+                "overdue" => filter.overdue = parse(&name, &value)?,
+                "milestone" => filter.milestone = Some(value.into_owned()),
+                "claimant" => filter.claimant = Some(value.into_owned()),
+                "sort" => {
+                    let deserializer: StrDeserializer<'_, serde::de::value::Error> =
+                        value.as_ref().into_deserializer();
+                    parsed.sort = Some(SortPolicy::deserialize(deserializer).map_err(|_| {
+                        ApiError::bad_request(format!(
+                            "Invalid sort '{value}'. Expected hybrid, priority or oldest"
+                        ))
+                    })?);
+                }
+                "limit" => {
+                    let limit = parse(&name, &value)?;
+                    if limit == 0 || limit > MAX_LIMIT {
+                        return Err(ApiError::bad_request(format!(
+                            "Invalid limit {limit}. Expected 1 to {MAX_LIMIT}"
+                        )));
+                    }
+                    parsed.page.limit = limit;
+                }
+                "offset" => parsed.page.offset = parse(&name, &value)?,
+                _ => {
+                    return Err(ApiError::bad_request(format!(
+                        "Unknown query parameter '{name}'"
+                    )));
+                }
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Parse the parameters of `GET /events`: repeated `issue_id`, `label` and
+/// `assignee` parameters.
+pub(crate) fn parse_event_filter(query: Option<&str>) -> Result<EventFilter, ApiError> {
+    let mut filter = EventFilter::default();
+    let pairs = url::form_urlencoded::parse(query.unwrap_or_default().as_bytes());
+    for (name, value) in pairs {
+        match name.as_ref() {
+            "issue_id" => filter.issue_ids.push(IssueId::new(value)),
+            "label" => filter.labels.push(value.into_owned()),
+            "assignee" => filter.assignees.push(value.into_owned()),
+            _ => {
+                return Err(ApiError::bad_request(format!(
+                    "Unknown query parameter '{name}'"
+                )));
+            }
+        }
+    }
+    Ok(filter)
+}
+
+/// Parse one query parameter value.
+fn parse<T>(name: &str, value: &str) -> Result<T, ApiError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|error| ApiError::bad_request(format!("Invalid {name} '{value}': {error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CustomFieldValue, IssueKind};
+
+    fn schema() -> CustomFieldSchema {
+        serde_yaml::from_str("severity:\n  type: enum\n  values: [low, high]\n").unwrap()
+    }
+
+    #[test]
+    fn parses_every_filter_parameter() {
+        let query = IssueQuery::parse(
+            Some(
+                "status=open&priority=1&issue_kind=bug&assignee=alice&label=ui\
+                 &field=severity%3Dhigh&overdue=true&milestone=v1&claimant=agent-1\
+                 &sort=oldest&limit=10&offset=20",
+            ),
+            &schema(),
+        )
+        .unwrap();
+
+        let filter = &query.filter;
+        assert_eq!(filter.status, Some(IssueStatus::Open));
+        assert_eq!(filter.priority, Some(1));
+        assert_eq!(filter.issue_kind, Some(IssueKind::Bug));
+        assert_eq!(filter.assignee.as_deref(), Some("alice"));
+        assert_eq!(filter.label.as_deref(), Some("ui"));
+        assert_eq!(
+            filter.custom_fields,
+            vec![(
+                "severity".to_string(),
+                CustomFieldValue::Text("high".to_string())
+            )]
+        );
+        assert!(filter.overdue);
+        assert_eq!(filter.milestone.as_deref(), Some("v1"));
+        assert_eq!(filter.claimant.as_deref(), Some("agent-1"));
+        assert_eq!(query.sort, Some(SortPolicy::Oldest));
+        assert_eq!(
+            query.page,
+            Page {
+                limit: 10,
+                offset: 20
+            }
+        );
+    }
+
+    #[test]
+    fn defaults_to_the_first_page_without_filters() {
+        let query = IssueQuery::parse(None, &schema()).unwrap();
+        assert_eq!(query.page, Page::default());
+        assert_eq!(query.filter.status, None);
+        assert_eq!(query.sort, None);
+    }
+
+    #[test]
+    fn parses_repeated_event_filter_parameters() {
+        let filter = parse_event_filter(Some(
+            "issue_id=test-1&label=ui&label=backend&assignee=alice",
+        ))
+        .unwrap();
+        assert_eq!(filter.issue_ids, vec![IssueId::new("test-1")]);
+        assert_eq!(filter.labels, vec!["ui", "backend"]);
+        assert_eq!(filter.assignees, vec!["alice"]);
+        assert!(parse_event_filter(Some("status=open")).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_parameters_and_invalid_values() {
+        for query in [
+            "colour=red",
+            "status=done",
+            "priority=high",
+            "limit=0",
+            "limit=5000",
+            "sort=newest",
+            "field=severity%3Dmedium",
+            "field=severity%3D",
+        ] {
+            assert!(
+                IssueQuery::parse(Some(query), &schema()).is_err(),
+                "query {query}"
+            );
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! WebSocket transport for `GET /events`.
+//!
+//! Each connection first receives a `connected` message, then one `event`
+//! message per matching change. Clients may send
+//! `{"type": "subscribe", ...}` with the fields of [`EventFilter`] at any time
+//! to replace their filter. Binary messages are refused.
+
+use super::SharedState;
+use super::error::ApiError;
+use super::events::{EventFilter, PublishedEvent};
+use super::query::parse_event_filter;
+use axum::extract::ws::rejection::WebSocketUpgradeRejection;
+use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code};
+use axum::extract::{RawQuery, State};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Largest message a client may send; subscriptions are small.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// A message the server sends.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    /// Sent once the connection is open.
+    Connected {
+        /// Sequence number of the last event published before this
+        /// subscriber joined; later events carry higher numbers.
+        sequence: u64,
+        filter: &'a EventFilter,
+    },
+    /// Acknowledges a `subscribe` message.
+    Subscribed { filter: &'a EventFilter },
+    /// A change matching the filter.
+    Event(&'a PublishedEvent),
+    /// The subscriber fell behind and this many events were dropped.
+    Lagged { missed: u64 },
+    /// A client message could not be understood.
+    Error { message: String },
+}
+
+/// A message a client sends.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Replace the connection's filter.
+    Subscribe(EventFilter),
+}
+
+/// `GET /events`
+pub(crate) async fn events(
+    State(state): State<SharedState>,
+    RawQuery(query): RawQuery,
+    upgrade: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Result<Response, ApiError> {
+    let filter = parse_event_filter(query.as_deref())?;
+    let upgrade = upgrade.map_err(|rejection| ApiError::bad_request(rejection.body_text()))?;
+
+    // Subscribe before responding so no change made after the handshake is
+    // missed.
+    let events = state.events.subscribe();
+    let sequence = state.events.sequence();
+    Ok(upgrade
+        .max_message_size(MAX_MESSAGE_SIZE)
+        .on_upgrade(move |socket| async move {
+            if let Err(error) = run(socket, filter, events, sequence).await {
+                tracing::debug!(%error, "WebSocket connection ended with an error");
+            }
+        }))
+}
+
+/// Stream events to one client until either side closes the connection.
+async fn run(
+    mut socket: WebSocket,
+    mut filter: EventFilter,
+    mut events: broadcast::Receiver<Arc<PublishedEvent>>,
+    sequence: u64,
+) -> Result<(), axum::Error> {
+    send(
+        &mut socket,
+        &ServerMessage::Connected {
+            sequence,
+            filter: &filter,
+        },
+    )
+    .await?;
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Subscribe(new_filter)) => {
+                            filter = new_filter;
+                            send(&mut socket, &ServerMessage::Subscribed { filter: &filter })
+                                .await?;
+                        }
+                        Err(error) => {
+                            let message = format!("Invalid message: {error}");
+                            send(&mut socket, &ServerMessage::Error { message }).await?;
+                        }
+                    }
+                }
+                Some(Ok(Message::Binary(_))) => {
+                    let reason = "Binary messages are not supported";
+                    return close(&mut socket, close_code::UNSUPPORTED, reason).await;
+                }
+                // Pings are answered by the WebSocket itself.
+                Some(Ok(Message::Ping(_) | Message::Pong(_))) => {}
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Err(error)) => return Err(error),
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if filter.matches(&event.subject) {
+                        send(&mut socket, &ServerMessage::Event(&event)).await?;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    send(&mut socket, &ServerMessage::Lagged { missed }).await?;
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return close(&mut socket, close_code::NORMAL, "").await;
+                }
+            },
+        }
+    }
+}
+
+/// Send a message as one text frame.
+async fn send(socket: &mut WebSocket, message: &ServerMessage<'_>) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).map_err(axum::Error::new)?;
+    socket.send(Message::text(text)).await
+}
+
+/// Start the closing handshake.
+async fn close(socket: &mut WebSocket, code: u16, reason: &str) -> Result<(), axum::Error> {
+    let frame = CloseFrame {
+        code,
+        reason: reason.into(),
+    };
+    socket.send(Message::Close(Some(frame))).await
+}
@@ -18,7 +18,9 @@
 //! ```
 
 use crate::commands::init::{CONFIG_FILE_NAME, RIVETS_DIR_NAME, RivetsConfig, find_rivets_root};
+use crate::domain::CustomFieldSchema;
 use crate::error::{ConfigError, Result};
+use crate::hooks::Hooks;
 use crate::storage::{IssueStorage, create_storage};
 use std::path::{Path, PathBuf};
 
@@ -34,15 +36,27 @@ pub struct App {
     /// Path to the rivets directory (.rivets)
     rivets_dir: PathBuf,
 
+    /// Path to the data file, relative to the workspace root
+    data_file: PathBuf,
+
     /// Issue ID prefix from configuration
     prefix: String,
+
+    /// Custom fields declared in configuration
+    custom_fields: CustomFieldSchema,
+
+    /// Hooks declared in configuration
+    hooks: Hooks,
 }
 
 impl std::fmt::Debug for App {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("App")
             .field("rivets_dir", &self.rivets_dir)
+            .field("data_file", &self.data_file)
             .field("prefix", &self.prefix)
+            .field("custom_fields", &self.custom_fields)
+            .field("hooks", &self.hooks)
             .field("storage", &"<dyn IssueStorage>")
             .finish()
     }
@@ -52,7 +66,9 @@ impl App {
     /// Create an App instance from the given working directory.
     ///
     /// Searches up the directory tree to find a `.rivets/` directory,
-    /// loads configuration, and initializes storage.
+    /// loads configuration, and initializes storage. When a daemon is
+    /// running for the workspace, storage calls are routed through it;
+    /// otherwise the data file is loaded directly.
     ///
     /// # Arguments
     ///
@@ -65,6 +81,35 @@ impl App {
     /// - Configuration cannot be loaded
     /// - Storage initialization fails
     pub async fn from_directory(working_dir: &Path) -> Result<Self> {
+        Self::load(working_dir, true, None).await
+    }
+
+    /// Create an App instance that always loads the data file itself,
+    /// bypassing any running daemon.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`from_directory`](Self::from_directory).
+    pub async fn from_directory_direct(working_dir: &Path) -> Result<Self> {
+        Self::load(working_dir, false, None).await
+    }
+
+    /// Create an App instance whose storage is the workspace's issues as
+    /// committed at git revision `rev`, such as a tag or branch.
+    ///
+    /// The issues are read with `git show` and are read-only: every
+    /// mutation and save fails. Configuration comes from the working tree.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`from_directory`](Self::from_directory), and
+    /// [`Error::Git`](crate::error::Error::Git) when `rev` does not name a
+    /// commit.
+    pub async fn from_directory_at(working_dir: &Path, rev: &str) -> Result<Self> {
+        Self::load(working_dir, false, Some(rev)).await
+    }
+
+    async fn load(working_dir: &Path, use_daemon: bool, revision: Option<&str>) -> Result<Self> {
         // Find rivets root directory
         let root_dir = find_rivets_root(working_dir).ok_or(ConfigError::NotInitialized)?;
 
@@ -75,13 +120,27 @@ impl App {
         let config = RivetsConfig::load(&config_path).await?;
 
         // Create storage based on configuration
-        let backend = config.storage.to_backend(&root_dir)?;
-        let storage = create_storage(backend, config.issue_prefix.clone()).await?;
+        let backend = match revision {
+            Some(rev) => config.storage.to_revision_backend(&root_dir, rev)?,
+            None => config.storage.to_backend(&root_dir)?,
+        };
+        let daemon = if use_daemon {
+            connect_daemon(&root_dir).await
+        } else {
+            None
+        };
+        let storage = match daemon {
+            Some(storage) => storage,
+            None => create_storage(backend, config.issue_prefix.clone()).await?,
+        };
 
         Ok(Self {
             storage,
             rivets_dir,
+            data_file: PathBuf::from(config.storage.data_file),
             prefix: config.issue_prefix,
+            custom_fields: config.custom_fields,
+            hooks: Hooks::new(root_dir, config.hooks),
         })
     }
 
@@ -100,6 +159,26 @@ impl App {
         &self.prefix
     }
 
+    /// Get the custom fields declared for this workspace.
+    pub fn custom_fields(&self) -> &CustomFieldSchema {
+        &self.custom_fields
+    }
+
+    /// Get the hooks declared for this workspace.
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    /// Get the workspace root, the directory containing the rivets directory.
+    pub fn root_dir(&self) -> &Path {
+        self.rivets_dir.parent().unwrap_or(&self.rivets_dir)
+    }
+
+    /// Get the path to the data file, relative to the workspace root.
+    pub fn data_file(&self) -> &Path {
+        &self.data_file
+    }
+
     /// Get the path to the rivets directory.
     pub fn rivets_dir(&self) -> &Path {
         &self.rivets_dir
@@ -111,6 +190,41 @@ impl App {
     pub async fn save(&self) -> Result<()> {
         self.storage.save().await
     }
+
+    /// Consume the App, keeping only its storage.
+    pub fn into_storage(self) -> Box<dyn IssueStorage> {
+        self.storage
+    }
+}
+
+/// Connect to the workspace's daemon, if one is running.
+///
+/// Failing to reach a daemon falls back to direct file access, so a broken
+/// daemon never stops a command from running.
+#[cfg(unix)]
+async fn connect_daemon(root_dir: &Path) -> Option<Box<dyn IssueStorage>> {
+    use crate::daemon::{DaemonPaths, DaemonStorage};
+
+    let paths = DaemonPaths::for_workspace(root_dir).ok()?;
+    match DaemonStorage::connect(&paths).await {
+        Ok(Some(storage)) => {
+            tracing::debug!(
+                pid = storage.status().pid,
+                "Routing storage through the rivets daemon"
+            );
+            Some(Box::new(storage))
+        }
+        Ok(None) => None,
+        Err(error) => {
+            tracing::warn!(%error, "Ignoring unreachable rivets daemon");
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn connect_daemon(_root_dir: &Path) -> Option<Box<dyn IssueStorage>> {
+    None
 }
 
 #[cfg(test)]
@@ -3,15 +3,20 @@
 //! Each command has its own argument struct with clap derive attributes
 //! for parsing and validation.
 
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 use super::types::{SortOrderArg, SortPolicyArg};
 use super::validators::{
-    validate_description, validate_issue_id, validate_label, validate_prefix, validate_title,
+    validate_date, validate_description, validate_due_date, validate_due_window,
+    validate_idle_timeout, validate_issue_id, validate_label, validate_lease_duration,
+    validate_milestone_name, validate_prefix, validate_template_name, validate_title,
 };
 use crate::domain::{
     DependencyType, IssueKind, IssueStatus, MAX_PRIORITY, MIN_PRIORITY, ResourceRole,
 };
+use crate::events::EventKind;
 
 /// Arguments for the `init` command
 #[derive(Parser, Debug, Clone)]
@@ -46,12 +51,16 @@ pub struct CreateArgs {
     pub description: Option<String>,
 
     /// Priority level (0=critical, 1=high, 2=medium, 3=low, 4=backlog)
-    #[arg(short, long, value_parser = clap::value_parser!(u8).range(MIN_PRIORITY as i64..=MAX_PRIORITY as i64), default_value = "2")]
-    pub priority: u8,
+    ///
+    /// Defaults to the template's priority, or 2 (medium).
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(MIN_PRIORITY as i64..=MAX_PRIORITY as i64))]
+    pub priority: Option<u8>,
 
     /// Issue kind
-    #[arg(short = 'k', long = "kind", value_enum, default_value = "task")]
-    pub issue_kind: IssueKind,
+    ///
+    /// Defaults to the template's kind, or task.
+    #[arg(short = 'k', long = "kind", value_enum)]
+    pub issue_kind: Option<IssueKind>,
 
     /// Assignee username
     #[arg(short, long)]
@@ -79,6 +88,38 @@ pub struct CreateArgs {
     /// Initial Note
     #[arg(long, allow_hyphen_values = true)]
     pub notes: Option<String>,
+
+    /// Issue template to apply (from `.rivets/templates/<name>.yaml`)
+    ///
+    /// Without this flag, the template named after the issue kind is
+    /// applied if it exists (e.g. `bug.yaml` for `--kind bug`).
+    #[arg(long, value_parser = validate_template_name, conflicts_with = "no_template")]
+    pub template: Option<String>,
+
+    /// Do not apply any issue template
+    #[arg(long, conflicts_with = "template")]
+    pub no_template: bool,
+
+    /// Open the new issue in `$EDITOR` before creating it
+    ///
+    /// The editor is prefilled with any values given as flags. The title
+    /// prompt is skipped; the title is entered in the editor instead.
+    #[arg(long)]
+    pub edit: bool,
+
+    /// Custom field value as `key=value` (repeatable)
+    ///
+    /// Fields must be declared under `custom-fields` in `.rivets/config.yaml`.
+    #[arg(long = "field", value_name = "KEY=VALUE")]
+    pub fields: Vec<String>,
+
+    /// Due date (YYYY-MM-DD, end of day UTC, or an RFC 3339 timestamp)
+    #[arg(long, value_parser = validate_due_date)]
+    pub due: Option<DateTime<Utc>>,
+
+    /// Milestone to plan the issue for (must exist)
+    #[arg(long, value_parser = validate_milestone_name)]
+    pub milestone: Option<String>,
 }
 
 /// Arguments for the `list` command
@@ -104,6 +145,18 @@ pub struct ListArgs {
     #[arg(short, long)]
     pub label: Option<String>,
 
+    /// Filter by custom field value as `key=value` (repeatable, all must match)
+    #[arg(long = "field", value_name = "KEY=VALUE")]
+    pub fields: Vec<String>,
+
+    /// Only show issues past their due date that are not closed
+    #[arg(long)]
+    pub overdue: bool,
+
+    /// Filter by milestone
+    #[arg(long, value_parser = validate_milestone_name)]
+    pub milestone: Option<String>,
+
     /// Maximum number of issues to display
     #[arg(short = 'n', long, default_value = "50")]
     pub limit: usize,
@@ -178,6 +231,26 @@ pub struct UpdateArgs {
     /// Note to append
     #[arg(long, allow_hyphen_values = true)]
     pub notes: Option<String>,
+
+    /// Set a custom field as `key=value` (repeatable); `key=` clears it
+    #[arg(long = "field", value_name = "KEY=VALUE")]
+    pub fields: Vec<String>,
+
+    /// New due date (YYYY-MM-DD, end of day UTC, or an RFC 3339 timestamp)
+    #[arg(long, value_parser = validate_due_date, conflicts_with = "no_due")]
+    pub due: Option<DateTime<Utc>>,
+
+    /// Remove the current due date
+    #[arg(long, conflicts_with = "due")]
+    pub no_due: bool,
+
+    /// Move the issue to a milestone (must exist)
+    #[arg(long, value_parser = validate_milestone_name, conflicts_with = "no_milestone")]
+    pub milestone: Option<String>,
+
+    /// Remove the issue from its milestone
+    #[arg(long, conflicts_with = "milestone")]
+    pub no_milestone: bool,
 }
 
 impl UpdateArgs {
@@ -220,9 +293,22 @@ impl UpdateArgs {
             || self.design.is_some()
             || self.acceptance.is_some()
             || self.notes.is_some()
+            || !self.fields.is_empty()
+            || self.due.is_some()
+            || self.no_due
+            || self.milestone.is_some()
+            || self.no_milestone
     }
 }
 
+/// Arguments for the `edit` command
+#[derive(Parser, Debug, Clone)]
+pub struct EditArgs {
+    /// Issue ID to edit
+    #[arg(value_parser = validate_issue_id)]
+    pub issue_id: String,
+}
+
 /// Arguments for the `close` command
 #[derive(Parser, Debug, Clone)]
 pub struct CloseArgs {
@@ -278,6 +364,19 @@ pub struct ReadyArgs {
     #[arg(short, long)]
     pub label: Option<String>,
 
+    /// Only show issues planned for this milestone
+    #[arg(long, value_parser = validate_milestone_name, conflicts_with = "current_milestone")]
+    pub milestone: Option<String>,
+
+    /// Only show issues planned for the current milestone (the open
+    /// milestone covering today)
+    #[arg(long, conflicts_with = "milestone")]
+    pub current_milestone: bool,
+
+    /// Agent identity; issues claimed by other agents are hidden
+    #[arg(long = "as", value_name = "AGENT", env = "RIVETS_AGENT")]
+    pub agent: Option<String>,
+
     /// Maximum number of issues to display
     #[arg(short = 'n', long, default_value = "10")]
     pub limit: usize,
@@ -287,6 +386,50 @@ pub struct ReadyArgs {
     pub sort: SortPolicyArg,
 }
 
+/// Arguments for the `claim` command
+#[derive(Parser, Debug, Clone)]
+pub struct ClaimArgs {
+    /// Issue ID to claim; claims the next ready issue if omitted
+    #[arg(value_parser = validate_issue_id)]
+    pub issue_id: Option<String>,
+
+    /// Agent identity to claim the issue for
+    #[arg(long = "as", value_name = "AGENT", env = "RIVETS_AGENT")]
+    pub agent: String,
+
+    /// How long the claim lasts unless renewed (e.g. 30m, 2h, 1d)
+    #[arg(long, value_parser = validate_lease_duration, default_value = "1h")]
+    pub lease: chrono::Duration,
+}
+
+/// Arguments for the `renew` command
+#[derive(Parser, Debug, Clone)]
+pub struct RenewArgs {
+    /// Issue ID whose claim to renew
+    #[arg(value_parser = validate_issue_id)]
+    pub issue_id: String,
+
+    /// Agent identity holding the claim
+    #[arg(long = "as", value_name = "AGENT", env = "RIVETS_AGENT")]
+    pub agent: String,
+
+    /// New lease length from now (e.g. 30m, 2h, 1d)
+    #[arg(long, value_parser = validate_lease_duration, default_value = "1h")]
+    pub lease: chrono::Duration,
+}
+
+/// Arguments for the `release` command
+#[derive(Parser, Debug, Clone)]
+pub struct ReleaseArgs {
+    /// Issue ID whose claim to release
+    #[arg(value_parser = validate_issue_id)]
+    pub issue_id: String,
+
+    /// Agent identity holding the claim
+    #[arg(long = "as", value_name = "AGENT", env = "RIVETS_AGENT")]
+    pub agent: String,
+}
+
 /// Arguments for the `dep` command
 #[derive(Parser, Debug, Clone)]
 pub struct DepArgs {
@@ -369,6 +512,237 @@ pub struct InfoArgs {
     // No arguments for now, just --json global flag
 }
 
+/// Arguments for the `due` command
+#[derive(Parser, Debug, Clone)]
+pub struct DueArgs {
+    /// Show open issues due within this window (e.g. 12h, 7d, 2w);
+    /// overdue issues are always included
+    #[arg(short, long, default_value = "7d", value_parser = validate_due_window)]
+    pub within: chrono::Duration,
+
+    /// Maximum number of issues to display
+    #[arg(short = 'n', long, default_value = "50")]
+    pub limit: usize,
+}
+
+/// Arguments for the `milestone` command
+#[derive(Parser, Debug, Clone)]
+pub struct MilestoneArgs {
+    /// Milestone subcommand
+    #[command(subcommand)]
+    pub action: MilestoneAction,
+}
+
+/// Milestone management actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum MilestoneAction {
+    /// Create a milestone covering a date range
+    Create {
+        /// Milestone name (ASCII letters, digits, dots, hyphens, underscores)
+        #[arg(value_parser = validate_milestone_name)]
+        name: String,
+
+        /// First day of the milestone (YYYY-MM-DD)
+        #[arg(long, value_parser = validate_date)]
+        start: NaiveDate,
+
+        /// Last day of the milestone (YYYY-MM-DD)
+        #[arg(long, value_parser = validate_date)]
+        end: NaiveDate,
+    },
+
+    /// List milestones with their completion
+    List,
+
+    /// Show a milestone's progress, burn-down and remaining blocked issues
+    Show {
+        /// Milestone name
+        #[arg(value_parser = validate_milestone_name)]
+        name: String,
+    },
+
+    /// Close a milestone
+    Close {
+        /// Milestone name
+        #[arg(value_parser = validate_milestone_name)]
+        name: String,
+    },
+}
+
+/// Arguments for the `daemon` command
+#[derive(Parser, Debug, Clone)]
+pub struct DaemonArgs {
+    /// Daemon subcommand
+    #[command(subcommand)]
+    pub action: DaemonAction,
+}
+
+/// Daemon management actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum DaemonAction {
+    /// Start the daemon for a workspace in the background
+    Start {
+        /// Workspace to serve (defaults to the current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace: Option<PathBuf>,
+
+        /// Shut down after this long without requests (e.g. 30m, 2h, 1d)
+        #[arg(long, value_parser = validate_idle_timeout, default_value = "1h")]
+        idle_timeout: chrono::Duration,
+
+        /// Run in the foreground instead of detaching (for debugging)
+        #[arg(long)]
+        foreground: bool,
+    },
+
+    /// Show whether a daemon is running for a workspace
+    Status {
+        /// Workspace to check (defaults to the current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace: Option<PathBuf>,
+    },
+
+    /// Stop the daemon for a workspace
+    Stop {
+        /// Workspace whose daemon to stop (defaults to the current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace: Option<PathBuf>,
+    },
+}
+
+/// Arguments for the `serve` command
+#[derive(Parser, Debug, Clone)]
+pub struct ServeArgs {
+    /// Address to listen on (loopback only)
+    #[arg(long, value_name = "ADDR", default_value = crate::api::DEFAULT_ADDR)]
+    pub addr: std::net::SocketAddr,
+}
+
+/// Arguments for the `hooks` command
+#[derive(Parser, Debug, Clone)]
+pub struct HooksArgs {
+    /// Hooks subcommand
+    #[command(subcommand)]
+    pub action: HooksAction,
+}
+
+/// Hook actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum HooksAction {
+    /// Deliver a sample event to the hooks that subscribe to it
+    ///
+    /// The payload is built from a real issue and marked `"dry_run": true`
+    /// so hooks can tell it apart from a real change. Nothing in the
+    /// workspace changes.
+    Test {
+        /// Event kind to send
+        #[arg(value_enum)]
+        event: EventKind,
+
+        /// Issue to build the sample event from (defaults to the most
+        /// recently updated issue)
+        #[arg(long, value_parser = validate_issue_id)]
+        issue: Option<String>,
+
+        /// Only deliver to the hook with this name
+        #[arg(long, value_name = "NAME")]
+        hook: Option<String>,
+    },
+}
+
+/// Arguments for the `scan-commits` command
+#[derive(Parser, Debug, Clone)]
+pub struct ScanCommitsArgs {
+    /// Commits to scan, in any form `git log` accepts (e.g. `main..HEAD`)
+    pub range: String,
+
+    /// Close issues named by `Fixes` or `Closes` lines
+    #[arg(long)]
+    pub close: bool,
+
+    /// Web link for commits, with `{sha}` standing for the commit (defaults
+    /// to one derived from the `origin` remote, or to `git:<sha>` without one)
+    #[arg(long, value_name = "TEMPLATE")]
+    pub commit_url: Option<String>,
+}
+
+/// Arguments for the `diff` command
+#[derive(Parser, Debug, Clone)]
+pub struct DiffArgs {
+    /// Revision to compare from (e.g. `main`, `v1.2.0`)
+    pub from: String,
+
+    /// Revision to compare to (defaults to the working tree)
+    pub to: Option<String>,
+}
+
+/// Arguments for the `doctor` command
+#[derive(Parser, Debug, Clone)]
+pub struct DoctorArgs {
+    /// Offer a repair for each problem, asking before each one unless --yes
+    /// is given
+    #[arg(long, conflicts_with = "fix_ids")]
+    pub repair: bool,
+
+    /// Give issues that reuse an earlier issue's ID a new one
+    #[arg(long)]
+    pub fix_ids: bool,
+}
+
+/// Arguments for the `rekey` command
+#[derive(Parser, Debug, Clone)]
+pub struct RekeyArgs {
+    /// The new issue prefix (2-20 alphanumeric characters)
+    #[arg(long)]
+    pub prefix: String,
+
+    /// Also rewrite renamed IDs mentioned in descriptions and notes
+    #[arg(long)]
+    pub rewrite_mentions: bool,
+}
+
+/// Arguments for the `git-hook` command
+#[derive(Parser, Debug, Clone)]
+pub struct GitHookArgs {
+    /// Git hook subcommand
+    #[command(subcommand)]
+    pub action: GitHookAction,
+}
+
+/// Git hook actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum GitHookAction {
+    /// Install `commit-msg` and `post-commit` hooks in this repository
+    ///
+    /// `commit-msg` rejects commits naming malformed or unknown issue IDs,
+    /// and `post-commit` links each new commit to the issues it names, as
+    /// `scan-commits` does.
+    Install {
+        /// Make `post-commit` close issues named by `Fixes` or `Closes`
+        #[arg(long)]
+        close: bool,
+
+        /// Replace existing hooks that rivets did not install
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check the issue references in a commit message file
+    #[command(hide = true)]
+    CommitMsg {
+        /// Path of the commit message file
+        file: PathBuf,
+    },
+
+    /// Link the commit just made to the issues it names
+    #[command(hide = true)]
+    PostCommit {
+        /// Close issues named by `Fixes` or `Closes` lines
+        #[arg(long)]
+        close: bool,
+    },
+}
+
 /// Arguments for the `stale` command
 #[derive(Parser, Debug, Clone)]
 pub struct StaleArgs {
@@ -550,6 +924,11 @@ mod tests {
                 design: None,
                 acceptance: None,
                 notes: None,
+                fields: vec![],
+                due: None,
+                no_due: false,
+                milestone: None,
+                no_milestone: false,
             }
         }
 
@@ -629,6 +1008,41 @@ mod tests {
             assert!(args.has_updates());
         }
 
+        #[test]
+        fn test_has_updates_fields() {
+            let mut args = create_empty_update_args();
+            args.fields = vec!["severity=high".to_string()];
+            assert!(args.has_updates());
+        }
+
+        #[test]
+        fn test_has_updates_due() {
+            let mut args = create_empty_update_args();
+            args.due = Some(chrono::Utc::now());
+            assert!(args.has_updates());
+        }
+
+        #[test]
+        fn test_has_updates_no_due() {
+            let mut args = create_empty_update_args();
+            args.no_due = true;
+            assert!(args.has_updates());
+        }
+
+        #[test]
+        fn test_has_updates_milestone() {
+            let mut args = create_empty_update_args();
+            args.milestone = Some("sprint-1".to_string());
+            assert!(args.has_updates());
+        }
+
+        #[test]
+        fn test_has_updates_no_milestone() {
+            let mut args = create_empty_update_args();
+            args.no_milestone = true;
+            assert!(args.has_updates());
+        }
+
         #[test]
         fn test_has_updates_multiple_fields() {
             let mut args = create_empty_update_args();
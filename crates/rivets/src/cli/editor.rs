@@ -0,0 +1,735 @@
+//! Editing issues as structured documents in `$EDITOR`.
+//!
+//! `rivets edit` and `rivets create --edit` render the editable fields of an
+//! issue as a YAML document, hand it to the user's editor, and parse the
+//! saved result back. Parse and validation errors reopen the editor with the
+//! error prepended as comment lines, so no typed text is lost.
+//!
+//! The document is validated with the same CLI validators used for flags
+//! (`validate_title`, `validate_description`, `validate_label`) and the
+//! workspace's custom field schema, and edits to an existing issue are
+//! reduced to an [`IssueUpdate`] containing only the fields that actually
+//! changed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::validators::{
+    validate_description, validate_label, validate_text_field, validate_title,
+};
+use crate::domain::{
+    CustomFieldSchema, CustomFieldValue, DUE_DATE_FORMATS, Issue, IssueKind, IssueStatus,
+    IssueUpdate, MAX_PRIORITY, MIN_PRIORITY, NewIssue, join_canonical_names, parse_due_date,
+    validate_milestone_name,
+};
+
+/// Prefix marking the error lines inserted above a rejected document.
+const ERROR_MARKER: &str = "# ERROR: ";
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Editable fields of an issue, as presented in the editor.
+///
+/// Optional text fields are represented as empty strings so every field is
+/// visible in the document; an empty value clears the field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct EditDocument {
+    /// Issue title
+    pub title: String,
+
+    /// Issue status (omitted when creating)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<IssueStatus>,
+
+    /// Priority level (0-4)
+    pub priority: u8,
+
+    /// Issue kind
+    pub kind: IssueKind,
+
+    /// Assignee (null for unassigned)
+    #[serde(default)]
+    pub assignee: Option<String>,
+
+    /// Labels
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Detailed description
+    #[serde(default)]
+    pub description: String,
+
+    /// Design notes
+    #[serde(default)]
+    pub design: String,
+
+    /// Acceptance criteria
+    #[serde(default)]
+    pub acceptance: String,
+
+    /// Due date as `YYYY-MM-DD` or an RFC 3339 timestamp (null for none)
+    #[serde(default)]
+    pub due: Option<String>,
+
+    /// Milestone name (null for none)
+    #[serde(default)]
+    pub milestone: Option<String>,
+
+    /// Custom field values; removing a field clears it
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+}
+
+impl EditDocument {
+    /// Build a document from an existing issue.
+    pub(crate) fn from_issue(issue: &Issue) -> Self {
+        Self {
+            title: issue.title.clone(),
+            status: Some(issue.status),
+            priority: issue.priority,
+            kind: issue.issue_kind,
+            assignee: issue.assignee.clone(),
+            labels: issue.labels.clone(),
+            description: issue.description.clone(),
+            design: issue.design.clone().unwrap_or_default(),
+            acceptance: issue.acceptance_criteria.clone().unwrap_or_default(),
+            due: issue.due_at.map(|due| due.to_rfc3339()),
+            milestone: issue.milestone.clone(),
+            custom_fields: issue.custom_fields.clone(),
+        }
+    }
+
+    /// Build a creation template from a partially filled [`NewIssue`].
+    pub(crate) fn from_new_issue(new_issue: &NewIssue) -> Self {
+        Self {
+            title: new_issue.title.clone(),
+            status: None,
+            priority: new_issue.priority,
+            kind: new_issue.issue_kind,
+            assignee: new_issue.assignee.clone(),
+            labels: new_issue.labels.clone(),
+            description: new_issue.description.clone(),
+            design: new_issue.design.clone().unwrap_or_default(),
+            acceptance: new_issue.acceptance_criteria.clone().unwrap_or_default(),
+            due: new_issue.due_at.map(|due| due.to_rfc3339()),
+            milestone: new_issue.milestone.clone(),
+            custom_fields: new_issue.custom_fields.clone(),
+        }
+    }
+
+    /// Render the document with an explanatory comment header.
+    pub(crate) fn render(&self, heading: &str) -> Result<String> {
+        let body = serde_yaml::to_string(self).context("Failed to render issue document")?;
+        Ok(format!(
+            "# {heading}\n\
+             #\n\
+             # Edit the fields below and save to apply. Lines starting with '#' are\n\
+             # ignored. Use '|' for multi-line text. Delete everything to abort.\n\
+             #\n\
+             # kind: {}\n\
+             # status: {}\n\
+             # due: {DUE_DATE_FORMATS}\n\
+             {body}",
+            join_canonical_names::<IssueKind>(),
+            IssueStatus::valid_values(),
+        ))
+    }
+
+    /// Parse and validate a saved document, checking custom fields against
+    /// `schema`.
+    ///
+    /// Returns `Ok(None)` when the document is empty (the user aborted).
+    /// Milestone names are checked for form only; callers check that the
+    /// milestone exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the first syntax or validation problem.
+    pub(crate) fn parse(text: &str, schema: &CustomFieldSchema) -> Result<Option<Self>, String> {
+        if text
+            .lines()
+            .all(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+        {
+            return Ok(None);
+        }
+
+        let mut doc: Self = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+
+        doc.title = validate_title(&doc.title)?;
+        if !(MIN_PRIORITY..=MAX_PRIORITY).contains(&doc.priority) {
+            return Err(format!(
+                "Priority must be in range {}-{} (got {})",
+                MIN_PRIORITY, MAX_PRIORITY, doc.priority
+            ));
+        }
+        doc.assignee = doc
+            .assignee
+            .map(|assignee| assignee.trim().to_string())
+            .filter(|assignee| !assignee.is_empty());
+        doc.labels = doc
+            .labels
+            .iter()
+            .map(|label| validate_label(label))
+            .collect::<Result<_, _>>()?;
+        doc.description = validate_description(doc.description.trim_end())?;
+        doc.design = validate_text_field(doc.design.trim_end(), "Design")?;
+        doc.acceptance = validate_text_field(doc.acceptance.trim_end(), "Acceptance criteria")?;
+        doc.due = non_blank(doc.due);
+        if let Some(due) = &doc.due {
+            parse_due_date(due).map_err(|e| e.to_string())?;
+        }
+        doc.milestone = non_blank(doc.milestone);
+        if let Some(milestone) = &doc.milestone {
+            validate_milestone_name(milestone).map_err(|e| e.to_string())?;
+        }
+        doc.custom_fields = std::mem::take(&mut doc.custom_fields)
+            .into_iter()
+            .map(|(name, value)| {
+                let value = schema.check(&name, value).map_err(|e| e.to_string())?;
+                Ok((name, value))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Some(doc))
+    }
+
+    /// Compute the update that turns `issue` into this document.
+    ///
+    /// Only changed fields are set. Status changes are checked against the
+    /// domain transition rules so an invalid status can be corrected in the
+    /// editor rather than failing at storage time.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the requested status transition is not allowed.
+    pub(crate) fn diff(&self, issue: &Issue) -> Result<IssueUpdate, String> {
+        let mut update = IssueUpdate::default();
+
+        if self.title != issue.title {
+            update.title = Some(self.title.clone());
+        }
+        if let Some(status) = self.status
+            && status != issue.status
+        {
+            issue
+                .status
+                .validate_transition(status)
+                .map_err(|e| e.to_string())?;
+            update.status = Some(status);
+        }
+        if self.priority != issue.priority {
+            update.priority = Some(self.priority);
+        }
+        if self.kind != issue.issue_kind {
+            update.issue_kind = Some(self.kind);
+        }
+        if self.assignee != issue.assignee {
+            update.assignee = Some(self.assignee.clone());
+        }
+        if self.labels != issue.labels {
+            update.labels = Some(self.labels.clone());
+        }
+        if self.description != issue.description.trim_end() {
+            update.description = Some(self.description.clone());
+        }
+        if self.design != issue.design.as_deref().unwrap_or_default().trim_end() {
+            update.design = Some(self.design.clone());
+        }
+        if self.acceptance
+            != issue
+                .acceptance_criteria
+                .as_deref()
+                .unwrap_or_default()
+                .trim_end()
+        {
+            update.acceptance_criteria = Some(self.acceptance.clone());
+        }
+        if self.due_at() != issue.due_at {
+            update.due_at = Some(self.due_at());
+        }
+        if self.milestone != issue.milestone {
+            update.milestone = Some(self.milestone.clone());
+        }
+        for (name, value) in &self.custom_fields {
+            if issue.custom_fields.get(name) != Some(value) {
+                update
+                    .custom_fields
+                    .insert(name.clone(), Some(value.clone()));
+            }
+        }
+        for name in issue.custom_fields.keys() {
+            if !self.custom_fields.contains_key(name) {
+                update.custom_fields.insert(name.clone(), None);
+            }
+        }
+
+        Ok(update)
+    }
+
+    /// Copy the document's fields onto a [`NewIssue`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the document sets a status, which new issues do
+    /// not accept.
+    pub(crate) fn apply_to_new_issue(&self, new_issue: &mut NewIssue) -> Result<(), String> {
+        if self.status.is_some() {
+            return Err("status cannot be set when creating an issue".to_string());
+        }
+
+        new_issue.title = self.title.clone();
+        new_issue.priority = self.priority;
+        new_issue.issue_kind = self.kind;
+        new_issue.assignee = self.assignee.clone();
+        new_issue.labels = self.labels.clone();
+        new_issue.description = self.description.clone();
+        new_issue.design = non_empty(&self.design);
+        new_issue.acceptance_criteria = non_empty(&self.acceptance);
+        new_issue.due_at = self.due_at();
+        new_issue.milestone = self.milestone.clone();
+        new_issue.custom_fields = self.custom_fields.clone();
+        Ok(())
+    }
+
+    /// The document's due date, which [`EditDocument::parse`] has checked.
+    fn due_at(&self) -> Option<DateTime<Utc>> {
+        self.due.as_deref().and_then(|due| parse_due_date(due).ok())
+    }
+}
+
+/// Returns true if the update changes nothing.
+pub(crate) fn is_empty_update(update: &IssueUpdate) -> bool {
+    update.title.is_none()
+        && update.description.is_none()
+        && update.status.is_none()
+        && update.priority.is_none()
+        && update.issue_kind.is_none()
+        && update.assignee.is_none()
+        && update.design.is_none()
+        && update.acceptance_criteria.is_none()
+        && update.note.is_none()
+        && update.labels.is_none()
+        && update.due_at.is_none()
+        && update.milestone.is_none()
+        && update.custom_fields.is_empty()
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// Trim an optional value, treating a blank one as unset.
+fn non_blank(s: Option<String>) -> Option<String> {
+    s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Remove error lines inserted by a previous failed attempt.
+fn strip_error_lines(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.starts_with(ERROR_MARKER))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+/// Prepend an error message to the document as comment lines.
+fn with_error_lines(text: &str, error: &str) -> String {
+    let mut annotated: String = error
+        .lines()
+        .map(|line| format!("{ERROR_MARKER}{line}\n"))
+        .collect();
+    annotated.push_str(&strip_error_lines(text));
+    annotated
+}
+
+/// Create the scratch file used to edit a document.
+///
+/// The file gets an unpredictable name and owner-only permissions. It is
+/// not removed on drop, so edits survive an editor failure;
+/// [`finish_scratch`] removes it once the edits are saved.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be created.
+pub(crate) fn scratch_file(name: &str) -> Result<PathBuf> {
+    tempfile::Builder::new()
+        .prefix(&format!("rivets-{name}-"))
+        .suffix(".yaml")
+        .tempfile()
+        .context("Failed to create a scratch file for the editor")?
+        .into_temp_path()
+        .keep()
+        .context("Failed to keep the editor scratch file")
+}
+
+/// Open the document in an editor until it parses, or the user aborts.
+///
+/// `initial` is written to `path` and `launch` is invoked to let the user
+/// edit it. The saved text is passed to `parse`; on error the text is
+/// rewritten with the error prepended and the editor is opened again.
+/// Returns `Ok(None)` if `parse` reports an abort.
+///
+/// The scratch file is removed if the user aborts. An accepted document
+/// stays on disk until the caller passes the outcome of applying it to
+/// [`finish_scratch`].
+///
+/// # Errors
+///
+/// Returns an error if the scratch file cannot be read or written, or if
+/// `launch` fails.
+pub(crate) fn edit_until_valid<T>(
+    path: &Path,
+    initial: &str,
+    mut launch: impl FnMut(&Path) -> Result<()>,
+    mut parse: impl FnMut(&str) -> Result<Option<T>, String>,
+) -> Result<Option<T>> {
+    std::fs::write(path, initial).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    loop {
+        launch(path)?;
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        match parse(&strip_error_lines(&text)) {
+            Ok(None) => {
+                let _ = std::fs::remove_file(path);
+                return Ok(None);
+            }
+            Ok(Some(value)) => return Ok(Some(value)),
+            Err(error) => {
+                std::fs::write(path, with_error_lines(&text, &error))
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+    }
+}
+
+/// Remove the scratch file once `result` shows the edits were applied.
+///
+/// On failure the file is kept and the error names it, so nothing the user
+/// typed is lost.
+///
+/// # Errors
+///
+/// Returns the error in `result`.
+pub(crate) fn finish_scratch<T>(path: &Path, result: Result<T>) -> Result<T> {
+    match result {
+        Ok(value) => {
+            let _ = std::fs::remove_file(path);
+            Ok(value)
+        }
+        Err(error) => Err(error.context(format!("Edits kept in {}", path.display()))),
+    }
+}
+
+/// Resolve the editor command from `$VISUAL`, then `$EDITOR`.
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Launch the user's editor on `path` and wait for it to exit.
+///
+/// The editor command is split on whitespace so values such as
+/// `code --wait` work.
+///
+/// # Errors
+///
+/// Returns an error if the editor cannot be started or exits unsuccessfully.
+pub(crate) fn launch_editor(path: &Path) -> Result<()> {
+    let editor = editor_command();
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_EDITOR);
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{editor}'"))?;
+
+    if !status.success() {
+        anyhow::bail!(
+            "Editor '{editor}' exited with {status}; edits kept in {}",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CustomFieldType, IssueId};
+    use tempfile::TempDir;
+
+    fn parse(text: &str) -> Result<Option<EditDocument>, String> {
+        EditDocument::parse(text, &CustomFieldSchema::default())
+    }
+
+    fn points_schema() -> CustomFieldSchema {
+        CustomFieldSchema::new(BTreeMap::from([(
+            "points".to_string(),
+            CustomFieldType::Int,
+        )]))
+        .unwrap()
+    }
+
+    fn sample_issue() -> Issue {
+        Issue {
+            id: IssueId::new("test-abc"),
+            title: "Original title".to_string(),
+            description: "First line\nSecond line".to_string(),
+            status: IssueStatus::Open,
+            priority: 2,
+            issue_kind: IssueKind::Task,
+            assignee: Some("alice".to_string()),
+            labels: vec!["backend".to_string()],
+            design: None,
+            acceptance_criteria: Some("It works".to_string()),
+            notes: vec![],
+            resources: vec![],
+            next_resource_id: 1,
+            dependencies: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            closed_at: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            lease: None,
+        }
+    }
+
+    #[test]
+    fn test_render_roundtrip_has_no_changes() {
+        let issue = sample_issue();
+        let text = EditDocument::from_issue(&issue).render("test-abc").unwrap();
+
+        let doc = parse(&text).unwrap().unwrap();
+        let update = doc.diff(&issue).unwrap();
+
+        assert!(is_empty_update(&update), "unexpected update: {update:?}");
+    }
+
+    #[test]
+    fn test_diff_only_contains_changed_fields() {
+        let issue = sample_issue();
+        let mut doc = EditDocument::from_issue(&issue);
+        doc.title = "New title".to_string();
+        doc.assignee = None;
+        doc.design = "Use a queue".to_string();
+
+        let update = doc.diff(&issue).unwrap();
+
+        assert_eq!(update.title.as_deref(), Some("New title"));
+        assert_eq!(update.assignee, Some(None));
+        assert_eq!(update.design.as_deref(), Some("Use a queue"));
+        assert!(update.description.is_none());
+        assert!(update.priority.is_none());
+        assert!(update.labels.is_none());
+        assert!(update.acceptance_criteria.is_none());
+    }
+
+    #[test]
+    fn test_due_milestone_and_custom_fields_roundtrip_and_diff() {
+        let mut issue = sample_issue();
+        issue.due_at = Some(parse_due_date("2026-03-01").unwrap());
+        issue.milestone = Some("v1.0".to_string());
+        issue.custom_fields = BTreeMap::from([("points".to_string(), CustomFieldValue::Int(3))]);
+        let schema = points_schema();
+
+        let text = EditDocument::from_issue(&issue).render("test-abc").unwrap();
+        let doc = EditDocument::parse(&text, &schema).unwrap().unwrap();
+        assert!(is_empty_update(&doc.diff(&issue).unwrap()));
+
+        let edited = text
+            .replace("2026-03-01T23:59:59+00:00", "2026-04-01")
+            .replace("milestone: v1.0", "milestone: null")
+            .replace("points: 3", "points: '5'");
+        let doc = EditDocument::parse(&edited, &schema).unwrap().unwrap();
+        let update = doc.diff(&issue).unwrap();
+        assert_eq!(
+            update.due_at,
+            Some(Some(parse_due_date("2026-04-01").unwrap()))
+        );
+        assert_eq!(update.milestone, Some(None));
+        assert_eq!(
+            update.custom_fields,
+            BTreeMap::from([("points".to_string(), Some(CustomFieldValue::Int(5)))])
+        );
+
+        let removed = text
+            .replace("  points: 3\n", "")
+            .replace("custom_fields:\n", "");
+        let update = EditDocument::parse(&removed, &schema)
+            .unwrap()
+            .unwrap()
+            .diff(&issue)
+            .unwrap();
+        assert_eq!(
+            update.custom_fields,
+            BTreeMap::from([("points".to_string(), None)])
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_due_milestone_and_custom_fields() {
+        let base = "title: x\npriority: 2\nkind: task\n";
+        let schema = points_schema();
+        assert!(EditDocument::parse(&format!("{base}due: someday\n"), &schema).is_err());
+        assert!(EditDocument::parse(&format!("{base}milestone: a b\n"), &schema).is_err());
+        assert!(
+            EditDocument::parse(&format!("{base}custom_fields:\n  points: many\n"), &schema)
+                .is_err()
+        );
+        assert!(
+            EditDocument::parse(&format!("{base}custom_fields:\n  other: 1\n"), &schema).is_err()
+        );
+    }
+
+    #[test]
+    fn test_diff_rejects_invalid_status_transition() {
+        let issue = sample_issue();
+        let mut doc = EditDocument::from_issue(&issue);
+        doc.status = Some(IssueStatus::Closed);
+        assert!(doc.diff(&issue).is_ok());
+
+        let mut closed = sample_issue();
+        closed.status = IssueStatus::InProgress;
+        doc.status = Some(IssueStatus::Open);
+        assert!(doc.diff(&closed).is_err());
+    }
+
+    #[test]
+    fn test_parse_multiline_block_scalar() {
+        let text =
+            "title: Fix it\npriority: 1\nkind: bug\ndescription: |\n  line one\n  line two\n";
+
+        let doc = parse(text).unwrap().unwrap();
+
+        assert_eq!(doc.description, "line one\nline two");
+        assert_eq!(doc.kind, IssueKind::Bug);
+    }
+
+    #[test]
+    fn test_parse_empty_document_aborts() {
+        assert_eq!(parse("# only comments\n\n").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_fields() {
+        let base = "priority: 2\nkind: task\n";
+        assert!(parse(&format!("title: ''\n{base}")).is_err());
+        assert!(parse("title: x\npriority: 9\nkind: task\n").is_err());
+        assert!(parse(&format!("title: x\n{base}labels: [Bad Label]\n")).is_err());
+        assert!(parse(&format!("title: x\n{base}unknown: 1\n")).is_err());
+        assert!(parse("title: x\npriority: 2\nkind: nope\n").is_err());
+    }
+
+    #[test]
+    fn test_apply_to_new_issue_rejects_status() {
+        let mut new_issue = NewIssue::default();
+        let mut doc = EditDocument::from_new_issue(&new_issue);
+        doc.design = "Design".to_string();
+        doc.apply_to_new_issue(&mut new_issue).unwrap();
+        assert_eq!(new_issue.design.as_deref(), Some("Design"));
+        assert!(new_issue.acceptance_criteria.is_none());
+
+        doc.status = Some(IssueStatus::Open);
+        assert!(doc.apply_to_new_issue(&mut new_issue).is_err());
+    }
+
+    #[test]
+    fn test_edit_until_valid_reopens_with_error_inline() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("doc.yaml");
+        let mut seen = Vec::new();
+
+        let result = edit_until_valid(
+            &path,
+            "title: ''\npriority: 2\nkind: task\n",
+            |path| {
+                let text = std::fs::read_to_string(path).unwrap();
+                seen.push(text.clone());
+                if seen.len() == 2 {
+                    std::fs::write(path, text.replace("title: ''", "title: Fixed")).unwrap();
+                }
+                Ok(())
+            },
+            parse,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(result.title, "Fixed");
+        assert_eq!(seen.len(), 2);
+        assert!(seen[1].starts_with("# ERROR: Title cannot be empty\n"));
+        assert!(seen[1].contains("title: ''"));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_edit_until_valid_removes_aborted_document() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("doc.yaml");
+
+        let result = edit_until_valid(
+            &path,
+            "title: x\n",
+            |path| std::fs::write(path, "").map_err(Into::into),
+            parse,
+        )
+        .unwrap();
+
+        assert!(result.is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_finish_scratch_keeps_edits_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("doc.yaml");
+        std::fs::write(&path, "title: x\n").unwrap();
+
+        let error = finish_scratch::<()>(&path, Err(anyhow::anyhow!("save failed"))).unwrap_err();
+        assert!(path.exists());
+        assert!(error.to_string().contains(&path.display().to_string()));
+        assert!(format!("{error:#}").contains("save failed"));
+
+        finish_scratch(&path, Ok(())).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_scratch_files_are_unique() {
+        let first = scratch_file("new").unwrap();
+        let second = scratch_file("new").unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.exists() && second.exists());
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn test_with_error_lines_replaces_previous_error() {
+        let once = with_error_lines("title: x\n", "first");
+        let twice = with_error_lines(&once, "second");
+
+        assert_eq!(twice, "# ERROR: second\ntitle: x\n");
+    }
+}
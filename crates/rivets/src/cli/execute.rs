@@ -2,16 +2,21 @@
 //!
 //! This module contains the implementation of all CLI commands.
 
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use anyhow::{Context, Result};
 
 use super::args::{
-    BlockedArgs, CloseArgs, CreateArgs, DeleteArgs, DepAction, DepArgs, InfoArgs, InitArgs,
-    LabelAction, LabelArgs, ListArgs, ReadyArgs, ReopenArgs, ResourceAction, ResourceArgs,
-    ShowArgs, StaleArgs, StatsArgs, UpdateArgs,
+    BlockedArgs, ClaimArgs, CloseArgs, CreateArgs, DaemonAction, DaemonArgs, DeleteArgs, DepAction,
+    DepArgs, DiffArgs, DoctorArgs, DueArgs, EditArgs, GitHookAction, GitHookArgs, HooksAction,
+    HooksArgs, InfoArgs, InitArgs, LabelAction, LabelArgs, ListArgs, MilestoneAction,
+    MilestoneArgs, ReadyArgs, RekeyArgs, ReleaseArgs, RenewArgs, ReopenArgs, ResourceAction,
+    ResourceArgs, ScanCommitsArgs, ServeArgs, ShowArgs, StaleArgs, StatsArgs, UpdateArgs,
 };
+use super::editor::{self, EditDocument};
 use super::types::{SortOrderArg, SortPolicyArg};
+use crate::commands::milestones::{find_milestone, load_milestones, require_milestone};
 use crate::domain::DependencyType;
 use crate::output::OutputMode;
 
@@ -66,6 +71,7 @@ pub async fn execute_init(args: &InitArgs) -> Result<()> {
         println!("Initialized rivets in {}", result.rivets_dir.display());
         println!("  Config: {}", result.config_file.display());
         println!("  Issues: {}", result.issues_file.display());
+        println!("  Templates: {}", result.templates_dir.display());
         println!("  Issue prefix: {}", result.prefix);
     }
 
@@ -126,12 +132,16 @@ pub async fn execute_create(
     args: &CreateArgs,
     output_mode: OutputMode,
 ) -> Result<()> {
-    use crate::domain::{DependencyType as DomainDepType, IssueId, NewIssue, NoteContent};
+    use crate::commands::templates::{ExplicitFields, IssueTemplate};
+    use crate::domain::{
+        DependencyType as DomainDepType, IssueId, IssueKind, NewIssue, NoteContent,
+    };
     use crate::output;
 
-    // Get title (interactive prompt if not provided)
+    // Get title (interactive prompt if not provided; the editor asks for it with --edit)
     let title = match &args.title {
         Some(t) => t.clone(),
+        None if args.edit => String::new(),
         None => {
             // Interactive mode: prompt for title
             eprint!("Title: ");
@@ -174,21 +184,78 @@ pub async fn execute_create(
         }
     }
 
-    let new_issue = NewIssue {
+    let mut new_issue = NewIssue {
         title,
         description: args.description.clone().unwrap_or_default(),
-        priority: args.priority,
-        issue_kind: args.issue_kind,
+        priority: args.priority.unwrap_or(2),
+        issue_kind: args.issue_kind.unwrap_or(IssueKind::Task),
         assignee: args.assignee.clone(),
         labels: args.labels.clone(),
         design: args.design.clone(),
         acceptance_criteria: args.acceptance.clone(),
         initial_note: args.notes.clone().map(NoteContent::new).transpose()?,
         dependencies,
+        custom_fields: parse_field_values(app.custom_fields(), &args.fields)?
+            .into_iter()
+            .collect(),
+        due_at: args.due,
+        milestone: args.milestone.clone(),
     };
 
-    let issue = app.storage_mut().create(new_issue).await?;
-    app.save().await?;
+    if !args.no_template
+        && let Some(template) = IssueTemplate::resolve(
+            app.rivets_dir(),
+            args.template.as_deref(),
+            new_issue.issue_kind,
+        )
+        .await?
+    {
+        template.apply(
+            &mut new_issue,
+            ExplicitFields {
+                kind: args.issue_kind.is_some(),
+                priority: args.priority.is_some(),
+                description: args.description.is_some(),
+            },
+        );
+    }
+
+    let mut scratch = None;
+    if args.edit {
+        let milestones = load_milestones(app.rivets_dir()).await?;
+        let template = EditDocument::from_new_issue(&new_issue).render("New issue")?;
+        let path = editor::scratch_file("new")?;
+        let edited = editor::edit_until_valid(&path, &template, editor::launch_editor, |text| {
+            let Some(doc) = EditDocument::parse(text, app.custom_fields())? else {
+                return Ok(None);
+            };
+            let mut candidate = new_issue.clone();
+            doc.apply_to_new_issue(&mut candidate)?;
+            candidate.validate()?;
+            if let Some(milestone) = &candidate.milestone {
+                find_milestone(&milestones, milestone).map_err(|e| e.to_string())?;
+            }
+            Ok(Some(candidate))
+        })?;
+        match edited {
+            Some(candidate) => new_issue = candidate,
+            None => anyhow::bail!("Aborting create: empty issue document"),
+        }
+        scratch = Some(path);
+    } else if let Some(milestone) = &new_issue.milestone {
+        require_milestone(app.rivets_dir(), milestone).await?;
+    }
+
+    let created = async {
+        let issue = app.storage_mut().create(new_issue).await?;
+        app.save().await?;
+        Ok(issue)
+    }
+    .await;
+    let issue = match &scratch {
+        Some(path) => editor::finish_scratch(path, created)?,
+        None => created?,
+    };
 
     match output_mode {
         output::OutputMode::Json => {
@@ -202,6 +269,20 @@ pub async fn execute_create(
     Ok(())
 }
 
+/// Parse `--field key=value` flags that must carry a value (create, list).
+fn parse_field_values(
+    schema: &crate::domain::CustomFieldSchema,
+    fields: &[String],
+) -> Result<Vec<(String, crate::domain::CustomFieldValue)>> {
+    fields
+        .iter()
+        .map(|field| match schema.parse_assignment(field)? {
+            (name, Some(value)) => Ok((name, value)),
+            (name, None) => anyhow::bail!("--field {name}= requires a value"),
+        })
+        .collect()
+}
+
 /// Execute the list command
 pub async fn execute_list(
     app: &crate::app::App,
@@ -218,6 +299,10 @@ pub async fn execute_list(
         issue_kind: args.issue_kind,
         assignee: args.assignee.clone(),
         label: args.label.clone(),
+        custom_fields: parse_field_values(app.custom_fields(), &args.fields)?,
+        overdue: args.overdue,
+        milestone: args.milestone.clone(),
+        claimant: None,
         limit: None,
     };
 
@@ -257,19 +342,32 @@ pub async fn execute_show(
     args: &ShowArgs,
     output_mode: OutputMode,
 ) -> Result<()> {
+    use crate::commands::ids::{load_aliases, resolve_alias};
     use crate::domain::IssueId;
     use crate::output;
 
     let mut results = Vec::new();
+    let mut aliases = None;
 
     for id_str in &args.issue_ids {
-        let issue_id = IssueId::new(id_str);
-
-        let issue = app
-            .storage()
-            .get(&issue_id)
-            .await?
-            .ok_or_else(|| crate::error::Error::IssueNotFound(issue_id.clone()))?;
+        let mut issue_id = IssueId::new(id_str);
+
+        let mut issue = app.storage().get(&issue_id).await?;
+        if issue.is_none() {
+            // The ID may have changed since it was written down; follow the
+            // alias table to the issue's current ID.
+            if aliases.is_none() {
+                aliases = Some(load_aliases(app.rivets_dir()).await?);
+            }
+            if let Some(new_id) = resolve_alias(aliases.as_deref().unwrap_or_default(), &issue_id) {
+                issue = app.storage().get(&new_id).await?;
+                if issue.is_some() && output_mode == OutputMode::Text {
+                    eprintln!("{issue_id} is now {new_id}");
+                }
+                issue_id = new_id;
+            }
+        }
+        let issue = issue.ok_or_else(|| crate::error::Error::IssueNotFound(issue_id.clone()))?;
 
         let deps = app.storage().get_dependencies(&issue_id).await?;
         let dependents = app.storage().get_dependents(&issue_id).await?;
@@ -297,9 +395,12 @@ pub async fn execute_show(
                         "acceptance_criteria": issue.acceptance_criteria,
                         "notes": issue.notes(),
                         "resources": issue.resources(),
+                        "custom_fields": issue.custom_fields,
                         "created_at": issue.created_at,
                         "updated_at": issue.updated_at,
                         "closed_at": issue.closed_at,
+                        "due_at": issue.due_at,
+                        "milestone": issue.milestone,
                         // Dependencies this issue has (issues it depends on)
                         "dependencies": deps,
                         // Issues that depend on this issue
@@ -351,6 +452,14 @@ pub async fn execute_update(
 
     let mut result = BatchResult::new();
     let note = args.notes.clone().map(NoteContent::new).transpose()?;
+    let custom_fields = args
+        .fields
+        .iter()
+        .map(|field| app.custom_fields().parse_assignment(field))
+        .collect::<std::result::Result<BTreeMap<_, _>, _>>()?;
+    if let Some(milestone) = &args.milestone {
+        require_milestone(app.rivets_dir(), milestone).await?;
+    }
 
     for id_str in &args.issue_ids {
         let issue_id = IssueId::new(id_str);
@@ -370,6 +479,17 @@ pub async fn execute_update(
             design: args.design.clone(),
             acceptance_criteria: args.acceptance.clone(),
             note: note.clone(),
+            custom_fields: custom_fields.clone(),
+            due_at: if args.no_due {
+                Some(None) // Clear the due date
+            } else {
+                args.due.map(Some)
+            },
+            milestone: if args.no_milestone {
+                Some(None) // Remove from the milestone
+            } else {
+                args.milestone.clone().map(Some)
+            },
             ..Default::default()
         };
 
@@ -381,6 +501,70 @@ pub async fn execute_update(
     bail_on_batch_failures(&result, "update")
 }
 
+/// Execute the edit command
+///
+/// Opens the issue in `$EDITOR`, then applies only the fields that changed.
+/// Documents that fail to parse or validate reopen the editor with the error
+/// inline. Emptying the document aborts without changes.
+pub async fn execute_edit(
+    app: &mut crate::app::App,
+    args: &EditArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::domain::IssueId;
+    use crate::output;
+
+    let issue_id = IssueId::new(&args.issue_id);
+    let issue = app
+        .storage()
+        .get(&issue_id)
+        .await?
+        .ok_or_else(|| crate::error::Error::IssueNotFound(issue_id.clone()))?;
+
+    let milestones = load_milestones(app.rivets_dir()).await?;
+    let document =
+        EditDocument::from_issue(&issue).render(&format!("{}: {}", issue.id, issue.title))?;
+    let scratch = editor::scratch_file(issue.id.as_str())?;
+    let update = editor::edit_until_valid(&scratch, &document, editor::launch_editor, |text| {
+        let Some(doc) = EditDocument::parse(text, app.custom_fields())? else {
+            return Ok(None);
+        };
+        let update = doc.diff(&issue)?;
+        if let Some(Some(milestone)) = &update.milestone {
+            find_milestone(&milestones, milestone).map_err(|e| e.to_string())?;
+        }
+        Ok(Some(update))
+    })?;
+
+    let Some(update) = update.filter(|update| !editor::is_empty_update(update)) else {
+        editor::finish_scratch(&scratch, Ok(()))?;
+        match output_mode {
+            output::OutputMode::Json => output::print_json(&issue)?,
+            output::OutputMode::Text => println!("No changes to {}", issue.id),
+        }
+        return Ok(());
+    };
+
+    let updated = async {
+        let updated = app.storage_mut().update(&issue_id, update).await?;
+        app.save().await?;
+        Ok(updated)
+    }
+    .await;
+    let updated = editor::finish_scratch(&scratch, updated)?;
+
+    match output_mode {
+        output::OutputMode::Json => {
+            output::print_json(&updated)?;
+        }
+        output::OutputMode::Text => {
+            println!("Updated issue: {}", updated.id);
+        }
+    }
+
+    Ok(())
+}
+
 /// Handle save-or-record-failure for batch operations.
 ///
 /// This helper encapsulates the common pattern of:
@@ -701,20 +885,31 @@ pub async fn execute_ready(
     args: &ReadyArgs,
     output_mode: OutputMode,
 ) -> Result<()> {
+    use crate::commands::milestones::current_milestone_name;
     use crate::domain::{IssueFilter, SortPolicy};
     use crate::output;
 
+    let milestone = if args.current_milestone {
+        Some(current_milestone_name(app.rivets_dir(), chrono::Utc::now().date_naive()).await?)
+    } else {
+        args.milestone.clone()
+    };
+
     // Only create filter if we have filtering criteria; limit is applied after via truncate
     let filter = if args.assignee.is_some()
         || args.priority.is_some()
         || args.issue_kind.is_some()
         || args.label.is_some()
+        || milestone.is_some()
+        || args.agent.is_some()
     {
         Some(IssueFilter {
             assignee: args.assignee.clone(),
             priority: args.priority,
             issue_kind: args.issue_kind,
             label: args.label.clone(),
+            milestone,
+            claimant: args.agent.clone(),
             ..Default::default()
         })
     } else {
@@ -755,6 +950,100 @@ pub async fn execute_ready(
     Ok(())
 }
 
+/// Execute the claim command
+///
+/// Claims the given issue, or the next ready issue the agent can take when
+/// no ID is given. Claiming an issue the agent already holds extends its
+/// lease.
+pub async fn execute_claim(
+    app: &mut crate::app::App,
+    args: &ClaimArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::commands::claims::claim_next;
+    use crate::domain::IssueId;
+    use crate::output;
+
+    let claimed = match &args.issue_id {
+        Some(id) => Some(
+            app.storage_mut()
+                .claim(&IssueId::new(id), &args.agent, args.lease)
+                .await?,
+        ),
+        None => claim_next(app.storage_mut(), &args.agent, args.lease).await?,
+    };
+    if claimed.is_some() {
+        app.save().await?;
+    }
+
+    match output_mode {
+        output::OutputMode::Json => output::print_json(&claimed)?,
+        output::OutputMode::Text => match &claimed {
+            Some(issue) => {
+                println!("Claimed {} for {}", issue.id, args.agent);
+                output::print_issue(issue, output_mode)?;
+            }
+            None => println!("No ready issues to claim."),
+        },
+    }
+
+    Ok(())
+}
+
+/// Execute the renew command
+pub async fn execute_renew(
+    app: &mut crate::app::App,
+    args: &RenewArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::domain::IssueId;
+    use crate::output;
+
+    let issue = app
+        .storage_mut()
+        .renew_lease(&IssueId::new(&args.issue_id), &args.agent, args.lease)
+        .await?;
+    app.save().await?;
+
+    match output_mode {
+        output::OutputMode::Json => output::print_json(&issue)?,
+        output::OutputMode::Text => {
+            if let Some(lease) = &issue.lease {
+                println!(
+                    "Renewed claim on {} until {}",
+                    issue.id,
+                    lease.expires_at.format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute the release command
+pub async fn execute_release(
+    app: &mut crate::app::App,
+    args: &ReleaseArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::domain::IssueId;
+    use crate::output;
+
+    let issue = app
+        .storage_mut()
+        .release(&IssueId::new(&args.issue_id), &args.agent)
+        .await?;
+    app.save().await?;
+
+    match output_mode {
+        output::OutputMode::Json => output::print_json(&issue)?,
+        output::OutputMode::Text => println!("Released claim on {}", issue.id),
+    }
+
+    Ok(())
+}
+
 /// Add a dependency between two issues.
 async fn execute_dep_add(
     app: &mut crate::app::App,
@@ -880,35 +1169,15 @@ async fn execute_dep_tree(
     depth: usize,
     output_mode: OutputMode,
 ) -> Result<()> {
+    use crate::commands::dep_tree::build_dep_tree;
     use crate::domain::IssueId;
-    use crate::output::{self, DepTreeNode};
-    use std::collections::HashSet;
+    use crate::output;
 
     let id = IssueId::new(issue_id);
 
-    // Verify the issue exists and get its details
-    let issue = app
-        .storage()
-        .get(&id)
-        .await?
-        .ok_or_else(|| crate::error::Error::IssueNotFound(id.clone()))?;
-
     // Convert depth: 0 means unlimited (None), otherwise Some(depth)
     let max_depth = if depth == 0 { None } else { Some(depth) };
-
-    // Build tree recursively using DFS
-    let mut visited = HashSet::new();
-    visited.insert(id.clone());
-    let children = build_dep_tree_children(app, &id, max_depth, 0, &mut visited).await?;
-
-    let root = DepTreeNode {
-        id: issue_id.to_string(),
-        dep_type: None,
-        status: Some(issue.status),
-        title: Some(issue.title.clone()),
-        priority: Some(issue.priority),
-        children,
-    };
+    let root = build_dep_tree(app.storage(), &id, max_depth).await?;
 
     // Get dependents (reverse dependencies)
     let dependents = app.storage().get_dependents(&id).await?;
@@ -933,68 +1202,6 @@ async fn execute_dep_tree(
     Ok(())
 }
 
-/// Recursively build dependency tree children via DFS.
-///
-/// Uses a visited set to prevent cycles, and respects the max depth limit.
-async fn build_dep_tree_children(
-    app: &crate::app::App,
-    parent_id: &crate::domain::IssueId,
-    max_depth: Option<usize>,
-    current_depth: usize,
-    visited: &mut std::collections::HashSet<crate::domain::IssueId>,
-) -> Result<Vec<crate::output::DepTreeNode>> {
-    use crate::output::DepTreeNode;
-
-    // Check depth limit
-    if let Some(max) = max_depth
-        && current_depth >= max
-    {
-        return Ok(vec![]);
-    }
-
-    let deps = app.storage().get_dependencies(parent_id).await?;
-    let mut children = Vec::new();
-
-    for dep in deps {
-        let child_id = dep.depends_on_id.clone();
-
-        // Skip already-visited nodes to prevent cycles
-        if !visited.insert(child_id.clone()) {
-            continue;
-        }
-
-        // Fetch status for the child node
-        let status = app
-            .storage()
-            .get(&child_id)
-            .await
-            .ok()
-            .flatten()
-            .map(|i| i.status);
-
-        // Recurse into children
-        let grandchildren = Box::pin(build_dep_tree_children(
-            app,
-            &child_id,
-            max_depth,
-            current_depth + 1,
-            visited,
-        ))
-        .await?;
-
-        children.push(DepTreeNode {
-            id: child_id.to_string(),
-            dep_type: Some(dep.dep_type),
-            status,
-            title: None,
-            priority: None,
-            children: grandchildren,
-        });
-    }
-
-    Ok(children)
-}
-
 /// Execute the dep command
 pub async fn execute_dep(
     app: &mut crate::app::App,
@@ -1375,7 +1582,8 @@ pub async fn execute_stale(
     use crate::output;
     use chrono::{Duration, Utc};
 
-    let cutoff = Utc::now() - Duration::days(i64::from(args.days));
+    let now = Utc::now();
+    let cutoff = now - Duration::days(i64::from(args.days));
 
     // Build filter based on status if provided
     let filter = IssueFilter {
@@ -1385,13 +1593,13 @@ pub async fn execute_stale(
 
     let all_issues = app.storage().list(&filter).await?;
 
-    // Filter to stale issues (not updated since cutoff)
+    // Filter to stale issues (not updated since cutoff, or abandoned claims)
     // When no status filter is provided, exclude closed issues by default
     // When a status filter IS provided (e.g., --status closed), respect it
     let mut stale_issues: Vec<_> = all_issues
         .into_iter()
         .filter(|i| {
-            let is_stale = i.updated_at < cutoff;
+            let is_stale = i.updated_at < cutoff || i.has_expired_lease(now);
             let include_issue = args.status.is_some() || i.status != IssueStatus::Closed;
             is_stale && include_issue
         })
@@ -1419,13 +1627,22 @@ pub async fn execute_stale(
                 println!();
                 let config = output::OutputConfig::from_env();
                 for issue in &stale_issues {
-                    let days_stale = (Utc::now() - issue.updated_at).num_days();
+                    let days_stale = (now - issue.updated_at).num_days();
                     output::print_issue(issue, output_mode)?;
-                    println!(
-                        "  {} {} days",
-                        output::warning("Stale:", &config),
-                        days_stale
-                    );
+                    if let Some(lease) = issue.lease.as_ref().filter(|l| !l.is_active(now)) {
+                        println!(
+                            "  {} claim by {} expired {}",
+                            output::warning("Stale:", &config),
+                            lease.holder,
+                            lease.expires_at.format("%Y-%m-%d %H:%M")
+                        );
+                    } else {
+                        println!(
+                            "  {} {} days",
+                            output::warning("Stale:", &config),
+                            days_stale
+                        );
+                    }
                 }
             }
         }
@@ -1434,6 +1651,169 @@ pub async fn execute_stale(
     Ok(())
 }
 
+/// Execute the due command
+///
+/// Lists issues that are not closed and are either overdue or due within
+/// the requested window, soonest due first.
+pub async fn execute_due(
+    app: &crate::app::App,
+    args: &DueArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::domain::{IssueFilter, IssueStatus};
+    use crate::output;
+    use chrono::Utc;
+
+    let now = Utc::now();
+    let horizon = now + args.within;
+
+    let all_issues = app.storage().list(&IssueFilter::default()).await?;
+    let mut due_issues: Vec<_> = all_issues
+        .into_iter()
+        .filter(|i| i.status != IssueStatus::Closed && i.due_at.is_some_and(|due| due <= horizon))
+        .collect();
+
+    // Soonest due first, then priority
+    due_issues.sort_by(|a, b| {
+        a.due_at
+            .cmp(&b.due_at)
+            .then(a.priority.cmp(&b.priority))
+            .then(a.id.cmp(&b.id))
+    });
+    due_issues.truncate(args.limit);
+
+    match output_mode {
+        output::OutputMode::Json => {
+            output::print_json(&due_issues)?;
+        }
+        output::OutputMode::Text => {
+            if due_issues.is_empty() {
+                println!("No open issues due within {}.", format_window(args.within));
+            } else {
+                let overdue = due_issues.iter().filter(|i| i.is_overdue(now)).count();
+                println!(
+                    "Due issues ({} overdue, {} due within {}):",
+                    overdue,
+                    due_issues.len() - overdue,
+                    format_window(args.within)
+                );
+                println!();
+                for issue in &due_issues {
+                    output::print_issue(issue, output_mode)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a due window as whole days, or hours when shorter than a day.
+fn format_window(window: chrono::Duration) -> String {
+    match window.num_days() {
+        0 => format!("{} hours", window.num_hours()),
+        1 => "1 day".to_string(),
+        days => format!("{days} days"),
+    }
+}
+
+/// Execute the milestone command
+pub async fn execute_milestone(
+    app: &crate::app::App,
+    args: &MilestoneArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::commands::milestones::{find_milestone, load_milestones, save_milestones};
+    use crate::domain::{
+        IssueFilter, IssueStatus, Milestone, MilestoneError, MilestoneProgress, current_milestone,
+    };
+    use crate::output;
+    use chrono::Utc;
+
+    let now = Utc::now();
+    let today = now.date_naive();
+    let mut milestones = load_milestones(app.rivets_dir()).await?;
+
+    let in_milestone = |name: &str| IssueFilter {
+        milestone: Some(name.to_string()),
+        ..Default::default()
+    };
+
+    match &args.action {
+        MilestoneAction::Create { name, start, end } => {
+            if milestones.iter().any(|m| &m.name == name) {
+                return Err(
+                    crate::error::Error::from(MilestoneError::AlreadyExists(name.clone())).into(),
+                );
+            }
+            let milestone =
+                Milestone::new(name, *start, *end, now).map_err(crate::error::Error::from)?;
+            milestones.push(milestone.clone());
+            save_milestones(app.rivets_dir(), &milestones).await?;
+
+            match output_mode {
+                output::OutputMode::Json => output::print_json(&milestone)?,
+                output::OutputMode::Text => println!("Created milestone: {}", milestone.name),
+            }
+        }
+        MilestoneAction::List => {
+            let current = current_milestone(&milestones, today).map(|m| m.name.clone());
+            let mut rows = Vec::with_capacity(milestones.len());
+            for milestone in milestones {
+                let issues = app.storage().list(&in_milestone(&milestone.name)).await?;
+                let progress = MilestoneProgress::compute(&milestone, &issues, today);
+                rows.push((milestone, progress));
+            }
+            output::print_milestones(&rows, current.as_deref(), output_mode)?;
+        }
+        MilestoneAction::Show { name } => {
+            let milestone = find_milestone(&milestones, name)?;
+            let current = current_milestone(&milestones, today)
+                .is_some_and(|current| current.name == milestone.name);
+            let issues = app.storage().list(&in_milestone(name)).await?;
+            let progress = MilestoneProgress::compute(milestone, &issues, today);
+
+            // Remaining blocked items: open issues in the milestone that are
+            // marked blocked or still wait on an open blocker.
+            let mut blockers_by_issue: BTreeMap<_, _> = app
+                .storage()
+                .blocked_issues()
+                .await?
+                .into_iter()
+                .map(|(issue, blockers)| (issue.id, blockers))
+                .collect();
+            let blocked: Vec<_> = issues
+                .into_iter()
+                .filter(|issue| issue.status != IssueStatus::Closed)
+                .filter_map(|issue| {
+                    let blockers = blockers_by_issue.remove(&issue.id);
+                    (issue.status == IssueStatus::Blocked || blockers.is_some())
+                        .then(|| (issue, blockers.unwrap_or_default()))
+                })
+                .collect();
+
+            output::print_milestone_report(milestone, &progress, current, &blocked, output_mode)?;
+        }
+        MilestoneAction::Close { name } => {
+            let index = milestones
+                .iter()
+                .position(|m| &m.name == name)
+                .ok_or_else(|| crate::error::Error::from(MilestoneError::NotFound(name.clone())))?;
+            milestones[index]
+                .close(now)
+                .map_err(crate::error::Error::from)?;
+            save_milestones(app.rivets_dir(), &milestones).await?;
+
+            match output_mode {
+                output::OutputMode::Json => output::print_json(&milestones[index])?,
+                output::OutputMode::Text => println!("Closed milestone: {name}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Execute the blocked command
 pub async fn execute_blocked(
     app: &crate::app::App,
@@ -1455,78 +1835,40 @@ pub async fn execute_stats(
     args: &StatsArgs,
     output_mode: OutputMode,
 ) -> Result<()> {
-    use crate::domain::IssueFilter;
+    use crate::commands::stats::collect_stats;
     use crate::output;
 
-    // Get all issues and count by status
-    let all_issues = app.storage().list(&IssueFilter::default()).await?;
-    let counts = count_by_status(&all_issues);
-
-    // Ready issues (not blocked by dependencies)
-    let ready = app.storage().ready_to_work(None, None).await?.len();
-
-    // Blocked issues (by dependencies)
-    let blocked_by_deps = app.storage().blocked_issues().await?.len();
+    let stats = collect_stats(app.storage(), args.detailed, chrono::Utc::now()).await?;
 
     match output_mode {
         output::OutputMode::Json => {
-            let mut stats = serde_json::json!({
-                "total": counts.total,
-                "by_status": {
-                    "open": counts.open,
-                    "in_progress": counts.in_progress,
-                    "blocked": counts.blocked,
-                    "closed": counts.closed
-                },
-                "ready": ready,
-                "blocked_by_dependencies": blocked_by_deps
-            });
-
-            if args.detailed {
-                // Add priority breakdown
-                let by_priority: Vec<usize> = (0..=4)
-                    .map(|p| all_issues.iter().filter(|i| i.priority == p).count())
-                    .collect();
-
-                stats["by_priority"] = serde_json::json!({
-                    "p0_critical": by_priority[0],
-                    "p1_high": by_priority[1],
-                    "p2_medium": by_priority[2],
-                    "p3_low": by_priority[3],
-                    "p4_backlog": by_priority[4]
-                });
-            }
-
             output::print_json(&stats)?;
         }
         output::OutputMode::Text => {
             println!("Project Statistics");
             println!("==================");
             println!();
-            println!("Total Issues:  {}", counts.total);
+            println!("Total Issues:  {}", stats.total);
             println!();
             println!("By Status:");
-            println!("  Open:        {}", counts.open);
-            println!("  In Progress: {}", counts.in_progress);
-            println!("  Blocked:     {}", counts.blocked);
-            println!("  Closed:      {}", counts.closed);
+            println!("  Open:        {}", stats.by_status.open);
+            println!("  In Progress: {}", stats.by_status.in_progress);
+            println!("  Blocked:     {}", stats.by_status.blocked);
+            println!("  Closed:      {}", stats.by_status.closed);
             println!();
-            println!("Ready to Work: {}", ready);
-            println!("Blocked by Dependencies: {}", blocked_by_deps);
+            println!("Ready to Work: {}", stats.ready);
+            println!("Blocked by Dependencies: {}", stats.blocked_by_dependencies);
+            if stats.overdue > 0 {
+                let config = output::OutputConfig::from_env();
+                println!("{} {}", output::error("Overdue:", &config), stats.overdue);
+            } else {
+                println!("Overdue: 0");
+            }
 
-            if args.detailed {
+            if let Some(by_priority) = &stats.by_priority {
                 println!();
                 println!("By Priority:");
-                for p in 0..=4 {
-                    let count = all_issues.iter().filter(|i| i.priority == p).count();
-                    let label = match p {
-                        0 => "P0 (Critical)",
-                        1 => "P1 (High)",
-                        2 => "P2 (Medium)",
-                        3 => "P3 (Low)",
-                        4 => "P4 (Backlog)",
-                        _ => unreachable!(),
-                    };
+                for (label, count) in by_priority.labeled() {
                     println!("  {}: {}", label, count);
                 }
             }
@@ -1536,6 +1878,553 @@ pub async fn execute_stats(
     Ok(())
 }
 
+/// How long `daemon start` and `daemon stop` wait for the daemon to come up
+/// or go away.
+#[cfg(unix)]
+const DAEMON_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Execute the daemon command
+#[cfg(unix)]
+pub async fn execute_daemon(args: &DaemonArgs, output_mode: OutputMode) -> Result<()> {
+    use crate::app::App;
+    use crate::commands::init::find_rivets_root;
+    use crate::daemon::{self, DaemonConfig, DaemonPaths};
+    use crate::error::ConfigError;
+    use std::os::unix::process::CommandExt;
+
+    let paths_for = |workspace: &Option<std::path::PathBuf>| -> Result<DaemonPaths> {
+        let start = match workspace {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        let root = find_rivets_root(&start).ok_or(ConfigError::NotInitialized)?;
+        Ok(DaemonPaths::for_workspace(&root)?)
+    };
+
+    match &args.action {
+        DaemonAction::Start {
+            workspace,
+            idle_timeout,
+            foreground,
+        } => {
+            let paths = paths_for(workspace)?;
+            if *foreground {
+                let app = App::from_directory_direct(paths.workspace_root()).await?;
+                let config = DaemonConfig {
+                    idle_timeout: idle_timeout
+                        .to_std()
+                        .context("Idle timeout must be positive")?,
+                    paths,
+                };
+                daemon::serve(config, app.into_storage()).await?;
+                return Ok(());
+            }
+
+            if let Some(status) = daemon::status(&paths).await? {
+                let message = format!(
+                    "Rivets daemon already running for {} (pid {})",
+                    paths.workspace_root().display(),
+                    status.pid
+                );
+                return print_daemon_status(&paths, Some(&status), true, &message, output_mode);
+            }
+
+            // Detach into its own process group so the daemon outlives this
+            // command and is not hit by signals sent to the terminal's
+            // foreground job.
+            let child = std::process::Command::new(std::env::current_exe()?)
+                .args(["daemon", "start", "--foreground", "--idle-timeout"])
+                .arg(format!("{}m", idle_timeout.num_minutes()))
+                .arg("--workspace")
+                .arg(paths.workspace_root())
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .process_group(0)
+                .spawn()
+                .context("Failed to spawn the rivets daemon")?;
+            drop(child);
+
+            let status = daemon::wait_until_running(&paths, DAEMON_WAIT_TIMEOUT).await?;
+            let message = format!(
+                "Started rivets daemon for {} (pid {})",
+                paths.workspace_root().display(),
+                status.pid
+            );
+            print_daemon_status(&paths, Some(&status), true, &message, output_mode)
+        }
+        DaemonAction::Status { workspace } => {
+            let paths = paths_for(workspace)?;
+            let status = daemon::status(&paths).await?;
+            let message = match &status {
+                Some(status) => format!(
+                    "Rivets daemon running for {}\n  PID:          {}\n  Socket:       {}\n  Started:      {}\n  Idle timeout: {}m",
+                    paths.workspace_root().display(),
+                    status.pid,
+                    paths.socket().display(),
+                    status.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    status.idle_timeout_secs / 60
+                ),
+                None => format!(
+                    "No rivets daemon running for {}",
+                    paths.workspace_root().display()
+                ),
+            };
+            print_daemon_status(
+                &paths,
+                status.as_ref(),
+                status.is_some(),
+                &message,
+                output_mode,
+            )
+        }
+        DaemonAction::Stop { workspace } => {
+            let paths = paths_for(workspace)?;
+            let status = daemon::stop(&paths, DAEMON_WAIT_TIMEOUT).await?;
+            let message = match &status {
+                Some(status) => format!(
+                    "Stopped rivets daemon for {} (pid {})",
+                    paths.workspace_root().display(),
+                    status.pid
+                ),
+                None => format!(
+                    "No rivets daemon running for {}",
+                    paths.workspace_root().display()
+                ),
+            };
+            print_daemon_status(&paths, status.as_ref(), false, &message, output_mode)
+        }
+    }
+}
+
+/// Report a daemon command's outcome: `message` as text, or the daemon's
+/// state as JSON.
+#[cfg(unix)]
+fn print_daemon_status(
+    paths: &crate::daemon::DaemonPaths,
+    status: Option<&crate::daemon::DaemonStatus>,
+    running: bool,
+    message: &str,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::output;
+
+    match output_mode {
+        output::OutputMode::Json => output::print_json(&serde_json::json!({
+            "workspace_root": paths.workspace_root().display().to_string(),
+            "socket": paths.socket().display().to_string(),
+            "running": running,
+            "daemon": status,
+        }))?,
+        output::OutputMode::Text => println!("{message}"),
+    }
+    Ok(())
+}
+
+/// Execute the serve command
+pub async fn execute_serve(args: &ServeArgs, output_mode: OutputMode) -> Result<()> {
+    use crate::api;
+    use crate::app::App;
+    use crate::output;
+
+    // The server is itself a long-lived cache of the workspace, so it loads
+    // the data file directly rather than holding a daemon connection open.
+    let app = App::from_directory_direct(&std::env::current_dir()?).await?;
+    let listener = api::bind(args.addr)
+        .await
+        .with_context(|| format!("Failed to listen on {}", args.addr))?;
+    let url = format!("http://{}{}", listener.local_addr()?, api::API_PREFIX);
+    let workspace_root = app.rivets_dir().parent().unwrap_or(app.rivets_dir());
+
+    match output_mode {
+        output::OutputMode::Json => output::print_json(&serde_json::json!({
+            "workspace_root": workspace_root.display().to_string(),
+            "url": url,
+        }))?,
+        output::OutputMode::Text => println!(
+            "Serving {} at {url} (press Ctrl+C to stop)",
+            workspace_root.display()
+        ),
+    }
+    std::io::stdout().flush()?;
+
+    api::serve(listener, app, async {
+        let _ = tokio::signal::ctrl_c().await;
+    })
+    .await?;
+    Ok(())
+}
+
+/// Execute the hooks command
+pub async fn execute_hooks(
+    app: &crate::app::App,
+    args: &HooksArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::domain::IssueId;
+    use crate::hooks::{CLI_SOURCE, sample_event};
+    use crate::output;
+
+    let HooksAction::Test { event, issue, hook } = &args.action;
+
+    let issue = match issue {
+        Some(id) => {
+            let id = IssueId::new(id);
+            app.storage()
+                .get(&id)
+                .await?
+                .ok_or(crate::error::Error::IssueNotFound(id))?
+        }
+        None => app
+            .storage()
+            .export_all()
+            .await?
+            .into_iter()
+            .max_by_key(|issue| issue.updated_at)
+            .context("No issues to build a sample event from; create one or pass --issue")?,
+    };
+
+    let hooks = app.hooks();
+    let targets: Vec<_> = hooks
+        .subscribed(*event)
+        .filter(|target| hook.as_ref().is_none_or(|name| &target.name == name))
+        .collect();
+    if targets.is_empty() {
+        match hook {
+            Some(name) => anyhow::bail!("No hook named '{name}' subscribes to {event} events"),
+            None => anyhow::bail!("No hooks subscribe to {event} events"),
+        }
+    }
+
+    let payload = hooks.payload(CLI_SOURCE, sample_event(*event, &issue), true);
+    let mut results = Vec::new();
+    for target in &targets {
+        let result = hooks.deliver(target, &payload).await;
+        results.push(serde_json::json!({
+            "hook": target.name,
+            "event": event,
+            "delivered": result.is_ok(),
+            "error": result.as_ref().err().map(ToString::to_string),
+        }));
+        if output_mode == output::OutputMode::Text {
+            match &result {
+                Ok(()) => println!("✓ {}: delivered {event} event", target.name),
+                Err(error) => println!("✗ {}: {error}", target.name),
+            }
+        }
+    }
+    if output_mode == output::OutputMode::Json {
+        output::print_json(&results)?;
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result["delivered"] == false)
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} hooks failed", results.len());
+    }
+    Ok(())
+}
+
+/// Execute the scan-commits command
+pub async fn execute_scan_commits(
+    app: &mut crate::app::App,
+    args: &ScanCommitsArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    let commits = crate::git::log(app.root_dir(), &args.range).await?;
+    let links = link_and_save(app, commits, args.close, args.commit_url.clone()).await?;
+    if output_mode == OutputMode::Text && links.is_empty() {
+        println!("No issue references found in {}", args.range);
+    }
+    print_commit_links(&links, output_mode)
+}
+
+/// Execute the diff command
+pub async fn execute_diff(
+    app: &crate::app::App,
+    args: &DiffArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::commands::diff::{TrackerDiff, issues_at};
+
+    let before = issues_at(app.root_dir(), app.data_file(), &args.from, app.prefix()).await?;
+    let (to, after) = match &args.to {
+        Some(rev) => (
+            rev.clone(),
+            issues_at(app.root_dir(), app.data_file(), rev, app.prefix()).await?,
+        ),
+        None => (
+            "working tree".to_string(),
+            app.storage().export_all().await?,
+        ),
+    };
+    let diff = TrackerDiff::new(args.from.clone(), to, &before, &after);
+    crate::output::print_tracker_diff(&diff, output_mode)?;
+    Ok(())
+}
+
+/// Execute the doctor command
+pub async fn execute_doctor(
+    app: &crate::app::App,
+    args: &DoctorArgs,
+    output_mode: OutputMode,
+    skip_confirm: bool,
+) -> Result<()> {
+    use crate::commands::doctor::{ProblemKind, check};
+    use crate::output;
+
+    let data_path = app.root_dir().join(app.data_file());
+    if args.fix_ids {
+        return doctor_fix_ids(app, &data_path, output_mode).await;
+    }
+    if args.repair {
+        return doctor_repair(app, &data_path, output_mode, skip_confirm).await;
+    }
+
+    let problems = check(&data_path, app.prefix()).await?;
+    if output_mode == OutputMode::Json {
+        return Ok(output::print_json(&problems)?);
+    }
+    if problems.is_empty() {
+        println!("No problems found in {}", data_path.display());
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("✗ {}", problem.message);
+    }
+    if problems
+        .iter()
+        .any(|problem| problem.kind != ProblemKind::DuplicateId)
+    {
+        println!("Run `rivets doctor --repair` to fix them");
+    }
+    if problems
+        .iter()
+        .any(|problem| problem.kind == ProblemKind::DuplicateId)
+    {
+        println!("Run `rivets doctor --fix-ids` to give duplicated issues new IDs");
+    }
+    Ok(())
+}
+
+async fn doctor_fix_ids(
+    app: &crate::app::App,
+    data_path: &std::path::Path,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::commands::doctor::fix_ids;
+    use crate::output;
+
+    let outcome = fix_ids(data_path, app.rivets_dir(), app.prefix()).await?;
+    if output_mode == OutputMode::Json {
+        return Ok(output::print_json(&outcome)?);
+    }
+    if outcome.renamed.is_empty() {
+        println!("No duplicate IDs in {}", data_path.display());
+    }
+    for renamed in &outcome.renamed {
+        println!(
+            "✓ line {}: {} -> {}",
+            renamed.line_number, renamed.old_id, renamed.new_id
+        );
+        for issue_id in &renamed.referenced_by {
+            println!(
+                "  - {issue_id} still depends on {}; check which issue it meant",
+                renamed.old_id
+            );
+        }
+    }
+    if let Some(backup) = &outcome.backup {
+        println!("Original saved to {}", backup.display());
+    }
+    Ok(())
+}
+
+/// Offer each planned repair, asking before each one unless `skip_confirm`,
+/// and apply the accepted ones.
+async fn doctor_repair(
+    app: &crate::app::App,
+    data_path: &std::path::Path,
+    output_mode: OutputMode,
+    skip_confirm: bool,
+) -> Result<()> {
+    use crate::commands::doctor::{apply_repairs, plan_repairs};
+    use crate::output;
+
+    let planned = plan_repairs(data_path, app.rivets_dir(), app.prefix()).await?;
+    let mut accepted = Vec::new();
+    for repair in planned {
+        if skip_confirm || confirm_action(&format!("Repair {repair}?"))? {
+            accepted.push(repair);
+        }
+    }
+    let outcome = apply_repairs(data_path, app.rivets_dir(), &accepted).await?;
+    if output_mode == OutputMode::Json {
+        return Ok(output::print_json(&outcome)?);
+    }
+    if outcome.applied.is_empty() {
+        println!("Nothing repaired in {}", data_path.display());
+    }
+    for repair in &outcome.applied {
+        println!("✓ {repair}");
+    }
+    if let Some(quarantine) = &outcome.quarantine {
+        println!("Quarantined lines saved to {}", quarantine.display());
+    }
+    if let Some(backup) = &outcome.backup {
+        println!("Original saved to {}", backup.display());
+    }
+    Ok(())
+}
+
+/// Execute the rekey command
+pub async fn execute_rekey(
+    app: &mut crate::app::App,
+    args: &RekeyArgs,
+    output_mode: OutputMode,
+    skip_confirm: bool,
+) -> Result<()> {
+    use crate::commands::rekey::rekey;
+    use crate::output;
+
+    let old_prefix = app.prefix().to_string();
+    if !skip_confirm
+        && !confirm_action(&format!(
+            "Rename every {old_prefix}- issue ID to {}-?",
+            args.prefix
+        ))?
+    {
+        println!("Rekey cancelled.");
+        return Ok(());
+    }
+
+    let rivets_dir = app.rivets_dir().to_path_buf();
+    let outcome = rekey(
+        app.storage_mut(),
+        &rivets_dir,
+        &old_prefix,
+        &args.prefix,
+        args.rewrite_mentions,
+    )
+    .await?;
+    if output_mode == OutputMode::Json {
+        return Ok(output::print_json(&outcome)?);
+    }
+    println!(
+        "✓ Rekeyed {} issues from {} to {}",
+        outcome.renamed, outcome.old_prefix, outcome.new_prefix
+    );
+    if !outcome.mentions_rewritten.is_empty() {
+        println!(
+            "✓ Rewrote ID mentions in {} issues",
+            outcome.mentions_rewritten.len()
+        );
+    }
+    for issue_id in &outcome.unchanged {
+        println!("  - {issue_id} does not use the {old_prefix} prefix and was left as is");
+    }
+    Ok(())
+}
+
+/// Execute the git-hook command
+pub async fn execute_git_hook(
+    app: &mut crate::app::App,
+    args: &GitHookArgs,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::commands::commits::{check_message, install_hooks};
+    use crate::output;
+
+    match &args.action {
+        GitHookAction::Install { close, force } => {
+            let paths = install_hooks(app.root_dir(), *close, *force).await?;
+            match output_mode {
+                OutputMode::Text => {
+                    for path in &paths {
+                        println!("✓ Installed {}", path.display());
+                    }
+                }
+                OutputMode::Json => output::print_json(&serde_json::json!({ "installed": paths }))?,
+            }
+            Ok(())
+        }
+        GitHookAction::CommitMsg { file } => {
+            let message = tokio::fs::read_to_string(file)
+                .await
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let problems = check_message(app.storage(), app.prefix(), &message).await?;
+            if problems.is_empty() {
+                return Ok(());
+            }
+            for (reference, problem) in &problems {
+                eprintln!("✗ {}: {problem}", reference.issue_id);
+            }
+            anyhow::bail!(
+                "Commit message names {} bad issue reference(s); fix them or commit with --no-verify",
+                problems.len()
+            )
+        }
+        GitHookAction::PostCommit { close } => {
+            let commit = crate::git::commit(app.root_dir(), "HEAD").await?;
+            let links = link_and_save(app, vec![commit], *close, None).await?;
+            print_commit_links(&links, output_mode)
+        }
+    }
+}
+
+/// Link `commits` to the issues they name and save, linking to the `origin`
+/// remote's web view of each commit unless `commit_url` overrides it, and to
+/// the commit's SHA when there is neither.
+async fn link_and_save(
+    app: &mut crate::app::App,
+    commits: Vec<crate::git::Commit>,
+    close: bool,
+    commit_url: Option<String>,
+) -> Result<Vec<crate::commands::commits::CommitLink>> {
+    use crate::commands::commits::{self, LinkOptions, LinkOutcome};
+
+    let commit_url = match commit_url {
+        Some(template) => Some(template),
+        None => crate::git::remote_commit_url(app.root_dir()).await,
+    };
+    let options = LinkOptions { close, commit_url };
+    let prefix = app.prefix().to_string();
+    let links = commits::link_commits(app.storage_mut(), &prefix, &commits, &options).await?;
+    if links
+        .iter()
+        .any(|link| matches!(link.outcome, LinkOutcome::Linked | LinkOutcome::Closed))
+    {
+        app.save().await?;
+    }
+    Ok(links)
+}
+
+fn print_commit_links(
+    links: &[crate::commands::commits::CommitLink],
+    output_mode: OutputMode,
+) -> Result<()> {
+    use crate::commands::commits::LinkOutcome;
+    use crate::output;
+
+    if output_mode == OutputMode::Json {
+        return Ok(output::print_json(&links)?);
+    }
+    for link in links {
+        let commit = &link.commit[..link.commit.len().min(7)];
+        let issue = &link.issue_id;
+        match link.outcome {
+            LinkOutcome::Linked => println!("✓ Linked {commit} to {issue}"),
+            LinkOutcome::Closed => println!("✓ Linked {commit} to {issue} and closed it"),
+            LinkOutcome::AlreadyLinked => println!("- {commit} is already linked to {issue}"),
+            LinkOutcome::InvalidId => println!("✗ {commit}: {issue} is not a valid issue ID"),
+            LinkOutcome::UnknownIssue => println!("✗ {commit}: no issue {issue}"),
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1566,6 +2455,10 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             closed_at: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            lease: None,
         }
     }
 
@@ -1915,6 +2808,11 @@ mod tests {
                 design: None,
                 acceptance: None,
                 notes: None,
+                fields: vec![],
+                due: None,
+                no_due: false,
+                milestone: None,
+                no_milestone: false,
             };
 
             let result = execute_update(&mut app, &args, OutputMode::Text).await;
@@ -2071,4 +2969,53 @@ mod tests {
             );
         }
     }
+
+    mod execute_scan_commits_tests {
+        use super::super::link_and_save;
+        use crate::domain::NewIssue;
+        use tempfile::TempDir;
+
+        #[tokio::test]
+        async fn test_scan_commits_without_remote_attaches_commit_sha() {
+            let temp_dir = TempDir::new().unwrap();
+            let root = temp_dir.path();
+            crate::commands::init::init(root, Some("test"))
+                .await
+                .unwrap();
+            let mut app = crate::app::App::from_directory_direct(root).await.unwrap();
+            let new_issue = NewIssue {
+                title: "Fixed locally".to_string(),
+                ..Default::default()
+            };
+            let issue = app.storage_mut().create(new_issue).await.unwrap();
+            app.save().await.unwrap();
+
+            let message = format!("Fix it\n\nFixes {}", issue.id);
+            for args in [
+                &["init", "--quiet"][..],
+                &["commit", "--quiet", "--allow-empty", "-m", &message],
+            ] {
+                let status = std::process::Command::new("git")
+                    .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                    .args(args)
+                    .current_dir(root)
+                    .status()
+                    .unwrap();
+                assert!(status.success(), "git {args:?} failed");
+            }
+
+            let commits = crate::git::log(root, "HEAD").await.unwrap();
+            let links = link_and_save(&mut app, commits.clone(), false, None)
+                .await
+                .unwrap();
+            assert_eq!(links.len(), 1);
+
+            let issue = app.storage().get(&issue.id).await.unwrap().unwrap();
+            assert_eq!(issue.resources().len(), 1);
+            assert_eq!(
+                issue.resources()[0].target().to_string(),
+                format!("git:{}", commits[0].sha)
+            );
+        }
+    }
 }
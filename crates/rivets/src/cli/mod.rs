@@ -10,9 +10,17 @@
 //! - `list`: List issues with optional filters
 //! - `show`: Show issue details
 //! - `update`: Update an existing issue
+//! - `edit`: Edit an issue in `$EDITOR`
 //! - `close`: Close an issue
 //! - `delete`: Delete an issue
 //! - `ready`: Show ready-to-work issues
+//! - `due`: Show overdue and upcoming issues
+//! - `milestone`: Manage milestones and show their progress
+//! - `daemon`: Start, stop and inspect the per-workspace daemon
+//! - `serve`: Serve the workspace over a local REST API
+//! - `hooks`: Try out the hooks configured in `config.yaml`
+//! - `scan-commits`: Link commits to the issues their messages name
+//! - `git-hook`: Install git hooks that check and link commits
 //!
 //! # Global Flags
 //!
@@ -29,6 +37,7 @@
 //! ```
 
 mod args;
+mod editor;
 mod execute;
 mod types;
 mod validators;
@@ -37,19 +46,24 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 
 use crate::app::App;
+use crate::hooks::CLI_SOURCE;
 
 // Re-export argument structs
 pub use args::{
-    BlockedArgs, CloseArgs, CreateArgs, DeleteArgs, DepAction, DepArgs, InfoArgs, InitArgs,
-    LabelAction, LabelArgs, ListArgs, ReadyArgs, ReopenArgs, ResourceAction, ResourceArgs,
-    ShowArgs, StaleArgs, StatsArgs, UpdateArgs,
+    BlockedArgs, ClaimArgs, CloseArgs, CreateArgs, DaemonAction, DaemonArgs, DeleteArgs, DepAction,
+    DepArgs, DiffArgs, DoctorArgs, DueArgs, EditArgs, GitHookAction, GitHookArgs, HooksAction,
+    HooksArgs, InfoArgs, InitArgs, LabelAction, LabelArgs, ListArgs, MilestoneAction,
+    MilestoneArgs, ReadyArgs, RekeyArgs, ReleaseArgs, RenewArgs, ReopenArgs, ResourceAction,
+    ResourceArgs, ScanCommitsArgs, ServeArgs, ShowArgs, StaleArgs, StatsArgs, UpdateArgs,
 };
 
 // Re-export types
 pub use types::{BatchError, BatchResult, SortOrderArg, SortPolicyArg};
 
 // Re-export validators for external use
-pub use validators::{validate_description, validate_issue_id, validate_prefix, validate_title};
+pub use validators::{
+    validate_description, validate_issue_id, validate_label, validate_prefix, validate_title,
+};
 
 /// Rivets - A Rust-based issue tracking system
 ///
@@ -68,6 +82,11 @@ pub struct Cli {
     #[arg(short = 'y', long, global = true)]
     pub yes: bool,
 
+    /// Read issues as committed at a git revision (e.g. `v1.2.0`, `main`)
+    /// instead of the working tree; the workspace is read-only
+    #[arg(long, global = true, value_name = "REV")]
+    pub at: Option<String>,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -111,6 +130,13 @@ pub enum Commands {
     /// are updated; other fields remain unchanged.
     Update(UpdateArgs),
 
+    /// Edit an issue in `$EDITOR`
+    ///
+    /// Opens the issue's editable fields as a YAML document. Only fields
+    /// changed in the editor are updated. Invalid documents reopen the
+    /// editor with the error shown at the top.
+    Edit(EditArgs),
+
     /// Close an issue
     ///
     /// Marks an issue as completed. Optionally provide a reason for closing.
@@ -133,6 +159,23 @@ pub enum Commands {
     /// by priority (hybrid by default) to help you pick what to work on next.
     Ready(ReadyArgs),
 
+    /// Claim an issue for an agent
+    ///
+    /// Assigns an unassigned issue to the agent with a lease that expires
+    /// unless renewed. Without an issue ID, claims the next ready issue.
+    /// Other agents' `ready` lists hide actively claimed issues.
+    Claim(ClaimArgs),
+
+    /// Renew an agent's claim on an issue
+    ///
+    /// Extends the claim's lease from now.
+    Renew(RenewArgs),
+
+    /// Release an agent's claim on an issue
+    ///
+    /// Drops the lease and unassigns the issue so others can claim it.
+    Release(ReleaseArgs),
+
     /// Add a dependency between issues
     ///
     /// Creates a dependency relationship where one issue depends on another.
@@ -153,6 +196,19 @@ pub enum Commands {
     /// Lists issues that haven't been updated in a specified number of days.
     Stale(StaleArgs),
 
+    /// Show overdue and upcoming issues
+    ///
+    /// Lists open issues that are past their due date or due within a
+    /// window (7 days by default), soonest first.
+    Due(DueArgs),
+
+    /// Manage milestones
+    ///
+    /// Create, list, show and close milestones (time-boxed groups of issues
+    /// such as sprints). `show` reports completion, burn-down and the
+    /// remaining blocked issues.
+    Milestone(MilestoneArgs),
+
     /// Show blocked issues
     ///
     /// Lists issues that are blocked by dependencies, along with their blockers.
@@ -162,15 +218,139 @@ pub enum Commands {
     ///
     /// Displays summary statistics about issues, completion rates, and trends.
     Stats(StatsArgs),
+
+    /// Manage the per-workspace daemon
+    ///
+    /// The daemon keeps a workspace's issues loaded in memory and serves
+    /// other rivets commands over a Unix socket, so they skip re-reading the
+    /// data file. Commands use a running daemon automatically and read the
+    /// file directly otherwise. The daemon exits after an idle timeout.
+    #[cfg(unix)]
+    Daemon(DaemonArgs),
+
+    /// Serve the workspace over a local REST API
+    ///
+    /// Exposes issues, dependencies, labels, resources and the ready and
+    /// blocked queries as JSON endpoints under /api/v1 on a loopback address,
+    /// for dashboards and editor plugins. Runs until interrupted.
+    Serve(ServeArgs),
+
+    /// Try out the hooks configured in config.yaml
+    ///
+    /// Hooks run a command or POST to a URL when issues are created,
+    /// updated, closed, reopened or linked.
+    Hooks(HooksArgs),
+
+    /// Link commits to the issues their messages name
+    ///
+    /// Finds `Fixes <id>`, `Closes <id>` and `Refs <id>` lines in the
+    /// messages of the given commits, attaches each commit to the issues it
+    /// names as an implementation resource with a note of its subject, and
+    /// with --close closes the issues it fixes. Commits already linked are
+    /// skipped, so overlapping ranges can be scanned safely.
+    ScanCommits(ScanCommitsArgs),
+
+    /// Manage the git hooks that check and link commits
+    GitHook(GitHookArgs),
+
+    /// Show how the tracker changed between two git revisions
+    ///
+    /// Compares the issues committed at FROM with those at TO, or with the
+    /// working tree when TO is omitted, listing issues created, closed or
+    /// reopened, fields changed, notes added, and dependencies and resources
+    /// added or removed. The text output reads well as a PR comment.
+    Diff(DiffArgs),
+
+    /// Check the data file for problems that loading skips over
+    ///
+    /// Lists malformed lines, invalid issues, duplicate IDs, broken
+    /// dependencies and conflicting legacy fields with their line numbers.
+    /// With --repair, offers to quarantine unloadable lines to
+    /// .rivets/quarantine.jsonl, drop or retarget broken dependencies and
+    /// rewrite conflicting issues. With --fix-ids, issues that reuse an
+    /// earlier issue's ID after a merge get new IDs, their dependencies and
+    /// notes are rewritten to match, and the old IDs are recorded in
+    /// .rivets/id-aliases.jsonl. Every repair backs up the original file
+    /// first.
+    Doctor(DoctorArgs),
+
+    /// Rename the workspace's issue prefix
+    ///
+    /// Rewrites every issue ID, dependency and child ID under the new
+    /// prefix and updates config.yaml. The old IDs are recorded in
+    /// .rivets/id-aliases.jsonl, so `show` still finds issues by the IDs in
+    /// older commit messages. With --rewrite-mentions, renamed IDs in
+    /// descriptions and notes are rewritten too.
+    Rekey(RekeyArgs),
+}
+
+impl Commands {
+    /// Whether `--at` can point the command at a git revision: it reads
+    /// issues through the App and writes nothing outside the issue storage,
+    /// which refuses writes at a revision.
+    fn supports_revision(&self) -> bool {
+        match self {
+            Self::Init(_) | Self::Serve(_) | Self::Diff(_) | Self::Doctor(_) | Self::Rekey(_) => {
+                false
+            }
+            #[cfg(unix)]
+            Self::Daemon(_) => false,
+            Self::Milestone(args) => matches!(
+                args.action,
+                MilestoneAction::List | MilestoneAction::Show { .. }
+            ),
+            _ => true,
+        }
+    }
 }
 
 /// Load the App from the current working directory.
 ///
 /// This helper centralizes the common pattern of initializing the App
 /// from `std::env::current_dir()`, reducing duplication in command handlers.
-async fn load_app_from_cwd() -> Result<App> {
+/// With `at`, issues are read as committed at that git revision.
+async fn load_app_from_cwd(at: Option<&str>) -> Result<App> {
+    let current_dir = std::env::current_dir()?;
     // Ok(...?) pattern converts crate::error::Error to anyhow::Error
-    Ok(App::from_directory(&std::env::current_dir()?).await?)
+    Ok(match at {
+        Some(rev) => App::from_directory_at(&current_dir, rev).await?,
+        None => App::from_directory(&current_dir).await?,
+    })
+}
+
+/// Load the app and run a command that may change issues, then deliver the
+/// workspace's hooks for whatever it changed.
+async fn run_with_hooks(
+    at: Option<&str>,
+    command: impl AsyncFnOnce(&mut App) -> Result<()>,
+) -> Result<()> {
+    let mut app = load_app_from_cwd(at).await?;
+    with_hooks(&mut app, command).await
+}
+
+/// Run `command` on `app`, then deliver the workspace's hooks for whatever it
+/// changed.
+///
+/// Commands return `Ok` only once their changes are saved, so hooks fire
+/// only for a command that succeeded; a failed command fires none, even if
+/// it saved some of its changes before failing.
+async fn with_hooks(
+    app: &mut App,
+    command: impl AsyncFnOnce(&mut App) -> Result<()>,
+) -> Result<()> {
+    let before = if app.hooks().is_empty() {
+        None
+    } else {
+        Some(app.storage().export_all().await?)
+    };
+    command(app).await?;
+    if let Some(before) = before {
+        match app.storage().export_all().await {
+            Ok(after) => app.hooks().fire(CLI_SOURCE, &before, &after).await,
+            Err(error) => tracing::warn!(%error, "Failed to read the workspace for hooks"),
+        }
+    }
+    Ok(())
 }
 
 impl Cli {
@@ -198,68 +378,160 @@ impl Cli {
             OutputMode::Text
         };
 
+        let at = self.at.as_deref();
+        if let (Some(rev), Some(command)) = (at, &self.command)
+            && !command.supports_revision()
+        {
+            anyhow::bail!("--at {rev} cannot be used with this command");
+        }
+
         match &self.command {
             Some(Commands::Init(args)) => execute::execute_init(args).await,
             Some(Commands::Info(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_info(&app, args, output_mode).await
             }
             Some(Commands::Create(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_create(&mut app, args, output_mode).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_create(app, args, output_mode).await
+                })
+                .await
             }
             Some(Commands::List(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_list(&app, args, output_mode).await
             }
             Some(Commands::Show(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_show(&app, args, output_mode).await
             }
             Some(Commands::Update(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_update(&mut app, args, output_mode).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_update(app, args, output_mode).await
+                })
+                .await
+            }
+            Some(Commands::Edit(args)) => {
+                run_with_hooks(at, async |app| {
+                    execute::execute_edit(app, args, output_mode).await
+                })
+                .await
             }
             Some(Commands::Close(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_close(&mut app, args, output_mode, self.yes).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_close(app, args, output_mode, self.yes).await
+                })
+                .await
             }
             Some(Commands::Reopen(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_reopen(&mut app, args, output_mode, self.yes).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_reopen(app, args, output_mode, self.yes).await
+                })
+                .await
             }
             Some(Commands::Delete(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_delete(&mut app, args, output_mode, self.yes).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_delete(app, args, output_mode, self.yes).await
+                })
+                .await
             }
             Some(Commands::Ready(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_ready(&app, args, output_mode).await
             }
+            Some(Commands::Claim(args)) => {
+                run_with_hooks(at, async |app| {
+                    execute::execute_claim(app, args, output_mode).await
+                })
+                .await
+            }
+            Some(Commands::Renew(args)) => {
+                run_with_hooks(at, async |app| {
+                    execute::execute_renew(app, args, output_mode).await
+                })
+                .await
+            }
+            Some(Commands::Release(args)) => {
+                run_with_hooks(at, async |app| {
+                    execute::execute_release(app, args, output_mode).await
+                })
+                .await
+            }
             Some(Commands::Dep(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_dep(&mut app, args, output_mode).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_dep(app, args, output_mode).await
+                })
+                .await
             }
             Some(Commands::Label(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_label(&mut app, args, output_mode).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_label(app, args, output_mode).await
+                })
+                .await
             }
             Some(Commands::Resource(args)) => {
-                let mut app = load_app_from_cwd().await?;
-                execute::execute_resource(&mut app, args, output_mode).await
+                run_with_hooks(at, async |app| {
+                    execute::execute_resource(app, args, output_mode).await
+                })
+                .await
             }
             Some(Commands::Stale(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_stale(&app, args, output_mode).await
             }
+            Some(Commands::Due(args)) => {
+                let app = load_app_from_cwd(at).await?;
+                execute::execute_due(&app, args, output_mode).await
+            }
+            Some(Commands::Milestone(args)) => {
+                let app = load_app_from_cwd(at).await?;
+                execute::execute_milestone(&app, args, output_mode).await
+            }
             Some(Commands::Blocked(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_blocked(&app, args, output_mode).await
             }
             Some(Commands::Stats(args)) => {
-                let app = load_app_from_cwd().await?;
+                let app = load_app_from_cwd(at).await?;
                 execute::execute_stats(&app, args, output_mode).await
             }
+            #[cfg(unix)]
+            Some(Commands::Daemon(args)) => execute::execute_daemon(args, output_mode).await,
+            Some(Commands::Serve(args)) => execute::execute_serve(args, output_mode).await,
+            Some(Commands::Hooks(args)) => {
+                let app = load_app_from_cwd(at).await?;
+                execute::execute_hooks(&app, args, output_mode).await
+            }
+            Some(Commands::ScanCommits(args)) => {
+                run_with_hooks(at, async |app| {
+                    execute::execute_scan_commits(app, args, output_mode).await
+                })
+                .await
+            }
+            Some(Commands::GitHook(args)) => match &args.action {
+                GitHookAction::PostCommit { .. } => {
+                    run_with_hooks(at, async |app| {
+                        execute::execute_git_hook(app, args, output_mode).await
+                    })
+                    .await
+                }
+                _ => {
+                    let mut app = load_app_from_cwd(at).await?;
+                    execute::execute_git_hook(&mut app, args, output_mode).await
+                }
+            },
+            Some(Commands::Diff(args)) => {
+                let app = load_app_from_cwd(at).await?;
+                execute::execute_diff(&app, args, output_mode).await
+            }
+            Some(Commands::Doctor(args)) => {
+                let app = App::from_directory_direct(&std::env::current_dir()?).await?;
+                execute::execute_doctor(&app, args, output_mode, self.yes).await
+            }
+            Some(Commands::Rekey(args)) => {
+                let mut app = App::from_directory_direct(&std::env::current_dir()?).await?;
+                execute::execute_rekey(&mut app, args, output_mode, self.yes).await
+            }
             None => {
                 println!("Rivets issue tracking system");
                 println!("Use --help for more information");
@@ -366,8 +638,10 @@ mod tests {
         match cli.command {
             Some(Commands::Create(args)) => {
                 assert!(args.title.is_none());
-                assert_eq!(args.priority, 2); // default
-                assert_eq!(args.issue_kind, IssueKind::Task); // default
+                // Unset so a template can supply them; defaults apply at execution
+                assert!(args.priority.is_none());
+                assert!(args.issue_kind.is_none());
+                assert!(args.template.is_none());
             }
             _ => panic!("Expected Create command"),
         }
@@ -397,8 +671,8 @@ mod tests {
             Some(Commands::Create(args)) => {
                 assert_eq!(args.title, Some("Fix bug".to_string()));
                 assert_eq!(args.description, Some("Detailed desc".to_string()));
-                assert_eq!(args.priority, 1);
-                assert_eq!(args.issue_kind, IssueKind::Bug);
+                assert_eq!(args.priority, Some(1));
+                assert_eq!(args.issue_kind, Some(IssueKind::Bug));
                 assert_eq!(args.assignee, Some("alice".to_string()));
                 assert_eq!(args.labels, vec!["urgent", "backend"]);
             }
@@ -406,6 +680,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_template_conflicts_with_no_template() {
+        let cli = Cli::try_parse_from(["rivets", "create", "--template", "incident"]).unwrap();
+        match cli.command {
+            Some(Commands::Create(args)) => {
+                assert_eq!(args.template, Some("incident".to_string()));
+            }
+            _ => panic!("Expected Create command"),
+        }
+
+        let result = Cli::try_parse_from([
+            "rivets",
+            "create",
+            "--template",
+            "incident",
+            "--no-template",
+        ]);
+        assert!(result.is_err());
+        assert!(Cli::try_parse_from(["rivets", "create", "--template", "../x"]).is_err());
+    }
+
     #[test]
     fn test_parse_create_invalid_priority() {
         let result = Cli::try_parse_from(["rivets", "create", "--priority", "5"]);
@@ -798,4 +1093,55 @@ mod tests {
             Err(e) => assert!(e.to_string().contains("cannot be used with")),
         }
     }
+
+    // ========== Hook Tests ==========
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hooks_fire_only_for_commands_that_succeed() {
+        use crate::commands::init::{CONFIG_FILE_NAME, RIVETS_DIR_NAME, RivetsConfig, init};
+        use crate::domain::NewIssue;
+
+        let workspace = tempfile::TempDir::new().unwrap();
+        init(workspace.path(), Some("test")).await.unwrap();
+        let config_path = workspace
+            .path()
+            .join(RIVETS_DIR_NAME)
+            .join(CONFIG_FILE_NAME);
+        let mut config = RivetsConfig::load(&config_path).await.unwrap();
+        config.hooks = serde_yaml::from_str(
+            "- name: log\n  command: sh\n  args: [-c, 'cat >> events.log && echo >> events.log']\n",
+        )
+        .unwrap();
+        config.save(&config_path).await.unwrap();
+        let mut app = App::from_directory_direct(workspace.path()).await.unwrap();
+
+        let create = async |app: &mut App, title: &str| {
+            let new_issue = NewIssue {
+                title: title.to_string(),
+                ..NewIssue::default()
+            };
+            app.storage_mut().create(new_issue).await?;
+            app.save().await?;
+            anyhow::Ok(())
+        };
+        let result = with_hooks(&mut app, async |app| {
+            create(app, "Saved").await?;
+            anyhow::bail!("later step failed")
+        })
+        .await;
+        assert!(result.is_err());
+        assert!(!workspace.path().join("events.log").exists());
+
+        with_hooks(&mut app, async |app| create(app, "Fired").await)
+            .await
+            .unwrap();
+        let log = std::fs::read_to_string(workspace.path().join("events.log")).unwrap();
+        let events: Vec<serde_json::Value> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["event"]["issue"]["title"], "Fired");
+    }
 }
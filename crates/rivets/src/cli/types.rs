@@ -111,7 +111,7 @@ impl std::fmt::Display for SortOrderArg {
 /// Sort policy for ready command
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SortPolicyArg {
-    /// Recent issues (48h) by priority, older by age
+    /// Overdue or soon-due issues first, then recent issues (48h) by priority, older by age
     #[default]
     Hybrid,
     /// Strict priority ordering (P0 -> P1 -> P2 -> P3 -> P4)
@@ -17,6 +17,65 @@ pub fn validate_prefix(s: &str) -> Result<String, String> {
     Ok(trimmed.to_string())
 }
 
+/// Validate an issue template name.
+///
+/// Delegates to the domain validator in `commands::templates`.
+pub fn validate_template_name(s: &str) -> Result<String, String> {
+    use crate::commands::templates;
+
+    let trimmed = s.trim();
+    templates::validate_template_name(trimmed).map_err(|e| e.to_string())?;
+    Ok(trimmed.to_string())
+}
+
+/// Parse a due date for `--due`.
+///
+/// Delegates to [`crate::domain::parse_due_date`].
+pub fn validate_due_date(s: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    crate::domain::parse_due_date(s).map_err(|e| e.to_string())
+}
+
+/// Parse a due window for `due --within`.
+///
+/// Delegates to [`crate::domain::parse_due_window`].
+pub fn validate_due_window(s: &str) -> Result<chrono::Duration, String> {
+    crate::domain::parse_due_window(s).map_err(|e| e.to_string())
+}
+
+/// Parse a lease duration for `--lease`.
+///
+/// Delegates to [`crate::domain::parse_lease_duration`].
+pub fn validate_lease_duration(s: &str) -> Result<chrono::Duration, String> {
+    crate::domain::parse_lease_duration(s).map_err(|e| e.to_string())
+}
+
+/// Parse an idle timeout for `daemon start --idle-timeout`.
+///
+/// Accepts the same formats as lease durations.
+pub fn validate_idle_timeout(s: &str) -> Result<chrono::Duration, String> {
+    crate::domain::parse_lease_duration(s).map_err(|_| {
+        format!(
+            "Invalid idle timeout '{s}'. Expected {}",
+            crate::domain::LEASE_DURATION_FORMATS
+        )
+    })
+}
+
+/// Validate a milestone name for `--milestone` and `milestone` subcommands.
+///
+/// Delegates to [`crate::domain::validate_milestone_name`].
+pub fn validate_milestone_name(s: &str) -> Result<String, String> {
+    crate::domain::validate_milestone_name(s)
+        .map(|()| s.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Parse a calendar date (`YYYY-MM-DD`) for milestone start and end dates.
+pub fn validate_date(s: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{s}'. Expected YYYY-MM-DD"))
+}
+
 /// Validate issue ID format.
 ///
 /// Expected format: `prefix-suffix` where:
@@ -125,7 +184,7 @@ pub fn validate_title(s: &str) -> Result<String, String> {
 ///
 /// Allows newlines but rejects control characters that could cause display issues.
 /// Unlike titles, multi-line text is acceptable for descriptions and notes.
-fn validate_text_field(s: &str, field_name: &str) -> Result<String, String> {
+pub(crate) fn validate_text_field(s: &str, field_name: &str) -> Result<String, String> {
     // Check for control characters (0x00-0x1F except tab and newlines, and 0x7F-0x9F)
     if let Some(pos) = s.chars().position(|c| {
         let code = c as u32;
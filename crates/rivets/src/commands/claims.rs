@@ -0,0 +1,116 @@
+//! Work claims shared by `rivets claim` and the MCP `claim` tool.
+//!
+//! Claiming a specific issue goes straight to [`IssueStorage::claim`]; this
+//! module covers claiming "whatever is next", which both interfaces offer
+//! when no issue ID is given.
+
+use crate::domain::{Issue, IssueFilter, SortPolicy};
+use crate::error::{Error, Result};
+use crate::storage::IssueStorage;
+use chrono::Duration;
+
+/// Claim the first ready issue `claimant` can take.
+///
+/// Issues are tried in hybrid priority order. Issues assigned to someone
+/// else without a lease are skipped rather than reported, since `ready`
+/// still lists them. Returns `None` when nothing is claimable.
+///
+/// # Errors
+///
+/// Returns any storage error other than a rejected claim.
+pub async fn claim_next(
+    storage: &mut dyn IssueStorage,
+    claimant: &str,
+    lease: Duration,
+) -> Result<Option<Issue>> {
+    Ok(claim_next_with_previous(storage, claimant, lease)
+        .await?
+        .map(|(_, issue)| issue))
+}
+
+/// Like [`claim_next`], also returning the claimed issue as it was before
+/// the claim.
+///
+/// # Errors
+///
+/// Returns any storage error other than a rejected claim.
+pub async fn claim_next_with_previous(
+    storage: &mut dyn IssueStorage,
+    claimant: &str,
+    lease: Duration,
+) -> Result<Option<(Issue, Issue)>> {
+    let filter = IssueFilter {
+        claimant: Some(claimant.to_string()),
+        ..Default::default()
+    };
+    let candidates = storage
+        .ready_to_work(Some(&filter), Some(SortPolicy::Hybrid))
+        .await?;
+
+    for candidate in candidates {
+        match storage.claim(&candidate.id, claimant, lease).await {
+            Ok(issue) => return Ok(Some((candidate, issue))),
+            Err(Error::Storage(error)) => {
+                error.try_into_lease_error().map_err(Error::Storage)?;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{IssueKind, NewIssue};
+    use crate::storage::in_memory::new_in_memory_storage;
+
+    fn new_issue(title: &str, priority: u8, assignee: Option<&str>) -> NewIssue {
+        NewIssue {
+            title: title.to_string(),
+            description: String::new(),
+            priority,
+            issue_kind: IssueKind::Task,
+            assignee: assignee.map(str::to_string),
+            labels: vec![],
+            design: None,
+            acceptance_criteria: None,
+            initial_note: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            dependencies: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn claims_next_unassigned_issue_in_priority_order() {
+        let mut storage = new_in_memory_storage("test".to_string());
+        storage
+            .create(new_issue("Taken", 0, Some("alice")))
+            .await
+            .unwrap();
+        let urgent = storage.create(new_issue("Urgent", 1, None)).await.unwrap();
+        let later = storage.create(new_issue("Later", 3, None)).await.unwrap();
+        let lease = Duration::hours(1);
+
+        let first = claim_next(storage.as_mut(), "agent-a", lease)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id, urgent.id);
+
+        let second = claim_next(storage.as_mut(), "agent-b", lease)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.id, later.id);
+
+        assert!(
+            claim_next(storage.as_mut(), "agent-c", lease)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,556 @@
+//! Linking commits to the issues their messages name.
+//!
+//! A commit names an issue with a `Fixes`, `Closes` or `Refs` line, with or
+//! without a colon, listing one or more issue IDs:
+//!
+//! ```text
+//! Handle empty config files
+//!
+//! Fixes: proj-a3f8
+//! Refs proj-b2c1, proj-b2c1.2
+//! ```
+//!
+//! Only words starting with the workspace's prefix count as issue IDs, so
+//! `Fixes #12` for another tracker is left alone; those that do are checked
+//! with [`validate_id`] before anything is looked up. Linking attaches the
+//! commit as an Implementation resource, pointing at its web link when it
+//! has one and at `git:<sha>` otherwise, and adds a Note with its subject,
+//! once per commit and issue however often the same commits are scanned.
+
+use crate::domain::{
+    CommitSha, Issue, IssueId, IssueStatus, IssueUpdate, NewResource, NoteContent, ResourceLabel,
+    ResourceRole, ResourceTarget, WebUrl,
+};
+use crate::error::{Error, Result, StorageError};
+use crate::git::Commit;
+use crate::id_generation::validate_id;
+use crate::storage::IssueStorage;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// First line of every hook script `rivets git-hook install` writes, so a
+/// later install can tell its own scripts from someone else's.
+const HOOK_MARKER: &str = "# Installed by rivets git-hook install";
+
+/// How a commit message refers to an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trailer {
+    /// `Fixes <id>`: the commit resolves the issue.
+    Fixes,
+    /// `Closes <id>`: the commit resolves the issue.
+    Closes,
+    /// `Refs <id>`: the commit relates to the issue without resolving it.
+    Refs,
+}
+
+impl Trailer {
+    fn parse(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "fixes" => Some(Self::Fixes),
+            "closes" => Some(Self::Closes),
+            "refs" => Some(Self::Refs),
+            _ => None,
+        }
+    }
+
+    /// Whether the commit resolves the issue, so `--close` closes it.
+    pub fn resolves(self) -> bool {
+        matches!(self, Self::Fixes | Self::Closes)
+    }
+}
+
+/// One issue ID named by a commit message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    /// How the message names the issue.
+    pub trailer: Trailer,
+    /// The ID as written.
+    pub issue_id: String,
+}
+
+/// The issue IDs `message` names, in order, each once.
+///
+/// An ID named by both a resolving and a `Refs` line is reported as
+/// resolved.
+pub fn references(message: &str, prefix: &str) -> Vec<Reference> {
+    let id_start = format!("{prefix}-");
+    let mut references: Vec<Reference> = Vec::new();
+    for line in message.lines() {
+        let line = line.trim_start();
+        let Some((word, rest)) = line.split_once(|c: char| c == ':' || c.is_whitespace()) else {
+            continue;
+        };
+        let Some(trailer) = Trailer::parse(word) else {
+            continue;
+        };
+        let ids = rest
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|word| word.trim_end_matches(['.', ';', ')']))
+            .filter(|word| word.starts_with(&id_start));
+        for issue_id in ids {
+            match references
+                .iter_mut()
+                .find(|reference| reference.issue_id == issue_id)
+            {
+                Some(reference) if trailer.resolves() => reference.trailer = trailer,
+                Some(_) => {}
+                None => references.push(Reference {
+                    trailer,
+                    issue_id: issue_id.to_string(),
+                }),
+            }
+        }
+    }
+    references
+}
+
+/// What went wrong with a reference, if anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Problem {
+    /// The ID is not a valid issue ID for this workspace.
+    InvalidId,
+    /// No issue has this ID.
+    UnknownIssue,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidId => "not a valid issue ID",
+            Self::UnknownIssue => "no such issue",
+        })
+    }
+}
+
+/// Check every reference in `message` against the workspace, returning the
+/// ones that are malformed or name a missing issue.
+///
+/// # Errors
+///
+/// Returns any storage error.
+pub async fn check_message(
+    storage: &dyn IssueStorage,
+    prefix: &str,
+    message: &str,
+) -> Result<Vec<(Reference, Problem)>> {
+    let mut problems = Vec::new();
+    for reference in references(message, prefix) {
+        if let Err(problem) = resolve(storage, prefix, &reference).await? {
+            problems.push((reference, problem));
+        }
+    }
+    Ok(problems)
+}
+
+async fn resolve(
+    storage: &dyn IssueStorage,
+    prefix: &str,
+    reference: &Reference,
+) -> Result<std::result::Result<Issue, Problem>> {
+    if !validate_id(&reference.issue_id, prefix) {
+        return Ok(Err(Problem::InvalidId));
+    }
+    Ok(storage
+        .get(&IssueId::new(&reference.issue_id))
+        .await?
+        .ok_or(Problem::UnknownIssue))
+}
+
+/// How [`link_commits`] treats the issues it finds.
+#[derive(Debug, Clone, Default)]
+pub struct LinkOptions {
+    /// Close issues named by `Fixes` and `Closes`.
+    pub close: bool,
+    /// Link template with `{sha}` standing for the commit; without one,
+    /// commits are attached by SHA.
+    pub commit_url: Option<String>,
+}
+
+/// What linking did with one reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkOutcome {
+    /// The commit was attached to the issue.
+    Linked,
+    /// The issue was closed, and the commit attached unless an earlier scan
+    /// already had.
+    Closed,
+    /// The commit was attached to the issue by an earlier scan.
+    AlreadyLinked,
+    /// The ID is not a valid issue ID for this workspace.
+    InvalidId,
+    /// No issue has this ID.
+    UnknownIssue,
+}
+
+impl From<Problem> for LinkOutcome {
+    fn from(problem: Problem) -> Self {
+        match problem {
+            Problem::InvalidId => Self::InvalidId,
+            Problem::UnknownIssue => Self::UnknownIssue,
+        }
+    }
+}
+
+/// One reference found by [`link_commits`] and what became of it.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitLink {
+    /// Full object name of the commit.
+    pub commit: String,
+    /// How the commit named the issue.
+    pub trailer: Trailer,
+    /// The issue ID as written.
+    pub issue_id: String,
+    /// What was done.
+    pub outcome: LinkOutcome,
+}
+
+/// Attach each of `commits` to the issues its message names.
+///
+/// Commits should be oldest first, so a later `Refs` note follows the
+/// `Fixes` that closed an issue. Invalid and unknown IDs are reported in the
+/// result rather than failing the scan. The caller saves the storage.
+///
+/// # Errors
+///
+/// Returns any storage error, or a validation error when `commit_url` does
+/// not produce a valid web URL.
+pub async fn link_commits(
+    storage: &mut dyn IssueStorage,
+    prefix: &str,
+    commits: &[Commit],
+    options: &LinkOptions,
+) -> Result<Vec<CommitLink>> {
+    let mut links = Vec::new();
+    for commit in commits {
+        for reference in references(&commit.message, prefix) {
+            let outcome = match resolve(storage, prefix, &reference).await? {
+                Ok(issue) => link(storage, &issue, commit, reference.trailer, options).await?,
+                Err(problem) => problem.into(),
+            };
+            links.push(CommitLink {
+                commit: commit.sha.clone(),
+                trailer: reference.trailer,
+                issue_id: reference.issue_id,
+                outcome,
+            });
+        }
+    }
+    Ok(links)
+}
+
+async fn link(
+    storage: &mut dyn IssueStorage,
+    issue: &Issue,
+    commit: &Commit,
+    trailer: Trailer,
+    options: &LinkOptions,
+) -> Result<LinkOutcome> {
+    let label = format!("Commit {}", commit.short_sha());
+    let target = match &options.commit_url {
+        Some(template) => WebUrl::new(template.replace("{sha}", &commit.sha))
+            .map(ResourceTarget::web)
+            .map_err(|error| Error::Validation {
+                field: "commit_url",
+                reason: error.to_string(),
+            })?,
+        None => {
+            ResourceTarget::commit(CommitSha::new(&commit.sha).map_err(StorageError::Resource)?)
+        }
+    };
+
+    let already_linked = issue
+        .notes()
+        .iter()
+        .any(|note| note.content().starts_with(&label))
+        || issue
+            .resources()
+            .iter()
+            .any(|resource| *resource.target() == target);
+    let close = options.close && trailer.resolves() && issue.status != IssueStatus::Closed;
+    if already_linked {
+        // A commit linked without --close, say by the post-commit hook, can
+        // still close its issue on a later scan.
+        if !close {
+            return Ok(LinkOutcome::AlreadyLinked);
+        }
+        let update = IssueUpdate {
+            status: Some(IssueStatus::Closed),
+            ..Default::default()
+        };
+        storage.update(&issue.id, update).await?;
+        return Ok(LinkOutcome::Closed);
+    }
+
+    let resource = NewResource {
+        target,
+        role: ResourceRole::Implementation,
+        label: Some(ResourceLabel::new(&label).map_err(StorageError::Resource)?),
+    };
+    storage.add_resource(&issue.id, resource).await?;
+
+    let subject: String = commit
+        .subject
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let note = match subject.trim() {
+        "" => label,
+        subject => format!("{label}: {subject}"),
+    };
+    let update = IssueUpdate {
+        status: close.then_some(IssueStatus::Closed),
+        note: Some(NoteContent::new(note).map_err(|error| Error::Validation {
+            field: "note",
+            reason: error.to_string(),
+        })?),
+        ..Default::default()
+    };
+    storage.update(&issue.id, update).await?;
+
+    Ok(if close {
+        LinkOutcome::Closed
+    } else {
+        LinkOutcome::Linked
+    })
+}
+
+/// Install the `commit-msg` and `post-commit` hooks in the repository
+/// containing `workspace_root`, returning their paths.
+///
+/// `commit-msg` rejects commits naming malformed or unknown issue IDs, and
+/// `post-commit` links each new commit to the issues it names, closing
+/// resolved ones when `close` is set. Hooks written by an earlier install
+/// are replaced; other existing hooks are only replaced with `force`.
+///
+/// # Errors
+///
+/// Returns a git error outside a repository, a validation error when a
+/// hook that rivets did not install is in the way, or an I/O error.
+pub async fn install_hooks(
+    workspace_root: &Path,
+    close: bool,
+    force: bool,
+) -> Result<Vec<PathBuf>> {
+    let dir = crate::git::hooks_dir(workspace_root).await?;
+    let post_commit = if close {
+        "rivets git-hook post-commit --close"
+    } else {
+        "rivets git-hook post-commit"
+    };
+    let hooks = [
+        ("commit-msg", "rivets git-hook commit-msg \"$1\""),
+        ("post-commit", post_commit),
+    ];
+
+    let mut paths = Vec::new();
+    for (name, _) in &hooks {
+        let path = dir.join(name);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(existing) if !force && !existing.contains(HOOK_MARKER) => {
+                return Err(Error::Validation {
+                    field: "hook",
+                    reason: format!(
+                        "{} already exists and was not installed by rivets; \
+                         pass --force to replace it",
+                        path.display()
+                    ),
+                });
+            }
+            Ok(_) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+        paths.push(path);
+    }
+
+    tokio::fs::create_dir_all(&dir).await?;
+    for (path, (_, command)) in paths.iter().zip(hooks) {
+        // Commits must keep working for anyone without rivets installed.
+        let script = format!(
+            "#!/bin/sh\n{HOOK_MARKER}\ncommand -v rivets >/dev/null 2>&1 || exit 0\nexec {command}\n"
+        );
+        tokio::fs::write(path, script).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).await?;
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{IssueKind, NewIssue};
+    use crate::storage::in_memory::new_in_memory_storage;
+
+    fn commit(sha: &str, message: &str) -> Commit {
+        Commit {
+            sha: sha.to_string(),
+            subject: message.lines().next().unwrap_or_default().to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    async fn issue(storage: &mut dyn IssueStorage, title: &str) -> Issue {
+        storage
+            .create(NewIssue {
+                title: title.to_string(),
+                issue_kind: IssueKind::Task,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_trailers_with_and_without_colons() {
+        let message = "Handle empty configs\n\n\
+                       Mentions proj-zzzz in prose.\n\
+                       Refs proj-b2c1, proj-b2c1.2\n\
+                       fixes: proj-a3f8.\n\
+                       Closes #12 proj-bad!\n\
+                       Fixes proj-b2c1";
+        let found: Vec<_> = references(message, "proj")
+            .into_iter()
+            .map(|reference| (reference.trailer, reference.issue_id))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Trailer::Fixes, "proj-b2c1".to_string()),
+                (Trailer::Refs, "proj-b2c1.2".to_string()),
+                (Trailer::Fixes, "proj-a3f8".to_string()),
+                (Trailer::Closes, "proj-bad!".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn links_commits_once_and_closes_resolved_issues() {
+        let mut storage = new_in_memory_storage("proj".to_string());
+        let fixed = issue(storage.as_mut(), "Fixed").await;
+        let referenced = issue(storage.as_mut(), "Referenced").await;
+        let commits = [
+            commit(
+                "a1b2c3d4e5f6",
+                &format!(
+                    "Handle empty configs\n\nFixes: {}\nRefs {}\nRefs proj-zzzz\nRefs proj-x",
+                    fixed.id, referenced.id
+                ),
+            ),
+            commit("0f9e8d7c6b5a", &format!("Tidy up\n\nRefs {}", fixed.id)),
+        ];
+        let mut options = LinkOptions {
+            close: false,
+            commit_url: Some("https://example.com/proj/commit/{sha}".to_string()),
+        };
+
+        let links = link_commits(storage.as_mut(), "proj", &commits, &options)
+            .await
+            .unwrap();
+        let outcomes: Vec<_> = links.iter().map(|link| link.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                LinkOutcome::Linked,
+                LinkOutcome::Linked,
+                LinkOutcome::UnknownIssue,
+                LinkOutcome::InvalidId,
+                LinkOutcome::Linked,
+            ]
+        );
+
+        assert_eq!(
+            storage.get(&fixed.id).await.unwrap().unwrap().status,
+            IssueStatus::Open
+        );
+
+        // Rescanning with --close closes what was fixed without linking twice.
+        options.close = true;
+        let again = link_commits(storage.as_mut(), "proj", &commits, &options)
+            .await
+            .unwrap();
+        let outcomes: Vec<_> = again.iter().map(|link| link.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                LinkOutcome::Closed,
+                LinkOutcome::AlreadyLinked,
+                LinkOutcome::UnknownIssue,
+                LinkOutcome::InvalidId,
+                LinkOutcome::AlreadyLinked,
+            ]
+        );
+
+        let fixed = storage.get(&fixed.id).await.unwrap().unwrap();
+        assert_eq!(fixed.status, IssueStatus::Closed);
+        assert_eq!(fixed.resources().len(), 2);
+        let notes: Vec<_> = fixed.notes().iter().map(|note| note.content()).collect();
+        assert_eq!(
+            notes,
+            [
+                "Commit a1b2c3d: Handle empty configs",
+                "Commit 0f9e8d7: Tidy up"
+            ]
+        );
+        let resource = &fixed.resources()[0];
+        assert_eq!(resource.role(), ResourceRole::Implementation);
+        assert_eq!(
+            resource.target().to_string(),
+            "https://example.com/proj/commit/a1b2c3d4e5f6"
+        );
+
+        let referenced = storage.get(&referenced.id).await.unwrap().unwrap();
+        assert_eq!(referenced.status, IssueStatus::Open);
+    }
+
+    #[tokio::test]
+    async fn links_commits_by_sha_without_a_web_link() {
+        let mut storage = new_in_memory_storage("proj".to_string());
+        let fixed = issue(storage.as_mut(), "Fixed").await;
+        let commits = [commit(
+            "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678",
+            &format!("Local fix\n\nFixes {}", fixed.id),
+        )];
+
+        let links = link_commits(storage.as_mut(), "proj", &commits, &LinkOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(links[0].outcome, LinkOutcome::Linked);
+
+        let fixed = storage.get(&fixed.id).await.unwrap().unwrap();
+        let resource = &fixed.resources()[0];
+        assert_eq!(resource.role(), ResourceRole::Implementation);
+        assert_eq!(
+            resource.target().to_string(),
+            "git:a1b2c3d4e5f60718293a4b5c6d7e8f9012345678"
+        );
+    }
+
+    #[tokio::test]
+    async fn checks_messages_for_bad_references() {
+        let mut storage = new_in_memory_storage("proj".to_string());
+        let known = issue(storage.as_mut(), "Known").await;
+        let message = format!("Subject\n\nFixes {}\nRefs proj-zzzz\nRefs proj-!", known.id);
+
+        let problems = check_message(storage.as_ref(), "proj", &message)
+            .await
+            .unwrap();
+        let problems: Vec<_> = problems
+            .into_iter()
+            .map(|(reference, problem)| (reference.issue_id, problem))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                ("proj-zzzz".to_string(), Problem::UnknownIssue),
+                ("proj-!".to_string(), Problem::InvalidId),
+            ]
+        );
+    }
+}
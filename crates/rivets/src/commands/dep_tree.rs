@@ -0,0 +1,102 @@
+//! Dependency tree construction shared by `rivets dep tree` and the MCP
+//! `dep_tree` tool.
+
+use crate::domain::IssueId;
+use crate::error::{Error, Result};
+use crate::output::DepTreeNode;
+use crate::storage::IssueStorage;
+use std::collections::HashSet;
+
+/// Build the dependency tree rooted at `id`.
+///
+/// The root carries the issue's title, status and priority; each child is a
+/// dependency with its type and status. `max_depth` limits how many levels
+/// below the root are expanded (`None` for unlimited), and an issue reached
+/// twice is only expanded the first time, so cycles terminate.
+///
+/// # Errors
+///
+/// Returns `Error::IssueNotFound` if `id` does not exist, or any storage
+/// error from the traversal.
+pub async fn build_dep_tree(
+    storage: &dyn IssueStorage,
+    id: &IssueId,
+    max_depth: Option<usize>,
+) -> Result<DepTreeNode> {
+    let issue = storage
+        .get(id)
+        .await?
+        .ok_or_else(|| Error::IssueNotFound(id.clone()))?;
+
+    let mut visited = HashSet::new();
+    visited.insert(id.clone());
+    let children = build_children(storage, id, max_depth, 0, &mut visited).await?;
+
+    Ok(DepTreeNode {
+        id: id.to_string(),
+        dep_type: None,
+        status: Some(issue.status),
+        title: Some(issue.title),
+        priority: Some(issue.priority),
+        children,
+    })
+}
+
+/// Recursively build dependency tree children via DFS.
+///
+/// Uses a visited set to prevent cycles, and respects the max depth limit.
+async fn build_children(
+    storage: &dyn IssueStorage,
+    parent_id: &IssueId,
+    max_depth: Option<usize>,
+    current_depth: usize,
+    visited: &mut HashSet<IssueId>,
+) -> Result<Vec<DepTreeNode>> {
+    // Check depth limit
+    if let Some(max) = max_depth
+        && current_depth >= max
+    {
+        return Ok(vec![]);
+    }
+
+    let deps = storage.get_dependencies(parent_id).await?;
+    let mut children = Vec::new();
+
+    for dep in deps {
+        let child_id = dep.depends_on_id.clone();
+
+        // Skip already-visited nodes to prevent cycles
+        if !visited.insert(child_id.clone()) {
+            continue;
+        }
+
+        // Fetch status for the child node
+        let status = storage
+            .get(&child_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|i| i.status);
+
+        // Recurse into children
+        let grandchildren = Box::pin(build_children(
+            storage,
+            &child_id,
+            max_depth,
+            current_depth + 1,
+            visited,
+        ))
+        .await?;
+
+        children.push(DepTreeNode {
+            id: child_id.to_string(),
+            dep_type: Some(dep.dep_type),
+            status,
+            title: None,
+            priority: None,
+            children: grandchildren,
+        });
+    }
+
+    Ok(children)
+}
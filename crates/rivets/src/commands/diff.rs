@@ -0,0 +1,335 @@
+//! Semantic diffs of the tracker between git revisions.
+//!
+//! The data file is read as committed at each revision through the read-only
+//! [`StorageBackend::GitRevision`] backend and compared issue by issue, so a
+//! pull request that touches `.rivets/issues.jsonl` can be reviewed as
+//! "created, closed, re-prioritised" instead of as raw JSON lines.
+
+use crate::domain::{AssociatedResource, Dependency, Issue, IssueId, Note};
+use crate::error::Result;
+use crate::events::{FieldChange, IssueEvent, ResourceChange, events_between};
+use crate::storage::{StorageBackend, create_storage};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The issue field holding its Notes, reported as added Notes rather than as
+/// a changed field.
+const NOTES_FIELD: &str = "notes";
+
+/// What happened to an issue overall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueChange {
+    /// The issue exists only in the newer revision.
+    Created,
+    /// The issue exists only in the older revision.
+    Deleted,
+    /// The issue was closed.
+    Closed,
+    /// The issue was reopened.
+    Reopened,
+    /// The issue changed without opening or closing.
+    Updated,
+}
+
+impl std::fmt::Display for IssueChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Created => "created",
+            Self::Deleted => "deleted",
+            Self::Closed => "closed",
+            Self::Reopened => "reopened",
+            Self::Updated => "updated",
+        })
+    }
+}
+
+/// How one issue differs between two revisions.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueDiff {
+    /// The issue.
+    pub issue_id: IssueId,
+    /// Its title in the newer revision, or the older one if it was deleted.
+    pub title: String,
+    /// What happened to it overall.
+    pub change: IssueChange,
+    /// Fields that changed, other than status, Notes, dependencies and
+    /// resources.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldChange>,
+    /// Notes appended.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes_added: Vec<Note>,
+    /// Dependencies gained.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies_added: Vec<Dependency>,
+    /// Dependencies lost.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies_removed: Vec<Dependency>,
+    /// Associated Resources attached.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resources_added: Vec<AssociatedResource>,
+    /// Associated Resources whose target, role or label changed, as they are
+    /// now.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resources_updated: Vec<AssociatedResource>,
+    /// Associated Resources removed, as they were.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resources_removed: Vec<AssociatedResource>,
+}
+
+impl IssueDiff {
+    fn new(issue: &Issue, change: IssueChange) -> Self {
+        Self {
+            issue_id: issue.id.clone(),
+            title: issue.title.clone(),
+            change,
+            fields: Vec::new(),
+            notes_added: Vec::new(),
+            dependencies_added: Vec::new(),
+            dependencies_removed: Vec::new(),
+            resources_added: Vec::new(),
+            resources_updated: Vec::new(),
+            resources_removed: Vec::new(),
+        }
+    }
+}
+
+/// The tracker changes between two revisions.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackerDiff {
+    /// The older revision, as given.
+    pub from: String,
+    /// The newer revision, as given, or `working tree`.
+    pub to: String,
+    /// The issues that differ, ordered by ID.
+    pub issues: Vec<IssueDiff>,
+}
+
+impl TrackerDiff {
+    /// Compare two snapshots of the workspace's issues.
+    pub fn new(from: String, to: String, before: &[Issue], after: &[Issue]) -> Self {
+        Self {
+            from,
+            to,
+            issues: diff_issues(before, after),
+        }
+    }
+
+    /// How many issues had `change` happen to them.
+    pub fn count(&self, change: IssueChange) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.change == change)
+            .count()
+    }
+}
+
+/// Every issue in the workspace at `rev`, as committed there.
+///
+/// `data_file` is the workspace's data file relative to `root`. A revision
+/// without it, such as one from before `rivets init`, has no issues. Lines
+/// that fail to load are skipped and logged, as when opening the workspace.
+///
+/// # Errors
+///
+/// Returns [`Error::Git`](crate::error::Error::Git) when `rev` does not name
+/// a commit.
+pub async fn issues_at(
+    root: &Path,
+    data_file: &Path,
+    rev: &str,
+    prefix: &str,
+) -> Result<Vec<Issue>> {
+    let backend = StorageBackend::GitRevision {
+        root: root.to_path_buf(),
+        data_file: data_file.to_path_buf(),
+        rev: rev.to_string(),
+    };
+    create_storage(backend, prefix.to_string())
+        .await?
+        .export_all()
+        .await
+}
+
+/// How each issue differs between two snapshots, ordered by ID.
+pub fn diff_issues(before: &[Issue], after: &[Issue]) -> Vec<IssueDiff> {
+    let old: BTreeMap<&IssueId, &Issue> = before.iter().map(|i| (&i.id, i)).collect();
+    let new: BTreeMap<&IssueId, &Issue> = after.iter().map(|i| (&i.id, i)).collect();
+
+    let mut diffs: Vec<IssueDiff> = Vec::new();
+    for (_, event) in events_between(before, after) {
+        let id = event.issue_id();
+        if diffs.last().is_none_or(|diff| &diff.issue_id != id) {
+            let issue = new.get(id).or_else(|| old.get(id)).copied();
+            let Some(issue) = issue else { continue };
+            diffs.push(IssueDiff::new(issue, IssueChange::Updated));
+        }
+        let Some(diff) = diffs.last_mut() else {
+            continue;
+        };
+
+        match event {
+            IssueEvent::Created { .. } => diff.change = IssueChange::Created,
+            IssueEvent::Deleted { .. } => diff.change = IssueChange::Deleted,
+            IssueEvent::Closed { .. } => diff.change = IssueChange::Closed,
+            IssueEvent::Reopened { .. } => diff.change = IssueChange::Reopened,
+            IssueEvent::Updated { issue_id, changes } => {
+                let (notes, fields): (Vec<_>, Vec<_>) = changes
+                    .into_iter()
+                    .partition(|change| change.field == NOTES_FIELD);
+                diff.fields = fields;
+                if !notes.is_empty()
+                    && let (Some(old), Some(new)) = (old.get(&issue_id), new.get(&issue_id))
+                {
+                    diff.notes_added = new
+                        .notes()
+                        .iter()
+                        .filter(|note| !old.notes().contains(note))
+                        .cloned()
+                        .collect();
+                }
+            }
+            IssueEvent::DependencyAdded { dependency, .. } => {
+                diff.dependencies_added.push(dependency);
+            }
+            IssueEvent::DependencyRemoved { dependency, .. } => {
+                diff.dependencies_removed.push(dependency);
+            }
+            IssueEvent::ResourceChanged {
+                issue_id,
+                resource_id,
+                change,
+                resource,
+            } => match (change, resource) {
+                (ResourceChange::Added, Some(resource)) => diff.resources_added.push(resource),
+                (ResourceChange::Updated, Some(resource)) => {
+                    diff.resources_updated.push(resource);
+                }
+                (ResourceChange::Removed, _) => {
+                    let removed = old.get(&issue_id).and_then(|issue| {
+                        issue
+                            .resources()
+                            .iter()
+                            .find(|resource| resource.id() == &resource_id)
+                    });
+                    diff.resources_removed.extend(removed.cloned());
+                }
+                (_, None) => {}
+            },
+        }
+    }
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        DependencyType, IssueStatus, IssueUpdate, NewIssue, NewResource, NoteContent, ResourceRole,
+        ResourceTarget, WebUrl,
+    };
+    use crate::storage::in_memory::new_in_memory_storage;
+
+    #[tokio::test]
+    async fn groups_changes_by_issue() {
+        let mut storage = new_in_memory_storage("test".to_string());
+        let mut ids = Vec::new();
+        for title in ["Kept", "Closed", "Deleted"] {
+            let issue = storage
+                .create(NewIssue {
+                    title: title.to_string(),
+                    ..NewIssue::default()
+                })
+                .await
+                .unwrap();
+            ids.push(issue.id);
+        }
+        let [kept, closed, deleted] = &ids[..] else {
+            unreachable!()
+        };
+        storage
+            .add_resource(
+                kept,
+                NewResource {
+                    target: ResourceTarget::web(WebUrl::new("https://example.com/old").unwrap()),
+                    role: ResourceRole::Reference,
+                    label: None,
+                },
+            )
+            .await
+            .unwrap();
+        let before = storage.export_all().await.unwrap();
+
+        storage
+            .update(
+                kept,
+                IssueUpdate {
+                    priority: Some(0),
+                    note: Some(NoteContent::new("Raised after triage").unwrap()),
+                    ..IssueUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+        storage
+            .add_dependency(kept, closed, DependencyType::Blocks)
+            .await
+            .unwrap();
+        let resource_id = before
+            .iter()
+            .find(|issue| &issue.id == kept)
+            .unwrap()
+            .resources()[0]
+            .id()
+            .clone();
+        storage.remove_resource(kept, &resource_id).await.unwrap();
+        storage
+            .update(
+                closed,
+                IssueUpdate {
+                    status: Some(IssueStatus::Closed),
+                    ..IssueUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+        storage.delete(deleted).await.unwrap();
+        let created = storage
+            .create(NewIssue {
+                title: "Created".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        let after = storage.export_all().await.unwrap();
+
+        let diff = TrackerDiff::new("a".into(), "b".into(), &before, &after);
+        let changes: BTreeMap<&IssueId, IssueChange> = diff
+            .issues
+            .iter()
+            .map(|issue| (&issue.issue_id, issue.change))
+            .collect();
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[kept], IssueChange::Updated);
+        assert_eq!(changes[closed], IssueChange::Closed);
+        assert_eq!(changes[deleted], IssueChange::Deleted);
+        assert_eq!(changes[&created.id], IssueChange::Created);
+
+        let kept = diff.issues.iter().find(|i| &i.issue_id == kept).unwrap();
+        let fields: Vec<&str> = kept.fields.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["priority"]);
+        assert_eq!(kept.notes_added.len(), 1);
+        assert_eq!(kept.notes_added[0].content(), "Raised after triage");
+        assert_eq!(kept.dependencies_added[0].depends_on_id, *closed);
+        assert_eq!(kept.resources_removed[0].id(), &resource_id);
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_diff() {
+        let diff = TrackerDiff::new("a".into(), "b".into(), &[], &[]);
+        assert!(diff.issues.is_empty());
+        assert_eq!(diff.count(IssueChange::Created), 0);
+    }
+}
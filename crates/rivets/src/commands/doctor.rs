@@ -0,0 +1,917 @@
+//! Checking and repairing a workspace's data file.
+//!
+//! Loading the data file skips whatever it cannot make sense of and carries
+//! on, leaving the workspace read-only when whole issues were lost. `rivets
+//! doctor` reports those problems with their line numbers. `rivets doctor
+//! --repair` then quarantines lines that cannot be loaded, drops or
+//! retargets broken dependencies and rewrites conflicting legacy fields,
+//! and `rivets doctor --fix-ids` repairs duplicate IDs left by merging
+//! branches that minted the same ID.
+//!
+//! Repairs edit the file line by line, leaving untouched lines byte for
+//! byte, and copy the original file aside before writing.
+
+use crate::commands::ids::{
+    AliasReason, IdAlias, load_aliases, record_aliases, replace_id_mentions, resolve_alias,
+};
+use crate::domain::IssueId;
+use crate::error::{Result, StorageError};
+use crate::id_generation::{IdGenerator, IdGeneratorConfig};
+use crate::storage::in_memory::{CanonicalIssueRecord, IssueRecord, LoadWarning, load_from_jsonl};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// The kinds of problem loading the data file runs into, one for each
+/// [`LoadWarning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemKind {
+    /// A line is not valid JSON.
+    MalformedJson,
+    /// A dependency names an issue that does not exist.
+    OrphanedDependency,
+    /// A dependency would close a cycle.
+    CircularDependency,
+    /// Legacy and canonical fields disagree.
+    MigrationConflict,
+    /// An issue fails validation.
+    InvalidIssueData,
+    /// An Associated Resource fails validation.
+    InvalidResourceData,
+    /// An issue reuses an earlier issue's ID.
+    DuplicateId,
+}
+
+/// A problem found in the data file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// What kind of problem it is.
+    pub kind: ProblemKind,
+    /// The line it is on.
+    pub line_number: usize,
+    /// The issue it concerns, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue_id: Option<IssueId>,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl From<&LoadWarning> for Problem {
+    fn from(warning: &LoadWarning) -> Self {
+        let (kind, line_number, issue_id) = match warning {
+            LoadWarning::MalformedJson { line_number, .. } => {
+                (ProblemKind::MalformedJson, *line_number, None)
+            }
+            LoadWarning::OrphanedDependency {
+                from, line_number, ..
+            } => (ProblemKind::OrphanedDependency, *line_number, Some(from)),
+            LoadWarning::CircularDependency {
+                from, line_number, ..
+            } => (ProblemKind::CircularDependency, *line_number, Some(from)),
+            LoadWarning::MigrationConflict {
+                issue_id,
+                line_number,
+                ..
+            } => (ProblemKind::MigrationConflict, *line_number, Some(issue_id)),
+            LoadWarning::InvalidIssueData {
+                issue_id,
+                line_number,
+                ..
+            } => (ProblemKind::InvalidIssueData, *line_number, Some(issue_id)),
+            LoadWarning::InvalidResourceData {
+                issue_id,
+                line_number,
+                ..
+            } => (
+                ProblemKind::InvalidResourceData,
+                *line_number,
+                Some(issue_id),
+            ),
+            LoadWarning::DuplicateId {
+                issue_id,
+                line_number,
+                ..
+            } => (ProblemKind::DuplicateId, *line_number, Some(issue_id)),
+        };
+        Self {
+            kind,
+            line_number,
+            issue_id: issue_id.cloned(),
+            message: warning.to_string(),
+        }
+    }
+}
+
+/// An issue given a new ID by [`fix_ids`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Renamed {
+    /// The ID it shared with an earlier issue.
+    pub old_id: IssueId,
+    /// Its new ID.
+    pub new_id: IssueId,
+    /// The line it is on.
+    pub line_number: usize,
+    /// Issues outside the repair that depend on the old ID. They are left
+    /// depending on the issue that kept it, which may not be what they
+    /// meant.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub referenced_by: Vec<IssueId>,
+}
+
+/// What [`fix_ids`] changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FixIdsOutcome {
+    /// The issues renamed, in file order.
+    pub renamed: Vec<Renamed>,
+    /// Where the original file was copied, when anything changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+}
+
+/// The problems loading the data file at `data_path` runs into, in the
+/// order they are found. A missing file has none.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub async fn check(data_path: &Path, prefix: &str) -> Result<Vec<Problem>> {
+    if !fs::try_exists(data_path).await? {
+        return Ok(Vec::new());
+    }
+    let (_, warnings) = load_from_jsonl(data_path, prefix.to_string()).await?;
+    Ok(warnings.iter().map(Problem::from).collect())
+}
+
+/// Give every issue that reuses an earlier issue's ID a new one.
+///
+/// The first record with an ID keeps it, as it does when loading. Each
+/// later record gets a fresh ID; a child ID such as `proj-a3f8.1` stays
+/// under its parent's new ID if the parent was renamed too, and otherwise
+/// takes the parent's next free child number.
+///
+/// The later records are taken to come from the same side of the merge, so
+/// their dependencies on, and Notes mentioning, any renamed ID are rewritten
+/// to the new ID. Other issues still depending on a renamed ID are reported
+/// in [`Renamed::referenced_by`] rather than guessed at. Every change is
+/// recorded in the alias table in `rivets_dir`.
+///
+/// # Errors
+///
+/// Returns an error if the data file cannot be read, backed up or written,
+/// or no unique ID can be generated.
+pub async fn fix_ids(data_path: &Path, rivets_dir: &Path, prefix: &str) -> Result<FixIdsOutcome> {
+    if !fs::try_exists(data_path).await? {
+        return Ok(FixIdsOutcome::default());
+    }
+    let (_, warnings) = load_from_jsonl(data_path, prefix.to_string()).await?;
+    let mut duplicates: Vec<(usize, IssueId)> = warnings
+        .into_iter()
+        .filter_map(|warning| match warning {
+            LoadWarning::DuplicateId {
+                issue_id,
+                line_number,
+                ..
+            } => Some((line_number, issue_id)),
+            _ => None,
+        })
+        .collect();
+    if duplicates.is_empty() {
+        return Ok(FixIdsOutcome::default());
+    }
+    // Parents first, so children can follow them to their new IDs.
+    duplicates.sort_by_key(|(line_number, id)| (id.as_str().matches('.').count(), *line_number));
+
+    let content = fs::read_to_string(data_path).await?;
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let records: BTreeMap<usize, Value> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| Some((index + 1, serde_json::from_str(line).ok()?)))
+        .collect();
+
+    let mut taken: HashSet<String> = records
+        .values()
+        .filter_map(|record| record["id"].as_str().map(str::to_string))
+        .collect();
+    let mut generator = IdGenerator::new(IdGeneratorConfig {
+        prefix: prefix.to_string(),
+        database_size: records.len(),
+    });
+    for id in &taken {
+        generator.register_id(id.clone());
+    }
+
+    let mut mapping: HashMap<String, String> = HashMap::new();
+    let mut renamed: Vec<Renamed> = Vec::new();
+    for (line_number, old_id) in duplicates {
+        let old = old_id.as_str();
+        let new = match old.rsplit_once('.') {
+            Some((parent, number)) => {
+                let parent = mapping.get(parent).map_or(parent, String::as_str);
+                next_child_id(&taken, parent, number)
+            }
+            None => {
+                let record = &records[&line_number];
+                let text = |field: &str| record[field].as_str().unwrap_or_default().to_string();
+                generator
+                    .generate(&text("title"), &text("description"), None, None)
+                    .map_err(|e| StorageError::IdGeneration(e.to_string()))?
+            }
+        };
+        taken.insert(new.clone());
+        generator.register_id(new.clone());
+        mapping
+            .entry(old.to_string())
+            .or_insert_with(|| new.clone());
+        renamed.push(Renamed {
+            old_id,
+            new_id: IssueId::new(new),
+            line_number,
+            referenced_by: Vec::new(),
+        });
+    }
+
+    let renamed_lines: HashSet<usize> = renamed.iter().map(|r| r.line_number).collect();
+    for entry in &mut renamed {
+        entry.referenced_by = records
+            .iter()
+            .filter(|(line_number, _)| !renamed_lines.contains(line_number))
+            .filter(|(_, record)| depends_on(record, entry.old_id.as_str()))
+            .filter_map(|(_, record)| record["id"].as_str().map(IssueId::new))
+            .collect();
+    }
+
+    // Longest first, so `proj-a3f8.1` is replaced before `proj-a3f8`.
+    let mut replacements: Vec<(&String, &String)> = mapping.iter().collect();
+    replacements.sort_by_key(|(old, _)| std::cmp::Reverse(old.len()));
+    for entry in &renamed {
+        let mut record = records[&entry.line_number].clone();
+        record["id"] = Value::from(entry.new_id.as_str());
+        rewrite_references(&mut record, &mapping, &replacements);
+        lines[entry.line_number - 1] =
+            serde_json::to_string(&record).map_err(StorageError::Serialization)?;
+    }
+
+    let backup = write_backup(data_path, Utc::now()).await?;
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    write_atomic(data_path, &contents).await?;
+
+    let changed_at = Utc::now();
+    let aliases: Vec<IdAlias> = renamed
+        .iter()
+        .map(|entry| IdAlias {
+            old_id: entry.old_id.clone(),
+            new_id: entry.new_id.clone(),
+            reason: AliasReason::Collision,
+            changed_at,
+        })
+        .collect();
+    record_aliases(rivets_dir, &aliases).await?;
+
+    renamed.sort_by_key(|entry| entry.line_number);
+    Ok(FixIdsOutcome {
+        renamed,
+        backup: Some(backup),
+    })
+}
+
+/// `parent.number` if no issue has it yet, otherwise the child of `parent`
+/// numbered one past its highest existing child.
+fn next_child_id(taken: &HashSet<String>, parent: &str, number: &str) -> String {
+    let candidate = format!("{parent}.{number}");
+    if !taken.contains(&candidate) {
+        return candidate;
+    }
+    let child_prefix = format!("{parent}.");
+    let highest = taken
+        .iter()
+        .filter_map(|id| id.strip_prefix(&child_prefix)?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("{parent}.{}", highest + 1)
+}
+
+/// Whether the raw `record` has a dependency on `id`.
+fn depends_on(record: &Value, id: &str) -> bool {
+    record["dependencies"]
+        .as_array()
+        .is_some_and(|deps| deps.iter().any(|dep| dep["depends_on_id"] == id))
+}
+
+/// Point the raw `record`'s dependencies and Notes at the new IDs in
+/// `mapping`. Notes may be a legacy string or a list of Notes.
+fn rewrite_references(
+    record: &mut Value,
+    mapping: &HashMap<String, String>,
+    replacements: &[(&String, &String)],
+) {
+    if let Some(deps) = record.get_mut("dependencies").and_then(Value::as_array_mut) {
+        for dep in deps {
+            if let Some(new) = dep["depends_on_id"].as_str().and_then(|id| mapping.get(id)) {
+                dep["depends_on_id"] = Value::from(new.as_str());
+            }
+        }
+    }
+    let rewrite = |text: &mut Value| {
+        if let Some(content) = text.as_str() {
+            let rewritten = replacements
+                .iter()
+                .fold(content.to_string(), |text, (old, new)| {
+                    replace_id_mentions(&text, old, new)
+                });
+            *text = Value::from(rewritten);
+        }
+    };
+    match record.get_mut("notes") {
+        Some(Value::Array(notes)) => notes
+            .iter_mut()
+            .filter_map(|note| note.get_mut("content"))
+            .for_each(rewrite),
+        Some(legacy) => rewrite(legacy),
+        None => {}
+    }
+}
+
+/// Name of the file within `.rivets` that lines removed by a repair are
+/// moved to
+pub const QUARANTINE_FILE_NAME: &str = "quarantine.jsonl";
+
+/// A change [`apply_repairs`] can make to fix one problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "repair", rename_all = "snake_case")]
+pub enum Repair {
+    /// Move a line that cannot be loaded to the quarantine file.
+    Quarantine {
+        /// The line.
+        line_number: usize,
+        /// Why it cannot be loaded.
+        reason: String,
+    },
+    /// Remove a dependency the loader skipped.
+    DropDependency {
+        /// The line of the issue with the dependency.
+        line_number: usize,
+        /// The issue with the dependency.
+        from: IssueId,
+        /// The issue it depends on.
+        to: IssueId,
+    },
+    /// Point a dependency on an issue that was given a new ID at the new ID.
+    RetargetDependency {
+        /// The line of the issue with the dependency.
+        line_number: usize,
+        /// The issue with the dependency.
+        from: IssueId,
+        /// The old ID it depends on.
+        to: IssueId,
+        /// The ID that issue has now.
+        new_to: IssueId,
+    },
+    /// Write an issue in the current format, dropping legacy fields that
+    /// disagree with the current ones.
+    Reemit {
+        /// The line.
+        line_number: usize,
+        /// The issue.
+        issue_id: IssueId,
+    },
+}
+
+impl Repair {
+    /// The line the repair changes.
+    pub fn line_number(&self) -> usize {
+        match self {
+            Self::Quarantine { line_number, .. }
+            | Self::DropDependency { line_number, .. }
+            | Self::RetargetDependency { line_number, .. }
+            | Self::Reemit { line_number, .. } => *line_number,
+        }
+    }
+}
+
+impl std::fmt::Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Quarantine {
+                line_number,
+                reason,
+            } => write!(f, "line {line_number}: quarantine the line ({reason})"),
+            Self::DropDependency {
+                line_number,
+                from,
+                to,
+            } => write!(
+                f,
+                "line {line_number}: drop the dependency from {from} to {to}"
+            ),
+            Self::RetargetDependency {
+                line_number,
+                from,
+                to,
+                new_to,
+            } => write!(
+                f,
+                "line {line_number}: point the dependency from {from} on {to} at its new ID {new_to}"
+            ),
+            Self::Reemit {
+                line_number,
+                issue_id,
+            } => write!(
+                f,
+                "line {line_number}: rewrite {issue_id} without its conflicting legacy field"
+            ),
+        }
+    }
+}
+
+/// What [`apply_repairs`] changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepairOutcome {
+    /// The repairs made.
+    pub applied: Vec<Repair>,
+    /// Where the original file was copied, when anything changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+    /// The quarantine file, when lines were moved to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantine: Option<PathBuf>,
+}
+
+/// A line moved out of the data file, kept in the quarantine file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedLine {
+    /// The line it was on.
+    pub line_number: usize,
+    /// Why it could not be loaded.
+    pub reason: String,
+    /// The line as it was.
+    pub content: String,
+    /// When it was moved.
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// The repairs that fix the problems loading the data file at `data_path`
+/// runs into, in line order.
+///
+/// Lines that cannot be loaded are quarantined. Dependencies the loader
+/// skipped are dropped, unless they name an issue that the alias table in
+/// `rivets_dir` says was given a new ID, in which case they follow it.
+/// Issues whose legacy and current fields disagree are rewritten. Duplicate
+/// IDs are left to [`fix_ids`].
+///
+/// # Errors
+///
+/// Returns an error if the data file or the alias table cannot be read.
+pub async fn plan_repairs(
+    data_path: &Path,
+    rivets_dir: &Path,
+    prefix: &str,
+) -> Result<Vec<Repair>> {
+    if !fs::try_exists(data_path).await? {
+        return Ok(Vec::new());
+    }
+    let (storage, warnings) = load_from_jsonl(data_path, prefix.to_string()).await?;
+    let aliases = load_aliases(rivets_dir).await?;
+
+    let mut repairs = Vec::new();
+    for warning in warnings {
+        let message = warning.to_string();
+        match warning {
+            LoadWarning::MalformedJson { line_number, .. }
+            | LoadWarning::InvalidIssueData { line_number, .. }
+            | LoadWarning::InvalidResourceData { line_number, .. } => {
+                let reason = message
+                    .strip_prefix(&format!("line {line_number}: "))
+                    .unwrap_or(&message)
+                    .to_string();
+                repairs.push(Repair::Quarantine {
+                    line_number,
+                    reason,
+                });
+            }
+            LoadWarning::OrphanedDependency {
+                from,
+                to,
+                line_number,
+            } => {
+                let new_to = resolve_alias(&aliases, &to);
+                let new_to = match new_to {
+                    Some(new_to) if storage.get(&new_to).await?.is_some() => Some(new_to),
+                    _ => None,
+                };
+                repairs.push(if let Some(new_to) = new_to {
+                    Repair::RetargetDependency {
+                        line_number,
+                        from,
+                        to,
+                        new_to,
+                    }
+                } else {
+                    Repair::DropDependency {
+                        line_number,
+                        from,
+                        to,
+                    }
+                });
+            }
+            LoadWarning::CircularDependency {
+                from,
+                to,
+                line_number,
+            } => repairs.push(Repair::DropDependency {
+                line_number,
+                from,
+                to,
+            }),
+            LoadWarning::MigrationConflict {
+                issue_id,
+                line_number,
+                ..
+            } => repairs.push(Repair::Reemit {
+                line_number,
+                issue_id,
+            }),
+            LoadWarning::DuplicateId { .. } => {}
+        }
+    }
+    // A line that is quarantined needs no other repair.
+    let quarantined: HashSet<usize> = repairs
+        .iter()
+        .filter(|repair| matches!(repair, Repair::Quarantine { .. }))
+        .map(Repair::line_number)
+        .collect();
+    repairs.retain(|repair| {
+        matches!(repair, Repair::Quarantine { .. }) || !quarantined.contains(&repair.line_number())
+    });
+    repairs.sort_by_key(Repair::line_number);
+    Ok(repairs)
+}
+
+/// Make `repairs`, as planned by [`plan_repairs`], to the data file at
+/// `data_path`.
+///
+/// The original file is copied aside first, and quarantined lines are
+/// appended to the quarantine file in `rivets_dir` before they are removed
+/// from the data file, so nothing is lost if a write fails.
+///
+/// # Errors
+///
+/// Returns an error if the data file cannot be read, backed up or written,
+/// or the quarantine file cannot be written.
+pub async fn apply_repairs(
+    data_path: &Path,
+    rivets_dir: &Path,
+    repairs: &[Repair],
+) -> Result<RepairOutcome> {
+    if repairs.is_empty() {
+        return Ok(RepairOutcome::default());
+    }
+    let content = fs::read_to_string(data_path).await?;
+    let mut lines: Vec<Option<String>> = content.lines().map(|l| Some(l.to_string())).collect();
+    let backup = write_backup(data_path, Utc::now()).await?;
+
+    let now = Utc::now();
+    let mut quarantined: Vec<QuarantinedLine> = Vec::new();
+    let mut edits: BTreeMap<usize, Vec<&Repair>> = BTreeMap::new();
+    for repair in repairs {
+        let line_number = repair.line_number();
+        let Some(line) = lines.get_mut(line_number - 1) else {
+            continue;
+        };
+        match repair {
+            Repair::Quarantine { reason, .. } => {
+                if let Some(content) = line.take() {
+                    quarantined.push(QuarantinedLine {
+                        line_number,
+                        reason: reason.clone(),
+                        content,
+                        quarantined_at: now,
+                    });
+                }
+            }
+            _ => edits.entry(line_number).or_default().push(repair),
+        }
+    }
+
+    for (line_number, repairs) in edits {
+        let Some(line) = lines[line_number - 1].as_mut() else {
+            continue;
+        };
+        *line = repair_line(line, &repairs)?;
+    }
+
+    let quarantine = if quarantined.is_empty() {
+        None
+    } else {
+        let path = rivets_dir.join(QUARANTINE_FILE_NAME);
+        let mut entries = String::new();
+        for entry in &quarantined {
+            entries.push_str(&serde_json::to_string(entry).map_err(StorageError::Serialization)?);
+            entries.push('\n');
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        file.write_all(entries.as_bytes()).await?;
+        file.flush().await?;
+        Some(path)
+    };
+
+    let mut contents: String = lines.into_iter().flatten().collect::<Vec<_>>().join("\n");
+    contents.push('\n');
+    write_atomic(data_path, &contents).await?;
+
+    Ok(RepairOutcome {
+        applied: repairs.to_vec(),
+        backup: Some(backup),
+        quarantine,
+    })
+}
+
+/// Apply the dependency and rewrite `repairs` for one line to it.
+fn repair_line(line: &str, repairs: &[&Repair]) -> Result<String> {
+    let mut record: Value = if repairs.iter().any(|r| matches!(r, Repair::Reemit { .. })) {
+        let mut issue = serde_json::from_str::<IssueRecord>(line)
+            .ok()
+            .and_then(|record| record.into_domain().ok())
+            .map(|conversion| conversion.issue)
+            .ok_or_else(|| StorageError::InvalidFormat(format!("cannot rewrite {line}")))?;
+        issue.dependencies.sort();
+        serde_json::to_value(CanonicalIssueRecord::from(issue))
+    } else {
+        serde_json::from_str(line)
+    }
+    .map_err(StorageError::Serialization)?;
+
+    if let Some(deps) = record.get_mut("dependencies").and_then(Value::as_array_mut) {
+        for repair in repairs {
+            match repair {
+                Repair::DropDependency { to, .. } => {
+                    deps.retain(|dep| dep["depends_on_id"] != to.as_str());
+                }
+                Repair::RetargetDependency { to, new_to, .. } => {
+                    for dep in deps.iter_mut() {
+                        if dep["depends_on_id"] == to.as_str() {
+                            dep["depends_on_id"] = Value::from(new_to.as_str());
+                        }
+                    }
+                }
+                Repair::Quarantine { .. } | Repair::Reemit { .. } => {}
+            }
+        }
+    }
+    serde_json::to_string(&record).map_err(|e| StorageError::Serialization(e).into())
+}
+
+/// Where a backup of `data_path` taken at `at` goes: beside it, named after
+/// it and the time.
+pub fn backup_path(data_path: &Path, at: DateTime<Utc>) -> PathBuf {
+    let mut name = data_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.bak", at.format("%Y%m%dT%H%M%SZ")));
+    data_path.with_file_name(name)
+}
+
+/// Copy `data_path` aside before repairing it, returning the copy's path.
+async fn write_backup(data_path: &Path, at: DateTime<Utc>) -> Result<PathBuf> {
+    let backup = backup_path(data_path, at);
+    fs::copy(data_path, &backup).await?;
+    Ok(backup)
+}
+
+/// Replace `path` with `contents` through a temporary file, so a failed
+/// write leaves the original in place.
+async fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut temp = path.as_os_str().to_os_string();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    if let Err(e) = fs::write(&temp, contents).await {
+        let _ = fs::remove_file(&temp).await;
+        return Err(e.into());
+    }
+    fs::rename(&temp, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn record(id: &str, title: &str, depends_on: &[&str], note: &str) -> String {
+        let dependencies: Vec<Value> = depends_on
+            .iter()
+            .map(|id| json!({ "depends_on_id": id, "dep_type": "blocks" }))
+            .collect();
+        json!({
+            "id": id,
+            "title": title,
+            "description": "",
+            "status": "open",
+            "priority": 2,
+            "issue_kind": "task",
+            "assignee": null,
+            "labels": [],
+            "design": null,
+            "acceptance_criteria": null,
+            "notes": note,
+            "dependencies": dependencies,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+            "closed_at": null,
+        })
+        .to_string()
+    }
+
+    async fn write_file(dir: &TempDir, lines: &[String]) -> PathBuf {
+        let path = dir.path().join("issues.jsonl");
+        fs::write(&path, lines.join("\n") + "\n").await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn check_reports_duplicate_ids_with_line_numbers() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(
+            &dir,
+            &[
+                record("t-aaaa", "Ours", &[], ""),
+                record("t-aaaa", "Theirs", &[], ""),
+            ],
+        )
+        .await;
+
+        let problems = check(&path, "t").await.unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, ProblemKind::DuplicateId);
+        assert_eq!(problems[0].line_number, 2);
+        assert_eq!(problems[0].issue_id, Some(IssueId::new("t-aaaa")));
+        assert!(
+            check(&dir.path().join("missing.jsonl"), "t")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn fix_ids_renames_later_records_and_their_references() {
+        let dir = TempDir::new().unwrap();
+        let ours = record("t-aaaa", "Ours", &[], "");
+        let path = write_file(
+            &dir,
+            &[
+                ours.clone(),
+                record("t-aaaa.1", "Our child", &["t-aaaa"], ""),
+                record("t-aaaa", "Theirs", &[], "Split out of t-aaaa.1"),
+                record("t-aaaa.1", "Their child", &["t-aaaa"], "Child of t-aaaa"),
+                record("t-bbbb", "Depends on ours", &["t-aaaa"], ""),
+            ],
+        )
+        .await;
+
+        let outcome = fix_ids(&path, dir.path(), "t").await.unwrap();
+        let renamed: Vec<(usize, &str)> = outcome
+            .renamed
+            .iter()
+            .map(|r| (r.line_number, r.old_id.as_str()))
+            .collect();
+        assert_eq!(renamed, vec![(3, "t-aaaa"), (4, "t-aaaa.1")]);
+        let theirs = outcome.renamed[0].new_id.as_str();
+        let their_child = format!("{theirs}.1");
+        assert_eq!(outcome.renamed[1].new_id.as_str(), their_child);
+        let referenced_by: Vec<&str> = outcome.renamed[0]
+            .referenced_by
+            .iter()
+            .map(IssueId::as_str)
+            .collect();
+        assert_eq!(referenced_by, vec!["t-aaaa.1", "t-bbbb"]);
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], ours, "untouched lines are kept byte for byte");
+        let child: Value = serde_json::from_str(lines[3]).unwrap();
+        assert_eq!(child["dependencies"][0]["depends_on_id"], theirs);
+        assert_eq!(child["notes"], format!("Child of {theirs}"));
+        let parent: Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(parent["notes"], format!("Split out of {their_child}"));
+
+        assert!(check(&path, "t").await.unwrap().is_empty());
+        let backup = fs::read_to_string(outcome.backup.unwrap()).await.unwrap();
+        assert_eq!(
+            backup.lines().nth(2),
+            Some(record("t-aaaa", "Theirs", &[], "Split out of t-aaaa.1").as_str())
+        );
+        let aliases = load_aliases(dir.path()).await.unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].new_id.as_str(), theirs);
+    }
+
+    #[tokio::test]
+    async fn fix_ids_without_duplicates_changes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let path = write_file(&dir, &[record("t-aaaa", "Only", &[], "")]).await;
+
+        let outcome = fix_ids(&path, dir.path(), "t").await.unwrap();
+        assert!(outcome.renamed.is_empty());
+        assert!(outcome.backup.is_none());
+    }
+
+    #[tokio::test]
+    async fn repairs_leave_a_file_that_loads_cleanly() {
+        let dir = TempDir::new().unwrap();
+        let mut conflicting: Value =
+            serde_json::from_str(&record("t-ffff", "Legacy", &[], "")).unwrap();
+        conflicting["issue_type"] = json!("bug");
+        let path = write_file(
+            &dir,
+            &[
+                record("t-aaaa", "Renamed", &[], ""),
+                "{not json".to_string(),
+                record("t-bbbb", "Follows rename", &["t-gone"], ""),
+                record("t-cccc", "Orphaned", &["t-nothere"], ""),
+                record("t-dddd", "Cycle one", &["t-eeee"], ""),
+                record("t-eeee", "Cycle two", &["t-dddd"], ""),
+                conflicting.to_string(),
+            ],
+        )
+        .await;
+        record_aliases(
+            dir.path(),
+            &[IdAlias {
+                old_id: IssueId::new("t-gone"),
+                new_id: IssueId::new("t-aaaa"),
+                reason: AliasReason::Collision,
+                changed_at: Utc::now(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        let problems = check(&path, "t").await.unwrap();
+        let lines: Vec<usize> = problems.iter().map(|p| p.line_number).collect();
+        assert_eq!(lines, vec![2, 7, 3, 4, 6]);
+
+        let repairs = plan_repairs(&path, dir.path(), "t").await.unwrap();
+        assert_eq!(repairs.len(), 5);
+        assert!(matches!(
+            &repairs[0],
+            Repair::Quarantine { line_number: 2, .. }
+        ));
+        assert!(matches!(
+            &repairs[1],
+            Repair::RetargetDependency { new_to, .. } if new_to.as_str() == "t-aaaa"
+        ));
+        assert!(matches!(
+            &repairs[2],
+            Repair::DropDependency { line_number: 4, .. }
+        ));
+        assert!(matches!(
+            &repairs[3],
+            Repair::DropDependency { line_number: 6, .. }
+        ));
+        assert!(matches!(&repairs[4], Repair::Reemit { line_number: 7, .. }));
+
+        let outcome = apply_repairs(&path, dir.path(), &repairs).await.unwrap();
+        assert_eq!(outcome.applied, repairs);
+        assert!(check(&path, "t").await.unwrap().is_empty());
+
+        let content = fs::read_to_string(&path).await.unwrap();
+        assert_eq!(content.lines().count(), 6);
+        assert_eq!(
+            content.lines().next(),
+            Some(record("t-aaaa", "Renamed", &[], "").as_str())
+        );
+        let reemitted: Value = serde_json::from_str(content.lines().last().unwrap()).unwrap();
+        assert!(reemitted.get("issue_type").is_none());
+
+        let quarantine = fs::read_to_string(outcome.quarantine.unwrap())
+            .await
+            .unwrap();
+        let entry: QuarantinedLine = serde_json::from_str(quarantine.trim()).unwrap();
+        assert_eq!(
+            (entry.line_number, entry.content.as_str()),
+            (2, "{not json")
+        );
+        let backup = fs::read_to_string(outcome.backup.unwrap()).await.unwrap();
+        assert_eq!(backup.lines().count(), 7);
+    }
+
+    #[test]
+    fn child_ids_take_the_next_free_number() {
+        let taken: HashSet<String> = ["t-a.1", "t-a.2", "t-a.2.1"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(next_child_id(&taken, "t-a", "1"), "t-a.3");
+        assert_eq!(next_child_id(&taken, "t-b", "1"), "t-b.1");
+    }
+}
@@ -0,0 +1,196 @@
+//! Issue IDs changed after creation, recorded in `.rivets/id-aliases.jsonl`.
+//!
+//! An issue's ID is normally fixed when it is created, but a merge can leave
+//! two issues with the same ID, and one of them has to move, and renaming the
+//! workspace's prefix moves them all. Every such change is recorded as an
+//! alias from the old ID to the new one, so commit messages and notes naming
+//! an old ID still lead to the issue.
+//!
+//! Like milestones, aliases live in their own file and load strictly.
+
+use crate::domain::IssueId;
+use crate::error::{Error, Result, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Name of the aliases file within `.rivets`
+pub const ALIASES_FILE_NAME: &str = "id-aliases.jsonl";
+
+/// Why an issue's ID changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasReason {
+    /// The issue shared its ID with another one and was given a new ID.
+    Collision,
+    /// The workspace's issue prefix was renamed.
+    Rekey,
+}
+
+/// One ID change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdAlias {
+    /// The ID the issue had.
+    pub old_id: IssueId,
+    /// The ID it has now.
+    pub new_id: IssueId,
+    /// Why it changed.
+    pub reason: AliasReason,
+    /// When it changed.
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Path of the aliases file within `rivets_dir`.
+pub fn aliases_path(rivets_dir: &Path) -> PathBuf {
+    rivets_dir.join(ALIASES_FILE_NAME)
+}
+
+/// Load all aliases from `rivets_dir`, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, or if any line is not a
+/// valid alias.
+pub async fn load_aliases(rivets_dir: &Path) -> Result<Vec<IdAlias>> {
+    let path = aliases_path(rivets_dir);
+    let content = match fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                StorageError::InvalidFormat(format!("{} line {}: {e}", path.display(), index + 1))
+                    .into()
+            })
+        })
+        .collect()
+}
+
+/// Append `aliases` to the aliases file in `rivets_dir`.
+///
+/// # Errors
+///
+/// Returns an error if the existing file cannot be loaded or the new one
+/// cannot be written.
+pub async fn record_aliases(rivets_dir: &Path, aliases: &[IdAlias]) -> Result<()> {
+    if aliases.is_empty() {
+        return Ok(());
+    }
+    let mut all = load_aliases(rivets_dir).await?;
+    all.extend_from_slice(aliases);
+    rivets_jsonl::write_jsonl_atomic(aliases_path(rivets_dir), &all)
+        .await
+        .map_err(|e| match e {
+            rivets_jsonl::Error::Io(io_err) => Error::Io(io_err),
+            rivets_jsonl::Error::Json(json_err) => StorageError::Serialization(json_err).into(),
+            rivets_jsonl::Error::InvalidFormat(msg) => StorageError::InvalidFormat(msg).into(),
+        })
+}
+
+/// The ID the issue once called `id` has now, following every recorded
+/// change, or `None` when `id` was never changed.
+pub fn resolve_alias(aliases: &[IdAlias], id: &IssueId) -> Option<IssueId> {
+    let mut current = id;
+    // Each alias is followed at most once, so a cycle cannot loop forever.
+    for _ in 0..aliases.len() {
+        match aliases.iter().rev().find(|alias| &alias.old_id == current) {
+            Some(alias) => current = &alias.new_id,
+            None => break,
+        }
+    }
+    (current != id).then(|| current.clone())
+}
+
+/// Replace every mention of the issue ID `old` in `text` with `new`.
+///
+/// Only whole IDs are replaced: `proj-a3f8` is not found inside
+/// `proj-a3f8b` or `xproj-a3f8`, nor inside the child ID `proj-a3f8.1`, which
+/// is a different issue.
+pub fn replace_id_mentions(text: &str, old: &str, new: &str) -> String {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(old) {
+        let (before, after) = (&rest[..start], &rest[start + old.len()..]);
+        let starts_word = !before.chars().next_back().is_some_and(is_id_char);
+        let mut following = after.chars();
+        let ends_word = match following.next() {
+            Some('.') => !following.next().is_some_and(|c| c.is_ascii_digit()),
+            Some(c) => !is_id_char(c),
+            None => true,
+        };
+        result.push_str(before);
+        result.push_str(if starts_word && ends_word { new } else { old });
+        rest = after;
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    #[rstest]
+    #[case("Blocked by proj-a3f8.", "Blocked by proj-k2m9.")]
+    #[case("proj-a3f8, proj-a3f8", "proj-k2m9, proj-k2m9")]
+    #[case("see (proj-a3f8)", "see (proj-k2m9)")]
+    #[case("proj-a3f8.1 is a child", "proj-a3f8.1 is a child")]
+    #[case("proj-a3f8b and xproj-a3f8", "proj-a3f8b and xproj-a3f8")]
+    fn replaces_whole_ids_only(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(
+            replace_id_mentions(text, "proj-a3f8", "proj-k2m9"),
+            expected
+        );
+    }
+
+    #[test]
+    fn resolves_through_every_change() {
+        let alias = |old: &str, new: &str, reason| IdAlias {
+            old_id: IssueId::new(old),
+            new_id: IssueId::new(new),
+            reason,
+            changed_at: Utc::now(),
+        };
+        let aliases = [
+            alias("old-a3f8", "old-k2m9", AliasReason::Collision),
+            alias("old-k2m9", "new-k2m9", AliasReason::Rekey),
+        ];
+        let resolve = |id: &str| resolve_alias(&aliases, &IssueId::new(id));
+        assert_eq!(resolve("old-a3f8"), Some(IssueId::new("new-k2m9")));
+        assert_eq!(resolve("old-k2m9"), Some(IssueId::new("new-k2m9")));
+        assert_eq!(resolve("new-k2m9"), None);
+    }
+
+    #[tokio::test]
+    async fn records_append_to_existing_aliases() {
+        let dir = TempDir::new().unwrap();
+        assert!(load_aliases(dir.path()).await.unwrap().is_empty());
+
+        let alias = |old: &str, new: &str| IdAlias {
+            old_id: IssueId::new(old),
+            new_id: IssueId::new(new),
+            reason: AliasReason::Collision,
+            changed_at: Utc::now(),
+        };
+        record_aliases(dir.path(), &[alias("t-a", "t-b")])
+            .await
+            .unwrap();
+        record_aliases(dir.path(), &[alias("t-c", "t-d")])
+            .await
+            .unwrap();
+
+        let aliases = load_aliases(dir.path()).await.unwrap();
+        let old: Vec<&str> = aliases.iter().map(|a| a.old_id.as_str()).collect();
+        assert_eq!(old, vec!["t-a", "t-c"]);
+    }
+}
@@ -33,7 +33,9 @@
 //! assert!(validate_prefix("my-proj").is_err());  // contains hyphen
 //! ```
 
+use crate::domain::CustomFieldSchema;
 use crate::error::{ConfigError, Result};
+use crate::hooks::HookConfig;
 use crate::storage::StorageBackend;
 use serde::{Deserialize, Serialize};
 use std::path::{Component, Path, PathBuf};
@@ -75,6 +77,18 @@ pub struct RivetsConfig {
 
     /// Storage configuration
     pub storage: StorageConfig,
+
+    /// Custom fields that issues in this workspace may carry
+    #[serde(
+        default,
+        rename = "custom-fields",
+        skip_serializing_if = "CustomFieldSchema::is_empty"
+    )]
+    pub custom_fields: CustomFieldSchema,
+
+    /// Hooks run when issues change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<HookConfig>,
 }
 
 /// Storage configuration section
@@ -98,8 +112,31 @@ impl StorageConfig {
     ///
     /// Returns an error if the backend type is not recognized or not supported.
     pub fn to_backend(&self, root_dir: impl AsRef<Path>) -> Result<StorageBackend> {
-        let root_dir = root_dir.as_ref();
+        let data_path = root_dir.as_ref().join(self.data_file_path()?);
+        Ok(StorageBackend::Jsonl(data_path))
+    }
 
+    /// Convert the storage config to a read-only [`StorageBackend`] for the
+    /// data file as committed at git revision `rev`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_backend`](Self::to_backend).
+    pub fn to_revision_backend(
+        &self,
+        root_dir: impl AsRef<Path>,
+        rev: &str,
+    ) -> Result<StorageBackend> {
+        Ok(StorageBackend::GitRevision {
+            root: root_dir.as_ref().to_path_buf(),
+            data_file: self.data_file_path()?.to_path_buf(),
+            rev: rev.to_string(),
+        })
+    }
+
+    /// The validated data file path, relative to the workspace root, for a
+    /// supported file-based backend.
+    fn data_file_path(&self) -> Result<&Path> {
         // Validate that data_file is a relative path with no parent traversal
         let data_file_path = Path::new(&self.data_file);
         if data_file_path.is_absolute() {
@@ -112,10 +149,8 @@ impl StorageConfig {
             return Err(ConfigError::PathTraversal.into());
         }
 
-        let data_path = root_dir.join(&self.data_file);
-
         match self.backend.as_str() {
-            "jsonl" => Ok(StorageBackend::Jsonl(data_path)),
+            "jsonl" => Ok(data_file_path),
             "postgresql" => Err(ConfigError::UnsupportedBackend("PostgreSQL".to_string()).into()),
             other => Err(ConfigError::UnknownBackend(other.to_string()).into()),
         }
@@ -131,12 +166,15 @@ impl RivetsConfig {
                 backend: DEFAULT_BACKEND.to_string(),
                 data_file: format!("{}/{}", RIVETS_DIR_NAME, ISSUES_FILE_NAME),
             },
+            custom_fields: CustomFieldSchema::default(),
+            hooks: Vec::new(),
         }
     }
 
     /// Load configuration from a file
     ///
-    /// Validates the configuration after loading, including prefix validation.
+    /// Validates the configuration after loading, including prefix validation,
+    /// custom field declarations and hook names.
     pub async fn load(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path).await?;
         let config: Self = serde_yaml::from_str(&content).map_err(|e| ConfigError::Parse {
@@ -146,6 +184,18 @@ impl RivetsConfig {
 
         // Validate the prefix
         validate_prefix(&config.issue_prefix)?;
+        config
+            .custom_fields
+            .validate()
+            .map_err(ConfigError::InvalidCustomField)?;
+        let mut hook_names = std::collections::BTreeSet::new();
+        if let Some(hook) = config
+            .hooks
+            .iter()
+            .find(|hook| !hook_names.insert(hook.name.as_str()))
+        {
+            return Err(ConfigError::DuplicateHook(hook.name.clone()).into());
+        }
 
         Ok(config)
     }
@@ -175,6 +225,8 @@ pub struct InitResult {
     pub issues_file: PathBuf,
     /// Path to the created gitignore file
     pub gitignore_file: PathBuf,
+    /// Path to the scaffolded issue templates directory
+    pub templates_dir: PathBuf,
     /// The prefix used for issue IDs
     pub prefix: String,
 }
@@ -264,11 +316,15 @@ pub async fn init(base_dir: &Path, prefix: Option<&str>) -> Result<InitResult> {
 ";
     fs::write(&gitignore_file, gitignore_content).await?;
 
+    // Scaffold default issue templates
+    let templates_dir = super::templates::scaffold_default_templates(&rivets_dir).await?;
+
     Ok(InitResult {
         rivets_dir,
         config_file,
         issues_file,
         gitignore_file,
+        templates_dir,
         prefix: prefix.to_string(),
     })
 }
@@ -425,6 +481,38 @@ storage:
         assert!(err_msg.contains("at least"));
     }
 
+    #[tokio::test]
+    async fn test_config_load_custom_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        let config = r#"issue-prefix: test
+storage:
+  backend: jsonl
+  data_file: .rivets/issues.jsonl
+custom-fields:
+  severity:
+    type: enum
+    values: [low, high]
+"#;
+        tokio::fs::write(&config_path, config).await.unwrap();
+
+        let loaded = RivetsConfig::load(&config_path).await.unwrap();
+        assert!(matches!(
+            loaded.custom_fields.get("severity"),
+            Some(crate::domain::CustomFieldType::Enum { values }) if values.len() == 2
+        ));
+
+        // An enum without values is rejected at load time
+        tokio::fs::write(&config_path, config.replace("[low, high]", "[]"))
+            .await
+            .unwrap();
+        let err_msg = RivetsConfig::load(&config_path)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err_msg.contains("declares no values"), "{err_msg}");
+    }
+
     // ========== StorageConfig Tests ==========
 
     #[test]
@@ -528,6 +616,7 @@ storage:
         assert!(result.config_file.exists());
         assert!(result.issues_file.exists());
         assert!(result.gitignore_file.exists());
+        assert!(result.templates_dir.join("bug.yaml").exists());
     }
 
     #[tokio::test]
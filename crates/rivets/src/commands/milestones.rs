@@ -0,0 +1,187 @@
+//! Milestones stored in `.rivets/milestones.jsonl`.
+//!
+//! Milestones live in their own file, one JSON object per line, so the issue
+//! file keeps its shape and its resilient loader only ever sees issues. A
+//! missing file simply means the workspace has no milestones.
+//!
+//! Unlike issue loading, milestone loading is strict: the file is rewritten
+//! in full on every change, so a malformed line is reported rather than
+//! skipped and silently dropped on the next save.
+
+use crate::domain::{Milestone, MilestoneError, current_milestone, validate_milestone_name};
+use crate::error::{Error, Result, StorageError};
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Name of the milestones file within `.rivets`
+pub const MILESTONES_FILE_NAME: &str = "milestones.jsonl";
+
+/// Path of the milestones file within `rivets_dir`.
+pub fn milestones_path(rivets_dir: &Path) -> PathBuf {
+    rivets_dir.join(MILESTONES_FILE_NAME)
+}
+
+/// Load all milestones from `rivets_dir`, in file order.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, or if any line is not a
+/// valid milestone.
+pub async fn load_milestones(rivets_dir: &Path) -> Result<Vec<Milestone>> {
+    let path = milestones_path(rivets_dir);
+    let content = match fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let invalid = |line_number: usize, error: &dyn std::fmt::Display| -> Error {
+        StorageError::InvalidFormat(format!("{} line {line_number}: {error}", path.display()))
+            .into()
+    };
+
+    let mut milestones: Vec<Milestone> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let milestone: Milestone =
+            serde_json::from_str(line).map_err(|e| invalid(index + 1, &e))?;
+        milestone.validate().map_err(|e| invalid(index + 1, &e))?;
+        if milestones.iter().any(|m| m.name == milestone.name) {
+            return Err(invalid(
+                index + 1,
+                &MilestoneError::AlreadyExists(milestone.name),
+            ));
+        }
+        milestones.push(milestone);
+    }
+    Ok(milestones)
+}
+
+/// Atomically write `milestones` to `rivets_dir`, ordered by start date and
+/// name so the file diffs cleanly.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub async fn save_milestones(rivets_dir: &Path, milestones: &[Milestone]) -> Result<()> {
+    let mut sorted = milestones.to_vec();
+    sorted.sort_by(|a, b| {
+        a.starts_on
+            .cmp(&b.starts_on)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rivets_jsonl::write_jsonl_atomic(milestones_path(rivets_dir), &sorted)
+        .await
+        .map_err(|e| match e {
+            rivets_jsonl::Error::Io(io_err) => Error::Io(io_err),
+            rivets_jsonl::Error::Json(json_err) => StorageError::Serialization(json_err).into(),
+            rivets_jsonl::Error::InvalidFormat(msg) => StorageError::InvalidFormat(msg).into(),
+        })
+}
+
+/// Find the milestone called `name`.
+///
+/// # Errors
+///
+/// Returns [`MilestoneError::InvalidName`] for a malformed name and
+/// [`MilestoneError::NotFound`] if no milestone has that name.
+pub fn find_milestone<'a>(milestones: &'a [Milestone], name: &str) -> Result<&'a Milestone> {
+    validate_milestone_name(name)?;
+    milestones
+        .iter()
+        .find(|milestone| milestone.name == name)
+        .ok_or_else(|| MilestoneError::NotFound(name.to_string()).into())
+}
+
+/// Load the milestones in `rivets_dir` and check that `name` is one of them.
+///
+/// Used before assigning an issue to a milestone.
+///
+/// # Errors
+///
+/// Returns any error from [`load_milestones`] or [`find_milestone`].
+pub async fn require_milestone(rivets_dir: &Path, name: &str) -> Result<()> {
+    let milestones = load_milestones(rivets_dir).await?;
+    find_milestone(&milestones, name)?;
+    Ok(())
+}
+
+/// Name of the current milestone in `rivets_dir` on `today`.
+///
+/// # Errors
+///
+/// Returns [`MilestoneError::NoCurrent`] if no open milestone covers
+/// `today`, or any error from [`load_milestones`].
+pub async fn current_milestone_name(rivets_dir: &Path, today: NaiveDate) -> Result<String> {
+    let milestones = load_milestones(rivets_dir).await?;
+    current_milestone(&milestones, today)
+        .map(|milestone| milestone.name.clone())
+        .ok_or_else(|| MilestoneError::NoCurrent(today).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[tokio::test]
+    async fn missing_file_means_no_milestones() {
+        let dir = TempDir::new().unwrap();
+        assert!(load_milestones(dir.path()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn save_and_load_roundtrip_sorted() {
+        let dir = TempDir::new().unwrap();
+        let now = Utc::now();
+        let later = Milestone::new("b", date("2026-04-01"), date("2026-04-14"), now).unwrap();
+        let mut earlier = Milestone::new("a", date("2026-03-01"), date("2026-03-14"), now).unwrap();
+        earlier.close(now).unwrap();
+
+        save_milestones(dir.path(), &[later.clone(), earlier.clone()])
+            .await
+            .unwrap();
+        let loaded = load_milestones(dir.path()).await.unwrap();
+        assert_eq!(loaded, vec![earlier, later]);
+
+        assert_eq!(find_milestone(&loaded, "b").unwrap().name, "b");
+        assert!(matches!(
+            find_milestone(&loaded, "c"),
+            Err(Error::Milestone(MilestoneError::NotFound(_)))
+        ));
+        assert_eq!(
+            current_milestone_name(dir.path(), date("2026-04-02"))
+                .await
+                .unwrap(),
+            "b"
+        );
+        assert!(matches!(
+            current_milestone_name(dir.path(), date("2026-03-02")).await,
+            Err(Error::Milestone(MilestoneError::NoCurrent(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn malformed_lines_are_errors() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            milestones_path(dir.path()),
+            "{\"name\":\"a\",\"starts_on\":\"2026-03-02\",\"ends_on\":\"2026-03-01\",\"state\":\"open\",\"created_at\":\"2026-03-01T00:00:00Z\"}\n",
+        )
+        .unwrap();
+        let error = load_milestones(dir.path()).await.unwrap_err();
+        assert!(error.to_string().contains("line 1"), "{error}");
+
+        std::fs::write(milestones_path(dir.path()), "\nnot json\n").unwrap();
+        let error = load_milestones(dir.path()).await.unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{error}");
+    }
+}
@@ -2,4 +2,14 @@
 //!
 //! This module contains the implementation of all CLI commands.
 
+pub mod claims;
+pub mod commits;
+pub mod dep_tree;
+pub mod diff;
+pub mod doctor;
+pub mod ids;
 pub mod init;
+pub mod milestones;
+pub mod rekey;
+pub mod stats;
+pub mod templates;
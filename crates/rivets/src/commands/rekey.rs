@@ -0,0 +1,371 @@
+//! Renaming a workspace's issue prefix.
+//!
+//! The prefix is chosen at `rivets init` and every ID starts with it, so
+//! renaming it rewrites every issue's ID, every dependency on it and the
+//! prefix in `config.yaml`. Child IDs keep their numbers under their
+//! parent's new ID. The old IDs are recorded in the alias table, so commit
+//! messages and notes that name them still lead to the issue.
+
+use crate::commands::ids::{AliasReason, IdAlias, record_aliases, replace_id_mentions};
+use crate::commands::init::{CONFIG_FILE_NAME, RivetsConfig, validate_prefix};
+use crate::domain::{Issue, IssueId, Note, NoteContent};
+use crate::error::{Error, Result};
+use crate::storage::IssueStorage;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// What [`rekey`] changed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RekeyOutcome {
+    /// The prefix before.
+    pub old_prefix: String,
+    /// The prefix now.
+    pub new_prefix: String,
+    /// How many issues were given a new ID.
+    pub renamed: usize,
+    /// Issues whose description or Notes mentioned a renamed ID and were
+    /// rewritten to the new one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mentions_rewritten: Vec<IssueId>,
+    /// Issues whose ID does not start with the old prefix, left as they are.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unchanged: Vec<IssueId>,
+}
+
+/// `id` with `old_prefix` replaced by `new_prefix`, or `None` when it does
+/// not start with `old_prefix`.
+pub fn rekey_id(id: &IssueId, old_prefix: &str, new_prefix: &str) -> Option<IssueId> {
+    let rest = id.as_str().strip_prefix(old_prefix)?.strip_prefix('-')?;
+    Some(IssueId::new(format!("{new_prefix}-{rest}")))
+}
+
+/// Rename the prefix of every issue in `storage` from `old_prefix` to
+/// `new_prefix`, save it, and update `config.yaml` and the alias table in
+/// `rivets_dir`.
+///
+/// If any step fails, the issues and `config.yaml` are put back as they were,
+/// so the workspace never ends up with IDs that do not match its prefix.
+///
+/// With `rewrite_mentions`, renamed IDs mentioned in descriptions and Notes
+/// are rewritten too.
+///
+/// # Errors
+///
+/// Returns a validation error if `new_prefix` is invalid, is the current
+/// prefix, or would give an issue the ID of one left unchanged, and a
+/// storage error if the workspace cannot be saved.
+pub async fn rekey(
+    storage: &mut dyn IssueStorage,
+    rivets_dir: &Path,
+    old_prefix: &str,
+    new_prefix: &str,
+    rewrite_mentions: bool,
+) -> Result<RekeyOutcome> {
+    validate_prefix(new_prefix)?;
+    if new_prefix == old_prefix {
+        return Err(Error::Validation {
+            field: "prefix",
+            reason: format!("The issue prefix is already {old_prefix}"),
+        });
+    }
+
+    let issues = storage.export_all().await?;
+    let mapping: BTreeMap<IssueId, IssueId> = issues
+        .iter()
+        .filter_map(|issue| {
+            Some((
+                issue.id.clone(),
+                rekey_id(&issue.id, old_prefix, new_prefix)?,
+            ))
+        })
+        .collect();
+    let unchanged: Vec<IssueId> = issues
+        .iter()
+        .filter(|issue| !mapping.contains_key(&issue.id))
+        .map(|issue| issue.id.clone())
+        .collect();
+    if let Some(taken) = mapping.values().find(|new_id| unchanged.contains(new_id)) {
+        return Err(Error::Validation {
+            field: "prefix",
+            reason: format!("Issue {taken} already exists"),
+        });
+    }
+
+    let mut mentions_rewritten = Vec::new();
+    let mut rekeyed = Vec::with_capacity(issues.len());
+    for issue in &issues {
+        let mut issue = issue.clone();
+        if rewrite_mentions && rewrite_issue_mentions(&mut issue, &mapping)? {
+            mentions_rewritten.push(mapping.get(&issue.id).unwrap_or(&issue.id).clone());
+        }
+        if let Some(new_id) = mapping.get(&issue.id) {
+            issue.id = new_id.clone();
+        }
+        for dep in &mut issue.dependencies {
+            if let Some(new_id) = mapping.get(&dep.depends_on_id) {
+                dep.depends_on_id = new_id.clone();
+            }
+        }
+        rekeyed.push(issue);
+    }
+
+    let config_path = rivets_dir.join(CONFIG_FILE_NAME);
+    let original_config = tokio::fs::read(&config_path).await?;
+    let mut config = RivetsConfig::load(&config_path).await?;
+    config.issue_prefix = new_prefix.to_string();
+
+    let changed_at = Utc::now();
+    let aliases: Vec<IdAlias> = mapping
+        .iter()
+        .map(|(old_id, new_id)| IdAlias {
+            old_id: old_id.clone(),
+            new_id: new_id.clone(),
+            reason: AliasReason::Rekey,
+            changed_at,
+        })
+        .collect();
+
+    let mut saved = false;
+    let applied = async {
+        replace_issues(storage, &issues, rekeyed).await?;
+        storage.save().await?;
+        saved = true;
+        config.save(&config_path).await?;
+        record_aliases(rivets_dir, &aliases).await
+    }
+    .await;
+    if let Err(error) = applied {
+        let created: Vec<IssueId> = mapping.into_values().collect();
+        let config = saved.then_some((config_path.as_path(), original_config.as_slice()));
+        if let Err(rollback_error) = roll_back(storage, issues, &created, config).await {
+            tracing::error!(%rollback_error, "Failed to roll back the prefix change");
+        }
+        return Err(error);
+    }
+
+    Ok(RekeyOutcome {
+        old_prefix: old_prefix.to_string(),
+        new_prefix: new_prefix.to_string(),
+        renamed: mapping.len(),
+        mentions_rewritten,
+        unchanged,
+    })
+}
+
+/// Take every issue in `issues` out of `storage`, dependencies first, and
+/// put the `rekeyed` ones in.
+async fn replace_issues(
+    storage: &mut dyn IssueStorage,
+    issues: &[Issue],
+    rekeyed: Vec<Issue>,
+) -> Result<()> {
+    for issue in issues {
+        for dep in storage.get_dependencies(&issue.id).await? {
+            storage
+                .remove_dependency(&issue.id, &dep.depends_on_id)
+                .await?;
+        }
+    }
+    for issue in issues {
+        storage.delete(&issue.id).await?;
+    }
+    storage.import_issues(rekeyed).await
+}
+
+/// Undo a [`rekey`] that failed part way: put the `before` issues back in
+/// place of the `created` ones and, when the renamed issues were already
+/// saved, save the originals again and write back the original `config`.
+async fn roll_back(
+    storage: &mut dyn IssueStorage,
+    before: Vec<Issue>,
+    created: &[IssueId],
+    config: Option<(&Path, &[u8])>,
+) -> Result<()> {
+    storage.restore_issues(before, created).await?;
+    if let Some((path, original)) = config {
+        storage.save().await?;
+        tokio::fs::write(path, original).await?;
+    }
+    Ok(())
+}
+
+/// Rewrite the renamed IDs `issue`'s description and Notes mention,
+/// returning whether any were found.
+fn rewrite_issue_mentions(issue: &mut Issue, mapping: &BTreeMap<IssueId, IssueId>) -> Result<bool> {
+    // Longest first, so `proj-a3f8.1` is replaced before `proj-a3f8`.
+    let mut replacements: Vec<(&IssueId, &IssueId)> = mapping.iter().collect();
+    replacements.sort_by_key(|(old, _)| std::cmp::Reverse(old.as_str().len()));
+    let rewrite = |text: &str| {
+        replacements
+            .iter()
+            .filter(|(old, _)| text.contains(old.as_str()))
+            .fold(text.to_string(), |text, (old, new)| {
+                replace_id_mentions(&text, old.as_str(), new.as_str())
+            })
+    };
+
+    let mut changed = false;
+    let description = rewrite(&issue.description);
+    if description != issue.description {
+        issue.description = description;
+        changed = true;
+    }
+    let mut notes = Vec::with_capacity(issue.notes.len());
+    for note in &issue.notes {
+        let content = rewrite(note.content());
+        if content == note.content() {
+            notes.push(note.clone());
+            continue;
+        }
+        let content = NoteContent::new(content).map_err(|e| Error::Validation {
+            field: "notes",
+            reason: e.to_string(),
+        })?;
+        notes.push(Note::from_parts(content, *note.created_at()));
+        changed = true;
+    }
+    issue.notes = notes;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DependencyType, IssueUpdate, NewIssue};
+    use crate::storage::in_memory::new_in_memory_storage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn rekeys_ids_under_the_old_prefix_only() {
+        let rekey = |id: &str| rekey_id(&IssueId::new(id), "old", "new");
+        assert_eq!(rekey("old-a3f8"), Some(IssueId::new("new-a3f8")));
+        assert_eq!(rekey("old-a3f8.1.2"), Some(IssueId::new("new-a3f8.1.2")));
+        assert_eq!(rekey("older-a3f8"), None);
+        assert_eq!(rekey("other-a3f8"), None);
+    }
+
+    #[tokio::test]
+    async fn rekey_renames_issues_dependencies_and_mentions() {
+        let dir = TempDir::new().unwrap();
+        RivetsConfig::new("old")
+            .save(&dir.path().join(CONFIG_FILE_NAME))
+            .await
+            .unwrap();
+        let mut storage = new_in_memory_storage("old".to_string());
+        let parent = storage
+            .create(NewIssue {
+                title: "Parent".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        let mut child = parent.clone();
+        child.id = IssueId::new(format!("{}.1", parent.id));
+        child.title = "Child".to_string();
+        child.description = format!("Split out of {}", parent.id);
+        storage.import_issues(vec![child.clone()]).await.unwrap();
+        storage
+            .add_dependency(&child.id, &parent.id, DependencyType::Blocks)
+            .await
+            .unwrap();
+        storage
+            .update(
+                &parent.id,
+                IssueUpdate {
+                    note: Some(NoteContent::new(format!("See {}", child.id)).unwrap()),
+                    ..IssueUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let outcome = rekey(storage.as_mut(), dir.path(), "old", "new", true)
+            .await
+            .unwrap();
+        assert_eq!(outcome.renamed, 2);
+        assert_eq!(outcome.mentions_rewritten.len(), 2);
+
+        let new_parent = rekey_id(&parent.id, "old", "new").unwrap();
+        let new_child = rekey_id(&child.id, "old", "new").unwrap();
+        assert!(storage.get(&parent.id).await.unwrap().is_none());
+        let renamed = storage.get(&new_child).await.unwrap().unwrap();
+        assert_eq!(renamed.description, format!("Split out of {new_parent}"));
+        let deps = storage.get_dependencies(&new_child).await.unwrap();
+        assert_eq!(deps[0].depends_on_id, new_parent);
+        let renamed = storage.get(&new_parent).await.unwrap().unwrap();
+        assert_eq!(renamed.notes()[0].content(), format!("See {new_child}"));
+
+        let config = RivetsConfig::load(&dir.path().join(CONFIG_FILE_NAME))
+            .await
+            .unwrap();
+        assert_eq!(config.issue_prefix, "new");
+        let aliases = crate::commands::ids::load_aliases(dir.path())
+            .await
+            .unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert!(
+            aliases
+                .iter()
+                .all(|alias| alias.reason == AliasReason::Rekey)
+        );
+    }
+
+    #[tokio::test]
+    async fn rekey_rolls_back_when_a_later_step_fails() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join(CONFIG_FILE_NAME);
+        RivetsConfig::new("old").save(&config_path).await.unwrap();
+        let original_config = std::fs::read(&config_path).unwrap();
+        // The alias table cannot be written over a directory.
+        std::fs::create_dir(crate::commands::ids::aliases_path(dir.path())).unwrap();
+        let mut storage = new_in_memory_storage("old".to_string());
+        let blocker = storage
+            .create(NewIssue {
+                title: "Blocker".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        let blocked = storage
+            .create(NewIssue {
+                title: "Blocked".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        storage
+            .add_dependency(&blocked.id, &blocker.id, DependencyType::Blocks)
+            .await
+            .unwrap();
+
+        let result = rekey(storage.as_mut(), dir.path(), "old", "new", false).await;
+        assert!(result.is_err());
+
+        let issues = storage.export_all().await.unwrap();
+        assert_eq!(issues.len(), 2);
+        assert!(
+            issues
+                .iter()
+                .all(|issue| issue.id.as_str().starts_with("old-"))
+        );
+        let deps = storage.get_dependencies(&blocked.id).await.unwrap();
+        assert_eq!(deps[0].depends_on_id, blocker.id);
+        assert_eq!(std::fs::read(&config_path).unwrap(), original_config);
+    }
+
+    #[tokio::test]
+    async fn rekey_rejects_the_current_prefix() {
+        let dir = TempDir::new().unwrap();
+        let mut storage = new_in_memory_storage("old".to_string());
+        let result = rekey(storage.as_mut(), dir.path(), "old", "old", false).await;
+        assert!(matches!(
+            result,
+            Err(Error::Validation {
+                field: "prefix",
+                ..
+            })
+        ));
+    }
+}
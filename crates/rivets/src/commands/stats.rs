@@ -0,0 +1,186 @@
+//! Project statistics shared by `rivets stats` and the MCP `stats` tool.
+//!
+//! [`IssueStats`] serializes to the `rivets stats --json` shape, so scripts
+//! and agents read the same fields whichever interface produced them.
+
+use crate::domain::{IssueFilter, IssueStatus};
+use crate::error::Result;
+use crate::storage::IssueStorage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Issue counts across a workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IssueStats {
+    /// Number of issues in any status.
+    pub total: usize,
+    /// Issue counts by status.
+    pub by_status: StatusBreakdown,
+    /// Issues with no open blockers.
+    pub ready: usize,
+    /// Issues blocked by an open dependency.
+    pub blocked_by_dependencies: usize,
+    /// Open issues past their due date.
+    pub overdue: usize,
+    /// Issue counts by priority, included for detailed stats only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_priority: Option<PriorityBreakdown>,
+}
+
+/// Issue counts by status.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StatusBreakdown {
+    pub open: usize,
+    pub in_progress: usize,
+    pub blocked: usize,
+    pub closed: usize,
+}
+
+/// Issue counts by priority.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PriorityBreakdown {
+    pub p0_critical: usize,
+    pub p1_high: usize,
+    pub p2_medium: usize,
+    pub p3_low: usize,
+    pub p4_backlog: usize,
+}
+
+impl PriorityBreakdown {
+    /// Counts paired with their display labels, from P0 to P4.
+    pub fn labeled(&self) -> [(&'static str, usize); 5] {
+        [
+            ("P0 (Critical)", self.p0_critical),
+            ("P1 (High)", self.p1_high),
+            ("P2 (Medium)", self.p2_medium),
+            ("P3 (Low)", self.p3_low),
+            ("P4 (Backlog)", self.p4_backlog),
+        ]
+    }
+}
+
+/// Compute statistics for every issue in `storage`.
+///
+/// `now` decides which issues are overdue. The priority breakdown is only
+/// computed when `detailed` is set.
+///
+/// # Errors
+///
+/// Returns an error if any storage query fails.
+pub async fn collect_stats(
+    storage: &dyn IssueStorage,
+    detailed: bool,
+    now: DateTime<Utc>,
+) -> Result<IssueStats> {
+    let issues = storage.list(&IssueFilter::default()).await?;
+
+    let mut by_status = StatusBreakdown::default();
+    let mut by_priority = PriorityBreakdown::default();
+    for issue in &issues {
+        match issue.status {
+            IssueStatus::Open => by_status.open += 1,
+            IssueStatus::InProgress => by_status.in_progress += 1,
+            IssueStatus::Blocked => by_status.blocked += 1,
+            IssueStatus::Closed => by_status.closed += 1,
+        }
+        match issue.priority {
+            0 => by_priority.p0_critical += 1,
+            1 => by_priority.p1_high += 1,
+            2 => by_priority.p2_medium += 1,
+            3 => by_priority.p3_low += 1,
+            _ => by_priority.p4_backlog += 1,
+        }
+    }
+
+    Ok(IssueStats {
+        total: issues.len(),
+        by_status,
+        ready: storage.ready_to_work(None, None).await?.len(),
+        blocked_by_dependencies: storage.blocked_issues().await?.len(),
+        overdue: issues.iter().filter(|issue| issue.is_overdue(now)).count(),
+        by_priority: detailed.then_some(by_priority),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DependencyType, IssueKind, IssueUpdate, NewIssue};
+    use crate::storage::in_memory::new_in_memory_storage;
+    use chrono::Duration;
+
+    fn new_issue(title: &str, priority: u8) -> NewIssue {
+        NewIssue {
+            title: title.to_string(),
+            description: String::new(),
+            priority,
+            issue_kind: IssueKind::Task,
+            assignee: None,
+            labels: vec![],
+            design: None,
+            acceptance_criteria: None,
+            initial_note: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            dependencies: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn collects_counts_and_serializes_like_the_cli() {
+        let mut storage = new_in_memory_storage("test".to_string());
+        let blocker = storage.create(new_issue("Blocker", 0)).await.unwrap();
+        let blocked = storage.create(new_issue("Blocked", 2)).await.unwrap();
+        let done = storage.create(new_issue("Done", 4)).await.unwrap();
+        storage
+            .add_dependency(&blocked.id, &blocker.id, DependencyType::Blocks)
+            .await
+            .unwrap();
+        storage
+            .update(
+                &done.id,
+                IssueUpdate {
+                    status: Some(IssueStatus::Closed),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let now = Utc::now();
+        storage
+            .update(
+                &blocker.id,
+                IssueUpdate {
+                    due_at: Some(Some(now - Duration::days(1))),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let stats = collect_stats(storage.as_ref(), false, now).await.unwrap();
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.by_status.open, 2);
+        assert_eq!(stats.by_status.closed, 1);
+        assert_eq!(stats.ready, 1);
+        assert_eq!(stats.blocked_by_dependencies, 1);
+        assert_eq!(stats.overdue, 1);
+        assert!(
+            serde_json::to_value(&stats)
+                .unwrap()
+                .get("by_priority")
+                .is_none()
+        );
+
+        let detailed = collect_stats(storage.as_ref(), true, now).await.unwrap();
+        let json = serde_json::to_value(&detailed).unwrap();
+        assert_eq!(json["by_priority"]["p0_critical"], 1);
+        assert_eq!(json["by_priority"]["p2_medium"], 1);
+        assert_eq!(json["by_priority"]["p4_backlog"], 1);
+        assert_eq!(json["by_status"]["in_progress"], 0);
+    }
+}
@@ -0,0 +1,452 @@
+//! Issue templates stored in `.rivets/templates/`.
+//!
+//! A template is a YAML file named `<name>.yaml` that prefills the text
+//! fields, default labels, priority and kind of a new issue. When creating an
+//! issue without an explicit template, the template named after the issue's
+//! kind (e.g. `bug.yaml`) is applied if it exists.
+//!
+//! Values given explicitly on creation always win over template values;
+//! template labels are merged with explicit labels.
+//!
+//! # Example
+//!
+//! ```yaml
+//! priority: 1
+//! labels: [bug]
+//! description: |
+//!   ## Steps to reproduce
+//!
+//!   ## Expected
+//!
+//!   ## Actual
+//! ```
+
+use crate::cli::validate_label;
+use crate::domain::{IssueKind, MAX_PRIORITY, MIN_PRIORITY, NewIssue};
+use crate::error::{ConfigError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Name of the templates directory within `.rivets`
+pub const TEMPLATES_DIR_NAME: &str = "templates";
+
+/// File extension for template files
+pub const TEMPLATE_EXTENSION: &str = "yaml";
+
+/// Templates written by `rivets init`, as `(name, contents)` pairs.
+pub const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "bug",
+        "\
+# Applied to `rivets create --kind bug`.
+labels: [bug]
+description: |
+  ## Steps to reproduce
+
+  1.
+
+  ## Expected
+
+  ## Actual
+acceptance: |
+  - The steps above no longer reproduce the problem
+  - A regression test covers the fix
+",
+    ),
+    (
+        "feature",
+        "\
+# Applied to `rivets create --kind feature`.
+description: |
+  ## Problem
+
+  ## Proposal
+acceptance: |
+  - [ ]
+",
+    ),
+    (
+        "epic",
+        "\
+# Applied to `rivets create --kind epic`.
+priority: 1
+description: |
+  ## Goal
+
+  ## Scope
+
+  ## Out of scope
+acceptance: |
+  - All child issues are closed
+",
+    ),
+];
+
+/// Prefilled values for a new issue.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IssueTemplate {
+    /// Issue kind used when none is given explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<IssueKind>,
+
+    /// Priority used when none is given explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+
+    /// Labels added to every issue created from this template
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    /// Description used when none is given explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Design notes used when none are given explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub design: Option<String>,
+
+    /// Acceptance criteria used when none are given explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acceptance: Option<String>,
+}
+
+impl IssueTemplate {
+    /// Load the template `name` from `rivets_dir`.
+    ///
+    /// Returns `Ok(None)` if no such template exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid, the file cannot be read or
+    /// parsed, a label is invalid, or the template's priority is out of
+    /// range.
+    pub async fn load(rivets_dir: &Path, name: &str) -> Result<Option<Self>> {
+        validate_template_name(name)?;
+        let path = template_path(rivets_dir, name);
+
+        let content = match fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut template: Self =
+            serde_yaml::from_str(&content).map_err(|e| ConfigError::Parse {
+                path: path.display().to_string(),
+                source: e,
+            })?;
+
+        template.labels = template
+            .labels
+            .iter()
+            .map(|label| validate_label(label))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|reason| ConfigError::InvalidTemplate {
+                name: name.to_string(),
+                reason,
+            })?;
+
+        if let Some(priority) = template.priority
+            && priority > MAX_PRIORITY
+        {
+            return Err(ConfigError::InvalidTemplate {
+                name: name.to_string(),
+                reason: format!(
+                    "priority must be in range {MIN_PRIORITY}-{MAX_PRIORITY} (got {priority})"
+                ),
+            }
+            .into());
+        }
+
+        Ok(Some(template))
+    }
+
+    /// Resolve the template for a new issue.
+    ///
+    /// An explicit `name` must exist. Otherwise the template named after
+    /// `kind` is used if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::TemplateNotFound`] if an explicitly named
+    /// template does not exist, or any error from [`IssueTemplate::load`].
+    pub async fn resolve(
+        rivets_dir: &Path,
+        name: Option<&str>,
+        kind: IssueKind,
+    ) -> Result<Option<Self>> {
+        match name {
+            Some(name) => Self::load(rivets_dir, name)
+                .await?
+                .map(Some)
+                .ok_or_else(|| ConfigError::TemplateNotFound(name.to_string()).into()),
+            None => Self::load(rivets_dir, &kind.to_string()).await,
+        }
+    }
+
+    /// Fill the unset fields of `new_issue` from this template.
+    ///
+    /// `explicit` describes which fields the caller set explicitly; those
+    /// are left untouched. Template labels are prepended to the issue's
+    /// labels, skipping duplicates.
+    pub fn apply(&self, new_issue: &mut NewIssue, explicit: ExplicitFields) {
+        if !explicit.kind
+            && let Some(kind) = self.kind
+        {
+            new_issue.issue_kind = kind;
+        }
+        if !explicit.priority
+            && let Some(priority) = self.priority
+        {
+            new_issue.priority = priority;
+        }
+        if !explicit.description
+            && let Some(description) = &self.description
+        {
+            new_issue.description = description.trim_end().to_string();
+        }
+        if new_issue.design.is_none() {
+            new_issue.design = self.design.as_deref().map(|s| s.trim_end().to_string());
+        }
+        if new_issue.acceptance_criteria.is_none() {
+            new_issue.acceptance_criteria =
+                self.acceptance.as_deref().map(|s| s.trim_end().to_string());
+        }
+
+        let mut labels = self.labels.clone();
+        for label in new_issue.labels.drain(..) {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        new_issue.labels = labels;
+    }
+}
+
+/// Which [`NewIssue`] fields were given explicitly by the caller.
+///
+/// Fields that are `Option` on [`NewIssue`] (design, acceptance criteria)
+/// need no flag: `Some` is treated as explicit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExplicitFields {
+    /// The issue kind was given explicitly
+    pub kind: bool,
+    /// The priority was given explicitly
+    pub priority: bool,
+    /// The description was given explicitly
+    pub description: bool,
+}
+
+/// Validate a template name.
+///
+/// Names become file names, so only ASCII alphanumerics, hyphens and
+/// underscores are allowed.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::InvalidTemplate`] if the name is empty or contains
+/// other characters.
+pub fn validate_template_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ConfigError::InvalidTemplate {
+            name: name.to_string(),
+            reason: "name must contain only alphanumerics, hyphens and underscores".to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Path of the template `name` within `rivets_dir`.
+pub fn template_path(rivets_dir: &Path, name: &str) -> PathBuf {
+    rivets_dir
+        .join(TEMPLATES_DIR_NAME)
+        .join(format!("{name}.{TEMPLATE_EXTENSION}"))
+}
+
+/// List the names of all templates in `rivets_dir`, sorted.
+///
+/// # Errors
+///
+/// Returns an error if the templates directory exists but cannot be read.
+pub async fn list_templates(rivets_dir: &Path) -> Result<Vec<String>> {
+    let mut entries = match fs::read_dir(rivets_dir.join(TEMPLATES_DIR_NAME)).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext == TEMPLATE_EXTENSION)
+            && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+        {
+            names.push(stem.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Write the [`DEFAULT_TEMPLATES`] into `rivets_dir`.
+///
+/// Existing templates are left untouched.
+///
+/// # Returns
+///
+/// The path of the templates directory.
+///
+/// # Errors
+///
+/// Returns an error if the directory or a template file cannot be written.
+pub async fn scaffold_default_templates(rivets_dir: &Path) -> Result<PathBuf> {
+    let templates_dir = rivets_dir.join(TEMPLATES_DIR_NAME);
+    fs::create_dir_all(&templates_dir).await?;
+
+    for (name, content) in DEFAULT_TEMPLATES {
+        let path = template_path(rivets_dir, name);
+        if !fs::try_exists(&path).await? {
+            fs::write(&path, content).await?;
+        }
+    }
+
+    Ok(templates_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_default_templates_parse() {
+        let temp_dir = TempDir::new().unwrap();
+        scaffold_default_templates(temp_dir.path()).await.unwrap();
+
+        for (name, _) in DEFAULT_TEMPLATES {
+            let template = IssueTemplate::load(temp_dir.path(), name).await.unwrap();
+            assert!(template.is_some(), "template {name} should load");
+        }
+        assert_eq!(
+            list_templates(temp_dir.path()).await.unwrap(),
+            vec!["bug", "epic", "feature"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scaffold_keeps_existing_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = template_path(temp_dir.path(), "bug");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "priority: 0\n").unwrap();
+
+        scaffold_default_templates(temp_dir.path()).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "priority: 0\n");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_uses_kind_template_when_present() {
+        let temp_dir = TempDir::new().unwrap();
+        scaffold_default_templates(temp_dir.path()).await.unwrap();
+
+        let bug = IssueTemplate::resolve(temp_dir.path(), None, IssueKind::Bug)
+            .await
+            .unwrap();
+        let task = IssueTemplate::resolve(temp_dir.path(), None, IssueKind::Task)
+            .await
+            .unwrap();
+
+        assert_eq!(bug.unwrap().labels, vec!["bug"]);
+        assert!(task.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_missing_named_template_fails() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let result = IssueTemplate::resolve(temp_dir.path(), Some("nope"), IssueKind::Task).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Config(ConfigError::TemplateNotFound(name))) if name == "nope"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_invalid_priority_and_unknown_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(TEMPLATES_DIR_NAME)).unwrap();
+        std::fs::write(template_path(temp_dir.path(), "high"), "priority: 7\n").unwrap();
+        std::fs::write(template_path(temp_dir.path(), "typo"), "lables: [x]\n").unwrap();
+
+        assert!(IssueTemplate::load(temp_dir.path(), "high").await.is_err());
+        assert!(IssueTemplate::load(temp_dir.path(), "typo").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_invalid_labels() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(TEMPLATES_DIR_NAME)).unwrap();
+        std::fs::write(
+            template_path(temp_dir.path(), "bad"),
+            "labels: [Needs Triage]\n",
+        )
+        .unwrap();
+
+        let result = IssueTemplate::load(temp_dir.path(), "bad").await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Config(ConfigError::InvalidTemplate { name, .. })) if name == "bad"
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case::traversal("../config")]
+    #[case::separator("a/b")]
+    #[case::empty("")]
+    fn test_validate_template_name_rejects(#[case] name: &str) {
+        assert!(validate_template_name(name).is_err());
+    }
+
+    #[test]
+    fn test_apply_respects_explicit_fields() {
+        let template = IssueTemplate {
+            kind: Some(IssueKind::Bug),
+            priority: Some(1),
+            labels: vec!["bug".to_string()],
+            description: Some("Steps\n".to_string()),
+            design: Some("Design".to_string()),
+            acceptance: None,
+        };
+        let mut new_issue = NewIssue {
+            description: "Given".to_string(),
+            labels: vec!["ui".to_string(), "bug".to_string()],
+            ..NewIssue::default()
+        };
+
+        template.apply(
+            &mut new_issue,
+            ExplicitFields {
+                description: true,
+                ..ExplicitFields::default()
+            },
+        );
+
+        assert_eq!(new_issue.issue_kind, IssueKind::Bug);
+        assert_eq!(new_issue.priority, 1);
+        assert_eq!(new_issue.description, "Given");
+        assert_eq!(new_issue.design.as_deref(), Some("Design"));
+        assert_eq!(new_issue.labels, vec!["bug", "ui"]);
+    }
+}
@@ -0,0 +1,330 @@
+//! Client side of the daemon socket.
+
+use super::protocol::{PROTOCOL_VERSION, Reply, Request, WireIssue, domain_issues, wire_issues};
+use super::{DaemonPaths, DaemonStatus};
+use crate::domain::{
+    Dependency, DependencyType, Issue, IssueFilter, IssueId, IssueUpdate, NewIssue, NewResource,
+    ResourceId, ResourceUpdate, SortPolicy,
+};
+use crate::error::{Result, StorageError};
+use crate::storage::IssueStorage;
+use async_trait::async_trait;
+use chrono::Duration;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::Mutex;
+
+/// One open connection to a daemon.
+pub(super) struct Connection {
+    replies: Lines<BufReader<OwnedReadHalf>>,
+    requests: OwnedWriteHalf,
+}
+
+impl Connection {
+    /// Connect to the daemon for `paths`, or `None` if none is running.
+    pub(super) async fn open(paths: &DaemonPaths) -> Result<Option<Self>> {
+        Ok(super::connect(paths).await?.map(|stream| {
+            let (reader, writer) = stream.into_split();
+            Self {
+                replies: BufReader::new(reader).lines(),
+                requests: writer,
+            }
+        }))
+    }
+
+    /// Send one request and decode its reply.
+    pub(super) async fn call<T: DeserializeOwned>(&mut self, request: Request) -> Result<T> {
+        let mut line = serde_json::to_string(&request).map_err(StorageError::Serialization)?;
+        line.push('\n');
+        self.requests.write_all(line.as_bytes()).await?;
+
+        let reply = self.replies.next_line().await?.ok_or_else(|| {
+            StorageError::Daemon("The rivets daemon closed the connection".to_string())
+        })?;
+        match serde_json::from_str(&reply).map_err(StorageError::Serialization)? {
+            Reply::Ok(value) => {
+                Ok(serde_json::from_value(value).map_err(StorageError::Serialization)?)
+            }
+            Reply::Err(error) => Err(error.into()),
+        }
+    }
+}
+
+/// [`IssueStorage`] served by a running daemon.
+///
+/// Every call is forwarded over the daemon's socket, so the caller never
+/// loads the data file itself.
+pub struct DaemonStorage {
+    connection: Mutex<Connection>,
+    status: DaemonStatus,
+}
+
+impl DaemonStorage {
+    /// Connect to the daemon for `paths`.
+    ///
+    /// Returns `None` when no daemon is running, or when the one running
+    /// speaks a different protocol version or serves a different workspace
+    /// (for example, one started by an older binary), so callers can fall
+    /// back to direct file access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket exists but the daemon cannot be talked
+    /// to.
+    pub async fn connect(paths: &DaemonPaths) -> Result<Option<Self>> {
+        let Some(mut connection) = Connection::open(paths).await? else {
+            return Ok(None);
+        };
+        let status: DaemonStatus = connection.call(Request::Status).await?;
+        if status.protocol_version != PROTOCOL_VERSION {
+            tracing::warn!(
+                pid = status.pid,
+                daemon = status.protocol_version,
+                client = PROTOCOL_VERSION,
+                "Ignoring rivets daemon with an incompatible protocol version; restart it"
+            );
+            return Ok(None);
+        }
+        if status.workspace_root != paths.workspace_root() {
+            tracing::warn!(
+                pid = status.pid,
+                daemon = %status.workspace_root.display(),
+                client = %paths.workspace_root().display(),
+                "Ignoring rivets daemon serving a different workspace"
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            connection: Mutex::new(connection),
+            status,
+        }))
+    }
+
+    /// What the daemon reported when this connection was opened.
+    pub fn status(&self) -> &DaemonStatus {
+        &self.status
+    }
+
+    async fn call<T: DeserializeOwned>(&self, request: Request) -> Result<T> {
+        self.connection.lock().await.call(request).await
+    }
+
+    async fn call_issue(&self, request: Request) -> Result<Issue> {
+        self.call::<WireIssue>(request).await.map(|issue| issue.0)
+    }
+
+    async fn call_issues(&self, request: Request) -> Result<Vec<Issue>> {
+        self.call(request).await.map(domain_issues)
+    }
+}
+
+#[async_trait]
+impl IssueStorage for DaemonStorage {
+    async fn create(&mut self, issue: NewIssue) -> Result<Issue> {
+        self.call_issue(Request::Create { issue }).await
+    }
+
+    async fn get(&self, id: &IssueId) -> Result<Option<Issue>> {
+        let issue: Option<WireIssue> = self.call(Request::Get { id: id.clone() }).await?;
+        Ok(issue.map(|issue| issue.0))
+    }
+
+    async fn update(&mut self, id: &IssueId, updates: IssueUpdate) -> Result<Issue> {
+        self.call_issue(Request::Update {
+            id: id.clone(),
+            updates,
+        })
+        .await
+    }
+
+    async fn delete(&mut self, id: &IssueId) -> Result<()> {
+        self.call(Request::Delete { id: id.clone() }).await
+    }
+
+    async fn add_dependency(
+        &mut self,
+        from: &IssueId,
+        to: &IssueId,
+        dep_type: DependencyType,
+    ) -> Result<()> {
+        self.call(Request::AddDependency {
+            from: from.clone(),
+            to: to.clone(),
+            dep_type,
+        })
+        .await
+    }
+
+    async fn remove_dependency(&mut self, from: &IssueId, to: &IssueId) -> Result<()> {
+        self.call(Request::RemoveDependency {
+            from: from.clone(),
+            to: to.clone(),
+        })
+        .await
+    }
+
+    async fn get_dependencies(&self, id: &IssueId) -> Result<Vec<Dependency>> {
+        self.call(Request::GetDependencies { id: id.clone() }).await
+    }
+
+    async fn get_dependents(&self, id: &IssueId) -> Result<Vec<Dependency>> {
+        self.call(Request::GetDependents { id: id.clone() }).await
+    }
+
+    async fn has_cycle(&self, from: &IssueId, to: &IssueId) -> Result<bool> {
+        self.call(Request::HasCycle {
+            from: from.clone(),
+            to: to.clone(),
+        })
+        .await
+    }
+
+    async fn get_dependency_tree(
+        &self,
+        id: &IssueId,
+        max_depth: Option<usize>,
+    ) -> Result<Vec<(Dependency, usize)>> {
+        self.call(Request::GetDependencyTree {
+            id: id.clone(),
+            max_depth,
+        })
+        .await
+    }
+
+    async fn list(&self, filter: &IssueFilter) -> Result<Vec<Issue>> {
+        self.call_issues(Request::List {
+            filter: filter.clone(),
+        })
+        .await
+    }
+
+    async fn ready_to_work(
+        &self,
+        filter: Option<&IssueFilter>,
+        sort_policy: Option<SortPolicy>,
+    ) -> Result<Vec<Issue>> {
+        self.call_issues(Request::ReadyToWork {
+            filter: filter.cloned(),
+            sort_policy,
+        })
+        .await
+    }
+
+    async fn blocked_issues(&self) -> Result<Vec<(Issue, Vec<Issue>)>> {
+        let blocked: Vec<(WireIssue, Vec<WireIssue>)> = self.call(Request::BlockedIssues).await?;
+        Ok(blocked
+            .into_iter()
+            .map(|(issue, blockers)| (issue.0, domain_issues(blockers)))
+            .collect())
+    }
+
+    async fn add_label(&mut self, id: &IssueId, label: &str) -> Result<Issue> {
+        self.call_issue(Request::AddLabel {
+            id: id.clone(),
+            label: label.to_string(),
+        })
+        .await
+    }
+
+    async fn remove_label(&mut self, id: &IssueId, label: &str) -> Result<Issue> {
+        self.call_issue(Request::RemoveLabel {
+            id: id.clone(),
+            label: label.to_string(),
+        })
+        .await
+    }
+
+    async fn add_resource(&mut self, id: &IssueId, resource: NewResource) -> Result<Issue> {
+        self.call_issue(Request::AddResource {
+            id: id.clone(),
+            resource,
+        })
+        .await
+    }
+
+    async fn update_resource(
+        &mut self,
+        id: &IssueId,
+        resource_id: &ResourceId,
+        update: ResourceUpdate,
+    ) -> Result<Issue> {
+        self.call_issue(Request::UpdateResource {
+            id: id.clone(),
+            resource_id: resource_id.clone(),
+            update,
+        })
+        .await
+    }
+
+    async fn remove_resource(&mut self, id: &IssueId, resource_id: &ResourceId) -> Result<Issue> {
+        self.call_issue(Request::RemoveResource {
+            id: id.clone(),
+            resource_id: resource_id.clone(),
+        })
+        .await
+    }
+
+    async fn claim(&mut self, id: &IssueId, claimant: &str, lease: Duration) -> Result<Issue> {
+        self.call_issue(Request::Claim {
+            id: id.clone(),
+            claimant: claimant.to_string(),
+            lease_seconds: lease.num_seconds(),
+        })
+        .await
+    }
+
+    async fn renew_lease(
+        &mut self,
+        id: &IssueId,
+        claimant: &str,
+        lease: Duration,
+    ) -> Result<Issue> {
+        self.call_issue(Request::RenewLease {
+            id: id.clone(),
+            claimant: claimant.to_string(),
+            lease_seconds: lease.num_seconds(),
+        })
+        .await
+    }
+
+    async fn release(&mut self, id: &IssueId, claimant: &str) -> Result<Issue> {
+        self.call_issue(Request::Release {
+            id: id.clone(),
+            claimant: claimant.to_string(),
+        })
+        .await
+    }
+
+    async fn import_issues(&mut self, issues: Vec<Issue>) -> Result<()> {
+        self.call(Request::ImportIssues {
+            issues: wire_issues(issues),
+        })
+        .await
+    }
+
+    async fn export_all(&self) -> Result<Vec<Issue>> {
+        self.call_issues(Request::ExportAll).await
+    }
+
+    async fn restore_issues(&mut self, before: Vec<Issue>, created: &[IssueId]) -> Result<()> {
+        self.call(Request::RestoreIssues {
+            before: wire_issues(before),
+            created: created.to_vec(),
+        })
+        .await
+    }
+
+    async fn save(&self) -> Result<()> {
+        self.call(Request::Save).await
+    }
+
+    async fn reload(&mut self) -> Result<()> {
+        self.call(Request::Reload).await
+    }
+
+    async fn changed_on_disk(&self) -> Result<bool> {
+        self.call(Request::ChangedOnDisk).await
+    }
+}
@@ -0,0 +1,301 @@
+//! Per-workspace background daemon.
+//!
+//! Without a daemon, every CLI invocation re-reads and re-parses the whole
+//! JSONL file and rebuilds the dependency graph. `rivets daemon start` keeps
+//! one workspace's [`IssueStorage`](crate::storage::IssueStorage) loaded in a
+//! background process that serves storage calls over a Unix domain socket
+//! and exits once it has been idle for a while. [`App`](crate::app::App)
+//! routes through the daemon when one is running and falls back to direct
+//! file access otherwise.
+//!
+//! # Files
+//!
+//! Each daemon owns a socket and a PID file in the daemon directory
+//! (`~/.cache/rivets/daemon`, or `$RIVETS_DAEMON_DIR`), named after the first
+//! 8 hex digits of the SHA-256 of the canonical workspace root. The socket is
+//! only accessible to its owner. A socket left behind by a daemon that died
+//! is removed by the next client that finds nobody listening on it.
+//!
+//! The directory deliberately lives outside `~/.rivets`: a `.rivets`
+//! directory in `$HOME` would make every directory below it look like a
+//! rivets workspace.
+//!
+//! # Consistency
+//!
+//! The daemon persists exactly when a client calls `save`, as direct access
+//! does. A client that mutates holds the workspace until it saves, reloads
+//! or disconnects; other clients wait, so nobody reads, saves or discards
+//! another client's unsaved changes. When a client disconnects with
+//! mutations it never saved, the daemon reloads the data file so the changes
+//! are dropped just as they would be when a CLI process exits early. Before
+//! serving a request with no unsaved changes pending, the daemon reloads the
+//! file if another writer changed it.
+//!
+//! See `docs/design/daemon-architecture.md` for the longer-term design.
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::DaemonStorage;
+pub use server::{DaemonConfig, serve};
+
+use crate::error::{Result, StorageError};
+use chrono::{DateTime, Utc};
+use client::Connection;
+use protocol::Request;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::net::UnixStream;
+
+/// Environment variable overriding the directory that holds daemon sockets
+/// and PID files.
+pub const DAEMON_DIR_ENV: &str = "RIVETS_DAEMON_DIR";
+
+/// How often [`wait_until_running`] and [`stop`] poll the socket.
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Where the daemon for one workspace listens and records its PID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemonPaths {
+    workspace_root: PathBuf,
+    socket: PathBuf,
+    pid_file: PathBuf,
+}
+
+impl DaemonPaths {
+    /// Paths for the daemon serving `workspace_root`, inside `daemon_dir`.
+    ///
+    /// The workspace root is canonicalized first, so every path to the same
+    /// workspace maps to the same daemon.
+    pub fn new(daemon_dir: &Path, workspace_root: &Path) -> Self {
+        let workspace_root =
+            std::fs::canonicalize(workspace_root).unwrap_or_else(|_| workspace_root.to_path_buf());
+        let digest = Sha256::digest(workspace_root.as_os_str().as_bytes());
+        let name: String = digest[..4]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        Self {
+            socket: daemon_dir.join(format!("{name}.sock")),
+            pid_file: daemon_dir.join(format!("{name}.pid")),
+            workspace_root,
+        }
+    }
+
+    /// Paths for the daemon serving `workspace_root` in the default daemon
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither `RIVETS_DAEMON_DIR` nor `HOME` is set.
+    pub fn for_workspace(workspace_root: &Path) -> Result<Self> {
+        Ok(Self::new(&default_daemon_dir()?, workspace_root))
+    }
+
+    /// The canonical workspace root the daemon serves.
+    pub fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
+    /// The Unix socket the daemon listens on.
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// The file holding the daemon's process ID.
+    pub fn pid_file(&self) -> &Path {
+        &self.pid_file
+    }
+
+    /// Remove the socket and PID file, ignoring files that are already gone.
+    fn remove_files(&self) {
+        for path in [&self.socket, &self.pid_file] {
+            if let Err(error) = std::fs::remove_file(path)
+                && error.kind() != io::ErrorKind::NotFound
+            {
+                tracing::warn!(path = %path.display(), %error, "Failed to remove daemon file");
+            }
+        }
+    }
+}
+
+/// The default daemon directory: `$RIVETS_DAEMON_DIR`, else
+/// `~/.cache/rivets/daemon`.
+///
+/// # Errors
+///
+/// Returns an error if neither `RIVETS_DAEMON_DIR` nor `HOME` is set.
+pub fn default_daemon_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(DAEMON_DIR_ENV) {
+        return Ok(PathBuf::from(dir));
+    }
+    let home = std::env::var_os("HOME").ok_or_else(|| {
+        StorageError::Daemon(format!(
+            "Cannot locate the daemon directory: set HOME or {DAEMON_DIR_ENV}"
+        ))
+    })?;
+    Ok(PathBuf::from(home)
+        .join(".cache")
+        .join("rivets")
+        .join("daemon"))
+}
+
+/// What a running daemon reports about itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    /// Process ID of the daemon.
+    pub pid: u32,
+
+    /// Canonical root of the workspace it serves.
+    pub workspace_root: PathBuf,
+
+    /// When the daemon started.
+    pub started_at: DateTime<Utc>,
+
+    /// Seconds without requests after which the daemon shuts down.
+    pub idle_timeout_secs: u64,
+
+    /// Version of the socket protocol the daemon speaks.
+    pub protocol_version: u32,
+}
+
+/// Connect to the daemon socket.
+///
+/// Returns `None` when no daemon is running. A socket nobody listens on is
+/// left over from a daemon that died, so it is removed along with its PID
+/// file.
+async fn connect(paths: &DaemonPaths) -> io::Result<Option<UnixStream>> {
+    match UnixStream::connect(&paths.socket).await {
+        Ok(stream) => Ok(Some(stream)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) if error.kind() == io::ErrorKind::ConnectionRefused => {
+            tracing::debug!(socket = %paths.socket.display(), "Removing stale daemon socket");
+            paths.remove_files();
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Ask the daemon for `paths` for its status.
+///
+/// Returns `None` when no daemon is running.
+///
+/// # Errors
+///
+/// Returns an error if the socket exists but the daemon cannot be talked to.
+pub async fn status(paths: &DaemonPaths) -> Result<Option<DaemonStatus>> {
+    match Connection::open(paths).await? {
+        Some(mut connection) => Ok(Some(connection.call(Request::Status).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Wait for the daemon for `paths` to accept connections.
+///
+/// # Errors
+///
+/// Returns an error if it is not running within `timeout`.
+pub async fn wait_until_running(paths: &DaemonPaths, timeout: Duration) -> Result<DaemonStatus> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(status) = status(paths).await? {
+            return Ok(status);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(StorageError::Daemon(format!(
+                "The rivets daemon for {} did not start within {}s",
+                paths.workspace_root.display(),
+                timeout.as_secs()
+            ))
+            .into());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Stop the daemon for `paths` and wait for it to remove its socket.
+///
+/// Returns the stopped daemon's status, or `None` if none was running.
+///
+/// # Errors
+///
+/// Returns an error if the daemon cannot be talked to or does not shut down
+/// within `timeout`.
+pub async fn stop(paths: &DaemonPaths, timeout: Duration) -> Result<Option<DaemonStatus>> {
+    let Some(mut connection) = Connection::open(paths).await? else {
+        return Ok(None);
+    };
+    let status: DaemonStatus = connection.call(Request::Status).await?;
+    connection.call::<()>(Request::Shutdown).await?;
+    drop(connection);
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    while paths.socket.exists() {
+        if tokio::time::Instant::now() >= deadline {
+            return Err(StorageError::Daemon(format!(
+                "The rivets daemon (pid {}) did not stop within {}s",
+                status.pid,
+                timeout.as_secs()
+            ))
+            .into());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(Some(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn paths_are_named_after_the_workspace_hash() {
+        let workspace = TempDir::new().unwrap();
+        let paths = DaemonPaths::new(Path::new("/run/rivets"), workspace.path());
+
+        let name = paths.socket().file_stem().unwrap().to_str().unwrap();
+        assert_eq!(name.len(), 8);
+        assert!(name.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(paths.socket().parent(), Some(Path::new("/run/rivets")));
+        assert_eq!(paths.pid_file(), paths.socket().with_extension("pid"));
+    }
+
+    #[test]
+    fn paths_are_stable_per_workspace_and_distinct_across_workspaces() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let dir = Path::new("/run/rivets");
+
+        let nested = first.path().join(".");
+        assert_eq!(
+            DaemonPaths::new(dir, first.path()),
+            DaemonPaths::new(dir, &nested)
+        );
+        assert_ne!(
+            DaemonPaths::new(dir, first.path()).socket(),
+            DaemonPaths::new(dir, second.path()).socket()
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_socket_is_removed_and_reported_as_not_running() {
+        let dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let paths = DaemonPaths::new(dir.path(), workspace.path());
+        // A bound listener that is dropped leaves its socket file behind.
+        drop(std::os::unix::net::UnixListener::bind(paths.socket()).unwrap());
+        std::fs::write(paths.pid_file(), "1").unwrap();
+
+        assert_eq!(status(&paths).await.unwrap(), None);
+        assert!(!paths.socket().exists());
+        assert!(!paths.pid_file().exists());
+    }
+}
@@ -0,0 +1,292 @@
+//! Wire protocol between the daemon and its clients.
+//!
+//! Each message is one line of JSON. A client writes a [`Request`] and reads
+//! exactly one [`Reply`] before sending the next, so no request IDs are
+//! needed. Requests mirror the [`IssueStorage`](crate::storage::IssueStorage)
+//! methods one to one.
+
+use crate::domain::{
+    DependencyType, Issue, IssueFilter, IssueId, IssueUpdate, LeaseError, NewIssue, NewResource,
+    ResourceId, ResourceUpdate, SortPolicy,
+};
+use crate::error::{Error, StorageError};
+use crate::storage::in_memory::{CanonicalIssueRecord, IssueRecord};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Bumped whenever a request or reply changes shape, so a client never talks
+/// to a daemon started from an incompatible binary.
+pub(super) const PROTOCOL_VERSION: u32 = 2;
+
+/// A call from a client, tagged by method name.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub(super) enum Request {
+    Status,
+    Shutdown,
+    Create {
+        issue: NewIssue,
+    },
+    Get {
+        id: IssueId,
+    },
+    Update {
+        id: IssueId,
+        updates: IssueUpdate,
+    },
+    Delete {
+        id: IssueId,
+    },
+    AddDependency {
+        from: IssueId,
+        to: IssueId,
+        dep_type: DependencyType,
+    },
+    RemoveDependency {
+        from: IssueId,
+        to: IssueId,
+    },
+    GetDependencies {
+        id: IssueId,
+    },
+    GetDependents {
+        id: IssueId,
+    },
+    HasCycle {
+        from: IssueId,
+        to: IssueId,
+    },
+    GetDependencyTree {
+        id: IssueId,
+        max_depth: Option<usize>,
+    },
+    List {
+        filter: IssueFilter,
+    },
+    ReadyToWork {
+        filter: Option<IssueFilter>,
+        sort_policy: Option<SortPolicy>,
+    },
+    BlockedIssues,
+    AddLabel {
+        id: IssueId,
+        label: String,
+    },
+    RemoveLabel {
+        id: IssueId,
+        label: String,
+    },
+    AddResource {
+        id: IssueId,
+        resource: NewResource,
+    },
+    UpdateResource {
+        id: IssueId,
+        resource_id: ResourceId,
+        update: ResourceUpdate,
+    },
+    RemoveResource {
+        id: IssueId,
+        resource_id: ResourceId,
+    },
+    Claim {
+        id: IssueId,
+        claimant: String,
+        lease_seconds: i64,
+    },
+    RenewLease {
+        id: IssueId,
+        claimant: String,
+        lease_seconds: i64,
+    },
+    Release {
+        id: IssueId,
+        claimant: String,
+    },
+    ImportIssues {
+        issues: Vec<WireIssue>,
+    },
+    ExportAll,
+    RestoreIssues {
+        before: Vec<WireIssue>,
+        created: Vec<IssueId>,
+    },
+    Save,
+    Reload,
+    ChangedOnDisk,
+}
+
+impl Request {
+    /// Whether the request changes the daemon's in-memory state without
+    /// persisting it.
+    pub(super) fn is_mutation(&self) -> bool {
+        match self {
+            Self::Create { .. }
+            | Self::Update { .. }
+            | Self::Delete { .. }
+            | Self::AddDependency { .. }
+            | Self::RemoveDependency { .. }
+            | Self::AddLabel { .. }
+            | Self::RemoveLabel { .. }
+            | Self::AddResource { .. }
+            | Self::UpdateResource { .. }
+            | Self::RemoveResource { .. }
+            | Self::Claim { .. }
+            | Self::RenewLease { .. }
+            | Self::Release { .. }
+            | Self::ImportIssues { .. }
+            | Self::RestoreIssues { .. } => true,
+            Self::Status
+            | Self::Shutdown
+            | Self::Get { .. }
+            | Self::GetDependencies { .. }
+            | Self::GetDependents { .. }
+            | Self::HasCycle { .. }
+            | Self::GetDependencyTree { .. }
+            | Self::List { .. }
+            | Self::ReadyToWork { .. }
+            | Self::BlockedIssues
+            | Self::ExportAll
+            | Self::Save
+            | Self::Reload
+            | Self::ChangedOnDisk => false,
+        }
+    }
+}
+
+/// The daemon's answer to one [`Request`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum Reply {
+    /// The method's return value.
+    Ok(serde_json::Value),
+    /// The method's error.
+    Err(WireError),
+}
+
+/// An error carried across the socket.
+///
+/// Errors that callers branch on keep their type; everything else keeps its
+/// message and arrives as [`StorageError::Daemon`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(super) enum WireError {
+    IssueNotFound { id: IssueId },
+    Lease { error: LeaseError },
+    Other { message: String },
+}
+
+impl From<Error> for WireError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::IssueNotFound(id) => Self::IssueNotFound { id },
+            Error::Storage(StorageError::Lease(error)) => Self::Lease { error },
+            error => Self::Other {
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl From<WireError> for Error {
+    fn from(error: WireError) -> Self {
+        match error {
+            WireError::IssueNotFound { id } => Self::IssueNotFound(id),
+            WireError::Lease { error } => StorageError::Lease(error).into(),
+            WireError::Other { message } => StorageError::Daemon(message).into(),
+        }
+    }
+}
+
+/// An [`Issue`] in its persisted record form.
+///
+/// The domain JSON omits state such as the resource identifier sequence, so
+/// issues cross the socket exactly as they are written to the JSONL file and
+/// are validated again on arrival.
+#[derive(Debug)]
+pub(super) struct WireIssue(pub(super) Issue);
+
+impl Serialize for WireIssue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CanonicalIssueRecord::from(self.0.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WireIssue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = IssueRecord::deserialize(deserializer)?;
+        record
+            .into_domain()
+            .map(|conversion| Self(conversion.issue))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Wrap issues for the wire.
+pub(super) fn wire_issues(issues: Vec<Issue>) -> Vec<WireIssue> {
+    issues.into_iter().map(WireIssue).collect()
+}
+
+/// Unwrap issues received from the wire.
+pub(super) fn domain_issues(issues: Vec<WireIssue>) -> Vec<Issue> {
+    issues.into_iter().map(|issue| issue.0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::IssueKind;
+    use crate::storage::in_memory::new_in_memory_storage;
+
+    #[tokio::test]
+    async fn wire_issue_round_trips_through_its_record_form() {
+        let mut storage = new_in_memory_storage("test".to_string());
+        let issue = storage
+            .create(NewIssue {
+                title: "Round trip".to_string(),
+                issue_kind: IssueKind::Bug,
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+
+        let json = serde_json::to_string(&WireIssue(issue.clone())).unwrap();
+        let decoded: WireIssue = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded.0).unwrap(),
+            serde_json::to_value(&issue).unwrap()
+        );
+    }
+
+    #[test]
+    fn double_option_updates_keep_absent_and_null_apart() {
+        let updates = IssueUpdate {
+            assignee: Some(None),
+            ..IssueUpdate::default()
+        };
+        let json = serde_json::to_string(&Request::Update {
+            id: IssueId::new("test-1"),
+            updates,
+        })
+        .unwrap();
+
+        let Request::Update { updates, .. } = serde_json::from_str(&json).unwrap() else {
+            panic!("expected an update request");
+        };
+        assert_eq!(updates.assignee, Some(None));
+        assert_eq!(updates.milestone, None);
+        assert_eq!(updates.due_at, None);
+    }
+
+    #[test]
+    fn lease_errors_keep_their_type() {
+        let error = Error::from(StorageError::Lease(LeaseError::EmptyClaimant));
+        let json = serde_json::to_string(&WireError::from(error)).unwrap();
+        let decoded = Error::from(serde_json::from_str::<WireError>(&json).unwrap());
+
+        assert!(matches!(
+            decoded,
+            Error::Storage(StorageError::Lease(LeaseError::EmptyClaimant))
+        ));
+    }
+}
@@ -0,0 +1,598 @@
+//! The daemon process: one workspace's storage served over a Unix socket.
+
+use super::protocol::{
+    PROTOCOL_VERSION, Reply, Request, WireError, WireIssue, domain_issues, wire_issues,
+};
+use super::{DaemonPaths, DaemonStatus};
+use crate::error::{Result, StorageError};
+use crate::storage::IssueStorage;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{Mutex, Notify, OwnedMutexGuard};
+
+/// How a daemon is run.
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    /// Socket and PID file locations for the workspace.
+    pub paths: DaemonPaths,
+
+    /// How long to wait without requests before shutting down.
+    pub idle_timeout: Duration,
+}
+
+/// Serve `storage` on the daemon socket until asked to stop or idle.
+///
+/// Writes the PID file once the socket is bound and removes both files on
+/// the way out.
+///
+/// # Errors
+///
+/// Returns an error if a daemon is already running for the workspace or the
+/// socket cannot be bound.
+pub async fn serve(config: DaemonConfig, storage: Box<dyn IssueStorage>) -> Result<()> {
+    let DaemonConfig {
+        paths,
+        idle_timeout,
+    } = config;
+    let listener = bind(&paths).await?;
+    std::fs::write(paths.pid_file(), std::process::id().to_string())?;
+
+    let daemon = Arc::new(Daemon {
+        state: Arc::new(Mutex::new(State {
+            storage,
+            unsaved: false,
+        })),
+        status: DaemonStatus {
+            pid: std::process::id(),
+            workspace_root: paths.workspace_root().to_path_buf(),
+            started_at: Utc::now(),
+            idle_timeout_secs: idle_timeout.as_secs(),
+            protocol_version: PROTOCOL_VERSION,
+        },
+        activity: std::sync::Mutex::new(Activity {
+            last: Instant::now(),
+            connections: 0,
+        }),
+        shutdown: Notify::new(),
+    });
+    tracing::info!(socket = %paths.socket().display(), "Rivets daemon listening");
+
+    let mut idle_check = tokio::time::interval(
+        (idle_timeout / 4).clamp(Duration::from_millis(10), Duration::from_secs(60)),
+    );
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    daemon.connection_opened();
+                    tokio::spawn(serve_connection(Arc::clone(&daemon), stream));
+                }
+                Err(error) => tracing::warn!(%error, "Failed to accept daemon connection"),
+            },
+            () = daemon.shutdown.notified() => {
+                tracing::info!("Rivets daemon stopping on request");
+                break;
+            }
+            _ = idle_check.tick() => {
+                if daemon.idle_for() >= idle_timeout {
+                    tracing::info!("Rivets daemon stopping after idle timeout");
+                    break;
+                }
+            }
+        }
+    }
+
+    drop(listener);
+    paths.remove_files();
+    Ok(())
+}
+
+/// Bind the workspace's socket, readable and writable only by its owner.
+async fn bind(paths: &DaemonPaths) -> Result<UnixListener> {
+    if let Some(dir) = paths.socket().parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)?;
+    }
+    // Connecting also clears a stale socket left behind by a dead daemon.
+    if super::connect(paths).await?.is_some() {
+        return Err(StorageError::Daemon(format!(
+            "A rivets daemon is already running for {}",
+            paths.workspace_root().display()
+        ))
+        .into());
+    }
+
+    let listener = UnixListener::bind(paths.socket())?;
+    std::fs::set_permissions(paths.socket(), std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+struct Daemon {
+    /// Held by one client from its first unsaved mutation until it saves,
+    /// reloads or disconnects, so clients never see or persist each other's
+    /// unsaved changes.
+    state: Arc<Mutex<State>>,
+    status: DaemonStatus,
+    activity: std::sync::Mutex<Activity>,
+    shutdown: Notify,
+}
+
+struct State {
+    storage: Box<dyn IssueStorage>,
+    /// Whether the storage holds mutations that were never saved.
+    unsaved: bool,
+}
+
+struct Activity {
+    last: Instant,
+    connections: usize,
+}
+
+impl Daemon {
+    fn activity(&self) -> std::sync::MutexGuard<'_, Activity> {
+        self.activity
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn connection_opened(&self) {
+        let mut activity = self.activity();
+        activity.connections += 1;
+        activity.last = Instant::now();
+    }
+
+    fn connection_closed(&self) {
+        let mut activity = self.activity();
+        activity.connections -= 1;
+        activity.last = Instant::now();
+    }
+
+    fn touch(&self) {
+        self.activity().last = Instant::now();
+    }
+
+    /// How long the daemon has gone without a client; zero while any client
+    /// is connected.
+    fn idle_for(&self) -> Duration {
+        let activity = self.activity();
+        if activity.connections > 0 {
+            Duration::ZERO
+        } else {
+            activity.last.elapsed()
+        }
+    }
+
+    /// Handle one request from a client.
+    ///
+    /// `held` is the state lock the client keeps while it has unsaved
+    /// changes. Status and shutdown requests are answered without the lock,
+    /// so a client in the middle of a change never blocks them.
+    async fn handle(&self, request: Request, held: &mut Option<OwnedMutexGuard<State>>) -> Reply {
+        let result = match request {
+            Request::Status => value(&self.status),
+            Request::Shutdown => value(()),
+            request => {
+                let mut state = match held.take() {
+                    Some(state) => state,
+                    None => Arc::clone(&self.state).lock_owned().await,
+                };
+                let result = state.dispatch(request).await;
+                if state.unsaved {
+                    *held = Some(state);
+                }
+                result
+            }
+        };
+        match result {
+            Ok(value) => Reply::Ok(value),
+            Err(error) => Reply::Err(WireError::from(error)),
+        }
+    }
+}
+
+/// Drop the unsaved changes of a client that disconnected, the way they
+/// would be lost if the CLI process had exited without saving.
+///
+/// Only the disconnected client can have unsaved changes while it holds the
+/// state, so reloading never discards another client's work.
+async fn discard_unsaved(mut state: OwnedMutexGuard<State>) {
+    match state.storage.reload().await {
+        Ok(()) => state.unsaved = false,
+        Err(error) => tracing::warn!(%error, "Failed to discard unsaved daemon changes"),
+    }
+}
+
+impl State {
+    /// Run one storage request, tracking whether it left unsaved changes.
+    async fn dispatch(&mut self, request: Request) -> Result<Value> {
+        let mutation = request.is_mutation();
+        let persists = matches!(request, Request::Save | Request::Reload);
+        let result = self.call(request).await;
+        if result.is_ok() {
+            if mutation {
+                self.unsaved = true;
+            } else if persists {
+                self.unsaved = false;
+            }
+        }
+        result
+    }
+
+    async fn call(&mut self, request: Request) -> Result<Value> {
+        let storage = self.storage.as_mut();
+        if !self.unsaved && storage.changed_on_disk().await? {
+            tracing::debug!("Data file changed on disk; reloading");
+            storage.reload().await?;
+        }
+
+        match request {
+            Request::Status | Request::Shutdown => {
+                unreachable!("status and shutdown are answered without the state")
+            }
+            Request::Create { issue } => value(WireIssue(storage.create(issue).await?)),
+            Request::Get { id } => value(storage.get(&id).await?.map(WireIssue)),
+            Request::Update { id, updates } => {
+                value(WireIssue(storage.update(&id, updates).await?))
+            }
+            Request::Delete { id } => value(storage.delete(&id).await?),
+            Request::AddDependency { from, to, dep_type } => {
+                value(storage.add_dependency(&from, &to, dep_type).await?)
+            }
+            Request::RemoveDependency { from, to } => {
+                value(storage.remove_dependency(&from, &to).await?)
+            }
+            Request::GetDependencies { id } => value(storage.get_dependencies(&id).await?),
+            Request::GetDependents { id } => value(storage.get_dependents(&id).await?),
+            Request::HasCycle { from, to } => value(storage.has_cycle(&from, &to).await?),
+            Request::GetDependencyTree { id, max_depth } => {
+                value(storage.get_dependency_tree(&id, max_depth).await?)
+            }
+            Request::List { filter } => value(wire_issues(storage.list(&filter).await?)),
+            Request::ReadyToWork {
+                filter,
+                sort_policy,
+            } => value(wire_issues(
+                storage.ready_to_work(filter.as_ref(), sort_policy).await?,
+            )),
+            Request::BlockedIssues => {
+                let blocked: Vec<_> = storage
+                    .blocked_issues()
+                    .await?
+                    .into_iter()
+                    .map(|(issue, blockers)| (WireIssue(issue), wire_issues(blockers)))
+                    .collect();
+                value(blocked)
+            }
+            Request::AddLabel { id, label } => {
+                value(WireIssue(storage.add_label(&id, &label).await?))
+            }
+            Request::RemoveLabel { id, label } => {
+                value(WireIssue(storage.remove_label(&id, &label).await?))
+            }
+            Request::AddResource { id, resource } => {
+                value(WireIssue(storage.add_resource(&id, resource).await?))
+            }
+            Request::UpdateResource {
+                id,
+                resource_id,
+                update,
+            } => value(WireIssue(
+                storage.update_resource(&id, &resource_id, update).await?,
+            )),
+            Request::RemoveResource { id, resource_id } => {
+                value(WireIssue(storage.remove_resource(&id, &resource_id).await?))
+            }
+            Request::Claim {
+                id,
+                claimant,
+                lease_seconds,
+            } => value(WireIssue(
+                storage
+                    .claim(&id, &claimant, chrono::Duration::seconds(lease_seconds))
+                    .await?,
+            )),
+            Request::RenewLease {
+                id,
+                claimant,
+                lease_seconds,
+            } => value(WireIssue(
+                storage
+                    .renew_lease(&id, &claimant, chrono::Duration::seconds(lease_seconds))
+                    .await?,
+            )),
+            Request::Release { id, claimant } => {
+                value(WireIssue(storage.release(&id, &claimant).await?))
+            }
+            Request::ImportIssues { issues } => {
+                value(storage.import_issues(domain_issues(issues)).await?)
+            }
+            Request::ExportAll => value(wire_issues(storage.export_all().await?)),
+            Request::RestoreIssues { before, created } => value(
+                storage
+                    .restore_issues(domain_issues(before), &created)
+                    .await?,
+            ),
+            Request::Save => value(storage.save().await?),
+            Request::Reload => value(storage.reload().await?),
+            Request::ChangedOnDisk => value(storage.changed_on_disk().await?),
+        }
+    }
+}
+
+fn value<T: Serialize>(value: T) -> Result<Value> {
+    Ok(serde_json::to_value(value).map_err(StorageError::Serialization)?)
+}
+
+/// Answer one client's requests until it disconnects.
+async fn serve_connection(daemon: Arc<Daemon>, stream: UnixStream) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut held = None;
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(error) => {
+                tracing::debug!(%error, "Daemon connection failed");
+                break;
+            }
+        };
+        daemon.touch();
+
+        let (reply, shutdown) = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let shutdown = matches!(request, Request::Shutdown);
+                (daemon.handle(request, &mut held).await, shutdown)
+            }
+            Err(error) => (
+                Reply::Err(WireError::Other {
+                    message: format!("Malformed daemon request: {error}"),
+                }),
+                false,
+            ),
+        };
+
+        let Ok(mut reply) = serde_json::to_string(&reply) else {
+            break;
+        };
+        reply.push('\n');
+        if let Err(error) = writer.write_all(reply.as_bytes()).await {
+            tracing::debug!(%error, "Failed to reply to daemon client");
+            break;
+        }
+        if shutdown {
+            daemon.shutdown.notify_one();
+            break;
+        }
+    }
+
+    if let Some(state) = held {
+        discard_unsaved(state).await;
+    }
+    daemon.connection_closed();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::{DaemonStorage, status, stop, wait_until_running};
+    use crate::domain::{IssueFilter, LeaseError, NewIssue};
+    use crate::error::Error;
+    use crate::storage::{StorageBackend, create_storage};
+    use tempfile::TempDir;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    struct Fixture {
+        _dirs: (TempDir, TempDir),
+        paths: DaemonPaths,
+        data_file: std::path::PathBuf,
+        server: tokio::task::JoinHandle<Result<()>>,
+    }
+
+    async fn start_daemon(idle_timeout: Duration) -> Fixture {
+        let daemon_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let paths = DaemonPaths::new(daemon_dir.path(), workspace.path());
+        let data_file = workspace.path().join("issues.jsonl");
+        let storage = create_storage(StorageBackend::Jsonl(data_file.clone()), "test".into())
+            .await
+            .unwrap();
+
+        let server = tokio::spawn(serve(
+            DaemonConfig {
+                paths: paths.clone(),
+                idle_timeout,
+            },
+            storage,
+        ));
+        wait_until_running(&paths, TIMEOUT).await.unwrap();
+
+        Fixture {
+            _dirs: (daemon_dir, workspace),
+            paths,
+            data_file,
+            server,
+        }
+    }
+
+    async fn connect(paths: &DaemonPaths) -> DaemonStorage {
+        DaemonStorage::connect(paths).await.unwrap().unwrap()
+    }
+
+    fn new_issue(title: &str) -> NewIssue {
+        NewIssue {
+            title: title.to_string(),
+            ..NewIssue::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_storage_and_persists_on_save() {
+        let fixture = start_daemon(Duration::from_secs(60)).await;
+        let mut client = connect(&fixture.paths).await;
+
+        let created = client.create(new_issue("Served")).await.unwrap();
+        assert_eq!(
+            client.get(&created.id).await.unwrap().unwrap().title,
+            "Served"
+        );
+        assert!(!fixture.data_file.exists());
+
+        client.save().await.unwrap();
+        let saved = std::fs::read_to_string(&fixture.data_file).unwrap();
+        assert!(saved.contains("Served"));
+
+        let other = connect(&fixture.paths).await;
+        let listed = other.list(&IssueFilter::default()).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, created.id);
+
+        drop((client, other));
+        stop(&fixture.paths, TIMEOUT).await.unwrap().unwrap();
+        fixture.server.await.unwrap().unwrap();
+        assert!(!fixture.paths.socket().exists());
+        assert!(!fixture.paths.pid_file().exists());
+    }
+
+    #[tokio::test]
+    async fn errors_keep_their_type_across_the_socket() {
+        let fixture = start_daemon(Duration::from_secs(60)).await;
+        let mut client = connect(&fixture.paths).await;
+        let issue = client.create(new_issue("Claimed")).await.unwrap();
+        let lease = chrono::Duration::hours(1);
+        client.claim(&issue.id, "alice", lease).await.unwrap();
+
+        let held = client.claim(&issue.id, "bob", lease).await.unwrap_err();
+        assert!(matches!(
+            held,
+            Error::Storage(StorageError::Lease(LeaseError::HeldByOther { .. }))
+        ));
+
+        let missing = client
+            .update(&"test-none".into(), Default::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(missing, Error::IssueNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn unsaved_changes_are_dropped_when_their_client_disconnects() {
+        let fixture = start_daemon(Duration::from_secs(60)).await;
+        let mut client = connect(&fixture.paths).await;
+        client.create(new_issue("Kept")).await.unwrap();
+        client.save().await.unwrap();
+        client.create(new_issue("Abandoned")).await.unwrap();
+        drop(client);
+
+        let client = connect(&fixture.paths).await;
+        let titles: Vec<_> = client
+            .list(&IssueFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|issue| issue.title)
+            .collect();
+        assert_eq!(titles, ["Kept"]);
+    }
+
+    #[tokio::test]
+    async fn clients_never_share_unsaved_changes() {
+        let fixture = start_daemon(Duration::from_secs(60)).await;
+        let titles = |issues: Vec<crate::domain::Issue>| {
+            let mut titles: Vec<_> = issues.into_iter().map(|issue| issue.title).collect();
+            titles.sort();
+            titles
+        };
+
+        let mut first = connect(&fixture.paths).await;
+        first.create(new_issue("Abandoned")).await.unwrap();
+
+        // The second client waits while the first has unsaved changes.
+        let mut second = connect(&fixture.paths).await;
+        let mut saving = tokio::spawn(async move {
+            second.create(new_issue("Saved")).await.unwrap();
+            second.save().await.unwrap();
+            second
+        });
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), &mut saving)
+                .await
+                .is_err()
+        );
+
+        // Dropping the first client's change leaves the second client's alone.
+        drop(first);
+        let mut second = saving.await.unwrap();
+        let saved = std::fs::read_to_string(&fixture.data_file).unwrap();
+        assert!(saved.contains("Saved") && !saved.contains("Abandoned"));
+
+        // A client disconnecting after another saved discards only its own change.
+        second.create(new_issue("Pending")).await.unwrap();
+        drop(second);
+        let third = connect(&fixture.paths).await;
+        let listed = third.list(&IssueFilter::default()).await.unwrap();
+        assert_eq!(titles(listed), ["Saved"]);
+    }
+
+    #[tokio::test]
+    async fn reloads_when_the_data_file_changes_on_disk() {
+        let fixture = start_daemon(Duration::from_secs(60)).await;
+        let mut client = connect(&fixture.paths).await;
+        client.create(new_issue("Original")).await.unwrap();
+        client.save().await.unwrap();
+
+        let mut direct = create_storage(
+            StorageBackend::Jsonl(fixture.data_file.clone()),
+            "test".into(),
+        )
+        .await
+        .unwrap();
+        direct.create(new_issue("Written directly")).await.unwrap();
+        direct.save().await.unwrap();
+
+        assert_eq!(client.list(&IssueFilter::default()).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn refuses_to_start_twice_for_one_workspace() {
+        let fixture = start_daemon(Duration::from_secs(60)).await;
+        let storage = create_storage(StorageBackend::InMemory, "test".into())
+            .await
+            .unwrap();
+
+        let error = serve(
+            DaemonConfig {
+                paths: fixture.paths.clone(),
+                idle_timeout: Duration::from_secs(60),
+            },
+            storage,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("already running"));
+        assert!(status(&fixture.paths).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn shuts_down_after_idle_timeout() {
+        let fixture = start_daemon(Duration::from_millis(100)).await;
+
+        tokio::time::timeout(TIMEOUT, fixture.server)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(!fixture.paths.socket().exists());
+        assert_eq!(status(&fixture.paths).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,442 @@
+//! Workspace-declared custom fields.
+//!
+//! A workspace declares typed custom fields in `config.yaml` under
+//! `custom-fields`. Issues carry values for those fields in a dedicated map.
+//! Values are stored in a canonical representation: dates and enum choices
+//! are text (`YYYY-MM-DD` for dates), so equality filtering and persistence do
+//! not depend on the schema; the schema only governs what may be written.
+//!
+//! ```yaml
+//! custom-fields:
+//!   severity:
+//!     type: enum
+//!     values: [low, medium, high]
+//!   story_points:
+//!     type: int
+//! ```
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::find_control_char;
+
+/// Maximum length of a custom field name.
+pub const MAX_CUSTOM_FIELD_NAME_LENGTH: usize = 50;
+
+/// Date format used for `date` custom field values.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The declared type of a custom field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CustomFieldType {
+    /// Free single-line text.
+    String,
+    /// Signed integer.
+    Int,
+    /// One of a fixed set of values.
+    Enum {
+        /// The allowed values.
+        values: Vec<String>,
+    },
+    /// Calendar date, written as `YYYY-MM-DD`.
+    Date,
+    /// `true` or `false`.
+    Bool,
+}
+
+impl fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String => write!(f, "string"),
+            Self::Int => write!(f, "int"),
+            Self::Enum { values } => write!(f, "one of: {}", values.join(", ")),
+            Self::Date => write!(f, "date (YYYY-MM-DD)"),
+            Self::Bool => write!(f, "bool (true or false)"),
+        }
+    }
+}
+
+/// A custom field value in its canonical stored form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CustomFieldValue {
+    /// A boolean value.
+    Bool(bool),
+    /// An integer value.
+    Int(i64),
+    /// A text value (also used for enum choices and dates).
+    Text(String),
+}
+
+impl fmt::Display for CustomFieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// A custom field declaration or value was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CustomFieldError {
+    /// The field is not declared in the workspace configuration.
+    #[error("Unknown custom field '{name}'. Declared fields: {declared}")]
+    UnknownField {
+        /// The rejected field name.
+        name: String,
+        /// Comma-separated declared field names (or "none").
+        declared: String,
+    },
+    /// The value does not match the field's declared type.
+    #[error("Invalid value '{value}' for custom field '{name}': expected {expected}")]
+    InvalidValue {
+        /// The field name.
+        name: String,
+        /// The rejected value.
+        value: String,
+        /// Description of the declared type.
+        expected: String,
+    },
+    /// A `key=value` assignment was malformed.
+    #[error("Invalid custom field assignment '{input}'. Expected key=value")]
+    InvalidAssignment {
+        /// The rejected input.
+        input: String,
+    },
+    /// A declared field name is not valid.
+    #[error(
+        "Invalid custom field name '{name}': must be 1-{MAX_CUSTOM_FIELD_NAME_LENGTH} lowercase letters, digits, hyphens or underscores"
+    )]
+    InvalidName {
+        /// The rejected name.
+        name: String,
+    },
+    /// An enum field was declared without values.
+    #[error("Custom field '{name}' is an enum but declares no values")]
+    EmptyEnum {
+        /// The field name.
+        name: String,
+    },
+}
+
+/// The custom fields declared by a workspace, keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CustomFieldSchema {
+    fields: BTreeMap<String, CustomFieldType>,
+}
+
+impl CustomFieldSchema {
+    /// Build a schema from field declarations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name is invalid or an enum declares no values.
+    pub fn new(fields: BTreeMap<String, CustomFieldType>) -> Result<Self, CustomFieldError> {
+        let schema = Self { fields };
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    /// Check every declaration: names are well formed and enums are non-empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid declaration.
+    pub fn validate(&self) -> Result<(), CustomFieldError> {
+        for (name, field_type) in &self.fields {
+            validate_field_name(name)?;
+            if let CustomFieldType::Enum { values } = field_type
+                && values.is_empty()
+            {
+                return Err(CustomFieldError::EmptyEnum { name: name.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if no custom fields are declared.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The declared type of `name`, if declared.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&CustomFieldType> {
+        self.fields.get(name)
+    }
+
+    /// Iterate over declared fields in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &CustomFieldType)> {
+        self.fields.iter()
+    }
+
+    /// Look up the declared type of `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CustomFieldError::UnknownField`] if the field is undeclared.
+    pub fn field_type(&self, name: &str) -> Result<&CustomFieldType, CustomFieldError> {
+        self.fields
+            .get(name)
+            .ok_or_else(|| CustomFieldError::UnknownField {
+                name: name.to_string(),
+                declared: if self.fields.is_empty() {
+                    "none".to_string()
+                } else {
+                    self.fields.keys().cloned().collect::<Vec<_>>().join(", ")
+                },
+            })
+    }
+
+    /// Parse a raw textual value for `name` into its canonical form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the field is undeclared or the value does not
+    /// match its type.
+    pub fn parse(&self, name: &str, raw: &str) -> Result<CustomFieldValue, CustomFieldError> {
+        let field_type = self.field_type(name)?;
+        let raw = raw.trim();
+        let invalid = || CustomFieldError::InvalidValue {
+            name: name.to_string(),
+            value: raw.to_string(),
+            expected: field_type.to_string(),
+        };
+
+        match field_type {
+            CustomFieldType::String => {
+                if raw.is_empty() || find_control_char(raw).is_some() {
+                    return Err(invalid());
+                }
+                Ok(CustomFieldValue::Text(raw.to_string()))
+            }
+            CustomFieldType::Int => raw
+                .parse()
+                .map(CustomFieldValue::Int)
+                .map_err(|_| invalid()),
+            CustomFieldType::Enum { values } => values
+                .iter()
+                .find(|value| value.as_str() == raw)
+                .map(|value| CustomFieldValue::Text(value.clone()))
+                .ok_or_else(invalid),
+            CustomFieldType::Date => NaiveDate::parse_from_str(raw, DATE_FORMAT)
+                .map(|date| CustomFieldValue::Text(date.format(DATE_FORMAT).to_string()))
+                .map_err(|_| invalid()),
+            CustomFieldType::Bool => raw
+                .parse()
+                .map(CustomFieldValue::Bool)
+                .map_err(|_| invalid()),
+        }
+    }
+
+    /// Check a typed value for `name`, converting it to canonical form.
+    ///
+    /// Text values are parsed as for [`CustomFieldSchema::parse`], so `"5"`
+    /// is accepted for an `int` field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the field is undeclared or the value does not
+    /// match its type.
+    pub fn check(
+        &self,
+        name: &str,
+        value: CustomFieldValue,
+    ) -> Result<CustomFieldValue, CustomFieldError> {
+        let field_type = self.field_type(name)?;
+        match (field_type, value) {
+            (CustomFieldType::Int, value @ CustomFieldValue::Int(_))
+            | (CustomFieldType::Bool, value @ CustomFieldValue::Bool(_)) => Ok(value),
+            (_, CustomFieldValue::Text(raw)) => self.parse(name, &raw),
+            (field_type, value) => Err(CustomFieldError::InvalidValue {
+                name: name.to_string(),
+                value: value.to_string(),
+                expected: field_type.to_string(),
+            }),
+        }
+    }
+
+    /// Parse a `key=value` assignment.
+    ///
+    /// An empty value (`key=`) yields `None`, meaning "clear the field".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input has no `=`, the field is undeclared, or
+    /// the value does not match the field's type.
+    pub fn parse_assignment(
+        &self,
+        input: &str,
+    ) -> Result<(String, Option<CustomFieldValue>), CustomFieldError> {
+        let (name, raw) =
+            input
+                .split_once('=')
+                .ok_or_else(|| CustomFieldError::InvalidAssignment {
+                    input: input.to_string(),
+                })?;
+        let name = name.trim();
+        if raw.trim().is_empty() {
+            self.field_type(name)?;
+            return Ok((name.to_string(), None));
+        }
+        let value = self.parse(name, raw)?;
+        Ok((name.to_string(), Some(value)))
+    }
+}
+
+/// Validate a custom field name.
+///
+/// # Errors
+///
+/// Returns [`CustomFieldError::InvalidName`] if the name is empty, too long, or
+/// contains characters other than lowercase letters, digits, `-` and `_`.
+pub fn validate_field_name(name: &str) -> Result<(), CustomFieldError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_CUSTOM_FIELD_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(CustomFieldError::InvalidName {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Check stored custom field values for well-formed names and text.
+///
+/// Defense-in-depth for values entering through non-CLI paths: only names
+/// and terminal safety are checked here, since the schema is workspace
+/// configuration rather than domain state.
+pub(crate) fn validate_custom_field_values(
+    fields: &BTreeMap<String, CustomFieldValue>,
+) -> Result<(), String> {
+    for (name, value) in fields {
+        validate_field_name(name).map_err(|e| e.to_string())?;
+        if let CustomFieldValue::Text(text) = value
+            && let Some(pos) = find_control_char(text)
+        {
+            return Err(format!(
+                "Custom field '{name}' contains invalid control character at position {pos}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn schema() -> CustomFieldSchema {
+        serde_yaml::from_str(
+            "severity:\n  type: enum\n  values: [low, high]\npoints:\n  type: int\n\
+             due:\n  type: date\ncustomer:\n  type: string\nhotfix:\n  type: bool\n",
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case::enum_value("severity", "high", CustomFieldValue::Text("high".into()))]
+    #[case::int("points", " 5 ", CustomFieldValue::Int(5))]
+    #[case::date("due", "2026-03-01", CustomFieldValue::Text("2026-03-01".into()))]
+    #[case::string("customer", "Acme", CustomFieldValue::Text("Acme".into()))]
+    #[case::bool("hotfix", "true", CustomFieldValue::Bool(true))]
+    fn test_parse_valid_values(
+        #[case] name: &str,
+        #[case] raw: &str,
+        #[case] expected: CustomFieldValue,
+    ) {
+        assert_eq!(schema().parse(name, raw).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::enum_value("severity", "medium")]
+    #[case::int("points", "five")]
+    #[case::date("due", "03/01/2026")]
+    #[case::bool("hotfix", "yes")]
+    #[case::empty_string("customer", " ")]
+    fn test_parse_invalid_values(#[case] name: &str, #[case] raw: &str) {
+        assert!(matches!(
+            schema().parse(name, raw),
+            Err(CustomFieldError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_unknown_field_lists_declared_fields() {
+        let error = schema().parse("component", "ui").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown custom field 'component'. Declared fields: customer, due, hotfix, points, severity"
+        );
+    }
+
+    #[test]
+    fn test_check_coerces_text_and_rejects_mismatched_types() {
+        let schema = schema();
+        assert_eq!(
+            schema
+                .check("points", CustomFieldValue::Text("3".into()))
+                .unwrap(),
+            CustomFieldValue::Int(3)
+        );
+        assert!(
+            schema
+                .check("points", CustomFieldValue::Bool(true))
+                .is_err()
+        );
+        assert!(schema.check("due", CustomFieldValue::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        let schema = schema();
+        assert_eq!(
+            schema.parse_assignment("points=8").unwrap(),
+            ("points".to_string(), Some(CustomFieldValue::Int(8)))
+        );
+        assert_eq!(
+            schema.parse_assignment("points=").unwrap(),
+            ("points".to_string(), None)
+        );
+        assert!(schema.parse_assignment("points").is_err());
+        assert!(schema.parse_assignment("nope=").is_err());
+    }
+
+    #[test]
+    fn test_schema_rejects_invalid_declarations() {
+        let empty_enum = BTreeMap::from([(
+            "severity".to_string(),
+            CustomFieldType::Enum { values: vec![] },
+        )]);
+        assert!(CustomFieldSchema::new(empty_enum).is_err());
+
+        let bad_name = BTreeMap::from([("Story Points".to_string(), CustomFieldType::Int)]);
+        assert!(CustomFieldSchema::new(bad_name).is_err());
+    }
+
+    #[test]
+    fn test_values_roundtrip_through_json() {
+        let values = BTreeMap::from([
+            ("a".to_string(), CustomFieldValue::Bool(false)),
+            ("b".to_string(), CustomFieldValue::Int(-2)),
+            ("c".to_string(), CustomFieldValue::Text("x".into())),
+        ]);
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(json, r#"{"a":false,"b":-2,"c":"x"}"#);
+        let parsed: BTreeMap<String, CustomFieldValue> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, values);
+    }
+}
@@ -0,0 +1,136 @@
+//! Due dates and due-date windows.
+//!
+//! A due date is stored as a UTC timestamp. Callers may supply either a full
+//! RFC 3339 timestamp or a calendar date (`YYYY-MM-DD`); a bare date means the
+//! end of that day in UTC, so an issue due "today" is not overdue until the
+//! day is over.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+
+use super::duration::parse_suffixed_duration;
+
+/// Accepted due date formats, for error messages.
+pub const DUE_DATE_FORMATS: &str = "YYYY-MM-DD or an RFC 3339 timestamp";
+
+/// Accepted due window formats, for error messages.
+pub const DUE_WINDOW_FORMATS: &str = "a number followed by h, d or w (e.g. 7d)";
+
+/// A due date or due window could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DueDateError {
+    /// The due date was not a date or timestamp.
+    #[error("Invalid due date '{input}'. Expected {DUE_DATE_FORMATS}")]
+    InvalidDate {
+        /// The rejected input.
+        input: String,
+    },
+    /// The window was not a positive count of hours, days or weeks.
+    #[error("Invalid due window '{input}'. Expected {DUE_WINDOW_FORMATS}")]
+    InvalidWindow {
+        /// The rejected input.
+        input: String,
+    },
+}
+
+/// Parse a due date.
+///
+/// # Errors
+///
+/// Returns [`DueDateError::InvalidDate`] if the input is neither a
+/// `YYYY-MM-DD` date nor an RFC 3339 timestamp.
+///
+/// # Examples
+///
+/// ```
+/// use rivets::domain::parse_due_date;
+///
+/// let due = parse_due_date("2026-03-01").unwrap();
+/// assert_eq!(due.to_rfc3339(), "2026-03-01T23:59:59+00:00");
+/// assert!(parse_due_date("next week").is_err());
+/// ```
+pub fn parse_due_date(input: &str) -> Result<DateTime<Utc>, DueDateError> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).expect("valid time of day");
+        return Ok(date.and_time(end_of_day).and_utc());
+    }
+    DateTime::parse_from_rfc3339(input)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| DueDateError::InvalidDate {
+            input: input.to_string(),
+        })
+}
+
+/// Parse a due window such as `12h`, `7d` or `2w`.
+///
+/// # Errors
+///
+/// Returns [`DueDateError::InvalidWindow`] if the input is not a positive
+/// count followed by `h`, `d` or `w`.
+pub fn parse_due_window(input: &str) -> Result<Duration, DueDateError> {
+    parse_suffixed_duration(
+        input,
+        &[
+            ("h", Duration::try_hours),
+            ("d", Duration::try_days),
+            ("w", Duration::try_weeks),
+        ],
+    )
+    .ok_or_else(|| DueDateError::InvalidWindow {
+        input: input.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn date_means_end_of_day_utc() {
+        let due = parse_due_date("2026-03-01").unwrap();
+        assert_eq!(due.to_rfc3339(), "2026-03-01T23:59:59+00:00");
+    }
+
+    #[test]
+    fn timestamp_is_normalized_to_utc() {
+        let due = parse_due_date("2026-03-01T09:00:00+02:00").unwrap();
+        assert_eq!(due.to_rfc3339(), "2026-03-01T07:00:00+00:00");
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("tomorrow")]
+    #[case("2026-02-30")]
+    #[case("03/01/2026")]
+    fn invalid_dates_are_rejected(#[case] input: &str) {
+        assert!(matches!(
+            parse_due_date(input),
+            Err(DueDateError::InvalidDate { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case("12h", Duration::hours(12))]
+    #[case("7d", Duration::days(7))]
+    #[case(" 2w ", Duration::weeks(2))]
+    fn windows_parse(#[case] input: &str, #[case] expected: Duration) {
+        assert_eq!(parse_due_window(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("d")]
+    #[case("0d")]
+    #[case("-1d")]
+    #[case("7")]
+    #[case("7m")]
+    #[case("7é")]
+    #[case("9999999999999w")]
+    fn invalid_windows_are_rejected(#[case] input: &str) {
+        assert!(matches!(
+            parse_due_window(input),
+            Err(DueDateError::InvalidWindow { .. })
+        ));
+    }
+}
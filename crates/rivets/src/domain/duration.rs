@@ -0,0 +1,23 @@
+//! Durations written as a positive count and a unit suffix, such as `30m`
+//! or `7d`.
+//!
+//! Due windows and lease durations share this syntax but accept different
+//! units and report their own errors.
+
+use chrono::Duration;
+
+/// A unit suffix and the function building a [`Duration`] of that many units.
+pub(super) type DurationUnit = (&'static str, fn(i64) -> Option<Duration>);
+
+/// Parse `input` as a positive count followed by one of `units`.
+///
+/// Returns `None` if the count is missing or not positive, the unit is not
+/// one of `units`, or the duration overflows.
+pub(super) fn parse_suffixed_duration(input: &str, units: &[DurationUnit]) -> Option<Duration> {
+    let trimmed = input.trim();
+    let (unit_start, _) = trimmed.char_indices().last()?;
+    let (count, unit) = trimmed.split_at(unit_start);
+    let count: i64 = count.parse().ok().filter(|count| *count > 0)?;
+    let (_, build) = units.iter().find(|(suffix, _)| *suffix == unit)?;
+    build(count)
+}
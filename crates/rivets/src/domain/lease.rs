@@ -0,0 +1,371 @@
+//! Work claims: an agent's time-limited lease on an issue.
+//!
+//! Claiming an issue assigns it to the claimant and records when the claim
+//! lapses. While the lease is active, other claimants cannot take the issue
+//! and `ready` hides it from them. A lease that expires without being renewed
+//! marks abandoned work: the issue shows up in `stale` and can be claimed by
+//! someone else.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::duration::parse_suffixed_duration;
+use super::{Issue, IssueId, IssueStatus};
+
+/// Accepted lease duration formats, for error messages.
+pub const LEASE_DURATION_FORMATS: &str = "a number followed by m, h or d (e.g. 30m)";
+
+/// Lease length used when a claim or renewal does not specify one.
+pub const DEFAULT_LEASE_DURATION: Duration = Duration::hours(1);
+
+/// A claimant's hold on an issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Lease {
+    /// Agent identity holding the claim.
+    pub holder: String,
+
+    /// When the claim lapses unless renewed.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Lease {
+    /// Whether the lease still holds at `now`.
+    #[must_use]
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at > now
+    }
+}
+
+/// A claim, renewal or release was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum LeaseError {
+    /// The claimant identity was empty.
+    #[error("Claimant must not be empty")]
+    EmptyClaimant,
+    /// The lease duration was not a positive count of minutes, hours or days.
+    #[error("Invalid lease duration '{input}'. Expected {LEASE_DURATION_FORMATS}")]
+    InvalidDuration {
+        /// The rejected input.
+        input: String,
+    },
+    /// Closed issues cannot be claimed.
+    #[error("Issue {0} is closed and cannot be claimed")]
+    Closed(IssueId),
+    /// Blocked issues cannot be claimed.
+    #[error("Issue {0} is blocked and cannot be claimed")]
+    Blocked(IssueId),
+    /// Another claimant holds an active lease.
+    #[error("Issue {id} is claimed by {holder} until {expires_at}")]
+    HeldByOther {
+        /// The claimed issue.
+        id: IssueId,
+        /// The current lease holder.
+        holder: String,
+        /// When their lease lapses.
+        expires_at: DateTime<Utc>,
+    },
+    /// The issue is assigned to someone without a lease.
+    #[error("Issue {id} is already assigned to {assignee}")]
+    Assigned {
+        /// The assigned issue.
+        id: IssueId,
+        /// The current assignee.
+        assignee: String,
+    },
+    /// The claimant does not hold the issue's lease.
+    #[error("Issue {id} is not claimed by {claimant}")]
+    NotHolder {
+        /// The issue.
+        id: IssueId,
+        /// The claimant that tried to renew or release.
+        claimant: String,
+    },
+}
+
+/// Parse a lease duration such as `30m`, `2h` or `1d`.
+///
+/// # Errors
+///
+/// Returns [`LeaseError::InvalidDuration`] if the input is not a positive
+/// count followed by `m`, `h` or `d`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::Duration;
+/// use rivets::domain::parse_lease_duration;
+///
+/// assert_eq!(parse_lease_duration("30m").unwrap(), Duration::minutes(30));
+/// assert!(parse_lease_duration("soon").is_err());
+/// ```
+pub fn parse_lease_duration(input: &str) -> Result<Duration, LeaseError> {
+    parse_suffixed_duration(
+        input,
+        &[
+            ("m", Duration::try_minutes),
+            ("h", Duration::try_hours),
+            ("d", Duration::try_days),
+        ],
+    )
+    .ok_or_else(|| LeaseError::InvalidDuration {
+        input: input.to_string(),
+    })
+}
+
+impl Issue {
+    /// The lease on this issue, if it is still active at `now`.
+    #[must_use]
+    pub fn active_lease(&self, now: DateTime<Utc>) -> Option<&Lease> {
+        self.lease.as_ref().filter(|lease| lease.is_active(now))
+    }
+
+    /// Whether the issue's lease lapsed without being renewed or released.
+    #[must_use]
+    pub fn has_expired_lease(&self, now: DateTime<Utc>) -> bool {
+        self.lease
+            .as_ref()
+            .is_some_and(|lease| !lease.is_active(now))
+    }
+
+    /// Whether `ready` should hide this issue from `claimant`.
+    ///
+    /// Issues under another claimant's active lease are hidden; `None` hides
+    /// every actively leased issue.
+    #[must_use]
+    pub fn is_leased_to_other(&self, claimant: Option<&str>, now: DateTime<Utc>) -> bool {
+        self.active_lease(now)
+            .is_some_and(|lease| Some(lease.holder.as_str()) != claimant)
+    }
+
+    /// Assign the issue to `claimant` with a lease lapsing at `expires_at`.
+    ///
+    /// The issue must be unassigned, already held by `claimant`, or held
+    /// under a lease that has expired. Callers check that it is not blocked.
+    pub(crate) fn claim(
+        &mut self,
+        claimant: &str,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(), LeaseError> {
+        if claimant.trim().is_empty() {
+            return Err(LeaseError::EmptyClaimant);
+        }
+        if self.status == IssueStatus::Closed {
+            return Err(LeaseError::Closed(self.id.clone()));
+        }
+        if let Some(lease) = self.active_lease(now)
+            && lease.holder != claimant
+        {
+            return Err(LeaseError::HeldByOther {
+                id: self.id.clone(),
+                holder: lease.holder.clone(),
+                expires_at: lease.expires_at,
+            });
+        }
+        let lease_holder = self.lease.as_ref().map(|lease| lease.holder.as_str());
+        if let Some(assignee) = &self.assignee
+            && assignee != claimant
+            && Some(assignee.as_str()) != lease_holder
+        {
+            return Err(LeaseError::Assigned {
+                id: self.id.clone(),
+                assignee: assignee.clone(),
+            });
+        }
+
+        self.assignee = Some(claimant.to_string());
+        self.lease = Some(Lease {
+            holder: claimant.to_string(),
+            expires_at,
+        });
+        Ok(())
+    }
+
+    /// Extend `claimant`'s lease to `expires_at`.
+    ///
+    /// An expired lease can be renewed as long as nobody else claimed the
+    /// issue in the meantime.
+    pub(crate) fn renew_lease(
+        &mut self,
+        claimant: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), LeaseError> {
+        let lease = self.held_lease_mut(claimant)?;
+        lease.expires_at = expires_at;
+        Ok(())
+    }
+
+    /// Give up `claimant`'s lease and unassign the issue.
+    pub(crate) fn release_lease(&mut self, claimant: &str) -> Result<(), LeaseError> {
+        self.held_lease_mut(claimant)?;
+        self.lease = None;
+        self.assignee = None;
+        Ok(())
+    }
+
+    /// Drop a lease the issue's other changes have made meaningless: the
+    /// issue was closed or reassigned to someone other than the holder.
+    pub(crate) fn clear_orphaned_lease(&mut self) {
+        if self.lease.as_ref().is_some_and(|lease| {
+            self.status == IssueStatus::Closed
+                || self.assignee.as_deref() != Some(lease.holder.as_str())
+        }) {
+            self.lease = None;
+        }
+    }
+
+    fn held_lease_mut(&mut self, claimant: &str) -> Result<&mut Lease, LeaseError> {
+        let id = self.id.clone();
+        self.lease
+            .as_mut()
+            .filter(|lease| lease.holder == claimant)
+            .ok_or_else(|| LeaseError::NotHolder {
+                id,
+                claimant: claimant.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::IssueKind;
+    use rstest::rstest;
+
+    fn issue() -> Issue {
+        let now = Utc::now();
+        Issue {
+            id: IssueId::new("test-1"),
+            title: "Claimable".to_string(),
+            description: String::new(),
+            status: IssueStatus::Open,
+            priority: 2,
+            issue_kind: IssueKind::Task,
+            assignee: None,
+            labels: vec![],
+            design: None,
+            acceptance_criteria: None,
+            custom_fields: Default::default(),
+            notes: vec![],
+            resources: vec![],
+            next_resource_id: 1,
+            dependencies: vec![],
+            created_at: now,
+            updated_at: now,
+            closed_at: None,
+            due_at: None,
+            milestone: None,
+            lease: None,
+        }
+    }
+
+    #[test]
+    fn claim_assigns_and_excludes_other_claimants() {
+        let now = Utc::now();
+        let mut issue = issue();
+        issue
+            .claim("agent-a", now + Duration::hours(1), now)
+            .unwrap();
+        assert_eq!(issue.assignee.as_deref(), Some("agent-a"));
+        assert!(issue.is_leased_to_other(Some("agent-b"), now));
+        assert!(issue.is_leased_to_other(None, now));
+        assert!(!issue.is_leased_to_other(Some("agent-a"), now));
+
+        assert!(matches!(
+            issue.claim("agent-b", now + Duration::hours(1), now),
+            Err(LeaseError::HeldByOther { holder, .. }) if holder == "agent-a"
+        ));
+        // Claiming again is a renewal for the holder.
+        issue
+            .claim("agent-a", now + Duration::hours(2), now)
+            .unwrap();
+    }
+
+    #[test]
+    fn expired_lease_can_be_taken_over() {
+        let now = Utc::now();
+        let mut issue = issue();
+        issue
+            .claim("agent-a", now - Duration::minutes(1), now)
+            .unwrap();
+        assert!(issue.has_expired_lease(now));
+        assert!(!issue.is_leased_to_other(None, now));
+
+        issue
+            .claim("agent-b", now + Duration::hours(1), now)
+            .unwrap();
+        assert_eq!(issue.assignee.as_deref(), Some("agent-b"));
+        assert!(matches!(
+            issue.renew_lease("agent-a", now + Duration::hours(1)),
+            Err(LeaseError::NotHolder { .. })
+        ));
+    }
+
+    #[test]
+    fn assigned_or_closed_issues_cannot_be_claimed() {
+        let now = Utc::now();
+        let mut assigned = issue();
+        assigned.assignee = Some("alice".to_string());
+        assert!(matches!(
+            assigned.claim("agent-a", now + Duration::hours(1), now),
+            Err(LeaseError::Assigned { .. })
+        ));
+
+        let mut closed = issue();
+        closed.status = IssueStatus::Closed;
+        assert!(matches!(
+            closed.claim("agent-a", now + Duration::hours(1), now),
+            Err(LeaseError::Closed(_))
+        ));
+        assert!(matches!(
+            issue().claim(" ", now + Duration::hours(1), now),
+            Err(LeaseError::EmptyClaimant)
+        ));
+    }
+
+    #[test]
+    fn release_unassigns_and_closing_clears_the_lease() {
+        let now = Utc::now();
+        let mut issue = issue();
+        issue
+            .claim("agent-a", now + Duration::hours(1), now)
+            .unwrap();
+        assert!(matches!(
+            issue.release_lease("agent-b"),
+            Err(LeaseError::NotHolder { .. })
+        ));
+        issue.release_lease("agent-a").unwrap();
+        assert!(issue.lease.is_none());
+        assert!(issue.assignee.is_none());
+
+        issue
+            .claim("agent-a", now + Duration::hours(1), now)
+            .unwrap();
+        issue.status = IssueStatus::Closed;
+        issue.clear_orphaned_lease();
+        assert!(issue.lease.is_none());
+    }
+
+    #[rstest]
+    #[case("30m", Duration::minutes(30))]
+    #[case("2h", Duration::hours(2))]
+    #[case(" 1d ", Duration::days(1))]
+    fn durations_parse(#[case] input: &str, #[case] expected: Duration) {
+        assert_eq!(parse_lease_duration(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("m")]
+    #[case("0m")]
+    #[case("-5m")]
+    #[case("30")]
+    #[case("1w")]
+    fn invalid_durations_are_rejected(#[case] input: &str) {
+        assert!(matches!(
+            parse_lease_duration(input),
+            Err(LeaseError::InvalidDuration { .. })
+        ));
+    }
+}
@@ -0,0 +1,414 @@
+//! Milestones: named, time-boxed groups of issues such as sprints.
+//!
+//! A milestone is identified by a short name (e.g. `sprint-12`) and covers an
+//! inclusive range of calendar days. Issues reference a milestone by name.
+//! Milestones are persisted separately from issues, in
+//! `.rivets/milestones.jsonl`.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{Issue, IssueStatus};
+
+/// Maximum length of a milestone name.
+pub const MAX_MILESTONE_NAME_LENGTH: usize = 50;
+
+/// A milestone was rejected or could not be found.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MilestoneError {
+    /// The milestone name is not valid.
+    #[error(
+        "Invalid milestone name '{name}': must be 1-{MAX_MILESTONE_NAME_LENGTH} ASCII letters, digits, dots, hyphens or underscores"
+    )]
+    InvalidName {
+        /// The rejected name.
+        name: String,
+    },
+    /// The milestone ends before it starts.
+    #[error("Milestone '{name}' ends ({ends_on}) before it starts ({starts_on})")]
+    InvalidRange {
+        /// The milestone name.
+        name: String,
+        /// First day of the milestone.
+        starts_on: NaiveDate,
+        /// Last day of the milestone.
+        ends_on: NaiveDate,
+    },
+    /// No milestone with this name exists.
+    #[error("Milestone not found: {0}")]
+    NotFound(String),
+    /// A milestone with this name already exists.
+    #[error("Milestone already exists: {0}")]
+    AlreadyExists(String),
+    /// The milestone is already closed.
+    #[error("Milestone '{0}' is already closed")]
+    AlreadyClosed(String),
+    /// No open milestone covers today's date.
+    #[error("No open milestone covers {0}")]
+    NoCurrent(NaiveDate),
+}
+
+/// Lifecycle state of a milestone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MilestoneState {
+    /// Work is planned or in progress.
+    Open,
+    /// The milestone is finished.
+    Closed,
+}
+
+impl fmt::Display for MilestoneState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => write!(f, "open"),
+            Self::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// A named, time-boxed group of issues.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Milestone {
+    /// Unique milestone name, referenced by issues
+    pub name: String,
+
+    /// First day of the milestone (inclusive)
+    pub starts_on: NaiveDate,
+
+    /// Last day of the milestone (inclusive)
+    pub ends_on: NaiveDate,
+
+    /// Lifecycle state
+    pub state: MilestoneState,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Closed timestamp (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl Milestone {
+    /// Create an open milestone covering `starts_on..=ends_on`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MilestoneError::InvalidName`] for an invalid name and
+    /// [`MilestoneError::InvalidRange`] if `ends_on` is before `starts_on`.
+    pub fn new(
+        name: impl Into<String>,
+        starts_on: NaiveDate,
+        ends_on: NaiveDate,
+        now: DateTime<Utc>,
+    ) -> Result<Self, MilestoneError> {
+        let milestone = Self {
+            name: name.into(),
+            starts_on,
+            ends_on,
+            state: MilestoneState::Open,
+            created_at: now,
+            closed_at: None,
+        };
+        milestone.validate()?;
+        Ok(milestone)
+    }
+
+    /// Check the milestone's invariants.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Milestone::new`].
+    pub fn validate(&self) -> Result<(), MilestoneError> {
+        validate_milestone_name(&self.name)?;
+        if self.ends_on < self.starts_on {
+            return Err(MilestoneError::InvalidRange {
+                name: self.name.clone(),
+                starts_on: self.starts_on,
+                ends_on: self.ends_on,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether the milestone is open.
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.state == MilestoneState::Open
+    }
+
+    /// Whether `date` falls within the milestone's date range.
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.starts_on <= date && date <= self.ends_on
+    }
+
+    /// Close the milestone at `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MilestoneError::AlreadyClosed`] if the milestone is closed.
+    pub fn close(&mut self, now: DateTime<Utc>) -> Result<(), MilestoneError> {
+        if !self.is_open() {
+            return Err(MilestoneError::AlreadyClosed(self.name.clone()));
+        }
+        self.state = MilestoneState::Closed;
+        self.closed_at = Some(now);
+        Ok(())
+    }
+}
+
+/// Pick the current milestone on `today`.
+///
+/// The current milestone is the open milestone whose date range contains
+/// `today`. If several overlap, the one ending first wins (ties broken by
+/// name), since it is the most pressing.
+#[must_use]
+pub fn current_milestone(milestones: &[Milestone], today: NaiveDate) -> Option<&Milestone> {
+    milestones
+        .iter()
+        .filter(|milestone| milestone.is_open() && milestone.contains(today))
+        .min_by(|a, b| a.ends_on.cmp(&b.ends_on).then_with(|| a.name.cmp(&b.name)))
+}
+
+/// Validate a milestone name.
+///
+/// Names are used as references on issues and on the command line, so only
+/// ASCII alphanumerics, dots, hyphens and underscores are allowed.
+///
+/// # Errors
+///
+/// Returns [`MilestoneError::InvalidName`] if the name is empty, too long or
+/// contains other characters.
+pub fn validate_milestone_name(name: &str) -> Result<(), MilestoneError> {
+    if name.is_empty()
+        || name.len() > MAX_MILESTONE_NAME_LENGTH
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err(MilestoneError::InvalidName {
+            name: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Number of open issues remaining at the end of one milestone day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BurndownPoint {
+    /// The calendar day
+    pub date: NaiveDate,
+    /// Issues created by the end of the day and not yet closed
+    pub remaining: usize,
+}
+
+/// Completion figures for the issues in a milestone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MilestoneProgress {
+    /// Number of issues in the milestone
+    pub total: usize,
+    /// Number of closed issues in the milestone
+    pub closed: usize,
+    /// Remaining issues at the end of each elapsed milestone day
+    pub burndown: Vec<BurndownPoint>,
+}
+
+impl MilestoneProgress {
+    /// Compute progress for `milestone` from its issues, as of `today`.
+    ///
+    /// `issues` should be the issues referencing the milestone. Burn-down
+    /// points cover each day from the start of the milestone up to the
+    /// earlier of its end and `today`. Issue history is not recorded, so an
+    /// issue counts towards a day from its creation, not from when it was
+    /// added to the milestone.
+    #[must_use]
+    pub fn compute(milestone: &Milestone, issues: &[Issue], today: NaiveDate) -> Self {
+        let closed = issues
+            .iter()
+            .filter(|issue| issue.status == IssueStatus::Closed)
+            .count();
+
+        let last_day = milestone.ends_on.min(today);
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).expect("valid time of day");
+        let burndown = milestone
+            .starts_on
+            .iter_days()
+            .take_while(|date| *date <= last_day)
+            .map(|date| {
+                let cutoff = date.and_time(end_of_day).and_utc();
+                let remaining = issues
+                    .iter()
+                    .filter(|issue| {
+                        issue.created_at <= cutoff
+                            && issue.closed_at.is_none_or(|closed_at| closed_at > cutoff)
+                    })
+                    .count();
+                BurndownPoint { date, remaining }
+            })
+            .collect();
+
+        Self {
+            total: issues.len(),
+            closed,
+            burndown,
+        }
+    }
+
+    /// Percentage of issues closed, rounded down. An empty milestone is 0%.
+    #[must_use]
+    pub fn percent_complete(&self) -> usize {
+        (self.closed * 100).checked_div(self.total).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{IssueId, IssueKind};
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn at(s: &str, hour: u32) -> DateTime<Utc> {
+        let day = date(s);
+        Utc.from_utc_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+    }
+
+    fn milestone(name: &str, start: &str, end: &str) -> Milestone {
+        Milestone::new(name, date(start), date(end), at(start, 0)).unwrap()
+    }
+
+    fn issue(id: &str, created: DateTime<Utc>, closed: Option<DateTime<Utc>>) -> Issue {
+        Issue {
+            id: IssueId::new(id),
+            title: id.to_string(),
+            description: String::new(),
+            status: if closed.is_some() {
+                IssueStatus::Closed
+            } else {
+                IssueStatus::Open
+            },
+            priority: 2,
+            issue_kind: IssueKind::Task,
+            assignee: None,
+            labels: vec![],
+            design: None,
+            acceptance_criteria: None,
+            custom_fields: Default::default(),
+            notes: vec![],
+            resources: vec![],
+            next_resource_id: 1,
+            dependencies: vec![],
+            created_at: created,
+            updated_at: created,
+            closed_at: closed,
+            due_at: None,
+            milestone: None,
+            lease: None,
+        }
+    }
+
+    #[rstest]
+    #[case("sprint-12")]
+    #[case("v1.2")]
+    #[case("Q3_2026")]
+    fn valid_names(#[case] name: &str) {
+        assert!(validate_milestone_name(name).is_ok());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("sprint 12")]
+    #[case("sprint/12")]
+    #[case("é")]
+    fn invalid_names(#[case] name: &str) {
+        assert!(matches!(
+            validate_milestone_name(name),
+            Err(MilestoneError::InvalidName { .. })
+        ));
+    }
+
+    #[test]
+    fn end_before_start_is_rejected() {
+        let result = Milestone::new("m", date("2026-03-10"), date("2026-03-01"), Utc::now());
+        assert!(matches!(result, Err(MilestoneError::InvalidRange { .. })));
+    }
+
+    #[test]
+    fn close_is_not_repeatable() {
+        let mut m = milestone("m", "2026-03-01", "2026-03-14");
+        m.close(at("2026-03-14", 12)).unwrap();
+        assert_eq!(m.state, MilestoneState::Closed);
+        assert!(m.closed_at.is_some());
+        assert_eq!(
+            m.close(Utc::now()),
+            Err(MilestoneError::AlreadyClosed("m".into()))
+        );
+    }
+
+    #[test]
+    fn current_milestone_prefers_open_and_earliest_end() {
+        let mut closed = milestone("closed", "2026-03-01", "2026-03-05");
+        closed.close(Utc::now()).unwrap();
+        let milestones = vec![
+            closed,
+            milestone("long", "2026-03-01", "2026-03-31"),
+            milestone("short", "2026-03-01", "2026-03-14"),
+            milestone("later", "2026-04-01", "2026-04-14"),
+        ];
+        let today = date("2026-03-03");
+        assert_eq!(current_milestone(&milestones, today).unwrap().name, "short");
+        assert_eq!(
+            current_milestone(&milestones, date("2026-03-20"))
+                .unwrap()
+                .name,
+            "long"
+        );
+        assert!(current_milestone(&milestones, date("2026-05-01")).is_none());
+    }
+
+    #[test]
+    fn progress_counts_completion_and_burndown() {
+        let m = milestone("m", "2026-03-01", "2026-03-05");
+        let issues = vec![
+            issue("a", at("2026-02-20", 9), Some(at("2026-03-02", 10))),
+            issue("b", at("2026-02-20", 9), None),
+            issue("c", at("2026-03-03", 9), Some(at("2026-03-03", 17))),
+            issue("d", at("2026-03-03", 9), None),
+        ];
+
+        let progress = MilestoneProgress::compute(&m, &issues, date("2026-03-04"));
+        assert_eq!(progress.total, 4);
+        assert_eq!(progress.closed, 2);
+        assert_eq!(progress.percent_complete(), 50);
+
+        let remaining: Vec<_> = progress
+            .burndown
+            .iter()
+            .map(|point| (point.date.to_string(), point.remaining))
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![
+                ("2026-03-01".to_string(), 2),
+                ("2026-03-02".to_string(), 1),
+                ("2026-03-03".to_string(), 2),
+                ("2026-03-04".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn progress_before_start_has_no_burndown() {
+        let m = milestone("m", "2026-03-01", "2026-03-05");
+        let progress = MilestoneProgress::compute(&m, &[], date("2026-02-01"));
+        assert!(progress.burndown.is_empty());
+        assert_eq!(progress.percent_complete(), 0);
+    }
+}
@@ -4,18 +4,38 @@
 
 use chrono::{DateTime, Utc};
 use clap::ValueEnum;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::OnceLock;
 
+mod custom_field;
+mod due;
+mod duration;
+mod lease;
+mod milestone;
 mod resource;
 #[cfg(test)]
 mod workspace_path_corpus;
 
+pub use custom_field::{
+    CustomFieldError, CustomFieldSchema, CustomFieldType, CustomFieldValue,
+    MAX_CUSTOM_FIELD_NAME_LENGTH, validate_field_name,
+};
+pub use due::{
+    DUE_DATE_FORMATS, DUE_WINDOW_FORMATS, DueDateError, parse_due_date, parse_due_window,
+};
+pub use lease::{
+    DEFAULT_LEASE_DURATION, LEASE_DURATION_FORMATS, Lease, LeaseError, parse_lease_duration,
+};
+pub use milestone::{
+    BurndownPoint, MAX_MILESTONE_NAME_LENGTH, Milestone, MilestoneError, MilestoneProgress,
+    MilestoneState, current_milestone, validate_milestone_name,
+};
 pub use resource::{
-    AssociatedResource, NewResource, ResourceError, ResourceId, ResourceLabel, ResourceRole,
-    ResourceTarget, ResourceUpdate, WebUrl, WorkspacePath,
+    AssociatedResource, CommitSha, NewResource, ResourceError, ResourceId, ResourceLabel,
+    ResourceRole, ResourceTarget, ResourceUpdate, WebUrl, WorkspacePath,
 };
 
 /// Unique identifier for an issue
@@ -23,6 +43,7 @@ pub use resource::{
 /// Wraps a string ID in a newtype for type safety. The inner field is private
 /// to enforce encapsulation and allow future changes to the ID format.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct IssueId(String);
 
 impl IssueId {
@@ -56,7 +77,8 @@ impl From<&str> for IssueId {
 }
 
 /// Validated content for a Note that has not yet been timestamped.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
 pub struct NoteContent(String);
 
 impl NoteContent {
@@ -103,6 +125,38 @@ impl NoteContent {
     }
 }
 
+impl<'de> Deserialize<'de> for NoteContent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_validated(deserializer, Self::new)
+    }
+}
+
+/// Deserialize a string newtype through its validating constructor, so
+/// decoded values uphold the same invariants as constructed ones.
+fn deserialize_validated<'de, D, T, E>(
+    deserializer: D,
+    new: impl FnOnce(String) -> Result<T, E>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    E: fmt::Display,
+{
+    let raw = String::deserialize(deserializer)?;
+    new(raw).map_err(serde::de::Error::custom)
+}
+
+/// Deserialize a present field of a double-Option update.
+///
+/// On a defaulted field skipped when `None`, an absent field decodes as `None` (leave unchanged) and an explicit
+/// `null` as `Some(None)` (clear).
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 /// A failure to construct valid Note content.
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum NoteError {
@@ -119,6 +173,7 @@ pub enum NoteError {
 
 /// An immutable, timestamped entry in an Issue's chronological history.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Note {
     content: String,
     created_at: DateTime<Utc>,
@@ -154,6 +209,7 @@ impl Note {
 /// `IssueStorage::get_dependencies()` rather than being stored on the Issue
 /// itself. This prevents data duplication and ensures a single source of truth.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Issue {
     /// Unique identifier for the issue
     pub id: IssueId,
@@ -185,6 +241,10 @@ pub struct Issue {
     /// Acceptance criteria (optional)
     pub acceptance_criteria: Option<String>,
 
+    /// Values of workspace-declared custom fields, keyed by field name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+
     /// Ordered, append-only Note history
     pub(crate) notes: Vec<Note>,
 
@@ -218,9 +278,27 @@ pub struct Issue {
 
     /// Closed timestamp (optional)
     pub closed_at: Option<DateTime<Utc>>,
+
+    /// Target completion timestamp (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// Name of the milestone this issue is planned for (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+
+    /// Claim held on the issue by an agent (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease: Option<Lease>,
 }
 
 impl Issue {
+    /// Whether the issue is still open past its due date at `now`.
+    #[must_use]
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.status != IssueStatus::Closed && self.due_at.is_some_and(|due_at| due_at < now)
+    }
+
     /// Return Notes in chronological insertion order.
     pub fn notes(&self) -> &[Note] {
         &self.notes
@@ -404,7 +482,11 @@ impl Issue {
             &self.labels,
             self.design.as_deref(),
             self.acceptance_criteria.as_deref(),
-        )
+        )?;
+        if let Some(milestone) = &self.milestone {
+            validate_milestone_name(milestone).map_err(|e| e.to_string())?;
+        }
+        custom_field::validate_custom_field_values(&self.custom_fields)
     }
 }
 
@@ -423,6 +505,7 @@ pub(crate) fn join_canonical_names<T: ValueEnum + fmt::Display>() -> String {
 /// Status of an issue
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum IssueStatus {
     /// Issue is open and ready to work on
     Open,
@@ -538,6 +621,7 @@ impl FromStr for IssueStatus {
 /// Current classification of an issue
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum IssueKind {
     /// Bug fix
     Bug,
@@ -597,6 +681,7 @@ impl FromStr for IssueKind {
 
 /// Dependency between issues
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Dependency {
     /// ID of the issue this depends on
     pub depends_on_id: IssueId,
@@ -608,6 +693,7 @@ pub struct Dependency {
 /// Type of dependency relationship
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum DependencyType {
     /// Hard blocker - prevents work
     Blocks,
@@ -675,12 +761,15 @@ impl FromStr for DependencyType {
 /// Sort policy for ready work queries.
 ///
 /// Controls how ready-to-work issues are ordered in the results.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortPolicy {
     /// Hybrid sorting (default): Recent issues (< 48h) by priority, older by age.
     ///
     /// This balances urgency with preventing starvation of older issues:
-    /// - Issues created within the last 48 hours are sorted by priority (P0 first)
+    /// - Issues created within the last 48 hours are sorted by priority (P0 first);
+    ///   within a priority, issues overdue or due within 72 hours come first,
+    ///   soonest due first
     /// - Older issues are sorted by creation date (oldest first)
     /// - Recent issues come before older issues at the same priority level
     #[default]
@@ -822,7 +911,7 @@ fn validate_text_fields(
 }
 
 /// Data for creating a new issue
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewIssue {
     /// Issue title
     pub title: String,
@@ -851,6 +940,15 @@ pub struct NewIssue {
     /// Initial Note recorded with the Issue creation timestamp
     pub initial_note: Option<NoteContent>,
 
+    /// Custom field values (validated against the workspace schema by callers)
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+
+    /// Target completion timestamp (optional)
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// Milestone name (optional; existence is checked by callers)
+    pub milestone: Option<String>,
+
     /// Dependencies
     pub dependencies: Vec<(IssueId, DependencyType)>,
 }
@@ -872,7 +970,11 @@ impl NewIssue {
             &self.labels,
             self.design.as_deref(),
             self.acceptance_criteria.as_deref(),
-        )
+        )?;
+        if let Some(milestone) = &self.milestone {
+            validate_milestone_name(milestone).map_err(|e| e.to_string())?;
+        }
+        custom_field::validate_custom_field_values(&self.custom_fields)
     }
 }
 
@@ -896,13 +998,17 @@ impl Default for NewIssue {
             design: None,
             acceptance_criteria: None,
             initial_note: None,
+            custom_fields: BTreeMap::new(),
             dependencies: vec![],
+            due_at: None,
+            milestone: None,
         }
     }
 }
 
 /// Data for updating an existing issue
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct IssueUpdate {
     /// New title (if updating)
     pub title: Option<String>,
@@ -925,6 +1031,10 @@ pub struct IssueUpdate {
     /// - `None`: Don't modify the assignee (leave unchanged)
     /// - `Some(None)`: Clear the assignee (set to unassigned)
     /// - `Some(Some(name))`: Set assignee to the given name
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present"
+    )]
     pub assignee: Option<Option<String>>,
 
     /// New design notes (if updating)
@@ -938,10 +1048,31 @@ pub struct IssueUpdate {
 
     /// New labels (if updating) - replaces existing labels
     pub labels: Option<Vec<String>>,
+
+    /// New due date (if updating), using the same double-Option pattern as
+    /// `assignee`: `Some(None)` clears the due date.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present"
+    )]
+    pub due_at: Option<Option<DateTime<Utc>>>,
+
+    /// New milestone (if updating); `Some(None)` removes the issue from its
+    /// milestone.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present"
+    )]
+    pub milestone: Option<Option<String>>,
+
+    /// Custom field changes: `Some` sets a value, `None` removes the field.
+    /// Fields not present are left unchanged.
+    pub custom_fields: BTreeMap<String, Option<CustomFieldValue>>,
 }
 
 /// Filter for querying issues
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct IssueFilter {
     /// Filter by status
     pub status: Option<IssueStatus>,
@@ -958,6 +1089,19 @@ pub struct IssueFilter {
     /// Filter by label
     pub label: Option<String>,
 
+    /// Filter by custom field values (all must match)
+    pub custom_fields: Vec<(String, CustomFieldValue)>,
+
+    /// Only include issues that are past their due date and not closed
+    pub overdue: bool,
+
+    /// Filter by milestone name
+    pub milestone: Option<String>,
+
+    /// Claimant whose own leases stay visible in `ready_to_work`; issues
+    /// under anyone else's active lease are excluded from it
+    pub claimant: Option<String>,
+
     /// Limit number of results
     pub limit: Option<usize>,
 }
@@ -1573,6 +1717,10 @@ mod tests {
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
                 closed_at: None,
+                custom_fields: Default::default(),
+                due_at: None,
+                milestone: None,
+                lease: None,
             }
         }
 
@@ -5,12 +5,12 @@
 //! effect on workflow or readiness. See ADR-0003 and `CONTEXT.md`.
 
 use clap::ValueEnum;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::str::FromStr;
 use std::sync::OnceLock;
 
-use super::{find_control_char, join_canonical_names};
+use super::{deserialize_present, deserialize_validated, find_control_char, join_canonical_names};
 
 /// Opaque, stable identifier of an Associated Resource within its Issue.
 ///
@@ -19,6 +19,7 @@ use super::{find_control_char, join_canonical_names};
 /// without positional indices.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ResourceId(String);
 
 impl ResourceId {
@@ -52,12 +53,19 @@ impl fmt::Display for ResourceId {
     }
 }
 
+impl<'de> Deserialize<'de> for ResourceId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_validated(deserializer, Self::new)
+    }
+}
+
 /// A validated absolute HTTP or HTTPS URL.
 ///
 /// Construction parses and normalizes the URL so that equality and duplicate
 /// detection are insensitive to purely syntactic differences.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WebUrl(String);
 
 impl WebUrl {
@@ -105,6 +113,12 @@ impl fmt::Display for WebUrl {
     }
 }
 
+impl<'de> Deserialize<'de> for WebUrl {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_validated(deserializer, Self::new)
+    }
+}
+
 /// Opaque causal detail for malformed Web URL syntax.
 ///
 /// The URL parser's concrete error remains private so third-party parser types
@@ -136,6 +150,7 @@ pub struct WebUrlSyntaxError {
 /// name are distinct targets (tracked in rivets-yuom).
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WorkspacePath(String);
 
 impl WorkspacePath {
@@ -198,6 +213,53 @@ impl fmt::Display for WorkspacePath {
     }
 }
 
+impl<'de> Deserialize<'de> for WorkspacePath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_validated(deserializer, Self::new)
+    }
+}
+
+/// The object name of a Git commit in the Workspace's repository.
+///
+/// Stored in lowercase so the same commit always compares equal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CommitSha(String);
+
+impl CommitSha {
+    /// Parse an abbreviated or full SHA-1 or SHA-256 object name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ResourceError::InvalidCommitSha`] unless the value is 7 to
+    /// 64 hexadecimal digits.
+    pub fn new(raw: impl Into<String>) -> Result<Self, ResourceError> {
+        let raw = raw.into();
+        if !(7..=64).contains(&raw.len()) || !raw.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ResourceError::InvalidCommitSha { sha: raw });
+        }
+        Ok(Self(raw.to_ascii_lowercase()))
+    }
+
+    /// Get the object name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CommitSha {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for CommitSha {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_validated(deserializer, Self::new)
+    }
+}
+
 /// A `C:`-style drive prefix anchors the path outside any workspace on
 /// Windows even without a separator (`C:notes.txt` is drive-relative there).
 fn starts_with_drive_prefix(raw: &str) -> bool {
@@ -208,10 +270,12 @@ fn starts_with_drive_prefix(raw: &str) -> bool {
 /// The location of an Associated Resource.
 ///
 /// A Web URL is absolute; a Workspace Path is normalized relative to its
-/// Workspace root and cannot escape that boundary. See ADR-0003 and
+/// Workspace root and cannot escape that boundary; a Commit names a commit in
+/// the Workspace's repository that has no web link. See ADR-0003 and
 /// `CONTEXT.md`.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ResourceTarget {
     /// An absolute HTTP or HTTPS URL.
     Web {
@@ -223,6 +287,11 @@ pub enum ResourceTarget {
         /// The validated, normalized path.
         path: WorkspacePath,
     },
+    /// A Git commit, shown as `git:<sha>`.
+    Commit {
+        /// The commit's object name.
+        sha: CommitSha,
+    },
 }
 
 impl ResourceTarget {
@@ -235,6 +304,11 @@ impl ResourceTarget {
     pub fn path(path: WorkspacePath) -> Self {
         Self::Path { path }
     }
+
+    /// Construct a Commit target from a validated [`CommitSha`].
+    pub fn commit(sha: CommitSha) -> Self {
+        Self::Commit { sha }
+    }
 }
 
 impl fmt::Display for ResourceTarget {
@@ -242,6 +316,7 @@ impl fmt::Display for ResourceTarget {
         match self {
             Self::Web { url } => write!(f, "{url}"),
             Self::Path { path } => write!(f, "{path}"),
+            Self::Commit { sha } => write!(f, "git:{sha}"),
         }
     }
 }
@@ -251,6 +326,7 @@ impl fmt::Display for ResourceTarget {
     Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum,
 )]
 #[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ResourceRole {
     /// Delivers work for the Issue (e.g., an implementation PR).
     Implementation,
@@ -308,6 +384,7 @@ impl FromStr for ResourceRole {
 /// A validated human-readable label for an Associated Resource.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ResourceLabel(String);
 
 impl ResourceLabel {
@@ -341,12 +418,19 @@ impl fmt::Display for ResourceLabel {
     }
 }
 
+impl<'de> Deserialize<'de> for ResourceLabel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_validated(deserializer, Self::new)
+    }
+}
+
 /// A typed reference from an Issue to relevant information or an artifact.
 ///
 /// Constructed only through validated paths (`Issue::add_resource` or the
 /// persistence boundary), so every instance carries a valid target, role,
 /// and label.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AssociatedResource {
     id: ResourceId,
     target: ResourceTarget,
@@ -404,7 +488,7 @@ impl fmt::Display for AssociatedResource {
 ///
 /// The resource identifier is assigned by the domain when the resource is
 /// added; callers supply only target, role, and optional label.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NewResource {
     /// Where the resource points.
     pub target: ResourceTarget,
@@ -421,13 +505,18 @@ pub struct NewResource {
 /// shifts the resource's position or reissues its identifier. The label uses
 /// the double-Option pattern (same as `IssueUpdate::assignee`): `None` keeps
 /// the current label, `Some(None)` clears it, and `Some(Some(label))` sets it.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ResourceUpdate {
     /// New target (if updating).
     pub target: Option<ResourceTarget>,
     /// New role (if updating).
     pub role: Option<ResourceRole>,
     /// New label (if updating); `Some(None)` clears the label.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present"
+    )]
     pub label: Option<Option<ResourceLabel>>,
 }
 
@@ -507,6 +596,12 @@ pub enum ResourceError {
         /// The rejected value.
         path: String,
     },
+    /// The commit was not a hexadecimal object name.
+    #[error("Invalid commit '{sha}': expected 7 to 64 hexadecimal digits")]
+    InvalidCommitSha {
+        /// The rejected value.
+        sha: String,
+    },
     /// An identical target-and-role association already exists on the Issue.
     #[error("Resource with target '{target}' and role '{role}' already exists on this issue")]
     DuplicateTargetRole {
@@ -872,6 +967,19 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn commit_sha_normalizes_case_and_rejects_non_hex() {
+        let sha = CommitSha::new("A1B2C3D4E5F6").expect("valid SHA");
+        assert_eq!(sha.as_str(), "a1b2c3d4e5f6");
+        assert_eq!(ResourceTarget::commit(sha).to_string(), "git:a1b2c3d4e5f6");
+        for invalid in ["a1b2c3", "a1b2c3d4e5g6", &"a".repeat(65)] {
+            assert!(matches!(
+                CommitSha::new(invalid),
+                Err(ResourceError::InvalidCommitSha { .. })
+            ));
+        }
+    }
+
     #[test]
     fn associated_resource_display_includes_optional_label() {
         let resource = AssociatedResource::from_parts(
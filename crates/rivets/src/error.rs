@@ -1,6 +1,8 @@
 //! Error types for rivets CLI operations.
 
-use crate::domain::{IssueId, ResourceError, StatusTransitionError};
+use crate::domain::{
+    CustomFieldError, IssueId, LeaseError, MilestoneError, ResourceError, StatusTransitionError,
+};
 use std::{fmt, io};
 use thiserror::Error;
 
@@ -63,6 +65,27 @@ pub enum ConfigError {
     /// is a configuration-level concern regardless of which layer detects it.
     #[error("Storage backend not yet implemented: {0}")]
     UnsupportedBackend(String),
+
+    /// An explicitly requested issue template does not exist.
+    #[error("Template '{0}' not found in .rivets/templates")]
+    TemplateNotFound(String),
+
+    /// An issue template has an invalid name or content.
+    #[error("Invalid template '{name}': {reason}")]
+    InvalidTemplate {
+        /// The template name.
+        name: String,
+        /// Why the template was rejected.
+        reason: String,
+    },
+
+    /// The `custom-fields` section of the configuration is invalid.
+    #[error("Invalid custom field declaration: {0}")]
+    InvalidCustomField(#[source] CustomFieldError),
+
+    /// Two hooks in the configuration share a name.
+    #[error("Hook '{0}' is declared more than once")]
+    DuplicateHook(String),
 }
 
 /// The reason one persisted Issue record was omitted during resilient loading.
@@ -98,6 +121,19 @@ pub enum SkippedIssueRecordCause {
         #[source]
         source: ResourceError,
     },
+    /// The record reuses the ID of an earlier record.
+    #[error(
+        "line {line_number}: issue {issue_id} duplicates the ID on line {first_line_number}; \
+         run `rivets doctor --fix-ids`"
+    )]
+    DuplicateId {
+        /// Physical 1-based line number in the JSONL file.
+        line_number: usize,
+        /// The duplicated issue identifier.
+        issue_id: IssueId,
+        /// Line number of the record that kept the ID.
+        first_line_number: usize,
+    },
 }
 
 impl SkippedIssueRecordCause {
@@ -107,7 +143,8 @@ impl SkippedIssueRecordCause {
         match self {
             Self::MalformedJson { line_number, .. }
             | Self::InvalidIssueData { line_number, .. }
-            | Self::InvalidResourceData { line_number, .. } => *line_number,
+            | Self::InvalidResourceData { line_number, .. }
+            | Self::DuplicateId { line_number, .. } => *line_number,
         }
     }
 }
@@ -222,6 +259,29 @@ pub enum StorageError {
     /// (ADR-0005).
     #[error(transparent)]
     InvalidStatusTransition(#[from] StatusTransitionError),
+
+    /// A claim, lease renewal or release was rejected.
+    #[error(transparent)]
+    Lease(#[from] LeaseError),
+
+    /// The data file changed on disk after it was loaded, so saving would
+    /// overwrite another writer's changes.
+    #[error(
+        "{} changed on disk since it was loaded; reload and retry",
+        .0.display()
+    )]
+    ConcurrentModification(std::path::PathBuf),
+
+    /// A write was attempted on issues loaded from a git revision.
+    #[error("Issues at revision {0} are read-only")]
+    ReadOnlyRevision(String),
+
+    /// The rivets daemon rejected a request or could not be talked to.
+    ///
+    /// Errors the daemon raises keep their message, so a command routed
+    /// through the daemon fails with the same text as one run directly.
+    #[error("{0}")]
+    Daemon(String),
 }
 
 impl StorageError {
@@ -245,7 +305,11 @@ impl StorageError {
             | Self::InvalidFormat(_)
             | Self::UnsafePartialLoad(_)
             | Self::Serialization(_)
-            | Self::InvalidStatusTransition(_)) => Err(error),
+            | Self::InvalidStatusTransition(_)
+            | Self::Lease(_)
+            | Self::ConcurrentModification(_)
+            | Self::ReadOnlyRevision(_)
+            | Self::Daemon(_)) => Err(error),
         }
     }
 
@@ -271,7 +335,35 @@ impl StorageError {
             | Self::InvalidFormat(_)
             | Self::UnsafePartialLoad(_)
             | Self::Serialization(_)
-            | Self::Resource(_)) => Err(error),
+            | Self::Resource(_)
+            | Self::Lease(_)
+            | Self::ConcurrentModification(_)
+            | Self::ReadOnlyRevision(_)
+            | Self::Daemon(_)) => Err(error),
+        }
+    }
+
+    /// Separates a rejected claim, renewal or release from other storage
+    /// failures, for the same reason as
+    /// [`try_into_resource_error`](Self::try_into_resource_error).
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error unchanged when it is not a lease rejection.
+    pub fn try_into_lease_error(self) -> std::result::Result<LeaseError, Self> {
+        match self {
+            Self::Lease(source) => Ok(source),
+            error @ (Self::Validation(_)
+            | Self::IdGeneration(_)
+            | Self::DuplicateDependency { .. }
+            | Self::InvalidFormat(_)
+            | Self::UnsafePartialLoad(_)
+            | Self::Serialization(_)
+            | Self::Resource(_)
+            | Self::InvalidStatusTransition(_)
+            | Self::ConcurrentModification(_)
+            | Self::ReadOnlyRevision(_)
+            | Self::Daemon(_)) => Err(error),
         }
     }
 }
@@ -354,6 +446,14 @@ pub enum Error {
     #[error("Issue already exists: {0}")]
     IssueAlreadyExists(IssueId),
 
+    /// Running `git` failed, or git rejected the request.
+    #[error("Git error: {0}")]
+    Git(String),
+
+    /// A milestone was invalid, missing or in the wrong state.
+    #[error(transparent)]
+    Milestone(#[from] MilestoneError),
+
     /// JSON parsing error (e.g., loading corrupt JSONL files).
     ///
     /// Note: Storage-layer serialization failures use [`StorageError::Serialization`]
@@ -0,0 +1,462 @@
+//! Typed change events.
+//!
+//! A change to a workspace is described as [`IssueEvent`]s by comparing
+//! every issue before and after it. The local REST API streams them to
+//! WebSocket subscribers, and [hooks](crate::hooks) deliver them to
+//! configured commands and HTTP endpoints.
+
+use crate::domain::{AssociatedResource, Dependency, Issue, IssueId, IssueStatus, ResourceId};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Fields reported by their own event kinds rather than in
+/// [`IssueEvent::Updated`], or not worth reporting on their own.
+const FIELDS_WITH_OWN_EVENTS: [&str; 3] = ["dependencies", "resources", "updated_at"];
+
+/// Fields a status event already reports.
+const STATUS_FIELDS: [&str; 2] = ["status", "closed_at"];
+
+/// One field of an issue that changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// The field, as named in the issue JSON.
+    pub field: String,
+    /// The previous value; `null` if the field was unset.
+    pub before: Value,
+    /// The new value; `null` if the field is now unset.
+    pub after: Value,
+}
+
+/// How an Associated Resource changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceChange {
+    /// The resource was attached.
+    Added,
+    /// The resource's target, role or label changed.
+    Updated,
+    /// The resource was removed.
+    Removed,
+}
+
+/// A change to one issue.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IssueEvent {
+    /// The issue was created.
+    Created {
+        /// The new issue.
+        issue: Box<Issue>,
+    },
+    /// Fields of the issue other than its status, dependencies and
+    /// resources changed.
+    Updated {
+        /// The changed issue.
+        issue_id: IssueId,
+        /// The fields that changed.
+        changes: Vec<FieldChange>,
+    },
+    /// The issue was closed.
+    Closed {
+        /// The closed issue.
+        issue_id: IssueId,
+        /// When it was closed.
+        closed_at: Option<DateTime<Utc>>,
+    },
+    /// A closed issue was reopened.
+    Reopened {
+        /// The reopened issue.
+        issue_id: IssueId,
+        /// Its new status.
+        status: IssueStatus,
+    },
+    /// The issue was deleted.
+    Deleted {
+        /// The deleted issue.
+        issue_id: IssueId,
+    },
+    /// The issue gained a dependency.
+    DependencyAdded {
+        /// The dependent issue.
+        issue_id: IssueId,
+        /// The new dependency.
+        dependency: Dependency,
+    },
+    /// The issue lost a dependency.
+    DependencyRemoved {
+        /// The dependent issue.
+        issue_id: IssueId,
+        /// The removed dependency.
+        dependency: Dependency,
+    },
+    /// One of the issue's Associated Resources was added, updated or removed.
+    ResourceChanged {
+        /// The issue the resource belongs to.
+        issue_id: IssueId,
+        /// The resource's stable identifier.
+        resource_id: ResourceId,
+        /// How it changed.
+        change: ResourceChange,
+        /// The resource as it is now; absent once removed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resource: Option<AssociatedResource>,
+    },
+}
+
+/// The kinds of [`IssueEvent`], as named in their `type` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum EventKind {
+    /// An issue was created.
+    Created,
+    /// Fields of an issue changed.
+    Updated,
+    /// An issue was closed.
+    Closed,
+    /// An issue was reopened.
+    Reopened,
+    /// An issue was deleted.
+    Deleted,
+    /// An issue gained a dependency.
+    DependencyAdded,
+    /// An issue lost a dependency.
+    DependencyRemoved,
+    /// An Associated Resource was added, updated or removed.
+    ResourceChanged,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Closed => "closed",
+            Self::Reopened => "reopened",
+            Self::Deleted => "deleted",
+            Self::DependencyAdded => "dependency_added",
+            Self::DependencyRemoved => "dependency_removed",
+            Self::ResourceChanged => "resource_changed",
+        };
+        f.write_str(name)
+    }
+}
+
+impl IssueEvent {
+    /// The kind of this event.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::Created { .. } => EventKind::Created,
+            Self::Updated { .. } => EventKind::Updated,
+            Self::Closed { .. } => EventKind::Closed,
+            Self::Reopened { .. } => EventKind::Reopened,
+            Self::Deleted { .. } => EventKind::Deleted,
+            Self::DependencyAdded { .. } => EventKind::DependencyAdded,
+            Self::DependencyRemoved { .. } => EventKind::DependencyRemoved,
+            Self::ResourceChanged { .. } => EventKind::ResourceChanged,
+        }
+    }
+
+    /// The issue the event is about.
+    pub fn issue_id(&self) -> &IssueId {
+        match self {
+            Self::Created { issue } => &issue.id,
+            Self::Updated { issue_id, .. }
+            | Self::Closed { issue_id, .. }
+            | Self::Reopened { issue_id, .. }
+            | Self::Deleted { issue_id }
+            | Self::DependencyAdded { issue_id, .. }
+            | Self::DependencyRemoved { issue_id, .. }
+            | Self::ResourceChanged { issue_id, .. } => issue_id,
+        }
+    }
+}
+
+/// What an event is about, for matching it against subscriptions.
+///
+/// Labels and assignees from both before and after the change are kept, so
+/// a subscriber watching a label also sees the change that removed it.
+#[derive(Debug, Clone)]
+pub(crate) struct Subject {
+    pub(crate) issue_id: IssueId,
+    pub(crate) labels: BTreeSet<String>,
+    pub(crate) assignees: BTreeSet<String>,
+}
+
+impl Subject {
+    fn of(issue_id: &IssueId, versions: &[Option<&Issue>]) -> Self {
+        let versions = versions.iter().flatten();
+        Self {
+            issue_id: issue_id.clone(),
+            labels: versions
+                .clone()
+                .flat_map(|issue| issue.labels.iter().cloned())
+                .collect(),
+            assignees: versions
+                .filter_map(|issue| issue.assignee.clone())
+                .collect(),
+        }
+    }
+}
+
+/// The events that turn one snapshot of a workspace into another, ordered by
+/// issue ID.
+pub(crate) fn events_between(before: &[Issue], after: &[Issue]) -> Vec<(Subject, IssueEvent)> {
+    let before: BTreeMap<&IssueId, &Issue> = before.iter().map(|i| (&i.id, i)).collect();
+    let after: BTreeMap<&IssueId, &Issue> = after.iter().map(|i| (&i.id, i)).collect();
+    let ids: BTreeSet<&IssueId> = before.keys().chain(after.keys()).copied().collect();
+
+    let mut events = Vec::new();
+    for id in ids {
+        let old = before.get(id).copied();
+        let new = after.get(id).copied();
+        let subject = Subject::of(id, &[old, new]);
+        let issue_events = match (old, new) {
+            (None, Some(new)) => vec![IssueEvent::Created {
+                issue: Box::new(new.clone()),
+            }],
+            (Some(_), None) => vec![IssueEvent::Deleted {
+                issue_id: id.clone(),
+            }],
+            (Some(old), Some(new)) => issue_changes(old, new),
+            (None, None) => Vec::new(),
+        };
+        events.extend(
+            issue_events
+                .into_iter()
+                .map(|event| (subject.clone(), event)),
+        );
+    }
+    events
+}
+
+/// The events that turn one version of an issue into another.
+fn issue_changes(old: &Issue, new: &Issue) -> Vec<IssueEvent> {
+    let id = &new.id;
+    let mut events = Vec::new();
+    let mut reported: Vec<&str> = FIELDS_WITH_OWN_EVENTS.to_vec();
+
+    match (old.status, new.status) {
+        (from, IssueStatus::Closed) if from != IssueStatus::Closed => {
+            events.push(IssueEvent::Closed {
+                issue_id: id.clone(),
+                closed_at: new.closed_at,
+            });
+            reported.extend(STATUS_FIELDS);
+        }
+        (IssueStatus::Closed, to) if to != IssueStatus::Closed => {
+            events.push(IssueEvent::Reopened {
+                issue_id: id.clone(),
+                status: to,
+            });
+            reported.extend(STATUS_FIELDS);
+        }
+        _ => {}
+    }
+
+    let changes = field_changes(old, new, &reported);
+    if !changes.is_empty() {
+        events.push(IssueEvent::Updated {
+            issue_id: id.clone(),
+            changes,
+        });
+    }
+
+    for dependency in &new.dependencies {
+        if !old.dependencies.contains(dependency) {
+            events.push(IssueEvent::DependencyAdded {
+                issue_id: id.clone(),
+                dependency: dependency.clone(),
+            });
+        }
+    }
+    for dependency in &old.dependencies {
+        if !new.dependencies.contains(dependency) {
+            events.push(IssueEvent::DependencyRemoved {
+                issue_id: id.clone(),
+                dependency: dependency.clone(),
+            });
+        }
+    }
+
+    let old_resources: BTreeMap<&ResourceId, &AssociatedResource> =
+        old.resources().iter().map(|r| (r.id(), r)).collect();
+    for resource in new.resources() {
+        let change = match old_resources.get(resource.id()) {
+            None => ResourceChange::Added,
+            Some(previous) if *previous != resource => ResourceChange::Updated,
+            Some(_) => continue,
+        };
+        events.push(IssueEvent::ResourceChanged {
+            issue_id: id.clone(),
+            resource_id: resource.id().clone(),
+            change,
+            resource: Some(resource.clone()),
+        });
+    }
+    for resource in old.resources() {
+        if !new.resources().iter().any(|r| r.id() == resource.id()) {
+            events.push(IssueEvent::ResourceChanged {
+                issue_id: id.clone(),
+                resource_id: resource.id().clone(),
+                change: ResourceChange::Removed,
+                resource: None,
+            });
+        }
+    }
+
+    events
+}
+
+/// The fields, other than `skip`, whose JSON values differ between two
+/// versions of an issue.
+fn field_changes(old: &Issue, new: &Issue, skip: &[&str]) -> Vec<FieldChange> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    let fields: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+    fields
+        .into_iter()
+        .filter(|field| !skip.contains(&field.as_str()))
+        .filter_map(|field| {
+            let before = old.get(field).unwrap_or(&Value::Null);
+            let after = new.get(field).unwrap_or(&Value::Null);
+            (before != after).then(|| FieldChange {
+                field: field.clone(),
+                before: before.clone(),
+                after: after.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        DependencyType, IssueUpdate, NewIssue, NewResource, ResourceRole, ResourceTarget, WebUrl,
+    };
+    use crate::storage::IssueStorage;
+    use crate::storage::in_memory::new_in_memory_storage;
+
+    async fn storage_with(titles: &[&str]) -> (Box<dyn IssueStorage>, Vec<IssueId>) {
+        let mut storage = new_in_memory_storage("test".to_string());
+        let mut ids = Vec::new();
+        for title in titles {
+            let issue = storage
+                .create(NewIssue {
+                    title: (*title).to_string(),
+                    ..NewIssue::default()
+                })
+                .await
+                .unwrap();
+            ids.push(issue.id);
+        }
+        (storage, ids)
+    }
+
+    fn kinds(events: &[(Subject, IssueEvent)]) -> Vec<String> {
+        events
+            .iter()
+            .map(|(_, event)| serde_json::to_value(event).unwrap()["type"].to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reports_creation_update_and_deletion() {
+        let (mut storage, ids) = storage_with(&["First"]).await;
+        let before = storage.export_all().await.unwrap();
+        storage
+            .update(
+                &ids[0],
+                IssueUpdate {
+                    title: Some("Renamed".to_string()),
+                    ..IssueUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+        let created = storage
+            .create(NewIssue {
+                title: "Second".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        let after = storage.export_all().await.unwrap();
+
+        let mut events = events_between(&before, &after);
+        events.sort_by_key(|(subject, _)| subject.issue_id == created.id);
+        assert_eq!(kinds(&events), ["\"updated\"", "\"created\""]);
+        let IssueEvent::Updated { changes, .. } = &events[0].1 else {
+            panic!("expected an update");
+        };
+        assert_eq!(
+            changes,
+            &[FieldChange {
+                field: "title".to_string(),
+                before: Value::from("First"),
+                after: Value::from("Renamed"),
+            }]
+        );
+
+        let deleted = events_between(&after, &before[..0]);
+        assert_eq!(kinds(&deleted), ["\"deleted\"", "\"deleted\""]);
+    }
+
+    #[tokio::test]
+    async fn reports_status_dependency_and_resource_changes_as_their_own_kinds() {
+        let (mut storage, ids) = storage_with(&["Blocked", "Blocker"]).await;
+        let before = storage.export_all().await.unwrap();
+        storage
+            .add_dependency(&ids[0], &ids[1], DependencyType::Blocks)
+            .await
+            .unwrap();
+        storage
+            .add_resource(
+                &ids[0],
+                NewResource {
+                    target: ResourceTarget::web(WebUrl::new("https://example.com").unwrap()),
+                    role: ResourceRole::Reference,
+                    label: None,
+                },
+            )
+            .await
+            .unwrap();
+        storage
+            .update(
+                &ids[1],
+                IssueUpdate {
+                    status: Some(IssueStatus::Closed),
+                    ..IssueUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+        let after = storage.export_all().await.unwrap();
+
+        let events = events_between(&before, &after);
+        let blocked: Vec<_> = events
+            .iter()
+            .filter(|(subject, _)| subject.issue_id == ids[0])
+            .cloned()
+            .collect();
+        let blocker: Vec<_> = events
+            .iter()
+            .filter(|(subject, _)| subject.issue_id == ids[1])
+            .cloned()
+            .collect();
+        assert_eq!(
+            kinds(&blocked),
+            ["\"dependency_added\"", "\"resource_changed\""]
+        );
+        assert_eq!(kinds(&blocker), ["\"closed\""]);
+    }
+}
@@ -0,0 +1,226 @@
+//! Running `git` for the commands that read a workspace's history.
+//!
+//! Rivets has no git library dependency; it shells out to the `git` on
+//! `PATH` from the workspace root, so repository discovery, worktrees and
+//! `core.hooksPath` behave exactly as they do for the user.
+
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use url::Url;
+
+/// Separates fields within one `git log` record.
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// Separates `git log` records.
+const RECORD_SEPARATOR: char = '\x1e';
+
+/// A commit as read from `git log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// Full object name.
+    pub sha: String,
+    /// First line of the message.
+    pub subject: String,
+    /// Full message, subject included.
+    pub message: String,
+}
+
+impl Commit {
+    /// The abbreviated object name used in messages.
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
+
+/// Run `git` with `args` in `dir`, returning its stdout.
+///
+/// # Errors
+///
+/// Returns [`Error::Git`] when git cannot be started or exits unsuccessfully,
+/// carrying git's own error message.
+pub async fn run(dir: &Path, args: &[&str]) -> Result<String> {
+    String::from_utf8(output(dir, args).await?)
+        .map_err(|_| Error::Git(format!("git {} printed invalid UTF-8", args.join(" "))))
+}
+
+async fn output(dir: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .map_err(|error| Error::Git(format!("could not run git: {error}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Git(match stderr.trim() {
+            "" => format!("git {} failed with {}", args.join(" "), output.status),
+            message => message.to_string(),
+        }));
+    }
+    Ok(output.stdout)
+}
+
+/// The commits in `range`, oldest first.
+///
+/// `range` is anything `git log` accepts: `main..HEAD`, `v1.2.0..`, a single
+/// commit and its ancestors, and so on.
+///
+/// # Errors
+///
+/// Returns [`Error::Git`] when git rejects the range.
+pub async fn log(dir: &Path, range: &str) -> Result<Vec<Commit>> {
+    read_log(dir, &["--reverse", range]).await
+}
+
+/// The commit `rev` names.
+///
+/// # Errors
+///
+/// Returns [`Error::Git`] when `rev` does not name a commit.
+pub async fn commit(dir: &Path, rev: &str) -> Result<Commit> {
+    read_log(dir, &["--max-count=1", rev])
+        .await?
+        .pop()
+        .ok_or_else(|| Error::Git(format!("{rev} does not name a commit")))
+}
+
+async fn read_log(dir: &Path, args: &[&str]) -> Result<Vec<Commit>> {
+    let format = format!("--format=%H{FIELD_SEPARATOR}%B{RECORD_SEPARATOR}");
+    let mut command = vec!["log", format.as_str()];
+    command.extend_from_slice(args);
+    command.push("--");
+    let output = run(dir, &command).await?;
+    Ok(output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let (sha, message) = record.trim_start().split_once(FIELD_SEPARATOR)?;
+            let message = message.trim_end().to_string();
+            Some(Commit {
+                sha: sha.to_string(),
+                subject: message.lines().next().unwrap_or_default().to_string(),
+                message,
+            })
+        })
+        .collect())
+}
+
+/// The contents of `path` at revision `rev`, or `None` when the file does
+/// not exist there.
+///
+/// `path` is relative to `dir`, not to the repository root, so a workspace
+/// nested inside a larger repository reads its own files.
+///
+/// # Errors
+///
+/// Returns [`Error::Git`] when `rev` does not name a commit.
+pub async fn show(dir: &Path, rev: &str, path: &Path) -> Result<Option<Vec<u8>>> {
+    run(
+        dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ],
+    )
+    .await
+    .map_err(|_| Error::Git(format!("{rev} does not name a commit")))?;
+    let object = format!("{rev}:./{}", path.display());
+    if run(dir, &["cat-file", "-e", &object]).await.is_err() {
+        return Ok(None);
+    }
+    output(dir, &["cat-file", "blob", &object]).await.map(Some)
+}
+
+/// The directory git runs this repository's hooks from, honouring
+/// `core.hooksPath`.
+///
+/// # Errors
+///
+/// Returns [`Error::Git`] when `dir` is not inside a git repository.
+pub async fn hooks_dir(dir: &Path) -> Result<PathBuf> {
+    let path = run(dir, &["rev-parse", "--git-path", "hooks"]).await?;
+    Ok(dir.join(path.trim()))
+}
+
+/// A template for web links to commits on the `origin` remote, with `{sha}`
+/// standing for the commit, or `None` when there is no such remote or its
+/// URL has no web equivalent.
+pub async fn remote_commit_url(dir: &Path) -> Option<String> {
+    let remote = run(dir, &["remote", "get-url", "origin"]).await.ok()?;
+    commit_url_template(remote.trim())
+}
+
+/// Map a remote URL to a commit link template.
+///
+/// HTTP(S) and SSH remotes, the latter in both `ssh://` and scp-like
+/// `git@host:path` form, map to `https://host/path/commit/{sha}`, the layout
+/// GitHub, GitLab and Gitea serve. Local paths and `file://` remotes have no
+/// web form.
+fn commit_url_template(remote: &str) -> Option<String> {
+    let (scheme, host, path) = match Url::parse(remote) {
+        Ok(url) => {
+            let scheme = match url.scheme() {
+                "http" => "http",
+                "https" | "ssh" | "git" => "https",
+                _ => return None,
+            };
+            // An SSH port says nothing about where the web interface is.
+            let host = match url.port() {
+                Some(port) if url.scheme().starts_with("http") => {
+                    format!("{}:{port}", url.host_str()?)
+                }
+                _ => url.host_str()?.to_string(),
+            };
+            (scheme, host, url.path().to_string())
+        }
+        Err(_) => {
+            // scp-like syntax; a single letter before the colon is a
+            // Windows drive, and a slash before it makes a local path.
+            let (user_host, path) = remote.split_once(':')?;
+            let host = user_host.rsplit('@').next()?;
+            if host.len() < 2 || user_host.contains('/') {
+                return None;
+            }
+            ("https", host.to_string(), path.to_string())
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if path.is_empty() {
+        return None;
+    }
+    Some(format!("{scheme}://{host}/{path}/commit/{{sha}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "https://github.com/acme/widgets.git",
+        Some("https://github.com/acme/widgets/commit/{sha}")
+    )]
+    #[case(
+        "git@github.com:acme/widgets.git",
+        Some("https://github.com/acme/widgets/commit/{sha}")
+    )]
+    #[case(
+        "ssh://git@gitlab.com/group/sub/widgets",
+        Some("https://gitlab.com/group/sub/widgets/commit/{sha}")
+    )]
+    #[case(
+        "http://git.local:3000/acme/widgets/",
+        Some("http://git.local:3000/acme/widgets/commit/{sha}")
+    )]
+    #[case("/srv/git/widgets.git", None)]
+    #[case("file:///srv/git/widgets.git", None)]
+    #[case("../widgets", None)]
+    #[case("C:\\repos\\widgets", None)]
+    fn maps_remotes_to_commit_links(#[case] remote: &str, #[case] expected: Option<&str>) {
+        assert_eq!(commit_url_template(remote).as_deref(), expected);
+    }
+}
@@ -0,0 +1,216 @@
+//! Delivery of hook events as signed HTTP POST requests.
+//!
+//! Requests go over HTTP or HTTPS. The body is signed with HMAC-SHA256 in
+//! `X-Rivets-Signature-256: sha256=<hex>` when a secret is configured, so
+//! receivers can check the request came from this workspace.
+
+use super::HookError;
+use crate::events::EventKind;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+use std::fmt::Write as _;
+use std::time::Duration;
+use url::Url;
+
+/// How long one attempt may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first retry; doubled before each later one.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// One hook delivery.
+pub(super) struct Request<'a> {
+    pub(super) url: &'a Url,
+    pub(super) hook: &'a str,
+    pub(super) event: EventKind,
+    pub(super) body: &'a [u8],
+    pub(super) secret: Option<&'a str>,
+}
+
+/// POST `request`, retrying connection failures, timeouts, `429` and `5xx`
+/// responses up to `retries` times with exponential backoff.
+pub(super) async fn post_with_retries(
+    request: &Request<'_>,
+    retries: u32,
+) -> Result<(), HookError> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("rivets/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(HookError::Connection)?;
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let error = match post(&client, request).await {
+            Ok(status) if (200..300).contains(&status) => return Ok(()),
+            Ok(status) if status == 429 || status >= 500 => HookError::Status(status),
+            Ok(status) => return Err(HookError::Status(status)),
+            Err(error) => error,
+        };
+        if attempt > retries {
+            return Err(if attempt == 1 {
+                error
+            } else {
+                HookError::Retried {
+                    attempts: attempt,
+                    last: Box::new(error),
+                }
+            });
+        }
+        tracing::debug!(hook = request.hook, attempt, %error, "Retrying hook");
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+/// Make one POST, returning the response status.
+async fn post(client: &reqwest::Client, request: &Request<'_>) -> Result<u16, HookError> {
+    let mut builder = client
+        .post(request.url.clone())
+        .header(CONTENT_TYPE, "application/json")
+        .header("X-Rivets-Hook", request.hook)
+        .header("X-Rivets-Event", request.event.to_string())
+        .body(request.body.to_vec());
+    if let Some(secret) = request.secret {
+        builder = builder.header(
+            "X-Rivets-Signature-256",
+            format!("sha256={}", signature(secret, request.body)),
+        );
+    }
+    match builder.send().await {
+        Ok(response) => Ok(response.status().as_u16()),
+        Err(error) if error.is_timeout() => Err(HookError::Timeout(REQUEST_TIMEOUT)),
+        Err(error) => Err(HookError::Connection(error)),
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`.
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn computes_rfc_4231_hmac_vectors() {
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    /// Requests an endpoint received, and the statuses it has left to answer.
+    #[derive(Default)]
+    struct Endpoint {
+        statuses: VecDeque<u16>,
+        requests: Vec<(String, HeaderMap, Bytes)>,
+    }
+
+    type SharedEndpoint = Arc<Mutex<Endpoint>>;
+
+    /// Serve `statuses` in turn, recording each request.
+    async fn endpoint(statuses: Vec<u16>) -> (Url, SharedEndpoint) {
+        let endpoint = Arc::new(Mutex::new(Endpoint {
+            statuses: statuses.into(),
+            ..Endpoint::default()
+        }));
+        let router = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(endpoint): State<SharedEndpoint>,
+                     uri: axum::http::Uri,
+                     headers: HeaderMap,
+                     body: Bytes| async move {
+                        let mut endpoint = endpoint.lock().unwrap();
+                        endpoint.requests.push((uri.to_string(), headers, body));
+                        let status = endpoint.statuses.pop_front().unwrap();
+                        StatusCode::from_u16(status).unwrap()
+                    },
+                ),
+            )
+            .with_state(Arc::clone(&endpoint));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/hook?team=dev",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (url, endpoint)
+    }
+
+    #[tokio::test]
+    async fn signs_requests_and_retries_server_errors() {
+        let (url, recorded) = endpoint(vec![503, 204]).await;
+        let body = br#"{"event":{"type":"closed"}}"#;
+        let request = Request {
+            url: &url,
+            hook: "ci",
+            event: EventKind::Closed,
+            body,
+            secret: Some("s3cret"),
+        };
+
+        post_with_retries(&request, 1).await.unwrap();
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.requests.len(), 2);
+        let expected = format!("sha256={}", signature("s3cret", body));
+        for (uri, headers, received) in &recorded.requests {
+            assert_eq!(uri, "/hook?team=dev");
+            assert_eq!(headers["x-rivets-event"], "closed");
+            assert_eq!(headers["x-rivets-signature-256"], expected.as_str());
+            assert_eq!(headers["content-type"], "application/json");
+            assert_eq!(&received[..], body);
+        }
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_client_errors_and_after_the_last_retry() {
+        let (url, recorded) = endpoint(vec![404]).await;
+        let request = Request {
+            url: &url,
+            hook: "ci",
+            event: EventKind::Created,
+            body: b"{}",
+            secret: None,
+        };
+        let error = post_with_retries(&request, 3).await.unwrap_err();
+        assert!(matches!(error, HookError::Status(404)), "{error}");
+        let (_, headers, _) = recorded.lock().unwrap().requests.remove(0);
+        assert!(!headers.contains_key("x-rivets-signature-256"));
+
+        let (url, _recorded) = endpoint(vec![500, 500]).await;
+        let request = Request {
+            url: &url,
+            ..request
+        };
+        let error = post_with_retries(&request, 1).await.unwrap_err();
+        assert!(
+            matches!(error, HookError::Retried { attempts: 2, .. }),
+            "{error}"
+        );
+    }
+}
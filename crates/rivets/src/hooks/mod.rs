@@ -0,0 +1,665 @@
+//! Hooks that run automation when issues change.
+//!
+//! A workspace lists hooks under `hooks` in `config.yaml`. Each hook either
+//! runs a local executable, which receives the event JSON on stdin, or POSTs
+//! the event JSON to an `http://` or `https://` URL, signed with HMAC-SHA256
+//! when a secret is configured and retried with exponential backoff:
+//!
+//! ```yaml
+//! hooks:
+//!   - name: notify-chat
+//!     events: [created, closed]
+//!     command: scripts/notify.sh
+//!     args: [--channel, dev]
+//!   - name: ci
+//!     url: http://127.0.0.1:9000/rivets
+//!     secret_env: RIVETS_HOOK_SECRET
+//!     retries: 3
+//! ```
+//!
+//! `events` limits a hook to some [`EventKind`]s; without it the hook
+//! receives every event. Hooks fire for changes made by CLI commands, MCP
+//! tools and the local REST API, once per event, in the order the events
+//! happened. A failing hook is logged and never undoes the change that
+//! triggered it.
+
+mod http;
+
+use crate::commands::init::{CONFIG_FILE_NAME, RIVETS_DIR_NAME, RivetsConfig};
+use crate::domain::{
+    AssociatedResource, Dependency, DependencyType, Issue, IssueStatus, ResourceId,
+};
+use crate::error::Result;
+use crate::events::{EventKind, FieldChange, IssueEvent, ResourceChange, events_between};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+/// How long a command hook may run.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Retries of an HTTP hook when `retries` is not configured.
+const DEFAULT_RETRIES: u32 = 3;
+
+/// Most retries an HTTP hook may configure.
+const MAX_RETRIES: u32 = 10;
+
+/// Most stderr a failed command hook reports.
+const MAX_STDERR: usize = 500;
+
+/// Source of hook events from CLI commands.
+pub const CLI_SOURCE: &str = "cli";
+
+/// One hook declared in `config.yaml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawHookConfig", into = "RawHookConfig")]
+pub struct HookConfig {
+    /// Name used in logs and by `rivets hooks test --hook`.
+    pub name: String,
+    /// Events the hook receives; empty means every event.
+    pub events: Vec<EventKind>,
+    /// Where events are delivered.
+    pub target: HookTarget,
+}
+
+impl HookConfig {
+    /// Whether the hook receives events of `kind`.
+    pub fn subscribes_to(&self, kind: EventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+/// Where a hook delivers events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookTarget {
+    /// Run an executable with the event JSON on stdin.
+    Command {
+        /// The executable; relative paths containing a `/` are resolved
+        /// against the workspace root, bare names against `PATH`.
+        command: String,
+        /// Arguments passed to the executable.
+        args: Vec<String>,
+    },
+    /// POST the event JSON to a URL.
+    Http {
+        /// The `http://` or `https://` URL to POST to.
+        url: Url,
+        /// Environment variable holding the HMAC-SHA256 signing secret.
+        secret_env: Option<String>,
+        /// Further attempts after a failed one.
+        retries: u32,
+    },
+}
+
+/// A hook as written in `config.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHookConfig {
+    name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    events: Vec<EventKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secret_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retries: Option<u32>,
+}
+
+impl TryFrom<RawHookConfig> for HookConfig {
+    type Error = String;
+
+    fn try_from(raw: RawHookConfig) -> std::result::Result<Self, String> {
+        let name = raw.name;
+        if name.trim().is_empty() {
+            return Err("hook name cannot be empty".to_string());
+        }
+        let target = match (raw.command, raw.url) {
+            (Some(command), None) => {
+                if raw.secret_env.is_some() || raw.retries.is_some() {
+                    return Err(format!(
+                        "hook '{name}': secret_env and retries only apply to url hooks"
+                    ));
+                }
+                HookTarget::Command {
+                    command,
+                    args: raw.args,
+                }
+            }
+            (None, Some(url)) => {
+                if !raw.args.is_empty() {
+                    return Err(format!("hook '{name}': args only apply to command hooks"));
+                }
+                let url = Url::parse(&url)
+                    .map_err(|error| format!("hook '{name}': invalid url '{url}': {error}"))?;
+                if !matches!(url.scheme(), "http" | "https") || url.host().is_none() {
+                    return Err(format!(
+                        "hook '{name}': url must be an http:// or https:// URL"
+                    ));
+                }
+                let retries = raw.retries.unwrap_or(DEFAULT_RETRIES);
+                if retries > MAX_RETRIES {
+                    return Err(format!(
+                        "hook '{name}': retries must be at most {MAX_RETRIES}"
+                    ));
+                }
+                HookTarget::Http {
+                    url,
+                    secret_env: raw.secret_env,
+                    retries,
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "hook '{name}': exactly one of command or url is required"
+                ));
+            }
+        };
+        Ok(Self {
+            name,
+            events: raw.events,
+            target,
+        })
+    }
+}
+
+impl From<HookConfig> for RawHookConfig {
+    fn from(hook: HookConfig) -> Self {
+        let mut raw = Self {
+            name: hook.name,
+            events: hook.events,
+            command: None,
+            args: Vec::new(),
+            url: None,
+            secret_env: None,
+            retries: None,
+        };
+        match hook.target {
+            HookTarget::Command { command, args } => {
+                raw.command = Some(command);
+                raw.args = args;
+            }
+            HookTarget::Http {
+                url,
+                secret_env,
+                retries,
+            } => {
+                raw.url = Some(url.into());
+                raw.secret_env = secret_env;
+                raw.retries = Some(retries);
+            }
+        }
+        raw
+    }
+}
+
+/// The JSON a hook receives for one event.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookPayload {
+    /// Root of the workspace the event happened in.
+    pub workspace: PathBuf,
+    /// What made the change: `cli`, `mcp` or `api`.
+    pub source: String,
+    /// When the hook was fired.
+    pub timestamp: DateTime<Utc>,
+    /// Set by `rivets hooks test`; the event did not really happen.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+    /// What changed.
+    pub event: IssueEvent,
+}
+
+/// A failure to deliver an event to a hook.
+#[derive(Debug, thiserror::Error)]
+pub enum HookError {
+    /// The command could not be started.
+    #[error("Failed to run '{command}': {source}")]
+    Spawn {
+        /// The command that failed to start.
+        command: String,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+
+    /// The command exited unsuccessfully.
+    #[error("Command exited with {status}{stderr}")]
+    Exit {
+        /// How the command exited.
+        status: ExitStatus,
+        /// The end of its stderr, prefixed with `: `, if it wrote any.
+        stderr: String,
+    },
+
+    /// The command or request took too long.
+    #[error("Timed out after {}s", .0.as_secs())]
+    Timeout(Duration),
+
+    /// The signing secret's environment variable is not set.
+    #[error("Environment variable {0} holding the signing secret is not set")]
+    MissingSecret(String),
+
+    /// The endpoint could not be reached or the request failed.
+    #[error("Request failed: {0}")]
+    Connection(reqwest::Error),
+
+    /// The endpoint answered with a non-2xx status.
+    #[error("Endpoint responded with HTTP {0}")]
+    Status(u16),
+
+    /// Every attempt failed.
+    #[error("Failed after {attempts} attempts: {last}")]
+    Retried {
+        /// Attempts made.
+        attempts: u32,
+        /// The last attempt's error.
+        last: Box<HookError>,
+    },
+
+    /// The payload could not be serialized.
+    #[error("Failed to serialize the event: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The hooks of one workspace.
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    workspace_root: PathBuf,
+    hooks: Vec<HookConfig>,
+}
+
+impl Hooks {
+    /// Hooks of the workspace at `workspace_root`.
+    pub fn new(workspace_root: impl Into<PathBuf>, hooks: Vec<HookConfig>) -> Self {
+        Self {
+            workspace_root: workspace_root.into(),
+            hooks,
+        }
+    }
+
+    /// Load the hooks declared in the workspace's `config.yaml`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration cannot be read or parsed.
+    pub async fn load(workspace_root: &Path) -> Result<Self> {
+        let config_path = workspace_root.join(RIVETS_DIR_NAME).join(CONFIG_FILE_NAME);
+        let config = RivetsConfig::load(&config_path).await?;
+        Ok(Self::new(workspace_root, config.hooks))
+    }
+
+    /// Whether no hooks are configured, so callers can skip snapshotting the
+    /// workspace.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// The hooks receiving events of `kind`.
+    pub fn subscribed(&self, kind: EventKind) -> impl Iterator<Item = &HookConfig> {
+        self.hooks
+            .iter()
+            .filter(move |hook| hook.subscribes_to(kind))
+    }
+
+    /// Deliver the events between two snapshots of the workspace to every
+    /// subscribed hook, logging failures.
+    pub async fn fire(&self, source: &str, before: &[Issue], after: &[Issue]) {
+        if self.is_empty() {
+            return;
+        }
+        for (_, event) in events_between(before, after) {
+            let kind = event.kind();
+            let payload = self.payload(source, event, false);
+            for hook in self.subscribed(kind) {
+                match self.deliver(hook, &payload).await {
+                    Ok(()) => tracing::debug!(hook = %hook.name, event = %kind, "Delivered hook"),
+                    Err(error) => {
+                        tracing::warn!(hook = %hook.name, event = %kind, %error, "Hook failed");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Wrap an event for delivery.
+    pub fn payload(&self, source: &str, event: IssueEvent, dry_run: bool) -> HookPayload {
+        HookPayload {
+            workspace: self.workspace_root.clone(),
+            source: source.to_string(),
+            timestamp: Utc::now(),
+            dry_run,
+            event,
+        }
+    }
+
+    /// Deliver one payload to one hook.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason delivery failed, after any retries.
+    pub async fn deliver(
+        &self,
+        hook: &HookConfig,
+        payload: &HookPayload,
+    ) -> std::result::Result<(), HookError> {
+        let body = serde_json::to_vec(payload)?;
+        match &hook.target {
+            HookTarget::Command { command, args } => {
+                self.run_command(hook, command, args, payload.event.kind(), &body)
+                    .await
+            }
+            HookTarget::Http {
+                url,
+                secret_env,
+                retries,
+            } => {
+                let secret = secret_env
+                    .as_ref()
+                    .map(|name| {
+                        std::env::var(name).map_err(|_| HookError::MissingSecret(name.clone()))
+                    })
+                    .transpose()?;
+                let request = http::Request {
+                    url,
+                    hook: &hook.name,
+                    event: payload.event.kind(),
+                    body: &body,
+                    secret: secret.as_deref(),
+                };
+                http::post_with_retries(&request, *retries).await
+            }
+        }
+    }
+
+    async fn run_command(
+        &self,
+        hook: &HookConfig,
+        command: &str,
+        args: &[String],
+        kind: EventKind,
+        body: &[u8],
+    ) -> std::result::Result<(), HookError> {
+        let program = if command.contains('/') && Path::new(command).is_relative() {
+            self.workspace_root.join(command)
+        } else {
+            PathBuf::from(command)
+        };
+        let mut child = tokio::process::Command::new(&program)
+            .args(args)
+            .current_dir(&self.workspace_root)
+            .env("RIVETS_HOOK", &hook.name)
+            .env("RIVETS_EVENT", kind.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|source| HookError::Spawn {
+                command: command.to_string(),
+                source,
+            })?;
+
+        let run = async {
+            if let Some(mut stdin) = child.stdin.take() {
+                // A command that ignores its input may exit before reading it.
+                if let Err(error) = stdin.write_all(body).await
+                    && error.kind() != std::io::ErrorKind::BrokenPipe
+                {
+                    return Err(HookError::Spawn {
+                        command: command.to_string(),
+                        source: error,
+                    });
+                }
+            }
+            child
+                .wait_with_output()
+                .await
+                .map_err(|source| HookError::Spawn {
+                    command: command.to_string(),
+                    source,
+                })
+        };
+        let output = tokio::time::timeout(COMMAND_TIMEOUT, run)
+            .await
+            .map_err(|_| HookError::Timeout(COMMAND_TIMEOUT))??;
+
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        let tail = stderr
+            .char_indices()
+            .rev()
+            .nth(MAX_STDERR)
+            .map_or(stderr, |(index, _)| &stderr[index..]);
+        Err(HookError::Exit {
+            status: output.status,
+            stderr: if tail.is_empty() {
+                String::new()
+            } else {
+                format!(": {tail}")
+            },
+        })
+    }
+}
+
+/// An example event of `kind` about `issue`, for `rivets hooks test`.
+pub fn sample_event(kind: EventKind, issue: &Issue) -> IssueEvent {
+    let issue_id = issue.id.clone();
+    match kind {
+        EventKind::Created => IssueEvent::Created {
+            issue: Box::new(issue.clone()),
+        },
+        EventKind::Updated => IssueEvent::Updated {
+            issue_id,
+            changes: vec![FieldChange {
+                field: "title".to_string(),
+                before: issue.title.clone().into(),
+                after: issue.title.clone().into(),
+            }],
+        },
+        EventKind::Closed => IssueEvent::Closed {
+            issue_id,
+            closed_at: issue.closed_at.or_else(|| Some(Utc::now())),
+        },
+        EventKind::Reopened => IssueEvent::Reopened {
+            issue_id,
+            status: IssueStatus::Open,
+        },
+        EventKind::Deleted => IssueEvent::Deleted { issue_id },
+        EventKind::DependencyAdded | EventKind::DependencyRemoved => {
+            let dependency = issue.dependencies.first().cloned().unwrap_or(Dependency {
+                depends_on_id: issue_id.clone(),
+                dep_type: DependencyType::Related,
+            });
+            if kind == EventKind::DependencyAdded {
+                IssueEvent::DependencyAdded {
+                    issue_id,
+                    dependency,
+                }
+            } else {
+                IssueEvent::DependencyRemoved {
+                    issue_id,
+                    dependency,
+                }
+            }
+        }
+        EventKind::ResourceChanged => {
+            let resource: Option<&AssociatedResource> = issue.resources().first();
+            IssueEvent::ResourceChanged {
+                issue_id,
+                resource_id: resource.map_or_else(
+                    || ResourceId::new("r1").expect("r1 is a valid resource ID"),
+                    |resource| resource.id().clone(),
+                ),
+                change: if resource.is_some() {
+                    ResourceChange::Updated
+                } else {
+                    ResourceChange::Removed
+                },
+                resource: resource.cloned(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{IssueUpdate, NewIssue};
+    use crate::storage::in_memory::new_in_memory_storage;
+    use clap::ValueEnum;
+    use tempfile::TempDir;
+
+    fn parse(yaml: &str) -> std::result::Result<Vec<HookConfig>, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    #[test]
+    fn parses_and_validates_hook_config() {
+        let hooks = parse(
+            "- name: chat\n  events: [created, dependency_added]\n  command: scripts/notify.sh\n  args: [-v]\n\
+             - name: ci\n  url: http://127.0.0.1:9000/hook\n  secret_env: SECRET\n",
+        )
+        .unwrap();
+        assert_eq!(
+            hooks[0].target,
+            HookTarget::Command {
+                command: "scripts/notify.sh".to_string(),
+                args: vec!["-v".to_string()],
+            }
+        );
+        assert!(hooks[0].subscribes_to(EventKind::DependencyAdded));
+        assert!(!hooks[0].subscribes_to(EventKind::Closed));
+        assert!(hooks[1].subscribes_to(EventKind::Closed));
+        assert!(matches!(
+            &hooks[1].target,
+            HookTarget::Http { retries: DEFAULT_RETRIES, secret_env: Some(env), .. } if env == "SECRET"
+        ));
+
+        for invalid in [
+            "- name: both\n  command: a\n  url: http://localhost/\n",
+            "- name: neither\n",
+            "- name: ftp\n  url: ftp://example.com/hook\n",
+            "- name: args\n  url: http://localhost/\n  args: [x]\n",
+            "- name: secret\n  command: a\n  secret_env: S\n",
+            "- name: many\n  url: http://localhost/\n  retries: 50\n",
+            "- name: typo\n  command: a\n  event: [created]\n",
+            "- name: kind\n  command: a\n  events: [merged]\n",
+        ] {
+            assert!(parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_hooks_receive_each_event_on_stdin() {
+        let workspace = TempDir::new().unwrap();
+        let log = workspace.path().join("events.log");
+        let hooks = Hooks::new(
+            workspace.path(),
+            parse(&format!(
+                "- name: log\n  events: [created, closed]\n  command: sh\n  args: [-c, 'cat >> {} && echo >> {}']\n",
+                log.display(),
+                log.display()
+            ))
+            .unwrap(),
+        );
+
+        let mut storage = new_in_memory_storage("test".to_string());
+        let before = storage.export_all().await.unwrap();
+        let issue = storage
+            .create(NewIssue {
+                title: "Hooked".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        let created = storage.export_all().await.unwrap();
+        hooks.fire(CLI_SOURCE, &before, &created).await;
+        storage
+            .update(
+                &issue.id,
+                IssueUpdate {
+                    title: Some("Renamed".to_string()),
+                    status: Some(IssueStatus::Closed),
+                    ..IssueUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+        hooks
+            .fire(CLI_SOURCE, &created, &storage.export_all().await.unwrap())
+            .await;
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2, "updated is not subscribed");
+        assert_eq!(lines[0]["source"], "cli");
+        assert_eq!(lines[0]["event"]["type"], "created");
+        assert_eq!(lines[0]["event"]["issue"]["title"], "Hooked");
+        assert_eq!(lines[1]["event"]["type"], "closed");
+        assert_eq!(lines[1]["event"]["issue_id"], issue.id.as_str());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reports_failing_commands_with_their_stderr() {
+        let workspace = TempDir::new().unwrap();
+        let hooks = Hooks::new(
+            workspace.path(),
+            parse("- name: fail\n  command: sh\n  args: [-c, 'echo broken >&2; exit 3']\n")
+                .unwrap(),
+        );
+        let mut storage = new_in_memory_storage("test".to_string());
+        let issue = storage
+            .create(NewIssue {
+                title: "Sample".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+
+        let payload = hooks.payload(CLI_SOURCE, sample_event(EventKind::Closed, &issue), true);
+        let hook = hooks.subscribed(EventKind::Closed).next().unwrap();
+        let error = hooks.deliver(hook, &payload).await.unwrap_err();
+        assert!(matches!(error, HookError::Exit { .. }), "{error}");
+        assert!(error.to_string().ends_with(": broken"), "{error}");
+
+        let missing = Hooks::new(
+            workspace.path(),
+            parse("- name: missing\n  command: ./no-such-hook\n").unwrap(),
+        );
+        let hook = missing.subscribed(EventKind::Closed).next().unwrap();
+        assert!(matches!(
+            missing.deliver(hook, &payload).await,
+            Err(HookError::Spawn { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn samples_every_event_kind() {
+        let mut storage = new_in_memory_storage("test".to_string());
+        let issue = storage
+            .create(NewIssue {
+                title: "Sample".to_string(),
+                ..NewIssue::default()
+            })
+            .await
+            .unwrap();
+        for kind in EventKind::value_variants() {
+            assert_eq!(sample_event(*kind, &issue).kind(), *kind);
+        }
+    }
+}
@@ -6,9 +6,15 @@
 #![forbid(unsafe_code)]
 
 // Public modules for library usage
+pub mod api;
 pub mod app;
+#[cfg(unix)]
+pub mod daemon;
 pub mod domain;
 pub mod error;
+pub mod events;
+pub mod git;
+pub mod hooks;
 pub mod id_generation;
 pub mod output;
 pub mod storage;
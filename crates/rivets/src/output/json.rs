@@ -1,6 +1,7 @@
 //! JSON output formatting for CLI commands.
 
-use crate::domain::{Dependency, Issue};
+use crate::commands::diff::TrackerDiff;
+use crate::domain::{BurndownPoint, Dependency, Issue, Milestone, MilestoneProgress};
 use serde::Serialize;
 use std::io::{self, Write};
 
@@ -42,9 +43,9 @@ pub(crate) fn print_issue_details_json<W: Write>(
 }
 
 #[derive(Serialize)]
-struct BlockedIssue<'a> {
-    issue: &'a Issue,
-    blocked_by: Vec<&'a Issue>,
+pub(crate) struct BlockedIssue<'a> {
+    pub issue: &'a Issue,
+    pub blocked_by: Vec<&'a Issue>,
 }
 
 pub(crate) fn print_blocked_json<W: Write>(
@@ -63,3 +64,82 @@ pub(crate) fn print_blocked_json<W: Write>(
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
     writeln!(w, "{}", json)
 }
+
+#[derive(Serialize)]
+struct MilestoneSummary<'a> {
+    #[serde(flatten)]
+    milestone: &'a Milestone,
+    current: bool,
+    total: usize,
+    closed: usize,
+    percent_complete: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    burndown: Option<&'a [BurndownPoint]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocked: Option<Vec<BlockedIssue<'a>>>,
+}
+
+impl<'a> MilestoneSummary<'a> {
+    fn new(milestone: &'a Milestone, progress: &'a MilestoneProgress, current: bool) -> Self {
+        Self {
+            milestone,
+            current,
+            total: progress.total,
+            closed: progress.closed,
+            percent_complete: progress.percent_complete(),
+            burndown: None,
+            blocked: None,
+        }
+    }
+}
+
+pub(crate) fn print_milestones_json<W: Write>(
+    w: &mut W,
+    milestones: &[(Milestone, MilestoneProgress)],
+    current: Option<&str>,
+) -> io::Result<()> {
+    let items: Vec<MilestoneSummary> = milestones
+        .iter()
+        .map(|(milestone, progress)| {
+            MilestoneSummary::new(
+                milestone,
+                progress,
+                current == Some(milestone.name.as_str()),
+            )
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&items)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writeln!(w, "{}", json)
+}
+
+pub(crate) fn print_milestone_report_json<W: Write>(
+    w: &mut W,
+    milestone: &Milestone,
+    progress: &MilestoneProgress,
+    current: bool,
+    blocked: &[(Issue, Vec<Issue>)],
+) -> io::Result<()> {
+    let mut report = MilestoneSummary::new(milestone, progress, current);
+    report.burndown = Some(&progress.burndown);
+    report.blocked = Some(
+        blocked
+            .iter()
+            .map(|(issue, blockers)| BlockedIssue {
+                issue,
+                blocked_by: blockers.iter().collect(),
+            })
+            .collect(),
+    );
+
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writeln!(w, "{}", json)
+}
+
+pub(crate) fn print_tracker_diff_json<W: Write>(w: &mut W, diff: &TrackerDiff) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(diff)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writeln!(w, "{}", json)
+}
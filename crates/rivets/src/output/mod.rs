@@ -9,10 +9,11 @@
 //! - [`tree`]: Dependency tree rendering with ASCII/Unicode connectors
 
 pub mod color;
-mod json;
+pub(crate) mod json;
 pub mod tree;
 
-use crate::domain::{Dependency, Issue, Note};
+use crate::commands::diff::{IssueChange, TrackerDiff};
+use crate::domain::{Dependency, Issue, Milestone, MilestoneProgress, Note};
 use colored::Colorize;
 use serde::Serialize;
 use std::env;
@@ -26,7 +27,10 @@ use color::{
     bold, colored_kind_icon, colored_status_icon, colorize_id, colorize_labels, colorize_priority,
     colorize_status, cyan, dimmed, yellow,
 };
-use json::{print_blocked_json, print_issue_details_json, print_issue_json, print_issues_json};
+use json::{
+    print_blocked_json, print_issue_details_json, print_issue_json, print_issues_json,
+    print_milestone_report_json, print_milestones_json, print_tracker_diff_json,
+};
 
 // ============================================================================
 // Output Configuration
@@ -296,6 +300,58 @@ pub fn print_blocked_issues(blocked: &[(Issue, Vec<Issue>)], mode: OutputMode) -
     }
 }
 
+/// Print milestones with their completion.
+///
+/// `current` names the current milestone, if any, so it can be marked.
+pub fn print_milestones(
+    milestones: &[(Milestone, MilestoneProgress)],
+    current: Option<&str>,
+    mode: OutputMode,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    let config = OutputConfig::from_env();
+
+    match mode {
+        OutputMode::Text => print_milestones_text(&mut handle, milestones, current, &config),
+        OutputMode::Json => print_milestones_json(&mut handle, milestones, current),
+    }
+}
+
+/// Print a milestone's progress, burn-down and remaining blocked issues.
+pub fn print_milestone_report(
+    milestone: &Milestone,
+    progress: &MilestoneProgress,
+    current: bool,
+    blocked: &[(Issue, Vec<Issue>)],
+    mode: OutputMode,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    let config = OutputConfig::from_env();
+
+    match mode {
+        OutputMode::Text => {
+            print_milestone_report_text(&mut handle, milestone, progress, current, blocked, &config)
+        }
+        OutputMode::Json => {
+            print_milestone_report_json(&mut handle, milestone, progress, current, blocked)
+        }
+    }
+}
+
+/// Print the tracker changes between two revisions.
+pub fn print_tracker_diff(diff: &TrackerDiff, mode: OutputMode) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    let config = OutputConfig::from_env();
+
+    match mode {
+        OutputMode::Text => print_tracker_diff_text(&mut handle, diff, &config),
+        OutputMode::Json => print_tracker_diff_json(&mut handle, diff),
+    }
+}
+
 /// Print a simple message
 pub fn print_message(msg: &str) -> io::Result<()> {
     let stdout = io::stdout();
@@ -340,9 +396,39 @@ fn print_issue_text<W: Write>(w: &mut W, issue: &Issue, config: &OutputConfig) -
         )?;
     }
 
+    if let Some(due) = format_due(issue, config) {
+        writeln!(w, "  {} {}", dimmed("Due:", config), due)?;
+    }
+
     Ok(())
 }
 
+/// Format an issue's claim, marking it when the lease has expired.
+fn format_lease(issue: &Issue, config: &OutputConfig) -> Option<String> {
+    let lease = issue.lease.as_ref()?;
+    let formatted = format!(
+        "by {} until {}",
+        lease.holder,
+        lease.expires_at.format("%Y-%m-%d %H:%M")
+    );
+    if lease.is_active(chrono::Utc::now()) {
+        Some(formatted)
+    } else {
+        Some(format!("{formatted} {}", error("(expired)", config)))
+    }
+}
+
+/// Format an issue's due date, marking it when overdue.
+fn format_due(issue: &Issue, config: &OutputConfig) -> Option<String> {
+    let due_at = issue.due_at?;
+    let formatted = due_at.format("%Y-%m-%d %H:%M").to_string();
+    if issue.is_overdue(chrono::Utc::now()) {
+        Some(format!("{formatted} {}", error("(overdue)", config)))
+    } else {
+        Some(formatted)
+    }
+}
+
 fn print_issues_text<W: Write>(
     w: &mut W,
     issues: &[Issue],
@@ -356,15 +442,22 @@ fn print_issues_text<W: Write>(
     writeln!(w, "Found {} issue(s):", issues.len())?;
     writeln!(w)?;
 
+    let now = chrono::Utc::now();
     for issue in issues {
+        let overdue_marker = if issue.is_overdue(now) {
+            format!("  {}", error("[overdue]", config))
+        } else {
+            String::new()
+        };
         writeln!(
             w,
-            "{} {}  {}  {}  {}",
+            "{} {}  {}  {}  {}{}",
             colored_status_icon(issue.status, config),
             colorize_id(issue.id.as_str(), config),
             colored_kind_icon(issue.issue_kind, config),
             colorize_priority(issue.priority, config),
-            issue.title
+            issue.title,
+            overdue_marker
         )?;
     }
 
@@ -412,6 +505,10 @@ fn print_issue_details_text<W: Write>(
         writeln!(w, "{} {}", dimmed("Assignee:", config), assignee)?;
     }
 
+    if let Some(lease) = format_lease(issue, config) {
+        writeln!(w, "{} {}", dimmed("Claimed:", config), lease)?;
+    }
+
     if !issue.labels.is_empty() {
         writeln!(
             w,
@@ -421,6 +518,18 @@ fn print_issue_details_text<W: Write>(
         )?;
     }
 
+    if let Some(due) = format_due(issue, config) {
+        writeln!(w, "{} {}", dimmed("Due:", config), due)?;
+    }
+
+    if let Some(ref milestone) = issue.milestone {
+        writeln!(w, "{} {}", dimmed("Milestone:", config), milestone)?;
+    }
+
+    for (name, value) in &issue.custom_fields {
+        writeln!(w, "{} {}", dimmed(&format!("{name}:"), config), value)?;
+    }
+
     let resources = issue.resources();
     if !resources.is_empty() {
         writeln!(w)?;
@@ -556,6 +665,215 @@ fn print_blocked_text<W: Write>(
     Ok(())
 }
 
+/// Widest burn-down bar, in characters.
+const MAX_BURNDOWN_BAR_WIDTH: usize = 40;
+
+/// One-line heading for a milestone: name, state and date range.
+fn milestone_heading(milestone: &Milestone, current: bool, config: &OutputConfig) -> String {
+    let state = if current {
+        format!("{}, current", milestone.state)
+    } else {
+        milestone.state.to_string()
+    };
+    format!(
+        "{} ({})  {} {} {}",
+        bold(&milestone.name, config),
+        state,
+        milestone.starts_on,
+        dimmed("to", config),
+        milestone.ends_on
+    )
+}
+
+fn print_milestones_text<W: Write>(
+    w: &mut W,
+    milestones: &[(Milestone, MilestoneProgress)],
+    current: Option<&str>,
+    config: &OutputConfig,
+) -> io::Result<()> {
+    if milestones.is_empty() {
+        writeln!(w, "No milestones found.")?;
+        return Ok(());
+    }
+
+    for (milestone, progress) in milestones {
+        writeln!(
+            w,
+            "{}  {}/{} closed ({}%)",
+            milestone_heading(milestone, current == Some(milestone.name.as_str()), config),
+            progress.closed,
+            progress.total,
+            progress.percent_complete()
+        )?;
+    }
+
+    Ok(())
+}
+
+fn print_milestone_report_text<W: Write>(
+    w: &mut W,
+    milestone: &Milestone,
+    progress: &MilestoneProgress,
+    current: bool,
+    blocked: &[(Issue, Vec<Issue>)],
+    config: &OutputConfig,
+) -> io::Result<()> {
+    writeln!(w, "{}", milestone_heading(milestone, current, config))?;
+    writeln!(
+        w,
+        "{} {}/{} closed ({}%)",
+        dimmed("Progress:", config),
+        progress.closed,
+        progress.total,
+        progress.percent_complete()
+    )?;
+
+    if !progress.burndown.is_empty() {
+        writeln!(w)?;
+        writeln!(w, "{}:", bold("Burn-down", config))?;
+        let scale = progress
+            .burndown
+            .iter()
+            .map(|point| point.remaining)
+            .max()
+            .unwrap_or(0)
+            .max(MAX_BURNDOWN_BAR_WIDTH);
+        for point in &progress.burndown {
+            let bar = "#".repeat(point.remaining * MAX_BURNDOWN_BAR_WIDTH / scale);
+            writeln!(
+                w,
+                "  {}  {:>4}  {}",
+                point.date,
+                point.remaining,
+                cyan(&bar, config)
+            )?;
+        }
+    }
+
+    writeln!(w)?;
+    if blocked.is_empty() {
+        writeln!(w, "No blocked issues remaining.")?;
+        return Ok(());
+    }
+    writeln!(w, "{} ({}):", bold("Blocked", config), blocked.len())?;
+    for (issue, blockers) in blocked {
+        writeln!(
+            w,
+            "  {} {}  {}  {}",
+            colored_status_icon(issue.status, config),
+            colorize_id(issue.id.as_str(), config),
+            colorize_priority(issue.priority, config),
+            issue.title
+        )?;
+        if !blockers.is_empty() {
+            let blocked_by: Vec<String> = blockers
+                .iter()
+                .map(|b| colorize_id(b.id.as_str(), config))
+                .collect();
+            writeln!(
+                w,
+                "    {} {}",
+                dimmed("Blocked by:", config),
+                blocked_by.join(", ")
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Longest field value shown inline in a diff; longer or multi-line values
+/// are reported as changed without their contents.
+const MAX_DIFF_VALUE_WIDTH: usize = 60;
+
+fn print_tracker_diff_text<W: Write>(
+    w: &mut W,
+    diff: &TrackerDiff,
+    config: &OutputConfig,
+) -> io::Result<()> {
+    if diff.issues.is_empty() {
+        writeln!(
+            w,
+            "No tracker changes between {} and {}.",
+            diff.from, diff.to
+        )?;
+        return Ok(());
+    }
+
+    let counts: Vec<String> = [
+        IssueChange::Created,
+        IssueChange::Closed,
+        IssueChange::Reopened,
+        IssueChange::Updated,
+        IssueChange::Deleted,
+    ]
+    .into_iter()
+    .filter_map(|change| match diff.count(change) {
+        0 => None,
+        count => Some(format!("{count} {change}")),
+    })
+    .collect();
+    writeln!(
+        w,
+        "{} {}..{}: {}",
+        bold("Tracker changes", config),
+        diff.from,
+        diff.to,
+        counts.join(", ")
+    )?;
+
+    for issue in &diff.issues {
+        writeln!(w)?;
+        writeln!(
+            w,
+            "{} {}  {}",
+            colorize_id(issue.issue_id.as_str(), config),
+            cyan(&issue.change.to_string(), config),
+            issue.title
+        )?;
+        for change in &issue.fields {
+            let (before, after) = (diff_value(&change.before), diff_value(&change.after));
+            if before.contains('\n')
+                || after.contains('\n')
+                || before.chars().count() + after.chars().count() > MAX_DIFF_VALUE_WIDTH
+            {
+                writeln!(w, "  {}: changed", change.field)?;
+            } else {
+                writeln!(w, "  {}: {} -> {}", change.field, before, after)?;
+            }
+        }
+        for note in &issue.notes_added {
+            writeln!(w, "  + note: {}", note.content())?;
+        }
+        for dep in &issue.dependencies_added {
+            writeln!(w, "  + depends on {} ({})", dep.depends_on_id, dep.dep_type)?;
+        }
+        for dep in &issue.dependencies_removed {
+            writeln!(w, "  - depends on {} ({})", dep.depends_on_id, dep.dep_type)?;
+        }
+        for resource in &issue.resources_added {
+            writeln!(w, "  + resource {resource}")?;
+        }
+        for resource in &issue.resources_updated {
+            writeln!(w, "  ~ resource {resource}")?;
+        }
+        for resource in &issue.resources_removed {
+            writeln!(w, "  - resource {resource}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A field value as shown in a diff: strings unquoted, unset as `(none)`.
+fn diff_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "(none)".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -580,6 +898,10 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             closed_at: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            lease: None,
         }
     }
 
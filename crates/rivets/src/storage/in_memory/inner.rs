@@ -6,6 +6,7 @@
 use crate::domain::{DependencyType, Issue, IssueId, NewIssue};
 use crate::error::{Result, StorageError};
 use crate::id_generation::{IdGenerator, IdGeneratorConfig};
+use petgraph::Direction;
 use petgraph::graph::{DiGraph, NodeIndex};
 use std::collections::HashMap;
 
@@ -113,4 +114,48 @@ impl InMemoryStorageInner {
 
         Ok(IssueId::new(id_str))
     }
+
+    /// Remove an issue and its graph node, with every edge touching it.
+    ///
+    /// `DiGraph::remove_node` moves the last node into the freed index, so
+    /// the moved issue's entry in `node_map` is repointed.
+    pub(super) fn remove_issue(&mut self, id: &IssueId) {
+        if let Some(node) = self.node_map.remove(id) {
+            self.graph.remove_node(node);
+            if let Some(moved) = self.graph.node_weight(node) {
+                self.node_map.insert(moved.clone(), node);
+            }
+        }
+        self.issues.remove(id);
+    }
+
+    /// Put back saved versions of issues, replacing their current state and
+    /// outgoing dependency edges.
+    pub(super) fn restore(&mut self, issues: Vec<Issue>) {
+        for issue in &issues {
+            match self.node_map.get(&issue.id) {
+                Some(&node) => {
+                    while let Some(edge) = self.graph.first_edge(node, Direction::Outgoing) {
+                        self.graph.remove_edge(edge);
+                    }
+                }
+                None => {
+                    let node = self.graph.add_node(issue.id.clone());
+                    self.node_map.insert(issue.id.clone(), node);
+                    self.id_generator.register_id(issue.id.as_str().to_string());
+                }
+            }
+        }
+        for issue in &issues {
+            let from = self.node_map[&issue.id];
+            for dep in &issue.dependencies {
+                if let Some(&to) = self.node_map.get(&dep.depends_on_id) {
+                    self.graph.add_edge(from, to, dep.dep_type);
+                }
+            }
+        }
+        for issue in issues {
+            self.issues.insert(issue.id.clone(), issue);
+        }
+    }
 }
@@ -1,12 +1,14 @@
 //! Compatibility boundary between persisted JSONL issue records and the domain model.
 
 use crate::domain::{
-    AssociatedResource, Dependency, Issue, IssueId, IssueKind, IssueStatus, NewResource, Note,
-    NoteContent, NoteError, ResourceError, ResourceId, ResourceLabel, ResourceRole, ResourceTarget,
-    WebUrl, WorkspacePath, is_unsafe_multiline_control,
+    AssociatedResource, CommitSha, CustomFieldValue, Dependency, Issue, IssueId, IssueKind,
+    IssueStatus, Lease, NewResource, Note, NoteContent, NoteError, ResourceError, ResourceId,
+    ResourceLabel, ResourceRole, ResourceTarget, WebUrl, WorkspacePath,
+    is_unsafe_multiline_control,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// A domain field with emitted and migration-only persisted names.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,7 +34,7 @@ impl MigrationField {
 }
 
 #[derive(Debug, thiserror::Error)]
-pub(super) enum IssueRecordError {
+pub(crate) enum IssueRecordError {
     #[error("invalid data for Issue '{issue_id}': {error}")]
     InvalidData {
         issue_id: IssueId,
@@ -121,6 +123,7 @@ impl Default for PersistedNotes {
 enum ResourceTargetRecord {
     Web { url: String },
     Path { path: String },
+    Commit { sha: String },
 }
 
 /// Persisted form of an Associated Resource.
@@ -137,6 +140,7 @@ impl ResourceRecord {
         let target = match self.target {
             ResourceTargetRecord::Web { url } => ResourceTarget::web(WebUrl::new(url)?),
             ResourceTargetRecord::Path { path } => ResourceTarget::path(WorkspacePath::new(path)?),
+            ResourceTargetRecord::Commit { sha } => ResourceTarget::commit(CommitSha::new(sha)?),
         };
         let label = self.label.map(ResourceLabel::new).transpose()?;
         Ok(AssociatedResource::from_parts(
@@ -157,6 +161,9 @@ impl From<AssociatedResource> for ResourceRecord {
             ResourceTarget::Path { path } => ResourceTargetRecord::Path {
                 path: path.as_str().to_string(),
             },
+            ResourceTarget::Commit { sha } => ResourceTargetRecord::Commit {
+                sha: sha.as_str().to_string(),
+            },
         };
         Self {
             id: resource.id().as_str().to_string(),
@@ -219,6 +226,7 @@ fn legacy_external_ref_url(external_ref: &str) -> Result<Option<WebUrl>, Resourc
             | ResourceError::AbsoluteWorkspacePath { .. }
             | ResourceError::WorkspacePathEscape { .. }
             | ResourceError::EmptyNormalizedWorkspacePath { .. }
+            | ResourceError::InvalidCommitSha { .. }
             | ResourceError::EmptyUpdate
             | ResourceError::ResourceNotFound { .. }),
         ) => Err(error),
@@ -250,6 +258,7 @@ fn add_migrated_resource(issue: &mut Issue, resource: NewResource) -> Result<(),
             | ResourceError::AbsoluteWorkspacePath { .. }
             | ResourceError::WorkspacePathEscape { .. }
             | ResourceError::EmptyNormalizedWorkspacePath { .. }
+            | ResourceError::InvalidCommitSha { .. }
             | ResourceError::EmptyUpdate
             | ResourceError::ResourceNotFound { .. }),
         ) => Err(error),
@@ -264,9 +273,9 @@ fn is_default_next_resource_id(value: &u64) -> bool {
     *value == DEFAULT_NEXT_RESOURCE_ID
 }
 
-pub(super) struct IssueRecordConversion {
-    pub(super) issue: Issue,
-    pub(super) migration_conflict: Option<MigrationField>,
+pub(crate) struct IssueRecordConversion {
+    pub(crate) issue: Issue,
+    pub(crate) migration_conflict: Option<MigrationField>,
 }
 
 /// A compatibility DTO for decoding persisted Issue records.
@@ -274,7 +283,7 @@ pub(super) struct IssueRecordConversion {
 /// Optional canonical and legacy Kind fields are confined to this read seam.
 /// Canonical writes use [`CanonicalIssueRecord`], whose Kind is required.
 #[derive(Debug, Deserialize)]
-pub(super) struct IssueRecord {
+pub(crate) struct IssueRecord {
     id: IssueId,
     title: String,
     description: String,
@@ -291,6 +300,9 @@ pub(super) struct IssueRecord {
     labels: Vec<String>,
     design: Option<String>,
     acceptance_criteria: Option<String>,
+    /// Workspace-declared custom field values.
+    #[serde(default)]
+    custom_fields: BTreeMap<String, CustomFieldValue>,
     #[serde(default)]
     notes: PersistedNotes,
     /// Canonical Associated Resource collection.
@@ -310,10 +322,16 @@ pub(super) struct IssueRecord {
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     closed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    milestone: Option<String>,
+    #[serde(default)]
+    lease: Option<Lease>,
 }
 
 impl IssueRecord {
-    pub(super) fn into_domain(self) -> Result<IssueRecordConversion, IssueRecordError> {
+    pub(crate) fn into_domain(self) -> Result<IssueRecordConversion, IssueRecordError> {
         let Self {
             id,
             title,
@@ -326,6 +344,7 @@ impl IssueRecord {
             labels,
             design,
             acceptance_criteria,
+            custom_fields,
             notes,
             resources,
             next_resource_id,
@@ -334,6 +353,9 @@ impl IssueRecord {
             created_at,
             updated_at,
             closed_at,
+            due_at,
+            milestone,
+            lease,
         } = self;
         let (issue_kind, migration_conflict) = match (issue_kind, issue_type) {
             (Some(issue_kind), None) | (None, Some(issue_kind)) => (issue_kind, None),
@@ -380,6 +402,7 @@ impl IssueRecord {
             labels,
             design,
             acceptance_criteria,
+            custom_fields,
             notes,
             resources: Vec::new(),
             next_resource_id: DEFAULT_NEXT_RESOURCE_ID,
@@ -387,6 +410,9 @@ impl IssueRecord {
             created_at,
             updated_at,
             closed_at,
+            due_at,
+            milestone,
+            lease,
         };
         issue
             .rehydrate_resources(resources, next_resource_id)
@@ -435,7 +461,7 @@ impl IssueRecord {
 
 /// The canonical Issue shape written to disk.
 #[derive(Debug, Serialize)]
-pub(super) struct CanonicalIssueRecord {
+pub(crate) struct CanonicalIssueRecord {
     id: IssueId,
     title: String,
     description: String,
@@ -446,6 +472,10 @@ pub(super) struct CanonicalIssueRecord {
     labels: Vec<String>,
     design: Option<String>,
     acceptance_criteria: Option<String>,
+    /// Workspace-declared custom field values. Omitted when empty so
+    /// records without custom fields keep their existing shape.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    custom_fields: BTreeMap<String, CustomFieldValue>,
     notes: PersistedNotes,
     /// Canonical Associated Resource collection.
     resources: Vec<ResourceRecord>,
@@ -460,6 +490,16 @@ pub(super) struct CanonicalIssueRecord {
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     closed_at: Option<DateTime<Utc>>,
+    /// Target completion timestamp. Omitted when unset so records without a
+    /// due date keep their existing shape.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_at: Option<DateTime<Utc>>,
+    /// Milestone name. Omitted when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone: Option<String>,
+    /// Agent claim on the issue. Omitted when unclaimed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lease: Option<Lease>,
 }
 
 impl From<Issue> for CanonicalIssueRecord {
@@ -475,6 +515,7 @@ impl From<Issue> for CanonicalIssueRecord {
             labels,
             design,
             acceptance_criteria,
+            custom_fields,
             notes,
             resources,
             next_resource_id,
@@ -482,6 +523,9 @@ impl From<Issue> for CanonicalIssueRecord {
             created_at,
             updated_at,
             closed_at,
+            due_at,
+            milestone,
+            lease,
         } = issue;
 
         Self {
@@ -495,6 +539,7 @@ impl From<Issue> for CanonicalIssueRecord {
             labels,
             design,
             acceptance_criteria,
+            custom_fields,
             notes: PersistedNotes::Canonical(notes.into_iter().map(Into::into).collect()),
             resources: resources.into_iter().map(Into::into).collect(),
             next_resource_id,
@@ -502,6 +547,9 @@ impl From<Issue> for CanonicalIssueRecord {
             created_at,
             updated_at,
             closed_at,
+            due_at,
+            milestone,
+            lease,
         }
     }
 }
@@ -11,7 +11,12 @@ use super::issue_record::{
 use crate::domain::{IssueId, ResourceError};
 use crate::error::{Error, Result, StorageError};
 use crate::storage::IssueStorage;
-use rivets_jsonl::{Warning as JsonlWarning, read_jsonl_resilient_with_line_numbers};
+use rivets_jsonl::{
+    Warning as JsonlWarning, parse_jsonl_resilient_with_line_numbers,
+    read_jsonl_resilient_with_line_numbers,
+};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::fs::File;
@@ -49,11 +54,11 @@ use tokio::sync::Mutex;
 ///         rivets::storage::in_memory::LoadWarning::MalformedJson { line_number, error } => {
 ///             eprintln!("Skipped malformed JSON at line {}: {}", line_number, error);
 ///         }
-///         rivets::storage::in_memory::LoadWarning::OrphanedDependency { from, to } => {
-///             eprintln!("Skipped orphaned dependency: {} -> {}", from, to);
+///         rivets::storage::in_memory::LoadWarning::OrphanedDependency { from, to, line_number } => {
+///             eprintln!("Skipped orphaned dependency at line {}: {} -> {}", line_number, from, to);
 ///         }
-///         rivets::storage::in_memory::LoadWarning::CircularDependency { from, to } => {
-///             eprintln!("Broke circular dependency: {} -> {}", from, to);
+///         rivets::storage::in_memory::LoadWarning::CircularDependency { from, to, line_number } => {
+///             eprintln!("Broke circular dependency at line {}: {} -> {}", line_number, from, to);
 ///         }
 ///         rivets::storage::in_memory::LoadWarning::InvalidIssueData { issue_id, line_number, error } => {
 ///             eprintln!("Skipped invalid issue {} at line {}: {}", issue_id, line_number, error);
@@ -66,6 +71,14 @@ use tokio::sync::Mutex;
 ///                 issue_id, line_number, source
 ///             );
 ///         }
+///         rivets::storage::in_memory::LoadWarning::DuplicateId {
+///             issue_id, line_number, first_line_number
+///         } => {
+///             eprintln!(
+///                 "Skipped issue {} at line {}: its ID is already used on line {}",
+///                 issue_id, line_number, first_line_number
+///             );
+///         }
 ///         rivets::storage::in_memory::LoadWarning::MigrationConflict {
 ///             issue_id, field, ..
 ///         } => {
@@ -95,16 +108,24 @@ pub enum LoadWarning {
     /// **Effect**: The dependency edge is skipped; both issues are still loaded,
     /// but the dependency relationship is not created.
     /// **Common causes**: Partial exports, deleted dependencies, file corruption.
-    #[error("orphaned dependency from {from} to {to}")]
-    OrphanedDependency { from: IssueId, to: IssueId },
+    #[error("line {line_number}: orphaned dependency from {from} to {to}")]
+    OrphanedDependency {
+        from: IssueId,
+        to: IssueId,
+        line_number: usize,
+    },
 
     /// Adding a dependency would create a circular reference
     ///
     /// **Effect**: The dependency edge is skipped to break the cycle; both issues
     /// are loaded but one dependency edge is omitted.
     /// **Common causes**: Manual JSONL editing, bugs in earlier versions.
-    #[error("circular dependency from {from} to {to}")]
-    CircularDependency { from: IssueId, to: IssueId },
+    #[error("line {line_number}: circular dependency from {from} to {to}")]
+    CircularDependency {
+        from: IssueId,
+        to: IssueId,
+        line_number: usize,
+    },
 
     /// Emitted and migration-only persisted fields disagree.
     ///
@@ -132,6 +153,18 @@ pub enum LoadWarning {
         error: String,
     },
 
+    /// A record reuses the ID of an earlier record in the file.
+    ///
+    /// **Effect**: The later record is skipped; the first one is loaded.
+    /// **Common causes**: Two branches minting the same ID before a merge.
+    /// `rivets doctor --fix-ids` gives the later record a new ID.
+    #[error("line {line_number}: Issue {issue_id} duplicates the ID on line {first_line_number}")]
+    DuplicateId {
+        issue_id: IssueId,
+        line_number: usize,
+        first_line_number: usize,
+    },
+
     /// An Associated Resource failed domain validation.
     ///
     /// **Effect**: The entire issue is skipped and not loaded into storage.
@@ -155,6 +188,8 @@ pub enum LoadWarning {
 /// - **Malformed JSON**: Skips the line and adds a warning
 /// - **Orphaned dependencies**: Skips the dependency edge and adds a warning
 /// - **Circular dependencies**: Skips the dependency edge and adds a warning
+/// - **Duplicate IDs**: Keeps the first record with an ID, skips later ones
+///   and adds a warning
 ///
 /// The JSONL-backed workspace adapter treats warnings that omit an entire
 /// Issue as unsafe for writes. It continues to serve the successfully loaded
@@ -204,6 +239,30 @@ pub async fn load_from_jsonl(
                 rivets_jsonl::Error::InvalidFormat(msg) => StorageError::InvalidFormat(msg).into(),
             })?;
 
+    build_storage(parsed_records, jsonl_warnings, prefix).await
+}
+
+/// Load storage from JSONL content already in memory.
+///
+/// Parses `content` exactly as [`load_from_jsonl`] parses a file, with the
+/// same warnings, so callers holding the file's bytes from elsewhere, such
+/// as a git revision, get the same resilience.
+pub async fn load_from_jsonl_bytes(
+    content: &[u8],
+    prefix: String,
+) -> Result<(Box<dyn IssueStorage>, Vec<LoadWarning>)> {
+    let (parsed_records, jsonl_warnings) =
+        parse_jsonl_resilient_with_line_numbers::<IssueRecord, _>(content).await;
+
+    build_storage(parsed_records, jsonl_warnings, prefix).await
+}
+
+/// Validate parsed records and build storage from the ones that pass.
+async fn build_storage(
+    parsed_records: Vec<(usize, IssueRecord)>,
+    jsonl_warnings: Vec<JsonlWarning>,
+    prefix: String,
+) -> Result<(Box<dyn IssueStorage>, Vec<LoadWarning>)> {
     let mut warnings = Vec::new();
 
     // Convert rivets_jsonl warnings to LoadWarnings
@@ -268,17 +327,35 @@ pub async fn load_from_jsonl(
             });
         }
         match outcome {
-            Ok(issue) => issues.push(issue),
+            Ok(issue) => issues.push((line_number, issue)),
             Err(warning) => warnings.push(warning),
         }
     }
 
+    // Keep the first record for each ID; later ones would silently replace it.
+    let mut first_lines: HashMap<IssueId, usize> = HashMap::new();
+    issues.retain(
+        |(line_number, issue)| match first_lines.entry(issue.id.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(*line_number);
+                true
+            }
+            Entry::Occupied(entry) => {
+                warnings.push(LoadWarning::DuplicateId {
+                    issue_id: issue.id.clone(),
+                    line_number: *line_number,
+                    first_line_number: *entry.get(),
+                });
+                false
+            }
+        },
+    );
     // Create storage and import issues
     let storage = Arc::new(Mutex::new(InMemoryStorageInner::new(prefix)));
     let mut inner = storage.lock().await;
 
     // Second pass: Import issues and create graph nodes
-    for issue in &issues {
+    for (_, issue) in &issues {
         let node = inner.graph.add_node(issue.id.clone());
         inner.node_map.insert(issue.id.clone(), node);
         inner.issues.insert(issue.id.clone(), issue.clone());
@@ -288,13 +365,14 @@ pub async fn load_from_jsonl(
     }
 
     // Third pass: Reconstruct dependencies with cycle detection
-    for issue in &issues {
+    for (line_number, issue) in &issues {
         for dep in &issue.dependencies {
             // Check if dependency target exists
             if !inner.node_map.contains_key(&dep.depends_on_id) {
                 warnings.push(LoadWarning::OrphanedDependency {
                     from: issue.id.clone(),
                     to: dep.depends_on_id.clone(),
+                    line_number: *line_number,
                 });
                 continue;
             }
@@ -304,6 +382,7 @@ pub async fn load_from_jsonl(
                 warnings.push(LoadWarning::CircularDependency {
                     from: issue.id.clone(),
                     to: dep.depends_on_id.clone(),
+                    line_number: *line_number,
                 });
                 continue;
             }
@@ -87,7 +87,8 @@ use tokio::sync::Mutex;
 
 // Re-export public API
 pub use issue_record::MigrationField;
-pub use jsonl::{LoadWarning, load_from_jsonl, save_to_jsonl};
+pub(crate) use issue_record::{CanonicalIssueRecord, IssueRecord};
+pub use jsonl::{LoadWarning, load_from_jsonl, load_from_jsonl_bytes, save_to_jsonl};
 
 /// Thread-safe in-memory storage.
 ///
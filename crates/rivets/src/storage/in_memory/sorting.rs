@@ -15,11 +15,20 @@ use chrono::{Duration, Utc};
 /// arise requiring different urgency/fairness trade-offs.
 pub(super) const HYBRID_SORT_RECENT_WINDOW_HOURS: i64 = 48;
 
+/// Due-date pressure window (in hours) for the hybrid sort policy.
+///
+/// Issues that are overdue or due within this window are "pressing". Among
+/// recent issues of the same priority, pressing ones come first, soonest due
+/// date first. Pressure never lifts an issue above a higher priority, so a
+/// P4 due tomorrow still waits behind a P0 with no due date.
+pub(super) const HYBRID_SORT_DUE_WINDOW_HOURS: i64 = 72;
+
 /// Sort issues according to the specified sort policy.
 ///
 /// # Sort Policies
 ///
-/// - `Hybrid`: Recent issues (< 48h) sorted by priority, older issues by age
+/// - `Hybrid`: Recent issues (< 48h) by priority, pressing issues (overdue
+///   or due within 72h) first within a priority, then older issues by age
 /// - `Priority`: Strict priority ordering (P0 -> P1 -> P2 -> P3 -> P4)
 /// - `Oldest`: Creation date ascending (oldest first)
 ///
@@ -37,22 +46,38 @@ pub(super) const HYBRID_SORT_RECENT_WINDOW_HOURS: i64 = 48;
 /// 1. High-priority recent work gets immediate attention
 /// 2. Older issues don't languish indefinitely (promoted after the window expires)
 /// 3. Within each tier, FIFO ordering maintains fairness
+///
+/// Due-date pressure (see [`HYBRID_SORT_DUE_WINDOW_HOURS`]) breaks ties
+/// between recent issues of the same priority, ahead of creation date, so
+/// approaching deadlines surface first without overriding priority. Older
+/// issues keep strict age order.
 pub(super) fn sort_by_policy(issues: &mut [Issue], policy: SortPolicy) {
     match policy {
         SortPolicy::Hybrid => {
             let now = Utc::now();
             let cutoff = now - Duration::hours(HYBRID_SORT_RECENT_WINDOW_HOURS);
+            let due_horizon = now + Duration::hours(HYBRID_SORT_DUE_WINDOW_HOURS);
+            let pressing_due = |issue: &Issue| issue.due_at.filter(|due| *due <= due_horizon);
+
+            // Pressing issues first, soonest deadline first
+            let by_pressure = |a: &Issue, b: &Issue| match (pressing_due(a), pressing_due(b)) {
+                (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            };
 
             issues.sort_by(|a, b| {
                 let a_is_recent = a.created_at > cutoff;
                 let b_is_recent = b.created_at > cutoff;
 
                 match (a_is_recent, b_is_recent) {
-                    // Both recent: sort by priority (P0 first), then oldest first within priority,
-                    // then by ID for determinism when timestamps match exactly.
+                    // Both recent: sort by priority (P0 first), then due pressure, then oldest
+                    // first within priority, then by ID for determinism when timestamps match.
                     (true, true) => a
                         .priority
                         .cmp(&b.priority)
+                        .then_with(|| by_pressure(a, b))
                         .then(a.created_at.cmp(&b.created_at))
                         .then(a.id.cmp(&b.id)),
                     // Both old: sort by age (oldest first), then by ID for determinism
@@ -4,13 +4,13 @@ use super::InMemoryStorage;
 use super::graph::{find_blocked_issues, get_dependency_tree_impl, has_cycle_impl};
 use super::sorting::sort_by_policy;
 use crate::domain::{
-    Dependency, DependencyType, Issue, IssueFilter, IssueId, IssueStatus, IssueUpdate,
+    Dependency, DependencyType, Issue, IssueFilter, IssueId, IssueStatus, IssueUpdate, LeaseError,
     MAX_PRIORITY, NewIssue, NewResource, Note, ResourceId, ResourceUpdate, SortPolicy,
 };
 use crate::error::{Error, Result, StorageError};
 use crate::storage::IssueStorage;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use petgraph::Direction;
 use petgraph::visit::EdgeRef;
 
@@ -38,6 +38,15 @@ fn matches_filter(issue: &Issue, filter: &IssueFilter) -> bool {
             .label
             .as_ref()
             .is_none_or(|label| issue.labels.contains(label))
+        && filter
+            .custom_fields
+            .iter()
+            .all(|(name, value)| issue.custom_fields.get(name) == Some(value))
+        && (!filter.overdue || issue.is_overdue(Utc::now()))
+        && filter
+            .milestone
+            .as_ref()
+            .is_none_or(|milestone| issue.milestone.as_ref() == Some(milestone))
 }
 
 #[async_trait]
@@ -104,6 +113,7 @@ impl IssueStorage for InMemoryStorage {
             labels: new_issue.labels,
             design: new_issue.design,
             acceptance_criteria: new_issue.acceptance_criteria,
+            custom_fields: new_issue.custom_fields,
             notes,
             resources: vec![],
             next_resource_id: 1,
@@ -111,6 +121,9 @@ impl IssueStorage for InMemoryStorage {
             created_at: now,
             updated_at: now,
             closed_at: None,
+            due_at: new_issue.due_at,
+            milestone: new_issue.milestone,
+            lease: None,
         };
 
         // Store issue (node already added during validation)
@@ -182,7 +195,24 @@ impl IssueStorage for InMemoryStorage {
         if let Some(labels) = updates.labels {
             candidate.labels = labels;
         }
+        if let Some(due_at) = updates.due_at {
+            candidate.due_at = due_at;
+        }
+        if let Some(milestone) = updates.milestone {
+            candidate.milestone = milestone;
+        }
+        for (name, value) in updates.custom_fields {
+            match value {
+                Some(value) => {
+                    candidate.custom_fields.insert(name, value);
+                }
+                None => {
+                    candidate.custom_fields.remove(&name);
+                }
+            }
+        }
 
+        candidate.clear_orphaned_lease();
         candidate.validate().map_err(StorageError::Validation)?;
         candidate.updated_at = now;
 
@@ -190,6 +220,67 @@ impl IssueStorage for InMemoryStorage {
         Ok(candidate)
     }
 
+    async fn claim(&mut self, id: &IssueId, claimant: &str, lease: Duration) -> Result<Issue> {
+        let mut inner = self.lock().await;
+        if !inner.issues.contains_key(id) {
+            return Err(Error::IssueNotFound(id.clone()));
+        }
+        if find_blocked_issues(&inner.graph, &inner.node_map, &inner.issues).contains(id) {
+            return Err(StorageError::Lease(LeaseError::Blocked(id.clone())).into());
+        }
+        let stored = inner
+            .issues
+            .get_mut(id)
+            .ok_or_else(|| Error::IssueNotFound(id.clone()))?;
+        let mut candidate = stored.clone();
+        let now = Utc::now();
+        candidate
+            .claim(claimant, now + lease, now)
+            .map_err(StorageError::from)?;
+        candidate.updated_at = now;
+
+        *stored = candidate.clone();
+        Ok(candidate)
+    }
+
+    async fn renew_lease(
+        &mut self,
+        id: &IssueId,
+        claimant: &str,
+        lease: Duration,
+    ) -> Result<Issue> {
+        let mut inner = self.lock().await;
+        let stored = inner
+            .issues
+            .get_mut(id)
+            .ok_or_else(|| Error::IssueNotFound(id.clone()))?;
+        let mut candidate = stored.clone();
+        let now = Utc::now();
+        candidate
+            .renew_lease(claimant, now + lease)
+            .map_err(StorageError::from)?;
+        candidate.updated_at = now;
+
+        *stored = candidate.clone();
+        Ok(candidate)
+    }
+
+    async fn release(&mut self, id: &IssueId, claimant: &str) -> Result<Issue> {
+        let mut inner = self.lock().await;
+        let stored = inner
+            .issues
+            .get_mut(id)
+            .ok_or_else(|| Error::IssueNotFound(id.clone()))?;
+        let mut candidate = stored.clone();
+        candidate
+            .release_lease(claimant)
+            .map_err(StorageError::from)?;
+        candidate.updated_at = Utc::now();
+
+        *stored = candidate.clone();
+        Ok(candidate)
+    }
+
     async fn add_resource(&mut self, id: &IssueId, resource: NewResource) -> Result<Issue> {
         let mut inner = self.lock().await;
         let stored = inner
@@ -267,12 +358,7 @@ impl IssueStorage for InMemoryStorage {
             });
         }
 
-        // Remove from graph
-        inner.graph.remove_node(node);
-        inner.node_map.remove(id);
-
-        // Remove from issues
-        inner.issues.remove(id);
+        inner.remove_issue(id);
 
         Ok(())
     }
@@ -448,11 +534,17 @@ impl IssueStorage for InMemoryStorage {
         // Find all blocked issues using BFS traversal
         let blocked = find_blocked_issues(&inner.graph, &inner.node_map, &inner.issues);
 
-        // Filter out blocked and closed issues
+        // Filter out blocked and closed issues, and issues other claimants hold
+        let now = Utc::now();
+        let claimant = filter.and_then(|filter| filter.claimant.as_deref());
         let mut ready: Vec<Issue> = inner
             .issues
             .values()
-            .filter(|issue| issue.status != IssueStatus::Closed && !blocked.contains(&issue.id))
+            .filter(|issue| {
+                issue.status != IssueStatus::Closed
+                    && !blocked.contains(&issue.id)
+                    && !issue.is_leased_to_other(claimant, now)
+            })
             .cloned()
             .collect();
 
@@ -587,6 +679,15 @@ impl IssueStorage for InMemoryStorage {
         Ok(inner.issues.values().cloned().collect())
     }
 
+    async fn restore_issues(&mut self, before: Vec<Issue>, created: &[IssueId]) -> Result<()> {
+        let mut inner = self.lock().await;
+        for id in created {
+            inner.remove_issue(id);
+        }
+        inner.restore(before);
+        Ok(())
+    }
+
     async fn save(&self) -> Result<()> {
         // In-memory storage doesn't persist to disk
         // This is a no-op for this implementation
@@ -625,6 +726,10 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             closed_at: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            lease: None,
         }
     }
 
@@ -709,7 +814,11 @@ mod tests {
             issue_kind: Some(IssueKind::Task),
             assignee: Some("alice".to_string()),
             label: Some("bug".to_string()),
+            claimant: None,
             limit: None,
+            custom_fields: Default::default(),
+            overdue: false,
+            milestone: None,
         };
         assert!(matches_filter(&issue, &filter));
 
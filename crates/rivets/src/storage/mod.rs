@@ -60,6 +60,9 @@
 //!         acceptance_criteria: None,
 //!         initial_note: None,
 //!         dependencies: vec![],
+//!         custom_fields: Default::default(),
+//!         due_at: None,
+//!         milestone: None,
 //!     };
 //!
 //!     let issue = storage.create(new_issue).await?;
@@ -75,6 +78,7 @@ use crate::domain::{
 };
 use crate::error::{PartialLoadError, Result, SkippedIssueRecordCause, StorageError};
 use async_trait::async_trait;
+use chrono::Duration;
 use std::path::{Path, PathBuf};
 
 // Storage backend implementations
@@ -236,11 +240,13 @@ pub trait IssueStorage: Send + Sync {
     /// - Not closed
     /// - Not blocked by dependencies
     /// - Not blocked transitively through parent-child relationships
+    /// - Not under an active lease held by anyone but `filter.claimant`
     ///
     /// # Sort Policies
     ///
     /// The `sort_policy` parameter controls result ordering:
-    /// - `Hybrid` (default): Recent issues (< 48h) by priority, older by age
+    /// - `Hybrid` (default): Overdue or soon-due issues by due date, then recent
+    ///   issues (< 48h) by priority, then older issues by age
     /// - `Priority`: Strict P0 -> P1 -> P2 -> P3 -> P4 ordering
     /// - `Oldest`: Creation date ascending (oldest first)
     ///
@@ -324,6 +330,40 @@ pub trait IssueStorage: Send + Sync {
     /// - `Error::Storage(StorageError::Resource(ResourceError::ResourceNotFound))` if the resource doesn't exist
     async fn remove_resource(&mut self, id: &IssueId, resource_id: &ResourceId) -> Result<Issue>;
 
+    // ========== Claims ==========
+
+    /// Atomically claim an issue for `claimant` with a lease of `lease`.
+    ///
+    /// The issue is assigned to `claimant` only if it is unassigned, already
+    /// held by `claimant`, or held under an expired lease. Claiming again as
+    /// the holder extends the lease.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::IssueNotFound` if the issue doesn't exist
+    /// - `Error::Storage(StorageError::Lease(_))` if the issue is closed,
+    ///   blocked, assigned, or actively leased by another claimant
+    async fn claim(&mut self, id: &IssueId, claimant: &str, lease: Duration) -> Result<Issue>;
+
+    /// Extend `claimant`'s lease on an issue to `lease` from now.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::IssueNotFound` if the issue doesn't exist
+    /// - `Error::Storage(StorageError::Lease(LeaseError::NotHolder))` if
+    ///   `claimant` does not hold the lease
+    async fn renew_lease(&mut self, id: &IssueId, claimant: &str, lease: Duration)
+    -> Result<Issue>;
+
+    /// Release `claimant`'s lease on an issue and unassign it.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::IssueNotFound` if the issue doesn't exist
+    /// - `Error::Storage(StorageError::Lease(LeaseError::NotHolder))` if
+    ///   `claimant` does not hold the lease
+    async fn release(&mut self, id: &IssueId, claimant: &str) -> Result<Issue>;
+
     // ========== Batch Operations ==========
 
     /// Import multiple issues.
@@ -337,6 +377,14 @@ pub trait IssueStorage: Send + Sync {
     /// Returns all issues in the storage, suitable for JSONL export or backup.
     async fn export_all(&self) -> Result<Vec<Issue>>;
 
+    /// Undo a partly applied change in memory.
+    ///
+    /// Removes the `created` issues, then puts back each issue in `before`
+    /// exactly as given, dependencies included. Unlike [`reload`](Self::reload),
+    /// this works for backends with nothing on disk to reload from, and leaves
+    /// issues outside the change alone.
+    async fn restore_issues(&mut self, before: Vec<Issue>, created: &[IssueId]) -> Result<()>;
+
     // ========== Persistence ==========
 
     /// Save changes to persistent storage.
@@ -354,7 +402,10 @@ pub trait IssueStorage: Send + Sync {
     /// # Errors
     ///
     /// JSONL-backed storage returns [`StorageError::UnsafePartialLoad`] when
-    /// resilient loading omitted any Issue record. No file write is attempted.
+    /// resilient loading omitted any Issue record, and
+    /// [`StorageError::ConcurrentModification`] when the file changed on disk
+    /// since it was last loaded or saved. No file write is attempted in
+    /// either case.
     async fn save(&self) -> Result<()>;
 
     /// Reload state from persistent storage, discarding in-memory changes.
@@ -382,6 +433,22 @@ pub trait IssueStorage: Send + Sync {
     ///
     /// Returns an error if the backing file cannot be read or parsed.
     async fn reload(&mut self) -> Result<()>;
+
+    /// Check whether persistent storage changed since it was last loaded or saved.
+    ///
+    /// Long-running processes (like MCP servers) call this before serving
+    /// cached state and `reload()` when it returns `true`, so edits made by
+    /// other processes — the CLI, `git pull`, `git checkout` — are picked up.
+    ///
+    /// The default returns `false`, for backends with no file that another
+    /// process could change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing file's metadata cannot be read.
+    async fn changed_on_disk(&self) -> Result<bool> {
+        Ok(false)
+    }
 }
 
 /// Storage backend configuration.
@@ -395,6 +462,16 @@ pub enum StorageBackend {
     /// JSONL file storage (persistent)
     Jsonl(PathBuf),
 
+    /// A JSONL data file as committed at a git revision (read-only)
+    GitRevision {
+        /// Directory git runs in, which the data file is relative to
+        root: PathBuf,
+        /// Path to the data file, relative to `root`
+        data_file: PathBuf,
+        /// Any revision `git` accepts, such as a tag, branch or commit
+        rev: String,
+    },
+
     /// PostgreSQL database (persistent, production-ready)
     #[allow(dead_code)]
     PostgreSQL(String),
@@ -408,7 +485,9 @@ impl StorageBackend {
     pub fn data_path(&self) -> Option<&Path> {
         match self {
             StorageBackend::Jsonl(path) => Some(path),
-            StorageBackend::InMemory | StorageBackend::PostgreSQL(_) => None,
+            StorageBackend::InMemory
+            | StorageBackend::GitRevision { .. }
+            | StorageBackend::PostgreSQL(_) => None,
         }
     }
 }
@@ -418,11 +497,44 @@ impl StorageBackend {
 /// Reads remain available after a resilient partial load. Mutations and saves
 /// are rejected before state changes when any Issue record was omitted, so an
 /// incomplete in-memory representation can never replace the source file.
+///
+/// Loaded from a git revision, it is a read-only snapshot: every mutation and
+/// save is rejected, and there is no file on disk to reload or watch.
 struct JsonlBackedStorage {
     inner: Box<dyn IssueStorage>,
     path: PathBuf,
     prefix: String,
     load_warnings: Vec<in_memory::LoadWarning>,
+    /// The git revision the issues were read from, if not the file on disk.
+    revision: Option<String>,
+    /// The file as of the last load or save; `None` if it did not exist.
+    ///
+    /// A mutex because `save()` takes `&self`.
+    fingerprint: std::sync::Mutex<Option<FileFingerprint>>,
+}
+
+/// Cheap identity of a data file's contents: its length and modification time.
+///
+/// Every save rewrites the file through an atomic rename, so any write by
+/// another process changes the modification time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileFingerprint {
+    len: u64,
+    modified: Option<std::time::SystemTime>,
+}
+
+impl FileFingerprint {
+    /// Fingerprint the file at `path`, or `None` if it does not exist.
+    async fn of(path: &Path) -> Result<Option<Self>> {
+        match tokio::fs::metadata(path).await {
+            Ok(metadata) => Ok(Some(Self {
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
 
 impl JsonlBackedStorage {
@@ -467,6 +579,15 @@ impl JsonlBackedStorage {
                     issue_id: issue_id.clone(),
                     source: source.clone(),
                 }),
+                in_memory::LoadWarning::DuplicateId {
+                    issue_id,
+                    line_number,
+                    first_line_number,
+                } => Some(SkippedIssueRecordCause::DuplicateId {
+                    line_number: *line_number,
+                    issue_id: issue_id.clone(),
+                    first_line_number: *first_line_number,
+                }),
             })
             .collect();
 
@@ -474,11 +595,28 @@ impl JsonlBackedStorage {
     }
 
     fn ensure_writable(&self) -> Result<()> {
+        if let Some(rev) = &self.revision {
+            return Err(StorageError::ReadOnlyRevision(rev.clone()).into());
+        }
         match self.unsafe_partial_load() {
             Some(error) => Err(error.into()),
             None => Ok(()),
         }
     }
+
+    fn recorded_fingerprint(&self) -> Option<FileFingerprint> {
+        *self
+            .fingerprint
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn record_fingerprint(&self, fingerprint: Option<FileFingerprint>) {
+        *self
+            .fingerprint
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = fingerprint;
+    }
 }
 
 #[async_trait]
@@ -583,6 +721,26 @@ impl IssueStorage for JsonlBackedStorage {
         self.inner.remove_resource(id, resource_id).await
     }
 
+    async fn claim(&mut self, id: &IssueId, claimant: &str, lease: Duration) -> Result<Issue> {
+        self.ensure_writable()?;
+        self.inner.claim(id, claimant, lease).await
+    }
+
+    async fn renew_lease(
+        &mut self,
+        id: &IssueId,
+        claimant: &str,
+        lease: Duration,
+    ) -> Result<Issue> {
+        self.ensure_writable()?;
+        self.inner.renew_lease(id, claimant, lease).await
+    }
+
+    async fn release(&mut self, id: &IssueId, claimant: &str) -> Result<Issue> {
+        self.ensure_writable()?;
+        self.inner.release(id, claimant).await
+    }
+
     async fn import_issues(&mut self, issues: Vec<Issue>) -> Result<()> {
         self.ensure_writable()?;
         self.inner.import_issues(issues).await
@@ -592,12 +750,30 @@ impl IssueStorage for JsonlBackedStorage {
         self.inner.export_all().await
     }
 
+    async fn restore_issues(&mut self, before: Vec<Issue>, created: &[IssueId]) -> Result<()> {
+        self.ensure_writable()?;
+        self.inner.restore_issues(before, created).await
+    }
+
     async fn save(&self) -> Result<()> {
         self.ensure_writable()?;
-        in_memory::save_to_jsonl(self.inner.as_ref(), &self.path).await
+        if self.changed_on_disk().await? {
+            return Err(StorageError::ConcurrentModification(self.path.clone()).into());
+        }
+        in_memory::save_to_jsonl(self.inner.as_ref(), &self.path).await?;
+        self.record_fingerprint(FileFingerprint::of(&self.path).await?);
+        Ok(())
     }
 
     async fn reload(&mut self) -> Result<()> {
+        if self.revision.is_some() {
+            return Ok(());
+        }
+
+        // Fingerprint before reading so a write that lands mid-read is
+        // detected by the next check rather than missed.
+        self.record_fingerprint(FileFingerprint::of(&self.path).await?);
+
         // Reload from the JSONL file, replacing the inner storage
         if self.path.exists() {
             let (new_storage, warnings) =
@@ -616,6 +792,13 @@ impl IssueStorage for JsonlBackedStorage {
         }
         Ok(())
     }
+
+    async fn changed_on_disk(&self) -> Result<bool> {
+        if self.revision.is_some() {
+            return Ok(false);
+        }
+        Ok(FileFingerprint::of(&self.path).await? != self.recorded_fingerprint())
+    }
 }
 
 /// Create a storage instance for the given backend.
@@ -644,6 +827,7 @@ impl IssueStorage for JsonlBackedStorage {
 /// # Errors
 ///
 /// - `Error::Io` if file operations fail (JSONL backend)
+/// - `Error::Git` if the revision cannot be read (git revision backend)
 /// - `Error::Storage` for backend-specific initialization errors
 pub async fn create_storage(
     backend: StorageBackend,
@@ -652,6 +836,7 @@ pub async fn create_storage(
     match backend {
         StorageBackend::InMemory => Ok(in_memory::new_in_memory_storage(prefix)),
         StorageBackend::Jsonl(path) => {
+            let fingerprint = FileFingerprint::of(&path).await?;
             // JSONL backend uses InMemoryStorage with file persistence
             let (inner, load_warnings) = if path.exists() {
                 let (storage, warnings) = in_memory::load_from_jsonl(&path, prefix.clone()).await?;
@@ -672,6 +857,35 @@ pub async fn create_storage(
                 path,
                 prefix,
                 load_warnings,
+                revision: None,
+                fingerprint: std::sync::Mutex::new(fingerprint),
+            }))
+        }
+        StorageBackend::GitRevision {
+            root,
+            data_file,
+            rev,
+        } => {
+            // The same resilient loading as the file on disk, applied to
+            // the blob; a revision without the file has no issues.
+            let (inner, load_warnings) = match crate::git::show(&root, &rev, &data_file).await? {
+                Some(content) => {
+                    let (storage, warnings) =
+                        in_memory::load_from_jsonl_bytes(&content, prefix.clone()).await?;
+                    for warning in &warnings {
+                        tracing::warn!(rev, warning = ?warning, "JSONL load warning");
+                    }
+                    (storage, warnings)
+                }
+                None => (in_memory::new_in_memory_storage(prefix.clone()), Vec::new()),
+            };
+            Ok(Box::new(JsonlBackedStorage {
+                inner,
+                path: root.join(data_file),
+                prefix,
+                load_warnings,
+                revision: Some(rev),
+                fingerprint: std::sync::Mutex::new(None),
             }))
         }
         StorageBackend::PostgreSQL(_conn_str) => {
@@ -798,6 +1012,10 @@ impl MockStorage {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             closed_at: None,
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+            lease: None,
         }
     }
 }
@@ -924,6 +1142,29 @@ impl IssueStorage for MockStorage {
         )
     }
 
+    async fn claim(&mut self, _id: &IssueId, _claimant: &str, _lease: Duration) -> Result<Issue> {
+        unimplemented!(
+            "MockStorage::claim() is not implemented. Use in_memory::new_in_memory_storage() for full CRUD."
+        )
+    }
+
+    async fn renew_lease(
+        &mut self,
+        _id: &IssueId,
+        _claimant: &str,
+        _lease: Duration,
+    ) -> Result<Issue> {
+        unimplemented!(
+            "MockStorage::renew_lease() is not implemented. Use in_memory::new_in_memory_storage() for full CRUD."
+        )
+    }
+
+    async fn release(&mut self, _id: &IssueId, _claimant: &str) -> Result<Issue> {
+        unimplemented!(
+            "MockStorage::release() is not implemented. Use in_memory::new_in_memory_storage() for full CRUD."
+        )
+    }
+
     async fn import_issues(&mut self, _issues: Vec<Issue>) -> Result<()> {
         Ok(())
     }
@@ -932,6 +1173,10 @@ impl IssueStorage for MockStorage {
         Ok(vec![])
     }
 
+    async fn restore_issues(&mut self, _before: Vec<Issue>, _created: &[IssueId]) -> Result<()> {
+        Ok(())
+    }
+
     async fn save(&self) -> Result<()> {
         Ok(())
     }
@@ -963,6 +1208,9 @@ mod tests {
             acceptance_criteria: None,
             initial_note: None,
             dependencies: vec![],
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
         };
 
         let issue = storage.create(new_issue).await.unwrap();
@@ -1041,6 +1289,9 @@ mod tests {
             acceptance_criteria: None,
             initial_note: None,
             dependencies: vec![],
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
         };
 
         let created = storage.create(new_issue).await.unwrap();
@@ -1166,6 +1417,9 @@ mod tests {
             acceptance_criteria: None,
             initial_note: None,
             dependencies: vec![],
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
         };
 
         let created = storage.create(new_issue).await.unwrap();
@@ -1183,6 +1437,117 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_jsonl_save_refuses_to_overwrite_external_changes() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let jsonl_path = temp_dir.path().join("issues.jsonl");
+        let new_issue = |title: &str| NewIssue {
+            title: title.to_string(),
+            description: "".to_string(),
+            priority: 2,
+            issue_kind: IssueKind::Task,
+            assignee: None,
+            labels: vec![],
+            design: None,
+            acceptance_criteria: None,
+            initial_note: None,
+            dependencies: vec![],
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
+        };
+
+        let mut server = create_storage(StorageBackend::Jsonl(jsonl_path.clone()), "test".into())
+            .await
+            .unwrap();
+        assert!(!server.changed_on_disk().await.unwrap());
+        server.create(new_issue("From server")).await.unwrap();
+        server.save().await.unwrap();
+        server.save().await.unwrap();
+        assert!(!server.changed_on_disk().await.unwrap());
+
+        // Another process loads the file, changes it and saves.
+        let mut cli = create_storage(StorageBackend::Jsonl(jsonl_path.clone()), "test".into())
+            .await
+            .unwrap();
+        let external = cli.create(new_issue("From CLI")).await.unwrap();
+        cli.save().await.unwrap();
+
+        assert!(server.changed_on_disk().await.unwrap());
+        server.create(new_issue("Lost update")).await.unwrap();
+        let refused = server.save().await;
+        assert!(matches!(
+            refused,
+            Err(crate::error::Error::Storage(
+                StorageError::ConcurrentModification(_)
+            ))
+        ));
+        let on_disk = std::fs::read_to_string(&jsonl_path).unwrap();
+        assert!(on_disk.contains("From CLI"));
+        assert!(!on_disk.contains("Lost update"));
+
+        server.reload().await.unwrap();
+        assert!(!server.changed_on_disk().await.unwrap());
+        assert!(server.get(&external.id).await.unwrap().is_some());
+        server.create(new_issue("After reload")).await.unwrap();
+        server.save().await.unwrap();
+        assert!(cli.changed_on_disk().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn git_revision_storage_reads_committed_issues_read_only() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(root)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?} failed");
+        };
+        let data_file = PathBuf::from("issues.jsonl");
+        let new_issue = |title: &str| NewIssue {
+            title: title.to_string(),
+            ..Default::default()
+        };
+
+        git(&["init", "--quiet"]);
+        let mut storage =
+            create_storage(StorageBackend::Jsonl(root.join(&data_file)), "test".into())
+                .await
+                .unwrap();
+        let committed = storage.create(new_issue("Committed")).await.unwrap();
+        storage.save().await.unwrap();
+        git(&["add", "issues.jsonl"]);
+        git(&["commit", "--quiet", "--message", "Add issue"]);
+        storage.create(new_issue("Uncommitted")).await.unwrap();
+        storage.save().await.unwrap();
+
+        let backend = StorageBackend::GitRevision {
+            root: root.to_path_buf(),
+            data_file,
+            rev: "HEAD".to_string(),
+        };
+        let mut at_head = create_storage(backend, "test".into()).await.unwrap();
+        let issues = at_head.export_all().await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, committed.id);
+
+        let refused = at_head.create(new_issue("Rejected")).await;
+        assert!(matches!(
+            refused,
+            Err(crate::error::Error::Storage(StorageError::ReadOnlyRevision(rev))) if rev == "HEAD"
+        ));
+        assert!(at_head.save().await.is_err());
+        assert!(!at_head.changed_on_disk().await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_in_memory_reload_is_noop() {
         let mut storage = create_storage(StorageBackend::InMemory, "test".into())
@@ -1200,6 +1565,9 @@ mod tests {
             acceptance_criteria: None,
             initial_note: None,
             dependencies: vec![],
+            custom_fields: Default::default(),
+            due_at: None,
+            milestone: None,
         };
 
         let created = storage.create(new_issue).await.unwrap();
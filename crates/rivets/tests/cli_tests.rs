@@ -584,6 +584,444 @@ fn test_cli_update_issue(initialized_dir: TempDir) {
     assert!(show_stdout.contains("in_progress"));
 }
 
+/// Run rivets with `EDITOR` set to a `sed` script that rewrites the document.
+#[cfg(unix)]
+fn run_rivets_with_editor(dir: &Path, sed_script: &str, args: &[&str]) -> std::process::Output {
+    Command::new(get_rivets_binary())
+        .args(args)
+        .current_dir(dir)
+        .env_remove("VISUAL")
+        .env("EDITOR", format!("sed -i -e {sed_script}"))
+        .output()
+        .expect("Failed to execute rivets binary")
+}
+
+#[cfg(unix)]
+#[rstest]
+fn test_cli_edit_applies_changed_fields(initialized_dir: TempDir) {
+    let issue_id = create_issue(
+        initialized_dir.path(),
+        "Original title",
+        &["--assignee", "alice"],
+    );
+
+    let output = run_rivets_with_editor(
+        initialized_dir.path(),
+        "s/^title:.*/title:\\x20Edited\\x20title/",
+        &["--json", "edit", &issue_id],
+    );
+    assert!(
+        output.status.success(),
+        "Edit failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["title"], "Edited title");
+    assert_eq!(json["assignee"], "alice");
+}
+
+#[cfg(unix)]
+#[rstest]
+fn test_cli_edit_without_changes_leaves_issue_untouched(initialized_dir: TempDir) {
+    let issue_id = create_issue(initialized_dir.path(), "Stable title", &[]);
+
+    let output = run_rivets_with_editor(initialized_dir.path(), "s/^$//", &["edit", &issue_id]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No changes"));
+}
+
+#[cfg(unix)]
+#[rstest]
+fn test_cli_create_edit_uses_editor_document(initialized_dir: TempDir) {
+    let output = run_rivets_with_editor(
+        initialized_dir.path(),
+        "s/^title:.*/title:\\x20From\\x20editor/",
+        &["--json", "create", "--edit", "--priority", "1"],
+    );
+    assert!(
+        output.status.success(),
+        "Create failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["title"], "From editor");
+    assert_eq!(json["priority"], 1);
+}
+
+#[cfg(unix)]
+#[rstest]
+fn test_cli_create_edit_aborts_on_empty_document(initialized_dir: TempDir) {
+    let output = run_rivets_with_editor(initialized_dir.path(), "d", &["create", "--edit"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("empty issue document"));
+}
+
+/// Create an issue with JSON output and return the parsed issue.
+fn create_issue_json(dir: &Path, args: &[&str]) -> serde_json::Value {
+    let mut full_args = vec!["--json", "create"];
+    full_args.extend(args);
+    let output = run_rivets_in_dir(dir, &full_args);
+    assert!(
+        output.status.success(),
+        "Create failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[rstest]
+fn test_cli_create_applies_kind_template(initialized_dir: TempDir) {
+    let json = create_issue_json(
+        initialized_dir.path(),
+        &["--title", "Crash", "--kind", "bug", "--labels", "ui"],
+    );
+
+    assert!(
+        json["description"]
+            .as_str()
+            .unwrap()
+            .contains("Steps to reproduce")
+    );
+    assert_eq!(json["labels"], serde_json::json!(["bug", "ui"]));
+}
+
+#[rstest]
+fn test_cli_create_explicit_values_override_template(initialized_dir: TempDir) {
+    let json = create_issue_json(
+        initialized_dir.path(),
+        &["--title", "Epic", "--kind", "epic", "-p", "3", "-D", "Mine"],
+    );
+
+    assert_eq!(json["description"], "Mine");
+    assert_eq!(json["priority"], 3);
+}
+
+#[rstest]
+fn test_cli_create_with_named_template(initialized_dir: TempDir) {
+    std::fs::write(
+        initialized_dir
+            .path()
+            .join(".rivets/templates/incident.yaml"),
+        "kind: bug\npriority: 0\nlabels: [incident]\n",
+    )
+    .unwrap();
+
+    let json = create_issue_json(
+        initialized_dir.path(),
+        &["--title", "Outage", "--template", "incident"],
+    );
+
+    assert_eq!(json["issue_kind"], "bug");
+    assert_eq!(json["priority"], 0);
+    assert_eq!(json["labels"], serde_json::json!(["incident"]));
+}
+
+#[rstest]
+fn test_cli_create_no_template_and_missing_template(initialized_dir: TempDir) {
+    let json = create_issue_json(
+        initialized_dir.path(),
+        &["--title", "Plain", "--kind", "bug", "--no-template"],
+    );
+    assert_eq!(json["description"], "");
+    assert_eq!(json["labels"], serde_json::json!([]));
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["create", "--title", "x", "--template", "missing"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Template 'missing' not found"));
+}
+
+/// Append a `custom-fields` section to the workspace configuration.
+fn declare_custom_fields(dir: &Path) {
+    let config_path = dir.join(".rivets/config.yaml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        "custom-fields:\n  severity:\n    type: enum\n    values: [low, high]\n  points:\n    type: int\n  due:\n    type: date\n",
+    );
+    std::fs::write(&config_path, config).unwrap();
+}
+
+#[rstest]
+fn test_cli_custom_fields_set_update_filter_and_show(initialized_dir: TempDir) {
+    let dir = initialized_dir.path();
+    declare_custom_fields(dir);
+
+    let created = create_issue_json(
+        dir,
+        &[
+            "--title",
+            "Sized",
+            "--field",
+            "severity=high",
+            "--field",
+            "points=5",
+        ],
+    );
+    assert_eq!(
+        created["custom_fields"],
+        serde_json::json!({"points": 5, "severity": "high"})
+    );
+    let id = created["id"].as_str().unwrap().to_string();
+    create_issue_json(dir, &["--title", "Unsized"]);
+
+    let output = run_rivets_in_dir(
+        dir,
+        &[
+            "update",
+            &id,
+            "--field",
+            "points=",
+            "--field",
+            "due=2026-01-05",
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "Update failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_rivets_in_dir(dir, &["--json", "list", "--field", "severity=high"]);
+    let listed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(
+        listed[0]["custom_fields"],
+        serde_json::json!({"due": "2026-01-05", "severity": "high"})
+    );
+
+    let output = run_rivets_in_dir(dir, &["show", &id]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("severity: high"), "show output: {stdout}");
+}
+
+#[rstest]
+#[case::undeclared(&["--field", "owner=me"], "Unknown custom field 'owner'")]
+#[case::bad_enum(&["--field", "severity=urgent"], "Invalid value 'urgent'")]
+#[case::bad_int(&["--field", "points=many"], "Invalid value 'many'")]
+#[case::missing_equals(&["--field", "points"], "Expected key=value")]
+fn test_cli_custom_fields_rejected(
+    initialized_dir: TempDir,
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) {
+    declare_custom_fields(initialized_dir.path());
+
+    let mut full_args = vec!["create", "--title", "x"];
+    full_args.extend(args);
+    let output = run_rivets_in_dir(initialized_dir.path(), &full_args);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(expected), "stderr: {stderr}");
+}
+
+#[rstest]
+fn test_cli_due_dates_overdue_and_due_command(initialized_dir: TempDir) {
+    let dir = initialized_dir.path();
+    let overdue = create_issue_json(dir, &["--title", "Late", "--due", "2020-01-01"]);
+    assert_eq!(overdue["due_at"], "2020-01-01T23:59:59Z");
+    let overdue_id = overdue["id"].as_str().unwrap().to_string();
+    let far_id = create_issue_json(dir, &["--title", "Far", "--due", "2099-01-01"])["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    create_issue_json(dir, &["--title", "Undated"]);
+
+    let output = run_rivets_in_dir(dir, &["--json", "list", "--overdue"]);
+    let listed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["id"], overdue_id.as_str());
+
+    let output = run_rivets_in_dir(dir, &["list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.matches("[overdue]").count(),
+        1,
+        "list output: {stdout}"
+    );
+
+    let output = run_rivets_in_dir(dir, &["--json", "due"]);
+    let due: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(due.as_array().unwrap().len(), 1);
+
+    let output = run_rivets_in_dir(dir, &["--json", "due", "--within", "5000w"]);
+    let due: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<_> = due
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, [overdue_id.as_str(), far_id.as_str()]);
+
+    let output = run_rivets_in_dir(dir, &["--json", "stats"]);
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["overdue"], 1);
+
+    let output = run_rivets_in_dir(dir, &["update", &overdue_id, "--no-due"]);
+    assert!(
+        output.status.success(),
+        "Update failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = run_rivets_in_dir(dir, &["--json", "show", &overdue_id]);
+    let shown: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(shown[0]["due_at"].is_null());
+}
+
+#[rstest]
+#[case::bad_date(&["create", "--title", "x", "--due", "soon"], "Invalid due date 'soon'")]
+#[case::bad_window(&["due", "--within", "7m"], "Invalid due window '7m'")]
+#[case::due_conflicts(&["update", "test-abc", "--due", "2026-01-01", "--no-due"], "cannot be used with")]
+fn test_cli_due_invalid_input(
+    initialized_dir: TempDir,
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) {
+    let output = run_rivets_in_dir(initialized_dir.path(), args);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(expected), "stderr: {stderr}");
+}
+
+/// Run a `--json` command, asserting success, and parse its output.
+fn run_json(dir: &Path, args: &[&str]) -> serde_json::Value {
+    let mut full_args = vec!["--json"];
+    full_args.extend_from_slice(args);
+    let output = run_rivets_in_dir(dir, &full_args);
+    assert!(
+        output.status.success(),
+        "{args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[rstest]
+fn test_cli_milestone_progress_and_scoped_ready(initialized_dir: TempDir) {
+    let dir = initialized_dir.path();
+    let today = chrono::Utc::now().date_naive();
+    let start = (today - chrono::Days::new(2)).to_string();
+    let end = (today + chrono::Days::new(11)).to_string();
+
+    let created = run_json(
+        dir,
+        &[
+            "milestone",
+            "create",
+            "sprint-1",
+            "--start",
+            &start,
+            "--end",
+            &end,
+        ],
+    );
+    assert_eq!(created["state"], "open");
+    run_json(
+        dir,
+        &[
+            "milestone",
+            "create",
+            "sprint-2",
+            "--start",
+            "2099-01-01",
+            "--end",
+            "2099-01-14",
+        ],
+    );
+
+    let blocked_id = create_issue(dir, "Blocked work", &["--milestone", "sprint-1"]);
+    let blocker_id = create_issue(dir, "Blocker", &["--milestone", "sprint-1"]);
+    let other_id = create_issue(dir, "Unplanned", &[]);
+    let output = run_rivets_in_dir(
+        dir,
+        &["dep", "add", &blocked_id, &blocker_id, "-t", "blocks"],
+    );
+    assert!(output.status.success());
+
+    let shown = run_json(dir, &["show", &blocked_id]);
+    assert_eq!(shown[0]["milestone"], "sprint-1");
+
+    let ready = run_json(dir, &["ready", "--current-milestone"]);
+    let ready_ids: Vec<_> = ready
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ready_ids, [blocker_id.as_str()]);
+
+    let report = run_json(dir, &["milestone", "show", "sprint-1"]);
+    assert_eq!(report["current"], true);
+    assert_eq!(report["total"], 2);
+    assert_eq!(report["percent_complete"], 0);
+    assert_eq!(report["burndown"].as_array().unwrap().len(), 3);
+    assert_eq!(report["blocked"][0]["issue"]["id"], blocked_id.as_str());
+    assert_eq!(
+        report["blocked"][0]["blocked_by"][0]["id"],
+        blocker_id.as_str()
+    );
+
+    let output = run_rivets_in_dir(dir, &["close", &blocker_id]);
+    assert!(output.status.success());
+    let report = run_json(dir, &["milestone", "show", "sprint-1"]);
+    assert_eq!(report["closed"], 1);
+    assert_eq!(report["percent_complete"], 50);
+    assert!(report["blocked"].as_array().unwrap().is_empty());
+
+    let output = run_rivets_in_dir(dir, &["milestone", "show", "sprint-1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1/2 closed (50%)"), "stdout: {stdout}");
+    assert!(stdout.contains("Burn-down"), "stdout: {stdout}");
+
+    let output = run_rivets_in_dir(dir, &["update", &other_id, "--milestone", "sprint-2"]);
+    assert!(output.status.success());
+    let listed = run_json(dir, &["list", "--milestone", "sprint-2"]);
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["id"], other_id.as_str());
+
+    let milestones = run_json(dir, &["milestone", "list"]);
+    let summary: Vec<_> = milestones
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| (m["name"].as_str().unwrap(), m["current"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(summary, [("sprint-1", true), ("sprint-2", false)]);
+
+    let closed = run_json(dir, &["milestone", "close", "sprint-1"]);
+    assert_eq!(closed["state"], "closed");
+    let output = run_rivets_in_dir(dir, &["ready", "--current-milestone"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No open milestone covers"));
+}
+
+#[rstest]
+#[case::unknown_on_create(&["create", "--title", "x", "--milestone", "nope"], "Milestone not found: nope")]
+#[case::bad_name(&["milestone", "create", "a b", "--start", "2026-01-01", "--end", "2026-01-02"], "Invalid milestone name")]
+#[case::bad_range(&["milestone", "create", "m", "--start", "2026-01-02", "--end", "2026-01-01"], "ends (2026-01-01) before it starts")]
+#[case::bad_date(&["milestone", "create", "m", "--start", "soon", "--end", "2026-01-01"], "Invalid date 'soon'")]
+#[case::unknown_show(&["milestone", "show", "nope"], "Milestone not found: nope")]
+#[case::ready_conflict(&["ready", "--milestone", "m", "--current-milestone"], "cannot be used with")]
+fn test_cli_milestone_invalid_input(
+    initialized_dir: TempDir,
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) {
+    let output = run_rivets_in_dir(initialized_dir.path(), args);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(expected), "stderr: {stderr}");
+}
+
 #[rstest]
 fn test_cli_notes_append_and_survive_restart(initialized_dir: TempDir) {
     let issue_id = create_issue(
@@ -982,6 +1420,115 @@ fn test_cli_ready_filters_by_kind_and_label(initialized_dir: TempDir) {
     assert_eq!(issues[0]["id"], expected_id);
 }
 
+// ============================================================================
+// Claim Command Tests
+// ============================================================================
+
+#[rstest]
+fn test_cli_claim_next_hides_issue_from_other_agents(initialized_dir: TempDir) {
+    let urgent = create_issue(initialized_dir.path(), "Urgent", &["--priority", "0"]);
+    let later = create_issue(initialized_dir.path(), "Later", &["--priority", "3"]);
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["--json", "claim", "--as", "agent-a", "--lease", "30m"],
+    );
+    assert!(
+        output.status.success(),
+        "Claim failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let claimed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(claimed["id"], urgent);
+    assert_eq!(claimed["assignee"], "agent-a");
+    assert_eq!(claimed["lease"]["holder"], "agent-a");
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["--json", "ready", "--as", "agent-b"],
+    );
+    let ready: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<_> = ready.iter().map(|issue| issue["id"].clone()).collect();
+    assert_eq!(ids, vec![serde_json::Value::from(later.clone())]);
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["claim", &urgent, "--as", "agent-b"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("claimed by agent-a"));
+}
+
+#[rstest]
+fn test_cli_renew_and_release(initialized_dir: TempDir) {
+    let id = create_issue(initialized_dir.path(), "Claimable", &[]);
+    let output = run_rivets_in_dir(initialized_dir.path(), &["claim", &id, "--as", "agent-a"]);
+    assert!(output.status.success());
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["renew", &id, "--as", "agent-b", "--lease", "2h"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not claimed by agent-b"));
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["renew", &id, "--as", "agent-a", "--lease", "2h"],
+    );
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Renewed claim"));
+
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["--json", "release", &id, "--as", "agent-a"],
+    );
+    assert!(output.status.success());
+    let released: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(released["assignee"].is_null());
+    assert!(released.get("lease").is_none());
+}
+
+#[rstest]
+fn test_cli_claim_rejects_invalid_lease(initialized_dir: TempDir) {
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["claim", "--as", "agent-a", "--lease", "soon"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid lease duration"));
+}
+
+#[rstest]
+fn test_cli_stale_includes_expired_claims(initialized_dir: TempDir) {
+    let id = create_issue(initialized_dir.path(), "Abandoned", &[]);
+    let output = run_rivets_in_dir(initialized_dir.path(), &["claim", &id, "--as", "agent-a"]);
+    assert!(output.status.success());
+
+    // Backdate the lease so it has expired.
+    let issues_path = initialized_dir.path().join(".rivets/issues.jsonl");
+    let mut record: serde_json::Value =
+        serde_json::from_str(std::fs::read_to_string(&issues_path).unwrap().trim()).unwrap();
+    record["lease"]["expires_at"] = "2020-01-01T00:00:00Z".into();
+    std::fs::write(&issues_path, format!("{record}\n")).unwrap();
+
+    let output = run_rivets_in_dir(initialized_dir.path(), &["stale"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Abandoned"), "Got: {stdout}");
+    assert!(stdout.contains("claim by agent-a expired"), "Got: {stdout}");
+
+    // Another agent can take over the abandoned claim.
+    let output = run_rivets_in_dir(
+        initialized_dir.path(),
+        &["--json", "claim", "--as", "agent-b"],
+    );
+    assert!(output.status.success());
+    let claimed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(claimed["id"], id);
+    assert_eq!(claimed["assignee"], "agent-b");
+}
+
 // ============================================================================
 // Dependency Command Tests
 // ============================================================================
@@ -2518,3 +3065,87 @@ fn list_resources(dir: &Path, issue_id: &str) -> Vec<serde_json::Value> {
         .expect("resource list should be an array")
         .clone()
 }
+
+// ============================================================================
+// Daemon Command Tests
+// ============================================================================
+
+/// A workspace with its own daemon directory; stops any daemon on drop so a
+/// failing test does not leave a background process behind.
+#[cfg(unix)]
+struct DaemonWorkspace {
+    workspace: TempDir,
+    daemon_dir: TempDir,
+}
+
+#[cfg(unix)]
+impl DaemonWorkspace {
+    fn run(&self, args: &[&str]) -> serde_json::Value {
+        let output = Command::new(get_rivets_binary())
+            .args(args)
+            .current_dir(self.workspace.path())
+            .env("RIVETS_DAEMON_DIR", self.daemon_dir.path())
+            .output()
+            .expect("Failed to execute rivets binary");
+        assert!(
+            output.status.success(),
+            "rivets {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).expect("output should be JSON")
+    }
+}
+
+#[cfg(unix)]
+impl Drop for DaemonWorkspace {
+    fn drop(&mut self) {
+        let _ = Command::new(get_rivets_binary())
+            .args(["daemon", "stop"])
+            .current_dir(self.workspace.path())
+            .env("RIVETS_DAEMON_DIR", self.daemon_dir.path())
+            .output();
+    }
+}
+
+#[cfg(unix)]
+#[rstest]
+fn test_daemon_serves_commands_until_stopped(initialized_dir: TempDir) {
+    let ws = DaemonWorkspace {
+        workspace: initialized_dir,
+        daemon_dir: TempDir::new().expect("Failed to create temp directory"),
+    };
+
+    assert_eq!(ws.run(&["--json", "daemon", "status"])["running"], false);
+
+    let started = ws.run(&["--json", "daemon", "start"]);
+    assert_eq!(started["running"], true);
+    let pid = started["daemon"]["pid"].clone();
+    let socket = std::path::PathBuf::from(started["socket"].as_str().expect("socket path"));
+    assert!(socket.exists());
+
+    // Starting again reports the daemon that is already running.
+    assert_eq!(ws.run(&["--json", "daemon", "start"])["daemon"]["pid"], pid);
+
+    let created = ws.run(&["--json", "create", "--title", "Through the daemon"]);
+    let id = created["id"].as_str().expect("issue id").to_string();
+    let persisted = std::fs::read_to_string(ws.workspace.path().join(".rivets/issues.jsonl"))
+        .expect("data file readable");
+    assert!(persisted.contains("Through the daemon"));
+
+    let shown = ws.run(&["--json", "show", &id]);
+    assert_eq!(shown[0]["title"], "Through the daemon");
+
+    let stopped = ws.run(&["--json", "daemon", "stop"]);
+    assert_eq!(stopped["running"], false);
+    assert_eq!(stopped["daemon"]["pid"], pid);
+    assert!(!socket.exists());
+
+    // Without a daemon, commands read the file directly again.
+    let listed = ws.run(&["--json", "list"]);
+    assert_eq!(listed.as_array().expect("list is an array").len(), 1);
+    assert_eq!(
+        ws.run(&["--json", "daemon", "stop"])["daemon"],
+        serde_json::Value::Null
+    );
+}
@@ -13,7 +13,8 @@
 
 use chrono::Utc;
 use rivets::domain::{
-    DependencyType, IssueId, IssueKind, IssueStatus, NewIssue, ResourceError, ResourceRole,
+    CustomFieldValue, DependencyType, IssueId, IssueKind, IssueStatus, NewIssue, ResourceError,
+    ResourceRole,
 };
 use rivets::storage::in_memory::{
     LoadWarning, MigrationField, load_from_jsonl, new_in_memory_storage, save_to_jsonl,
@@ -46,6 +47,9 @@ fn create_test_issue(title: &str) -> NewIssue {
         acceptance_criteria: None,
         initial_note: None,
         dependencies: vec![],
+        custom_fields: Default::default(),
+        due_at: None,
+        milestone: None,
     }
 }
 
@@ -98,12 +102,18 @@ mod load_warning_tests {
         let warning = LoadWarning::OrphanedDependency {
             from: IssueId::new("test-1"),
             to: IssueId::new("nonexistent"),
+            line_number: 3,
         };
 
         match warning {
-            LoadWarning::OrphanedDependency { from, to } => {
+            LoadWarning::OrphanedDependency {
+                from,
+                to,
+                line_number,
+            } => {
                 assert_eq!(from.as_str(), "test-1");
                 assert_eq!(to.as_str(), "nonexistent");
+                assert_eq!(line_number, 3);
             }
             _ => panic!("Expected OrphanedDependency variant"),
         }
@@ -114,12 +124,18 @@ mod load_warning_tests {
         let warning = LoadWarning::CircularDependency {
             from: IssueId::new("test-1"),
             to: IssueId::new("test-2"),
+            line_number: 7,
         };
 
         match warning {
-            LoadWarning::CircularDependency { from, to } => {
+            LoadWarning::CircularDependency {
+                from,
+                to,
+                line_number,
+            } => {
                 assert_eq!(from.as_str(), "test-1");
                 assert_eq!(to.as_str(), "test-2");
+                assert_eq!(line_number, 7);
             }
             _ => panic!("Expected CircularDependency variant"),
         }
@@ -334,9 +350,14 @@ mod load_from_jsonl_tests {
         // Should have 1 warning for orphaned dependency
         assert_eq!(warnings.len(), 1);
         match &warnings[0] {
-            LoadWarning::OrphanedDependency { from, to } => {
+            LoadWarning::OrphanedDependency {
+                from,
+                to,
+                line_number,
+            } => {
                 assert_eq!(from.as_str(), "test-2");
                 assert_eq!(to.as_str(), "nonexistent");
+                assert_eq!(*line_number, 2);
             }
             _ => panic!("Expected OrphanedDependency warning"),
         }
@@ -353,6 +374,43 @@ mod load_from_jsonl_tests {
         assert!(deps.is_empty());
     }
 
+    #[tokio::test]
+    async fn load_with_duplicate_id_keeps_first_record() {
+        let content = format!(
+            "{}\n{}\n{}",
+            create_valid_issue_json("test-a3f8", "From main"),
+            create_valid_issue_json("test-b2c1", "Unrelated"),
+            create_valid_issue_json("test-a3f8", "From feature branch")
+        );
+        let file = create_temp_jsonl_file(&content);
+
+        let (storage, warnings) = load_from_jsonl(file.path(), "test".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            LoadWarning::DuplicateId {
+                issue_id,
+                line_number,
+                first_line_number,
+            } => {
+                assert_eq!(issue_id.as_str(), "test-a3f8");
+                assert_eq!(*line_number, 3);
+                assert_eq!(*first_line_number, 1);
+            }
+            other => panic!("Expected DuplicateId warning, got {other:?}"),
+        }
+
+        let kept = storage
+            .get(&IssueId::new("test-a3f8"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.title, "From main");
+        assert_eq!(storage.export_all().await.unwrap().len(), 2);
+    }
+
     #[tokio::test]
     async fn load_with_circular_dependency() {
         // Create two issues that depend on each other
@@ -375,11 +433,17 @@ mod load_from_jsonl_tests {
         // Should have 1 warning for circular dependency (one edge broken)
         assert_eq!(warnings.len(), 1);
         match &warnings[0] {
-            LoadWarning::CircularDependency { from, to } => {
-                // One of the circular edges should be flagged
+            LoadWarning::CircularDependency {
+                from,
+                to,
+                line_number,
+            } => {
+                // One of the circular edges should be flagged, on its own line
                 assert!(
-                    (from.as_str() == "test-1" && to.as_str() == "test-2")
-                        || (from.as_str() == "test-2" && to.as_str() == "test-1")
+                    (from.as_str() == "test-1" && to.as_str() == "test-2" && *line_number == 1)
+                        || (from.as_str() == "test-2"
+                            && to.as_str() == "test-1"
+                            && *line_number == 2)
                 );
             }
             _ => panic!("Expected CircularDependency warning"),
@@ -497,7 +561,8 @@ mod load_from_jsonl_tests {
                 LoadWarning::InvalidIssueData { .. } => has_invalid = true,
                 LoadWarning::CircularDependency { .. }
                 | LoadWarning::MigrationConflict { .. }
-                | LoadWarning::InvalidResourceData { .. } => {}
+                | LoadWarning::InvalidResourceData { .. }
+                | LoadWarning::DuplicateId { .. } => {}
             }
         }
 
@@ -1091,6 +1156,9 @@ mod load_from_jsonl_tests {
                 acceptance_criteria: None,
                 initial_note: None,
                 dependencies: Vec::new(),
+                custom_fields: Default::default(),
+                due_at: None,
+                milestone: None,
             })
             .await
             .expect("create should succeed after a MigrationConflict warning");
@@ -1292,6 +1360,95 @@ mod round_trip_tests {
         assert_eq!(deps[0].dep_type, DependencyType::Blocks);
     }
 
+    #[tokio::test]
+    async fn save_and_reload_preserves_custom_fields() {
+        let mut storage = new_in_memory_storage("test".to_string());
+
+        let mut new_issue = create_test_issue("Sized");
+        new_issue.custom_fields = [
+            ("points".to_string(), CustomFieldValue::Int(5)),
+            (
+                "severity".to_string(),
+                CustomFieldValue::Text("high".to_string()),
+            ),
+            ("triaged".to_string(), CustomFieldValue::Bool(true)),
+        ]
+        .into();
+        let sized = storage.create(new_issue).await.unwrap();
+        let plain = storage.create(create_test_issue("Plain")).await.unwrap();
+
+        let file = NamedTempFile::new().unwrap();
+        save_to_jsonl(storage.as_ref(), file.path()).await.unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(content.matches("custom_fields").count(), 1);
+
+        let (reloaded, warnings) = load_from_jsonl(file.path(), "test".to_string())
+            .await
+            .unwrap();
+
+        assert!(warnings.is_empty());
+        let loaded = reloaded.get(&sized.id).await.unwrap().unwrap();
+        assert_eq!(loaded.custom_fields, sized.custom_fields);
+        let loaded = reloaded.get(&plain.id).await.unwrap().unwrap();
+        assert!(loaded.custom_fields.is_empty());
+    }
+
+    #[tokio::test]
+    async fn save_and_reload_preserves_due_dates() {
+        let mut storage = new_in_memory_storage("test".to_string());
+
+        let mut new_issue = create_test_issue("Dated");
+        new_issue.due_at = Some("2026-03-01T12:00:00Z".parse().unwrap());
+        let dated = storage.create(new_issue).await.unwrap();
+        let undated = storage.create(create_test_issue("Undated")).await.unwrap();
+
+        let file = NamedTempFile::new().unwrap();
+        save_to_jsonl(storage.as_ref(), file.path()).await.unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(content.matches("due_at").count(), 1);
+
+        let (reloaded, warnings) = load_from_jsonl(file.path(), "test".to_string())
+            .await
+            .unwrap();
+
+        assert!(warnings.is_empty());
+        let loaded = reloaded.get(&dated.id).await.unwrap().unwrap();
+        assert_eq!(loaded.due_at, dated.due_at);
+        let loaded = reloaded.get(&undated.id).await.unwrap().unwrap();
+        assert_eq!(loaded.due_at, None);
+    }
+
+    #[tokio::test]
+    async fn save_and_reload_preserves_milestones() {
+        let mut storage = new_in_memory_storage("test".to_string());
+
+        let mut new_issue = create_test_issue("Planned");
+        new_issue.milestone = Some("sprint-1".to_string());
+        let planned = storage.create(new_issue).await.unwrap();
+        let unplanned = storage
+            .create(create_test_issue("Unplanned"))
+            .await
+            .unwrap();
+
+        let file = NamedTempFile::new().unwrap();
+        save_to_jsonl(storage.as_ref(), file.path()).await.unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(content.matches("\"milestone\"").count(), 1);
+
+        let (reloaded, warnings) = load_from_jsonl(file.path(), "test".to_string())
+            .await
+            .unwrap();
+
+        assert!(warnings.is_empty());
+        let loaded = reloaded.get(&planned.id).await.unwrap().unwrap();
+        assert_eq!(loaded.milestone.as_deref(), Some("sprint-1"));
+        let loaded = reloaded.get(&unplanned.id).await.unwrap().unwrap();
+        assert_eq!(loaded.milestone, None);
+    }
+
     #[tokio::test]
     async fn corrupted_file_gracefully_loads_valid_data() {
         // Create storage with issues
@@ -5,11 +5,11 @@
 //! semantics, and sort policies.
 
 use rivets::domain::{
-    DependencyType, IssueFilter, IssueId, IssueKind, IssueStatus, IssueUpdate, MAX_PRIORITY,
-    NewIssue, NewResource, NoteContent, ResourceId, ResourceLabel, ResourceRole, ResourceTarget,
-    ResourceUpdate, SortPolicy, WebUrl, WorkspacePath,
+    DependencyType, IssueFilter, IssueId, IssueKind, IssueStatus, IssueUpdate, LeaseError,
+    MAX_PRIORITY, NewIssue, NewResource, NoteContent, ResourceId, ResourceLabel, ResourceRole,
+    ResourceTarget, ResourceUpdate, SortPolicy, WebUrl, WorkspacePath,
 };
-use rivets::error::Error;
+use rivets::error::{Error, StorageError};
 use rivets::storage::IssueStorage;
 use rivets::storage::in_memory::{load_from_jsonl, new_in_memory_storage, save_to_jsonl};
 use rstest::rstest;
@@ -27,6 +27,9 @@ fn create_test_issue(title: &str) -> NewIssue {
         acceptance_criteria: None,
         initial_note: None,
         dependencies: vec![],
+        custom_fields: Default::default(),
+        due_at: None,
+        milestone: None,
     }
 }
 
@@ -42,6 +45,9 @@ fn create_test_issue_with_priority(title: &str, priority: u8) -> NewIssue {
         acceptance_criteria: None,
         initial_note: None,
         dependencies: vec![],
+        custom_fields: Default::default(),
+        due_at: None,
+        milestone: None,
     }
 }
 
@@ -180,6 +186,69 @@ async fn test_delete_with_dependents() {
     assert!(matches!(result.unwrap_err(), Error::HasDependents { .. }));
 }
 
+#[tokio::test]
+async fn test_delete_keeps_later_issues_in_the_graph() {
+    let mut storage = new_in_memory_storage("test".to_string());
+
+    let first = storage.create(create_test_issue("First")).await.unwrap();
+    let second = storage.create(create_test_issue("Second")).await.unwrap();
+    let last = storage.create(create_test_issue("Last")).await.unwrap();
+    storage.delete(&first.id).await.unwrap();
+
+    storage
+        .add_dependency(&last.id, &second.id, DependencyType::Blocks)
+        .await
+        .unwrap();
+    let deps = storage.get_dependencies(&last.id).await.unwrap();
+    assert_eq!(deps.len(), 1);
+    assert_eq!(deps[0].depends_on_id, second.id);
+}
+
+#[tokio::test]
+async fn test_restore_issues_undoes_changes() {
+    let mut storage = new_in_memory_storage("test".to_string());
+
+    let existing = storage.create(create_test_issue("Existing")).await.unwrap();
+    let blocker = storage.create(create_test_issue("Blocker")).await.unwrap();
+    let before = vec![existing.clone(), blocker.clone()];
+
+    storage.add_label(&existing.id, "backend").await.unwrap();
+    storage
+        .add_dependency(&existing.id, &blocker.id, DependencyType::Blocks)
+        .await
+        .unwrap();
+    let created = storage.create(create_test_issue("Created")).await.unwrap();
+    storage
+        .add_dependency(&blocker.id, &created.id, DependencyType::Blocks)
+        .await
+        .unwrap();
+
+    storage
+        .restore_issues(before, std::slice::from_ref(&created.id))
+        .await
+        .unwrap();
+
+    assert!(storage.get(&created.id).await.unwrap().is_none());
+    let restored = storage.get(&existing.id).await.unwrap().unwrap();
+    assert!(restored.labels.is_empty());
+    assert_eq!(restored.updated_at, existing.updated_at);
+    assert!(
+        storage
+            .get_dependencies(&existing.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        storage
+            .get_dependents(&blocker.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(storage.export_all().await.unwrap().len(), 2);
+}
+
 // ========== Dependency Tests ==========
 
 #[tokio::test]
@@ -629,6 +698,133 @@ async fn test_sort_policy_oldest() {
     assert_eq!(ready[2].id, third.id);
 }
 
+#[tokio::test]
+async fn test_sort_policy_hybrid_breaks_priority_ties_by_due_pressure() {
+    use chrono::{Duration, Utc};
+
+    let mut storage = new_in_memory_storage("test".to_string());
+
+    let urgent = storage
+        .create(create_test_issue_with_priority("P0 no due date", 0))
+        .await
+        .unwrap();
+    let plain = storage
+        .create(create_test_issue_with_priority("P2 no due date", 2))
+        .await
+        .unwrap();
+    let mut due_later = create_test_issue_with_priority("P2 due in a month", 2);
+    due_later.due_at = Some(Utc::now() + Duration::days(30));
+    let due_later = storage.create(due_later).await.unwrap();
+    let mut due_soon = create_test_issue_with_priority("P2 due tomorrow", 2);
+    due_soon.due_at = Some(Utc::now() + Duration::days(1));
+    let due_soon = storage.create(due_soon).await.unwrap();
+    let mut overdue = create_test_issue_with_priority("P2 overdue", 2);
+    overdue.due_at = Some(Utc::now() - Duration::days(1));
+    let overdue = storage.create(overdue).await.unwrap();
+
+    let ready = storage
+        .ready_to_work(None, Some(SortPolicy::Hybrid))
+        .await
+        .unwrap();
+
+    // Within P2: overdue, then due soon, then the rest oldest first
+    let ids: Vec<_> = ready.iter().map(|issue| &issue.id).collect();
+    assert_eq!(
+        ids,
+        [
+            &urgent.id,
+            &overdue.id,
+            &due_soon.id,
+            &plain.id,
+            &due_later.id
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_sort_policy_hybrid_due_pressure_never_beats_priority() {
+    use chrono::{Duration, Utc};
+
+    let mut storage = new_in_memory_storage("test".to_string());
+
+    let mut overdue_p4 = create_test_issue_with_priority("P4 overdue", 4);
+    overdue_p4.due_at = Some(Utc::now() - Duration::days(2));
+    let overdue_p4 = storage.create(overdue_p4).await.unwrap();
+    let mut due_soon_p3 = create_test_issue_with_priority("P3 due tomorrow", 3);
+    due_soon_p3.due_at = Some(Utc::now() + Duration::days(1));
+    let due_soon_p3 = storage.create(due_soon_p3).await.unwrap();
+    let p0 = storage
+        .create(create_test_issue_with_priority("P0 no due date", 0))
+        .await
+        .unwrap();
+
+    let ready = storage
+        .ready_to_work(None, Some(SortPolicy::Hybrid))
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = ready.iter().map(|issue| &issue.id).collect();
+    assert_eq!(ids, [&p0.id, &due_soon_p3.id, &overdue_p4.id]);
+}
+
+#[tokio::test]
+async fn test_list_overdue_filter_and_due_date_updates() {
+    use chrono::{Duration, Utc};
+
+    let mut storage = new_in_memory_storage("test".to_string());
+
+    let mut overdue = create_test_issue("Overdue");
+    overdue.due_at = Some(Utc::now() - Duration::hours(1));
+    let overdue = storage.create(overdue).await.unwrap();
+    let on_time = storage.create(create_test_issue("On time")).await.unwrap();
+
+    let filter = IssueFilter {
+        overdue: true,
+        ..Default::default()
+    };
+    let listed = storage.list(&filter).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, overdue.id);
+
+    // Setting a past due date makes an issue overdue; clearing it does not
+    storage
+        .update(
+            &on_time.id,
+            IssueUpdate {
+                due_at: Some(Some(Utc::now() - Duration::days(2))),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    storage
+        .update(
+            &overdue.id,
+            IssueUpdate {
+                due_at: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let listed = storage.list(&filter).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, on_time.id);
+
+    // Closed issues are never overdue
+    storage
+        .update(
+            &on_time.id,
+            IssueUpdate {
+                status: Some(IssueStatus::Closed),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(storage.list(&filter).await.unwrap().is_empty());
+}
+
 // ========== Blocked Issues Tests ==========
 
 #[tokio::test]
@@ -1280,3 +1476,139 @@ async fn resource_update_and_remove_round_trip_through_jsonl() {
     ));
     temp_dir.close().expect("temp dir should close cleanly");
 }
+
+// ========== Claim Tests ==========
+
+fn lease_error(error: Error) -> LeaseError {
+    match error {
+        Error::Storage(StorageError::Lease(source)) => source,
+        other => panic!("expected a lease error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn claim_hides_issue_from_other_agents_ready_list() {
+    let mut storage = new_in_memory_storage("test".to_string());
+    let issue = storage
+        .create(create_test_issue("Claimable"))
+        .await
+        .unwrap();
+
+    let claimed = storage
+        .claim(&issue.id, "agent-a", chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(claimed.assignee.as_deref(), Some("agent-a"));
+    assert_eq!(claimed.lease.as_ref().unwrap().holder, "agent-a");
+
+    let ready_for = |claimant: Option<&str>| IssueFilter {
+        claimant: claimant.map(str::to_string),
+        ..Default::default()
+    };
+    let ready = storage
+        .ready_to_work(Some(&ready_for(Some("agent-b"))), None)
+        .await
+        .unwrap();
+    assert!(ready.is_empty());
+    let ready = storage.ready_to_work(None, None).await.unwrap();
+    assert!(ready.is_empty());
+    let ready = storage
+        .ready_to_work(Some(&ready_for(Some("agent-a"))), None)
+        .await
+        .unwrap();
+    assert_eq!(ready.len(), 1);
+
+    let err = storage
+        .claim(&issue.id, "agent-b", chrono::Duration::hours(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        lease_error(err),
+        LeaseError::HeldByOther { holder, .. } if holder == "agent-a"
+    ));
+}
+
+#[tokio::test]
+async fn claim_rejects_blocked_and_missing_issues() {
+    let mut storage = new_in_memory_storage("test".to_string());
+    let blocker = storage.create(create_test_issue("Blocker")).await.unwrap();
+    let blocked = storage.create(create_test_issue("Blocked")).await.unwrap();
+    storage
+        .add_dependency(&blocked.id, &blocker.id, DependencyType::Blocks)
+        .await
+        .unwrap();
+
+    let err = storage
+        .claim(&blocked.id, "agent-a", chrono::Duration::hours(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(lease_error(err), LeaseError::Blocked(_)));
+
+    let err = storage
+        .claim(
+            &IssueId::new("test-missing"),
+            "agent-a",
+            chrono::Duration::hours(1),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::IssueNotFound(_)));
+}
+
+#[tokio::test]
+async fn release_and_reassignment_clear_the_lease() {
+    let mut storage = new_in_memory_storage("test".to_string());
+    let issue = storage
+        .create(create_test_issue("Claimable"))
+        .await
+        .unwrap();
+    storage
+        .claim(&issue.id, "agent-a", chrono::Duration::hours(1))
+        .await
+        .unwrap();
+
+    let err = storage.release(&issue.id, "agent-b").await.unwrap_err();
+    assert!(matches!(lease_error(err), LeaseError::NotHolder { .. }));
+    let released = storage.release(&issue.id, "agent-a").await.unwrap();
+    assert!(released.lease.is_none());
+    assert!(released.assignee.is_none());
+
+    storage
+        .claim(&issue.id, "agent-a", chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    let reassigned = storage
+        .update(
+            &issue.id,
+            IssueUpdate {
+                assignee: Some(Some("alice".to_string())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(reassigned.lease.is_none());
+}
+
+#[tokio::test]
+async fn lease_round_trips_through_jsonl() {
+    let temp_dir = tempdir().unwrap();
+    let jsonl_path = temp_dir.path().join("issues.jsonl");
+
+    let mut storage = new_in_memory_storage("test".to_string());
+    let issue = storage
+        .create(create_test_issue("Claimable"))
+        .await
+        .unwrap();
+    let claimed = storage
+        .claim(&issue.id, "agent-a", chrono::Duration::minutes(30))
+        .await
+        .unwrap();
+    save_to_jsonl(storage.as_ref(), &jsonl_path).await.unwrap();
+
+    let (loaded, _warnings) = load_from_jsonl(&jsonl_path, "test".to_string())
+        .await
+        .unwrap();
+    let reloaded = loaded.get(&issue.id).await.unwrap().unwrap();
+    assert_eq!(reloaded.lease, claimed.lease);
+}